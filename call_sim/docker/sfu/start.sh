@@ -0,0 +1,60 @@
+#!/bin/bash
+
+#
+# Copyright 2026 Signal Messenger, LLC
+# SPDX-License-Identifier: AGPL-3.0-only
+#
+
+# Starts DynamoDB Local, the Calling Service backend and then the frontend, which serves
+# the SFU's http API. The options follow the "running locally" instructions of the
+# Calling Service and may need to be updated along with CALLING_SERVICE_REF.
+
+set -e
+
+# The address that clients reach us at on the call_sim network.
+SFU_IP=${SFU_IP:-172.28.0.252}
+
+# Must match the key that call_sim uses to generate client profiles.
+AUTH_KEY=${AUTH_KEY:-deaddeaddeaddeaddeaddeaddeaddeaddeaddeaddeaddeaddeaddeaddeaddead}
+
+TABLE=Conferences
+
+java -jar /usr/src/dynamodb/DynamoDBLocal.jar -inMemory -port 8000 &
+
+# Create the table for calls once DynamoDB Local is up. It accepts any credentials, but
+# needs a signed-looking request.
+for _ in $(seq 1 50); do
+    if curl -sf -o /dev/null -X POST http://127.0.0.1:8000 \
+        -H "Content-Type: application/x-amz-json-1.0" \
+        -H "X-Amz-Target: DynamoDB_20120810.CreateTable" \
+        -H "Authorization: AWS4-HMAC-SHA256 Credential=local/20240101/us-east-1/dynamodb/aws4_request, SignedHeaders=host, Signature=local" \
+        -d "{\"TableName\": \"${TABLE}\",
+             \"AttributeDefinitions\": [{\"AttributeName\": \"roomId\", \"AttributeType\": \"S\"}],
+             \"KeySchema\": [{\"AttributeName\": \"roomId\", \"KeyType\": \"HASH\"}],
+             \"BillingMode\": \"PAY_PER_REQUEST\"}"; then
+        break
+    fi
+    sleep 0.2
+done
+
+export AWS_ACCESS_KEY_ID=local
+export AWS_SECRET_ACCESS_KEY=local
+
+calling_backend \
+    --binding-ip 0.0.0.0 \
+    --ice-candidate-ip "${SFU_IP}" \
+    --ice-candidate-port 10000 \
+    --signaling-ip 127.0.0.1 \
+    --signaling-port 8080 &
+
+exec calling_frontend \
+    --server-ip 0.0.0.0 \
+    --server-port 8090 \
+    --authentication-key "${AUTH_KEY}" \
+    --region local \
+    --version local \
+    --regional-url "http://${SFU_IP}:8090" \
+    --calling-server-url http://127.0.0.1:8080 \
+    --storage-table "${TABLE}" \
+    --storage-region us-east-1 \
+    --storage-endpoint http://127.0.0.1:8000
@@ -3,11 +3,16 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 //
 
+use serde::{Deserialize, Deserializer};
 use std::{fmt, path::Path, time::Duration};
 
 /// ChartDimension is used for summary reports, to help automate the summary charting and
 /// display of most tracked `dimensions` that are available.
+///
+/// Scenario files refer to dimensions by their [ChartDimension::get_name].
 #[allow(dead_code)]
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ChartDimension {
     MosSpeech,
     MosAudio,
@@ -17,17 +22,21 @@ pub enum ChartDimension {
     ContainerTxBitrate,
     ContainerRxBitrate,
 
+    #[serde(rename = "connection_current_rtt")]
     ConnectionCurrentRoundTripTime,
     ConnectionOutgoingBitrate,
 
+    #[serde(rename = "audio_send_pps")]
     AudioSendPacketsPerSecond,
     AudioSendPacketSize,
     AudioSendBitrate,
     AudioSendRemotePacketLoss,
     AudioSendRemoteJitter,
+    #[serde(rename = "audio_send_remote_rtt")]
     AudioSendRemoteRoundTripTime,
     AudioSendAudioEnergy,
 
+    #[serde(rename = "audio_receive_pps")]
     AudioReceivePacketsPerSecond,
     AudioReceivePacketLoss,
     AudioReceiveBitrate,
@@ -35,24 +44,33 @@ pub enum ChartDimension {
     AudioReceiveAudioEnergy,
     AudioReceiveJitterBufferDelay,
 
+    #[serde(rename = "video_send_pps")]
     VideoSendPacketsPerSecond,
     VideoSendPacketSize,
     VideoSendBitrate,
     VideoSendFramerate,
+    #[serde(rename = "video_key_frames_encoded")]
     VideoSendKeyFramesEncoded,
+    #[serde(rename = "video_retransmitted_pps")]
     VideoSendRetransmittedPacketsSent,
+    #[serde(rename = "video_retransmitted_bitrate")]
     VideoSendRetransmittedBitrate,
     VideoSendDelayPerPacket,
+    #[serde(rename = "video_nack_count_received")]
     VideoSendNackCount,
+    #[serde(rename = "video_pli_count_received")]
     VideoSendPliCount,
     VideoSendRemotePacketLoss,
     VideoSendRemoteJitter,
+    #[serde(rename = "video_send_remote_rtt")]
     VideoSendRemoteRoundTripTime,
 
+    #[serde(rename = "video_receive_pps")]
     VideoReceivePacketsPerSecond,
     VideoReceivePacketLoss,
     VideoReceiveBitrate,
     VideoReceiveFramerate,
+    #[serde(rename = "video_key_frames_decoded")]
     VideoReceiveKeyFramesDecoded,
 }
 
@@ -160,7 +178,8 @@ impl ChartDimension {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SummaryReportColumns {
     pub show_visqol_mos_speech: bool,
     pub show_visqol_mos_audio: bool,
@@ -191,12 +210,13 @@ pub struct GroupConfig {
     /// Specify the charts to be displayed in the summary report for the group.
     pub chart_dimensions: Vec<ChartDimension>,
     /// The labels to use for the charts on the x-axis.
-    pub x_labels: &'static [&'static str],
+    pub x_labels: Vec<String>,
     /// Columns to show in summary reports.
     pub summary_report_columns: SummaryReportColumns,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct TestCaseConfig {
     /// A name to give the test case uniqueness among others.
     pub test_case_name: String,
@@ -230,7 +250,115 @@ impl Default for TestCaseConfig {
     }
 }
 
-#[derive(Clone, Debug)]
+/// A group call test case where any number of clients join through the SFU. Each client
+/// has its own configuration, network emulation and script of actions.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MultiPartyTestCaseConfig {
+    /// A name to give the test case uniqueness among others.
+    pub test_case_name: String,
+    /// The amount of time that the test should consume (once client instances have started).
+    pub length_seconds: u16,
+    /// The clients in the call, named `client_1`, `client_2`, etc. in that order.
+    #[serde(rename = "client")]
+    pub clients: Vec<MultiPartyClientConfig>,
+    /// The number of times to run the test case.
+    pub iterations: u16,
+}
+
+impl Default for MultiPartyTestCaseConfig {
+    fn default() -> Self {
+        Self {
+            test_case_name: "default".to_string(),
+            length_seconds: 30,
+            clients: vec![Default::default(), Default::default()],
+            iterations: 1,
+        }
+    }
+}
+
+/// The configuration of one client in a [MultiPartyTestCaseConfig].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MultiPartyClientConfig {
+    /// The overall configuration of the client.
+    pub call_config: CallConfig,
+    /// The network emulation for the client, with offsets counted from the start of the test.
+    pub network_profile: NetworkProfile,
+    /// When the client joins the call.
+    #[serde(rename = "join_offset_secs", deserialize_with = "deserialize_secs")]
+    pub join_offset: Duration,
+    /// When the client leaves the call, if before the end of the test.
+    #[serde(
+        rename = "leave_offset_secs",
+        deserialize_with = "deserialize_optional_secs"
+    )]
+    pub leave_offset: Option<Duration>,
+    /// Other things the client does during the call.
+    pub actions: Vec<GroupActionConfig>,
+}
+
+impl Default for MultiPartyClientConfig {
+    fn default() -> Self {
+        Self {
+            call_config: Default::default(),
+            network_profile: NetworkProfile::None,
+            join_offset: Duration::ZERO,
+            leave_offset: None,
+            actions: vec![],
+        }
+    }
+}
+
+impl MultiPartyClientConfig {
+    /// The script for the client, as `--group-action` values for the cli.
+    pub fn group_actions(&self) -> Vec<String> {
+        let join = GroupActionConfig {
+            offset: self.join_offset,
+            action: "join".to_string(),
+        };
+        let leave = self.leave_offset.map(|offset| GroupActionConfig {
+            offset,
+            action: "leave".to_string(),
+        });
+        std::iter::once(&join)
+            .chain(&self.actions)
+            .chain(&leave)
+            .map(|action| format!("{}:{}", action.offset.as_secs(), action.action))
+            .collect()
+    }
+}
+
+/// The actions that a client can take in a group call. The `react` and `request-video`
+/// actions take a value, i.e. `react=👍` or `request-video=640x360`.
+pub const GROUP_ACTIONS: &[&str] = &[
+    "join",
+    "leave",
+    "mute-audio",
+    "unmute-audio",
+    "mute-video",
+    "unmute-video",
+    "present",
+    "stop-presenting",
+    "raise-hand",
+    "lower-hand",
+    "react",
+    "request-video",
+];
+
+/// An action for a client to take at a particular time offset.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupActionConfig {
+    /// Counted from the start of the test.
+    #[serde(rename = "offset_secs", deserialize_with = "deserialize_secs")]
+    pub offset: Duration,
+    /// One of [GROUP_ACTIONS], with its value if it takes one.
+    pub action: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CallProfile {
     /// Don't set any special profile for the call.
     None,
@@ -241,7 +369,8 @@ pub enum CallProfile {
 }
 
 /// General structure for configuration settings to send to the cli.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct CallConfig {
     /// The maximum bitrate allowed for the call (audio and video).
     pub allowed_bitrate_kbps: u16,
@@ -308,7 +437,8 @@ impl Default for CallConfig {
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 #[repr(i32)]
 pub enum AudioBandwidth {
     // Constants in libopus.
@@ -334,7 +464,8 @@ impl fmt::Display for AudioBandwidth {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AudioAnalysisMode {
     /// Skip audio analysis. Shows up as None in reports.
     None,
@@ -349,7 +480,8 @@ pub enum AudioAnalysisMode {
 
 /// The configuration to use for all things related to audio. Note that the only audio/speech
 /// codec used is Opus, and most settings are specific for it.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AudioConfig {
     /// The name (without path or extension) of the audio file to use as source material.
     pub input_name: String,
@@ -407,6 +539,9 @@ pub struct AudioConfig {
     pub pesq_speech_analysis: bool,
     /// Flag to enable plc speech analysis.
     pub plc_speech_analysis: bool,
+    /// Flag to enable the built-in signal analysis (SNR, glitches and silence), which
+    /// doesn't need Docker. It is always done when running in-process.
+    pub signal_analysis: bool,
     /// The mechanism to use when analyzing speech/audio.
     pub analysis_mode: AudioAnalysisMode,
     /// Sometimes spectrogram generation takes too long, so we might want to disable it.
@@ -458,13 +593,15 @@ impl Default for AudioConfig {
             visqol_audio_analysis: false,
             pesq_speech_analysis: false,
             plc_speech_analysis: false,
+            signal_analysis: false,
             analysis_mode: AudioAnalysisMode::Normal,
             generate_spectrogram: true,
         }
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct VideoConfig {
     /// The name (without path or extension) of the video file to use as source material.
     pub input_name: Option<String>,
@@ -499,17 +636,21 @@ impl VideoConfig {
 }
 
 /// A NetworkConfig item to be applied at a particular time offset.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NetworkConfigWithOffset {
     /// The offset is a duration, but in practice it will be quantized to 1 second.
+    #[serde(rename = "offset_secs", deserialize_with = "deserialize_secs")]
     pub offset: Duration,
     /// The network configuration to apply at the given time.
+    #[serde(default)]
     pub network_config: NetworkConfig,
 }
 
 /// General structure for network emulation settings.
 /// (see https://manpages.ubuntu.com/manpages/jammy/man8/tc-netem.8.html)
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Copy, Clone, Default, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct NetworkConfig {
     /// ms (if 0, won't be used)
     pub delay: u32,
@@ -540,7 +681,8 @@ pub struct NetworkConfig {
 }
 
 #[allow(dead_code)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DelayVariationStrategy {
     /// %
     Correlation(u8),
@@ -550,7 +692,8 @@ pub enum DelayVariationStrategy {
 
 /// A probability distribution which can be sampled from.
 #[allow(dead_code)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Distribution {
     Uniform,
     Normal,
@@ -579,7 +722,8 @@ impl fmt::Display for Distribution {
 /// in. Generally one state (the "bad" state) will have higher packet loss. The probability of
 /// transitioning out of the bad state can be kept low to simulate bursty packet loss.
 #[allow(dead_code)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum GeLossModel {
     Bernoulli {
         p: u8,
@@ -612,7 +756,8 @@ pub enum GeLossModel {
 /// State 3 to to burst losses.
 /// State 4 to independent losses.
 #[allow(dead_code)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum MarkovLossModel {
     Bernoulli {
         p13: u8,
@@ -637,7 +782,8 @@ pub enum MarkovLossModel {
 }
 
 #[allow(dead_code)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Loss {
     /// % of packets to drop.
     Percentage(u8),
@@ -687,13 +833,15 @@ const MLBS_DATA: [MlbsData; 24] = [
 ];
 
 #[allow(dead_code)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NetworkProfile {
     /// Don't set any network emulation.
     None,
     /// Provide a network configuration but it has no impediments and a high rate.
     Default,
     /// Provide your own timed configuration(s) along with a name.
+    #[serde(deserialize_with = "deserialize_custom_profile")]
     Custom(String, Vec<NetworkConfigWithOffset>),
     /// Some delay (100ms), jitter (25ms), Loss (5% normal), constant.
     Moderate,
@@ -829,6 +977,33 @@ impl NetworkProfile {
     }
 }
 
+/// Offsets in scenario files are given in whole seconds.
+fn deserialize_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
+
+fn deserialize_optional_secs<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    deserialize_secs(deserializer).map(Some)
+}
+
+/// Scenario files describe a custom profile as a table, i.e.
+/// `{ custom = { name = "...", schedule = [...] } }`.
+fn deserialize_custom_profile<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<(String, Vec<NetworkConfigWithOffset>), D::Error> {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Custom {
+        name: String,
+        schedule: Vec<NetworkConfigWithOffset>,
+    }
+
+    let custom = Custom::deserialize(deserializer)?;
+    Ok((custom.name, custom.schedule))
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClientProfile {
@@ -60,6 +60,24 @@ pub async fn build_images() -> Result<()> {
         .wait()
         .await?;
 
+    println!("sfu:");
+    stdout().flush().await?;
+    // The Calling Service commit defaults to the one in call_sim/docker/sfu/Dockerfile.
+    let mut sfu_args = vec!["build", "-t", "sfu", "-q"];
+    let calling_service_ref = std::env::var("CALLING_SERVICE_REF")
+        .ok()
+        .map(|calling_service_ref| format!("CALLING_SERVICE_REF={}", calling_service_ref));
+    if let Some(build_arg) = &calling_service_ref {
+        sfu_args.extend(["--build-arg", build_arg.as_str()]);
+    }
+    sfu_args.push(".");
+    let _ = Command::new("docker")
+        .current_dir("call_sim/docker/sfu")
+        .args(sfu_args)
+        .spawn()?
+        .wait()
+        .await?;
+
     println!("visqol_mos:");
     stdout().flush().await?;
     let _ = Command::new("docker")
@@ -225,6 +243,36 @@ pub async fn start_turn_server() -> Result<()> {
     Ok(())
 }
 
+/// The URL of the SFU started by [start_sfu_server].
+pub const LOCAL_SFU_URL: &str = "http://172.28.0.252:8090";
+
+/// Starts a stand-in SFU at 172.28.0.252, built from the open source Signal Calling
+/// Service. Its HTTP API is at port 8090 (see [LOCAL_SFU_URL]) and media flows through UDP
+/// port 10000. It accepts the membership proofs of generated client profiles.
+pub async fn start_sfu_server() -> Result<()> {
+    println!("\nStarting SFU");
+
+    let _ = Command::new("docker")
+        .args([
+            "run",
+            "--name",
+            "sfu",
+            "-d",
+            "--network",
+            "ringrtc_default",
+            "--ip",
+            "172.28.0.252",
+            "--stop-signal",
+            "SIGINT",
+            "sfu",
+        ])
+        .spawn()?
+        .wait()
+        .await?;
+
+    Ok(())
+}
+
 pub async fn start_tcp_dump(report_path: &str) -> Result<()> {
     println!("\nStarting tcpdump");
 
@@ -246,11 +294,29 @@ pub async fn start_tcp_dump(report_path: &str) -> Result<()> {
     Ok(())
 }
 
+/// The address of a client container. Two-party tests use `client_a` and `client_b`, while
+/// multi-party tests use `client_1`, `client_2`, etc.
+pub fn client_ip(name: &str) -> Result<String> {
+    let host = match name {
+        "client_a" => 2,
+        "client_b" => 3,
+        name => name
+            .strip_prefix("client_")
+            .and_then(|index| index.parse::<u8>().ok())
+            .filter(|index| *index < 200)
+            .map(|index| 10 + index)
+            .ok_or_else(|| anyhow::anyhow!("No client address for container name: {}", name))?,
+    };
+    Ok(format!("172.28.0.{}", host))
+}
+
 /// Starts a client in a bash shell, waiting for future exec commands to actually do
 /// something useful.
 pub async fn start_client(name: &str, report_path: &str, media_path: &str) -> Result<()> {
     println!("\nStarting Client `{}`:", name);
 
+    let ip = client_ip(name)?;
+
     let _ = Command::new("docker")
         .args([
             "run",
@@ -260,6 +326,8 @@ pub async fn start_client(name: &str, report_path: &str, media_path: &str) -> Re
             "--privileged",
             "--network",
             "ringrtc_default",
+            "--ip",
+            &ip,
             "-v",
             &format!("{}:/report", report_path),
             "-v",
@@ -678,25 +746,63 @@ pub async fn start_cli(
     remote_call_config: &CallConfig,
     client_profile: &ClientProfile,
     call_type: &CallTypeConfig,
+    group_actions: &[String],
 ) -> Result<()> {
     println!("Starting cli for `{}`", name);
-    let log_file_arg = format!("/report/{}.log", name);
-    let input_file_arg = format!("/media/{}", media_io.audio_input_file);
-    let output_file_arg = format!("/report/{}", media_io.audio_output_file);
 
-    let mut args = [
-        "exec",
-        "-d",
+    let mut args = ["exec", "-d", name, "call_sim-cli"]
+        .map(String::from)
+        .to_vec();
+    args.extend(cli_args(
         name,
-        "call_sim-cli",
+        &format!("/report/{}.log", name),
+        MediaFileIo {
+            audio_input_file: format!("/media/{}", media_io.audio_input_file),
+            audio_output_file: format!("/report/{}", media_io.audio_output_file),
+            video_input_file: media_io
+                .video_input_file
+                .map(|file| format!("/media/{}", file)),
+            video_output_file: media_io
+                .video_output_file
+                .map(|file| format!("/report/{}", file)),
+        },
+        call_config,
+        remote_call_config,
+        client_profile,
+        call_type,
+    )?);
+    args.extend(
+        group_actions
+            .iter()
+            .map(|action| format!("--group-action={}", action)),
+    );
+
+    println!("Final Client args: {}", args.join(" "));
+    let _ = Command::new("docker").args(&args).spawn()?.wait().await?;
+
+    Ok(())
+}
+
+/// Builds the `call_sim-cli` arguments for a client. The media and log paths are passed
+/// through as given.
+pub fn cli_args(
+    name: &str,
+    log_file: &str,
+    media_io: MediaFileIo,
+    call_config: &CallConfig,
+    remote_call_config: &CallConfig,
+    client_profile: &ClientProfile,
+    call_type: &CallTypeConfig,
+) -> Result<Vec<String>> {
+    let mut args = [
         "--name",
         name,
         "--log-file",
-        &log_file_arg,
+        log_file,
         "--input-file",
-        &input_file_arg,
+        &media_io.audio_input_file,
         "--output-file",
-        &output_file_arg,
+        &media_io.audio_output_file,
     ]
     .map(String::from)
     .to_vec();
@@ -798,10 +904,10 @@ pub async fn start_cli(
     ));
 
     if let Some(input_video_file) = media_io.video_input_file {
-        args.push(format!("--input-video-file=/media/{}", input_video_file));
+        args.push(format!("--input-video-file={}", input_video_file));
     }
     if let Some(output_video_file) = media_io.video_output_file {
-        args.push(format!("--output-video-file=/report/{}", output_video_file));
+        args.push(format!("--output-video-file={}", output_video_file));
     }
 
     if let Some((width, height)) = remote_call_config.video.dimensions() {
@@ -809,11 +915,7 @@ pub async fn start_cli(
         args.push(format!("--output-video-height={}", height));
     }
 
-    if name == "client_a" {
-        args.push("--ip=172.28.0.2".to_string());
-    } else {
-        args.push("--ip=172.28.0.3".to_string());
-    }
+    args.push(format!("--ip={}", client_ip(name)?));
 
     if let CallProfile::DeterministicLoss(loss_rate) = call_config.profile {
         args.push(format!("--deterministic-loss={}", loss_rate));
@@ -857,10 +959,7 @@ pub async fn start_cli(
         args.push(format!("--group-member-info={}", member_info));
     }
 
-    println!("Final Client args: {}", args.join(" "));
-    let _ = Command::new("docker").args(&args).spawn()?.wait().await?;
-
-    Ok(())
+    Ok(args)
 }
 
 pub async fn convert_raw_to_wav(
@@ -1273,6 +1372,26 @@ pub async fn get_turn_server_logs(path: &str) -> Result<()> {
     Ok(())
 }
 
+pub async fn get_sfu_server_logs(path: &str) -> Result<()> {
+    // Get the logs.
+    let output = Command::new("docker")
+        .args(["logs", "sfu"])
+        .output()
+        .await?;
+
+    // Save the logs.
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(format!("{}/sfu.log", path))
+        .await?;
+    file.write_all(&output.stdout).await?;
+    file.write_all(&output.stderr).await?;
+
+    Ok(())
+}
+
 pub struct DockerStats {
     docker: Docker,
 }
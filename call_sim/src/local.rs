@@ -0,0 +1,411 @@
+//
+// Copyright 2026 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Counterparts to the docker functions for running tests without Docker: both clients
+//! run in a single local `call_sim-cli` process and media is converted natively.
+
+use anyhow::{anyhow, Result};
+use std::{f64::consts::PI, fs, process::Stdio, time::Duration};
+use tokio::{fs::File, io::AsyncWriteExt, process::Command};
+
+use crate::{
+    common::{
+        CallConfig, CallProfile, ClientProfile, GeLossModel, Loss, MarkovLossModel, NetworkConfig,
+        NetworkConfigWithOffset, TestCaseConfig,
+    },
+    docker::cli_args,
+    test::{CallTypeConfig, MediaFileIo, TestCase},
+};
+
+/// The sample rate of all raw audio, which is always 16-bit stereo.
+const RAW_SAMPLE_RATE: u32 = 48000;
+const RAW_CHANNELS: u16 = 2;
+
+/// The sample rate used for speech analysis.
+const SPEECH_SAMPLE_RATE: u32 = 16000;
+
+/// How long to wait beyond the length of a test for the cli to finish.
+const EXIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Returns why a test case can't run in-process, if it can't.
+pub fn unsupported_reason(
+    test_case_config: &TestCaseConfig,
+    call_type: &CallTypeConfig,
+) -> Option<&'static str> {
+    let client_configs = [
+        &test_case_config.client_a_config,
+        &test_case_config.client_b_config,
+    ];
+    if *call_type != CallTypeConfig::Direct {
+        Some("group calls need an SFU")
+    } else if client_configs
+        .iter()
+        .any(|config| config.video.input_name.is_some())
+    {
+        Some("video conversion and analysis need Docker")
+    } else if client_configs
+        .iter()
+        .any(|config| !config.relay_servers.is_empty())
+    {
+        Some("relay servers need the turn container")
+    } else if client_configs
+        .iter()
+        .any(|config| matches!(config.profile, CallProfile::DeterministicLoss(_)))
+    {
+        Some("deterministic loss needs the Docker network")
+    } else if test_case_config.tcp_dump {
+        Some("tcpdump needs the Docker network")
+    } else {
+        None
+    }
+}
+
+/// Formats a network config as a `--link` argument for `call_sim-cli`. The simulated
+/// network only has uniform delay and two-state loss, so netem options beyond that are
+/// approximated or ignored.
+fn link_arg(timed_network_config: &NetworkConfigWithOffset) -> String {
+    let NetworkConfig {
+        delay,
+        delay_variability,
+        loss,
+        rate,
+        limit,
+        ..
+    } = timed_network_config.network_config;
+
+    // The loss probability in the good state and after a loss, in percent.
+    let (loss, burst_loss) = match loss {
+        None => (0, 0),
+        Some(Loss::Percentage(p))
+        | Some(Loss::GeModel(GeLossModel::Bernoulli { p }))
+        | Some(Loss::State(MarkovLossModel::Bernoulli { p13: p })) => (p, p),
+        Some(Loss::GeModel(
+            GeLossModel::SimpleGilbert { p, r }
+            | GeLossModel::Gilbert { p, r, .. }
+            | GeLossModel::GilbertElliot { p, r, .. },
+        )) => (p, 100u8.saturating_sub(r)),
+        Some(Loss::State(
+            MarkovLossModel::TwoState { p13, p31 }
+            | MarkovLossModel::ThreeState { p13, p31, .. }
+            | MarkovLossModel::FourState { p13, p31, .. },
+        )) => (p13, 100u8.saturating_sub(p31)),
+    };
+
+    let mut arg = format!(
+        "--link=offset={},delay={},jitter={},loss={},burst_loss={},rate={}",
+        timed_network_config.offset.as_millis(),
+        delay,
+        delay_variability,
+        loss,
+        burst_loss,
+        rate
+    );
+    if limit > 0 {
+        arg.push_str(&format!(",limit={}", limit));
+    }
+    arg
+}
+
+#[allow(clippy::too_many_arguments)]
+fn client_args(
+    name: &str,
+    test_path: &str,
+    set_path: &str,
+    media_io: MediaFileIo,
+    call_config: &CallConfig,
+    remote_call_config: &CallConfig,
+    client_profile: &ClientProfile,
+    network_configs: &[NetworkConfigWithOffset],
+) -> Result<Vec<String>> {
+    let mut args = cli_args(
+        name,
+        &format!("{}/{}.log", test_path, name),
+        MediaFileIo {
+            audio_input_file: format!("{}/{}", set_path, media_io.audio_input_file),
+            audio_output_file: format!("{}/{}", test_path, media_io.audio_output_file),
+            video_input_file: None,
+            video_output_file: None,
+        },
+        call_config,
+        remote_call_config,
+        client_profile,
+        &CallTypeConfig::Direct,
+    )?;
+    // Like netem in the containers, the emulation applies to each client's uplink.
+    args.extend(network_configs.iter().map(link_arg));
+    Ok(args)
+}
+
+/// Runs both clients of a test case in one local `call_sim-cli` process and waits for the
+/// call to end. Like the containers, the process' resource usage is written to each
+/// client's stats log.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_in_process(
+    cli_path: &str,
+    set_path: &str,
+    test_case: &TestCase<'_>,
+    test_case_config: &TestCaseConfig,
+    network_configs: &[NetworkConfigWithOffset],
+    client_a_media_io: MediaFileIo,
+    client_b_media_io: MediaFileIo,
+    client_profiles: &[ClientProfile],
+) -> Result<()> {
+    let mut args = vec![
+        "--in-process".to_string(),
+        format!("--call-length-secs={}", test_case_config.length_seconds),
+    ];
+    args.extend(client_args(
+        test_case.client_a.name,
+        &test_case.test_path,
+        set_path,
+        client_a_media_io,
+        &test_case_config.client_a_config,
+        &test_case_config.client_b_config,
+        &client_profiles[0],
+        network_configs,
+    )?);
+    args.push("--".to_string());
+    args.extend(client_args(
+        test_case.client_b.name,
+        &test_case.test_path,
+        set_path,
+        client_b_media_io,
+        &test_case_config.client_b_config,
+        &test_case_config.client_a_config,
+        &client_profiles[1],
+        network_configs,
+    )?);
+
+    println!("Final Client args: {}", args.join(" "));
+    println!("\nRunning test...");
+
+    let mut child = Command::new(cli_path)
+        .args(&args)
+        .stdout(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    let pid = child
+        .id()
+        .ok_or_else(|| anyhow!("call_sim-cli exited immediately"))?;
+
+    let timeout = Duration::from_secs(test_case_config.length_seconds.into()) + EXIT_TIMEOUT;
+    let mut stats = ProcessStats::new(pid);
+    let mut elapsed = Duration::ZERO;
+    let status = loop {
+        match tokio::time::timeout(Duration::from_secs(1), child.wait()).await {
+            Ok(status) => break status?,
+            Err(_) => {
+                stats.sample();
+                elapsed += Duration::from_secs(1);
+                eprint!("\r{} seconds elapsed...", elapsed.as_secs());
+                if elapsed > timeout {
+                    child.kill().await?;
+                    return Err(anyhow!("call_sim-cli did not finish within {:?}", timeout));
+                }
+            }
+        }
+    };
+    println!("\r  Test complete.");
+
+    for client in [test_case.client_a, test_case.client_b] {
+        stats
+            .write(&format!(
+                "{}/{}_stats.log",
+                test_case.test_path, client.name
+            ))
+            .await?;
+    }
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("call_sim-cli failed: {}", status))
+    }
+}
+
+/// Samples the CPU and memory usage of a process from procfs, in the format of the
+/// container stats logs. Network usage isn't tracked and is always 0.
+struct ProcessStats {
+    pid: u32,
+    prev_cpu_ticks: Option<u64>,
+    lines: Vec<String>,
+}
+
+impl ProcessStats {
+    /// The kernel reports CPU time in ticks of 1/100 s on all common configurations.
+    const TICKS_PER_SECOND: u64 = 100;
+    const PAGE_SIZE: u64 = 4096;
+
+    fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            prev_cpu_ticks: None,
+            lines: vec![],
+        }
+    }
+
+    fn read(&self) -> Option<(u64, u64)> {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", self.pid)).ok()?;
+        // Skip past the command name, which may contain spaces. The next field is the
+        // state, and utime and stime are the 14th and 15th fields overall.
+        let mut fields = stat.rsplit_once(')')?.1.split_whitespace().skip(11);
+        let utime: u64 = fields.next()?.parse().ok()?;
+        let stime: u64 = fields.next()?.parse().ok()?;
+
+        let statm = fs::read_to_string(format!("/proc/{}/statm", self.pid)).ok()?;
+        let resident_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+
+        Some((utime + stime, resident_pages * Self::PAGE_SIZE))
+    }
+
+    /// Records a sample, assuming one second has passed since the previous one.
+    fn sample(&mut self) {
+        let Some((cpu_ticks, mem_bytes)) = self.read() else {
+            return;
+        };
+        if let Some(prev_cpu_ticks) = self.prev_cpu_ticks {
+            let cpu_percent =
+                (cpu_ticks - prev_cpu_ticks) as f64 * 100.0 / Self::TICKS_PER_SECOND as f64;
+            self.lines.push(format!(
+                "{}\t{:.2}\t{}\t0\t0\n",
+                chrono::Local::now().timestamp(),
+                cpu_percent,
+                mem_bytes
+            ));
+        }
+        self.prev_cpu_ticks = Some(cpu_ticks);
+    }
+
+    async fn write(&self, file_name: &str) -> Result<()> {
+        let mut file = File::create(file_name).await?;
+        file.write_all(b"Timestamp\tCPU\tMEM\tTX_Bitrate\tRX_Bitrate\n")
+            .await?;
+        for line in &self.lines {
+            file.write_all(line.as_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts raw 48kHz/16-bit/stereo audio to wav, padding or trimming it to `length`
+/// seconds if given.
+pub async fn convert_raw_to_wav(
+    location: &str,
+    raw_file: &str,
+    wav_file: &str,
+    length: Option<u16>,
+) -> Result<()> {
+    println!("\nConverting raw file `{}` to wav:", raw_file);
+
+    let raw = tokio::fs::read(format!("{}/{}", location, raw_file)).await?;
+    let mut samples: Vec<i16> = raw
+        .chunks_exact(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect();
+
+    if let Some(length) = length {
+        // Make sure the wav audio is of the expected length to smooth out MOS measurements.
+        samples.resize(
+            length as usize * RAW_SAMPLE_RATE as usize * RAW_CHANNELS as usize,
+            0,
+        );
+    }
+
+    write_wav(
+        &format!("{}/{}", location, wav_file),
+        RAW_SAMPLE_RATE,
+        RAW_CHANNELS,
+        &samples,
+    )
+}
+
+/// Converts a wav file to 16kHz/mono for speech analysis. The input sample rate must be a
+/// multiple of 16kHz.
+pub async fn convert_wav_to_16khz_mono(
+    location: &str,
+    input_file: &str,
+    output_file: &str,
+) -> Result<()> {
+    println!("\nConverting file `{}` to 16kHz/mono wav:", input_file);
+
+    let mut reader = hound::WavReader::open(format!("{}/{}", location, input_file))?;
+    let spec = reader.spec();
+    if spec.sample_rate % SPEECH_SAMPLE_RATE != 0 {
+        return Err(anyhow!(
+            "Can't convert {}Hz audio to 16kHz",
+            spec.sample_rate
+        ));
+    }
+
+    let samples = reader.samples::<i16>().collect::<Result<Vec<_>, _>>()?;
+    let mono: Vec<f64> = samples
+        .chunks_exact(spec.channels as usize)
+        .map(|frame| frame.iter().map(|&s| s as f64).sum::<f64>() / frame.len() as f64)
+        .collect();
+
+    let factor = (spec.sample_rate / SPEECH_SAMPLE_RATE) as usize;
+    let downsampled = decimate(&mono, factor);
+
+    write_wav(
+        &format!("{}/{}", location, output_file),
+        SPEECH_SAMPLE_RATE,
+        1,
+        &downsampled,
+    )
+}
+
+/// Low-pass filters the signal below the new Nyquist frequency with a windowed sinc and
+/// keeps every `factor`th sample.
+fn decimate(samples: &[f64], factor: usize) -> Vec<i16> {
+    const TAPS: usize = 127;
+
+    let cutoff = 0.9 / factor as f64;
+    let center = (TAPS / 2) as f64;
+    let taps: Vec<f64> = (0..TAPS)
+        .map(|i| {
+            let x = i as f64 - center;
+            let sinc = if x == 0.0 {
+                cutoff
+            } else {
+                (PI * cutoff * x).sin() / (PI * x)
+            };
+            // Blackman window
+            let phase = 2.0 * PI * i as f64 / (TAPS - 1) as f64;
+            sinc * (0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos())
+        })
+        .collect();
+
+    (0..samples.len())
+        .step_by(factor)
+        .map(|n| {
+            let value: f64 = taps
+                .iter()
+                .enumerate()
+                .filter_map(|(i, tap)| {
+                    (n + i)
+                        .checked_sub(TAPS / 2)
+                        .and_then(|index| samples.get(index))
+                        .map(|sample| sample * tap)
+                })
+                .sum();
+            value.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16
+        })
+        .collect()
+}
+
+fn write_wav(path: &str, sample_rate: u32, channels: u16, samples: &[i16]) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
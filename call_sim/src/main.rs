@@ -7,17 +7,21 @@ mod audio;
 mod common;
 mod config;
 mod docker;
+mod local;
+mod quality;
 mod report;
+mod results;
+mod scenario;
 mod test;
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
 use common::ClientProfile;
 use hex::FromHex;
 use itertools::Itertools;
 use std::env;
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use crate::common::{
@@ -26,7 +30,9 @@ use crate::common::{
     TestCaseConfig, VideoConfig,
 };
 use crate::docker::{build_images, clean_network, clean_up};
-use crate::test::{CallTypeConfig, Test};
+use crate::results::{SetResults, Threshold};
+use crate::scenario::Scenario;
+use crate::test::{CallTypeConfig, Test, TestRunner};
 
 fn compile_time_root_directory() -> &'static std::ffi::OsStr {
     std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -36,7 +42,11 @@ fn compile_time_root_directory() -> &'static std::ffi::OsStr {
 }
 
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Specifies which tests to run.
     test_sets: Vec<String>,
 
@@ -70,6 +80,46 @@ struct Args {
     /// If None, then uses the first group in the list
     #[arg(long)]
     group_name: Option<String>,
+
+    /// Runs both clients in a single local `call_sim-cli` process instead of in Docker
+    /// containers. Signaling and network emulation happen in that process, so neither
+    /// Docker nor root is needed. Test cases that need other containers are skipped and
+    /// audio is only analyzed by the built-in signal analysis.
+    #[arg(long)]
+    in_process: bool,
+
+    /// The `call_sim-cli` binary to use with `--in-process`, relative to the root.
+    #[arg(long, default_value = "target/release/call_sim-cli")]
+    cli_path: String,
+
+    /// Runs the test set described by a TOML scenario file, in addition to any named test
+    /// sets. The test set is named after the file. Can be given more than once.
+    #[arg(long)]
+    scenario: Vec<PathBuf>,
+
+    /// Runs group calls against an SFU in a local container instead of the test SFU. The
+    /// image is built with `--build`.
+    #[arg(long)]
+    local_sfu: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compares the results of two test sets per test case and fails if there are regressions.
+    Compare {
+        /// The baseline results, either a test set directory or its `results.json` file.
+        baseline: PathBuf,
+
+        /// The results to check against the baseline, in the same form.
+        current: PathBuf,
+
+        /// The allowed change of a metric as `<metric>=<limit>`, i.e. `mos.*=-0.1` or
+        /// `container.cpu_usage.ave=+25%`. Can be given more than once. By default, MOS
+        /// values may drop by 0.1, VMAF and the segmental SNR by 2 and CPU or memory usage
+        /// may rise by 25%.
+        #[arg(long)]
+        threshold: Vec<Threshold>,
+    },
 }
 
 // Set these two values when running call sim group calls. The Auth Key is used to generate profiles
@@ -776,6 +826,28 @@ async fn run_deterministic_loss_test(test: &mut Test) -> Result<()> {
 async fn main() -> Result<()> {
     let args = Args::parse();
 
+    if let Some(Command::Compare {
+        baseline,
+        current,
+        threshold,
+    }) = args.command
+    {
+        return compare_results(&baseline, &current, threshold);
+    }
+
+    // Load scenarios before changing to the root, since their paths are given relative to
+    // the current directory. This also reports invalid files before anything is run.
+    let scenarios = args
+        .scenario
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .map_or_else(|| "scenario".into(), |stem| stem.to_string_lossy());
+            Ok((name.into_owned(), Scenario::load(path)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
     println!("Starting the call simulator...");
 
     let mut root_path = env::current_dir()?;
@@ -788,33 +860,60 @@ async fn main() -> Result<()> {
     }
 
     if args.clean {
-        clean_up(vec![
-            "client_a",
-            "client_b",
-            "signaling_server",
-            "turn",
-            "tcpdump",
-            "visqol",
-        ])
+        // Multi-party test cases can have any number of clients, but rarely more than this.
+        let multi_party_clients = (1..=8).map(|i| format!("client_{i}")).collect::<Vec<_>>();
+        clean_up(
+            [
+                "client_a",
+                "client_b",
+                "signaling_server",
+                "sfu",
+                "turn",
+                "tcpdump",
+                "visqol",
+            ]
+            .into_iter()
+            .chain(multi_party_clients.iter().map(String::as_str))
+            .collect(),
+        )
         .await?;
         clean_network().await?;
     }
 
-    let client_profiles = args
-        .client_profile_dir
-        .map_or_else(generate_client_profiles, |client_profile_dir| {
-            get_client_profiles(&client_profile_dir)
-        });
+    // Multi-party test cases need a profile for each of their clients.
+    let client_count = scenarios
+        .iter()
+        .flat_map(|(_, scenario)| &scenario.multi_party_groups)
+        .flat_map(|group| &group.test_cases)
+        .map(|test_case| test_case.clients.len())
+        .fold(2, usize::max);
+    let client_profiles = args.client_profile_dir.map_or_else(
+        || generate_client_profiles(client_count),
+        |client_profile_dir| get_client_profiles(&client_profile_dir),
+    );
 
     let mut test_sets = args.test_sets;
-    if test_sets.is_empty() {
+    if test_sets.is_empty() && scenarios.is_empty() {
         // For quick testing, change this to the name of your test case.
         test_sets.push("minimal_example".to_string());
     }
 
+    let runner = if args.in_process {
+        TestRunner::InProcess {
+            cli_path: root_path.join(&args.cli_path).display().to_string(),
+        }
+    } else {
+        TestRunner::Docker
+    };
+
     let direct_call_config = CallTypeConfig::Direct;
+    let sfu_url = if args.local_sfu {
+        docker::LOCAL_SFU_URL
+    } else {
+        SFU_URL
+    };
     let group_call_config = CallTypeConfig::Group {
-        sfu_url: SFU_URL.to_owned(),
+        sfu_url: sfu_url.to_owned(),
         group_name: args.group_name,
     };
 
@@ -836,6 +935,7 @@ async fn main() -> Result<()> {
             &test_set_name,
             client_profiles.clone(),
             call_type_config,
+            runner.clone(),
         )?;
         match test_set_name.as_str() {
             "minimal_example" => run_minimal_example(test).await?,
@@ -853,12 +953,47 @@ async fn main() -> Result<()> {
         test.report().await?;
     }
 
+    for (scenario_name, scenario) in scenarios {
+        let call_type_config = if scenario.group_call {
+            group_call_config.clone()
+        } else {
+            direct_call_config.clone()
+        };
+        println!(
+            "Running scenario {} as call type {:?}",
+            scenario_name, call_type_config,
+        );
+        let test = &mut Test::new(
+            &root_path,
+            &args.output_dir,
+            &args.media_dir,
+            &scenario_name,
+            client_profiles.clone(),
+            call_type_config,
+            runner.clone(),
+        )?;
+        scenario.run(test).await?;
+        test.report().await?;
+    }
+
+    Ok(())
+}
+
+fn compare_results(baseline: &Path, current: &Path, thresholds: Vec<Threshold>) -> Result<()> {
+    let regressions = results::compare(
+        &SetResults::read(baseline)?,
+        &SetResults::read(current)?,
+        &results::thresholds_or_defaults(thresholds)?,
+    );
+    if regressions > 0 {
+        return Err(anyhow!("Found {} regression(s)", regressions));
+    }
     Ok(())
 }
 
-fn generate_client_profiles() -> Vec<ClientProfile> {
+fn generate_client_profiles(count: usize) -> Vec<ClientProfile> {
     let now = SystemTime::now();
-    config::generate_client_profiles(2, &group_auth_key_gen(), now)
+    config::generate_client_profiles(count, &group_auth_key_gen(), now)
 }
 
 fn get_client_profiles(dir_path: &str) -> Vec<ClientProfile> {
@@ -0,0 +1,635 @@
+//
+// Copyright 2026 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Audio quality metrics that are computed natively instead of by the ViSQOL, PESQ and
+//! PLC-MOS containers. They don't predict a MOS, but compare the received audio to the
+//! reference closely enough to catch regressions on any machine: after aligning the two
+//! signals, the SNR measures how much the waveform changed, dropouts and clicks are counted
+//! as glitches and the silent part of the received audio is measured.
+
+use anyhow::{anyhow, Result};
+use std::{f32::consts::PI, fs, io::BufWriter, ops::RangeInclusive};
+
+use crate::audio::AudioFiles;
+use crate::report::{Stats, StatsConfig, StatsData};
+
+/// The length of the frames that energy is measured over.
+const FRAME_MS: u32 = 10;
+/// How far the received audio may be shifted from the reference in either direction.
+const MAX_DELAY_MS: u32 = 2000;
+/// The length of the loudest part of the reference used to align signals per sample.
+const REFINE_MS: u32 = 1000;
+/// Reference frames quieter than this don't count towards the segmental SNR or dropouts.
+const ACTIVE_THRESHOLD_DB: f32 = -45.0;
+/// Received frames quieter than this are silent.
+const SILENCE_THRESHOLD_DB: f32 = -60.0;
+/// An active frame that is this much quieter than the reference was most likely concealed
+/// with silence or comfort noise after packets were lost.
+const DROPOUT_DB: f32 = 20.0;
+/// The usual limits of per-frame SNR, so that silent or perfect frames don't dominate.
+const SEGMENTAL_SNR_MIN: f32 = -10.0;
+const SEGMENTAL_SNR_MAX: f32 = 35.0;
+/// A step between samples this many times larger than the frame's RMS step is a click.
+const CLICK_FACTOR: f32 = 8.0;
+/// Steps smaller than this are never clicks, to ignore quiet noise.
+const CLICK_MIN_STEP: f32 = 0.05;
+
+/// The size of the spectrogram FFT, which gives it 257 rows like the sox spectrograms.
+const SPECTROGRAM_FFT_SIZE: usize = 512;
+const SPECTROGRAM_WIDTH: usize = 800;
+/// The range of the spectrogram below full scale.
+const SPECTROGRAM_RANGE_DB: f32 = 120.0;
+
+/// Audio mixed down to mono, with samples between -1 and 1.
+pub struct Signal {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl Signal {
+    /// Reads a 16-bit wav file, like those converted from raw audio.
+    pub fn read_wav(path: &str) -> Result<Self> {
+        let mut reader = hound::WavReader::open(path)?;
+        let spec = reader.spec();
+        if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+            return Err(anyhow!("Only 16-bit wav files can be analyzed: {}", path));
+        }
+
+        let samples = reader.samples::<i16>().collect::<Result<Vec<_>, _>>()?;
+        let samples = samples
+            .chunks_exact(spec.channels as usize)
+            .map(|frame| {
+                frame.iter().map(|&s| s as f32).sum::<f32>() / (frame.len() as f32 * 32768.0)
+            })
+            .collect();
+
+        Ok(Self {
+            sample_rate: spec.sample_rate,
+            samples,
+        })
+    }
+
+    fn frame_len(&self) -> usize {
+        (self.sample_rate * FRAME_MS / 1000) as usize
+    }
+}
+
+/// The results of [analyze].
+#[derive(Debug)]
+pub struct SignalQuality {
+    /// How much later the received audio is than the reference, averaged over all parts.
+    pub delay_ms: f32,
+    /// The ratio of the reference to the difference of the received audio, in dB.
+    pub snr: f32,
+    /// The average SNR of active frames for each second, in dB.
+    pub segmental_snr: Stats,
+    /// Dropouts and clicks that aren't in the reference.
+    pub glitches: usize,
+    pub glitches_per_minute: f32,
+    /// The fraction of the received audio that is silent.
+    pub silence_ratio: f32,
+}
+
+/// Compares a received wav file to its reference. If `chopped`, the received audio is
+/// split into parts as long as the reference, like [crate::audio::chop_audio] does, and
+/// each part is aligned separately. Otherwise, only the start of the received audio is
+/// compared.
+pub fn analyze(
+    audio_files: &AudioFiles<'_>,
+    chopped: bool,
+    client_name: &str,
+) -> Result<SignalQuality> {
+    println!(
+        "\nAnalyzing signal quality for `{}`:",
+        audio_files.degraded_file
+    );
+
+    let reference = Signal::read_wav(&format!(
+        "{}/{}",
+        audio_files.ref_path, audio_files.ref_file
+    ))?;
+    let degraded = Signal::read_wav(&format!(
+        "{}/{}",
+        audio_files.degraded_path, audio_files.degraded_file
+    ))?;
+    if reference.sample_rate != degraded.sample_rate {
+        return Err(anyhow!(
+            "Can't compare {}Hz audio to a {}Hz reference",
+            degraded.sample_rate,
+            reference.sample_rate
+        ));
+    }
+    if reference.samples.is_empty() || degraded.samples.is_empty() {
+        return Err(anyhow!("Can't analyze empty audio"));
+    }
+
+    let sample_rate = reference.sample_rate;
+    let frame_len = reference.frame_len();
+    let frames_per_second = (1000 / FRAME_MS) as usize;
+    let part_len = reference.samples.len();
+    let part_count = if chopped {
+        degraded.samples.len().div_ceil(part_len)
+    } else {
+        1
+    };
+
+    let mut signal_energy = 0f64;
+    let mut noise_energy = 0f64;
+    let mut delays = vec![];
+    let mut glitches = 0;
+    let mut analyzed_len = 0;
+    // The active frames' SNR values for each second of the received audio.
+    let mut seconds: Vec<Vec<f32>> = vec![];
+
+    for part in 0..part_count {
+        let part_start = part * part_len;
+        let part_end = (part_start + part_len).min(degraded.samples.len());
+        let delay = find_delay(
+            &reference.samples,
+            &degraded.samples[part_start..part_end],
+            frame_len,
+        )
+        .unwrap_or(0);
+        delays.push(delay as f32 * 1000.0 / sample_rate as f32);
+        analyzed_len += part_end - part_start;
+
+        // Line up the received audio with the reference. Missing audio counts as silence.
+        let aligned = (0..part_end - part_start)
+            .map(|i| {
+                usize::try_from((part_start + i) as isize + delay)
+                    .ok()
+                    .and_then(|j| degraded.samples.get(j))
+                    .copied()
+                    .unwrap_or(0.0)
+            })
+            .collect::<Vec<_>>();
+        let reference = &reference.samples[..aligned.len()];
+
+        // Undo any change in level, i.e. by automatic gain control.
+        let (cross, power) = reference
+            .iter()
+            .zip(&aligned)
+            .fold((0f64, 0f64), |(cross, power), (&r, &d)| {
+                (cross + (r * d) as f64, power + (d * d) as f64)
+            });
+        let gain = if power > 0.0 {
+            (cross / power) as f32
+        } else {
+            1.0
+        };
+        let aligned = aligned.iter().map(|d| d * gain).collect::<Vec<_>>();
+
+        let mut in_dropout = false;
+        for (index, (reference_frame, degraded_frame)) in reference
+            .chunks(frame_len)
+            .zip(aligned.chunks(frame_len))
+            .enumerate()
+        {
+            let reference_energy = energy(reference_frame.iter().copied());
+            let degraded_energy = energy(degraded_frame.iter().copied());
+            let noise = reference_frame
+                .iter()
+                .zip(degraded_frame)
+                .map(|(r, d)| r - d);
+            let noise_frame_energy = energy(noise);
+
+            let frame_samples = reference_frame.len() as f32;
+            signal_energy += (reference_energy * frame_samples) as f64;
+            noise_energy += (noise_frame_energy * frame_samples) as f64;
+
+            let active = to_db(reference_energy) > ACTIVE_THRESHOLD_DB;
+            if active {
+                let second = (part_start / frame_len + index) / frames_per_second;
+                if seconds.len() <= second {
+                    seconds.resize(second + 1, vec![]);
+                }
+                seconds[second].push(
+                    (to_db(reference_energy) - to_db(noise_frame_energy))
+                        .clamp(SEGMENTAL_SNR_MIN, SEGMENTAL_SNR_MAX),
+                );
+            }
+
+            // Count each run of dropped frames once.
+            let dropout = active && to_db(degraded_energy) < to_db(reference_energy) - DROPOUT_DB;
+            if dropout && !in_dropout {
+                glitches += 1;
+            }
+            in_dropout = dropout;
+
+            if !dropout && has_click(degraded_frame) && !has_click(reference_frame) {
+                glitches += 1;
+            }
+        }
+    }
+
+    let silent_frames = degraded
+        .samples
+        .chunks(frame_len)
+        .filter(|frame| to_db(energy(frame.iter().copied())) < SILENCE_THRESHOLD_DB)
+        .count();
+    let silence_ratio = silent_frames as f32 / degraded.samples.len().div_ceil(frame_len) as f32;
+
+    let mut data = StatsData::new_skip_n(0);
+    for (second, values) in seconds.iter().enumerate() {
+        if !values.is_empty() {
+            data.push_with_index(
+                (second + 1) as f32,
+                values.iter().sum::<f32>() / values.len() as f32,
+            );
+        }
+    }
+    let analyzed_secs = analyzed_len as f32 / sample_rate as f32;
+    let segmental_snr = Stats {
+        config: StatsConfig {
+            title: "Segmental SNR".to_string(),
+            chart_name: format!("{}.artifacts.segmental_snr.svg", client_name),
+            x_label: "Test Seconds".to_string(),
+            y_label: "dB".to_string(),
+            x_max: Some(analyzed_secs),
+            y_min: Some(SEGMENTAL_SNR_MIN),
+            y_max: Some(SEGMENTAL_SNR_MAX),
+            ..Default::default()
+        },
+        data,
+    };
+
+    Ok(SignalQuality {
+        delay_ms: delays.iter().sum::<f32>() / delays.len() as f32,
+        snr: (10.0 * (signal_energy.max(1e-12) / noise_energy.max(1e-12)).log10()) as f32,
+        segmental_snr,
+        glitches,
+        glitches_per_minute: glitches as f32 * 60.0 / analyzed_secs,
+        silence_ratio,
+    })
+}
+
+/// The mean square of the samples.
+fn energy(samples: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = samples.fold((0f32, 0usize), |(sum, count), s| (sum + s * s, count + 1));
+    if count > 0 {
+        sum / count as f32
+    } else {
+        0.0
+    }
+}
+
+fn to_db(energy: f32) -> f32 {
+    10.0 * energy.max(1e-12).log10()
+}
+
+/// Whether a step between samples stands out from the rest of the frame.
+fn has_click(frame: &[f32]) -> bool {
+    let steps = frame.windows(2).map(|pair| pair[1] - pair[0]);
+    let rms = energy(steps.clone()).sqrt();
+    steps
+        .map(f32::abs)
+        .any(|step| step > CLICK_MIN_STEP && step > CLICK_FACTOR * rms)
+}
+
+/// Finds how many samples later `degraded` is than `reference`, which may be negative. The
+/// frame energy envelopes are matched first and the result is then refined per sample over
+/// the loudest part of the reference. Returns None if either signal is silent.
+fn find_delay(reference: &[f32], degraded: &[f32], frame_len: usize) -> Option<isize> {
+    let envelope = |samples: &[f32]| {
+        let rms = samples
+            .chunks(frame_len)
+            .map(|frame| energy(frame.iter().copied()).sqrt())
+            .collect::<Vec<_>>();
+        let mean = rms.iter().sum::<f32>() / rms.len().max(1) as f32;
+        rms.into_iter().map(|x| x - mean).collect::<Vec<_>>()
+    };
+    let reference_envelope = envelope(reference);
+    let max_frames = (MAX_DELAY_MS / FRAME_MS) as isize;
+    let frames = best_lag(
+        &reference_envelope,
+        &envelope(degraded),
+        0,
+        -max_frames..=max_frames,
+    )?;
+
+    let refine_frames = (REFINE_MS / FRAME_MS) as usize;
+    let loudest = (0..reference_envelope
+        .len()
+        .saturating_sub(refine_frames)
+        .max(1))
+        .max_by(|&a, &b| {
+            let sum = |start: usize| -> f32 {
+                reference_envelope
+                    .iter()
+                    .skip(start)
+                    .take(refine_frames)
+                    .sum()
+            };
+            sum(a).total_cmp(&sum(b))
+        })?;
+    let start = loudest * frame_len;
+    let end = (start + refine_frames * frame_len).min(reference.len());
+    let coarse = frames * frame_len as isize;
+    best_lag(
+        &reference[start..end],
+        degraded,
+        start,
+        coarse - frame_len as isize..=coarse + frame_len as isize,
+    )
+}
+
+/// Returns the lag with the highest cross-correlation, comparing `a[i]` to
+/// `b[offset + i + lag]`. Unlike a correlation normalized per lag, this favors lags where
+/// the signals overlap fully. Returns None if the signals never overlap with any energy.
+fn best_lag(a: &[f32], b: &[f32], offset: usize, lags: RangeInclusive<isize>) -> Option<isize> {
+    lags.filter_map(|lag| {
+        let (mut ab, mut bb) = (0f64, 0f64);
+        for (i, &x) in a.iter().enumerate() {
+            let Some(&y) = usize::try_from((offset + i) as isize + lag)
+                .ok()
+                .and_then(|j| b.get(j))
+            else {
+                continue;
+            };
+            ab += (x * y) as f64;
+            bb += (y * y) as f64;
+        }
+        (bb > 0.0).then_some((lag, ab))
+    })
+    .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    .map(|(lag, _)| lag)
+}
+
+/// Writes a spectrogram of a wav file to `{wav_file}.{extension}` as a png, like the sox
+/// container does.
+pub fn generate_spectrogram(location: &str, wav_file: &str, extension: &str) -> Result<()> {
+    println!("\nGenerating spectrogram for `{}`:", wav_file);
+
+    let signal = Signal::read_wav(&format!("{}/{}", location, wav_file))?;
+    if signal.samples.len() < SPECTROGRAM_FFT_SIZE {
+        return Err(anyhow!("`{}` is too short for a spectrogram", wav_file));
+    }
+
+    let height = SPECTROGRAM_FFT_SIZE / 2 + 1;
+    let hop = (signal.samples.len() / SPECTROGRAM_WIDTH).max(1);
+    let width = signal.samples.len().div_ceil(hop).min(SPECTROGRAM_WIDTH);
+
+    let window = (0..SPECTROGRAM_FFT_SIZE)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / SPECTROGRAM_FFT_SIZE as f32).cos())
+        .collect::<Vec<_>>();
+    // A full scale sine has this magnitude with the Hann window.
+    let full_scale = SPECTROGRAM_FFT_SIZE as f32 / 4.0;
+
+    let mut pixels = vec![0u8; width * height * 3];
+    let mut re = vec![0f32; SPECTROGRAM_FFT_SIZE];
+    let mut im = vec![0f32; SPECTROGRAM_FFT_SIZE];
+    for column in 0..width {
+        let start = column * hop;
+        for (i, value) in re.iter_mut().enumerate() {
+            *value = signal.samples.get(start + i).copied().unwrap_or(0.0) * window[i];
+        }
+        im.fill(0.0);
+        fft(&mut re, &mut im);
+
+        for (bin, (re, im)) in re.iter().zip(&im).take(height).enumerate() {
+            let magnitude = (re * re + im * im).sqrt() / full_scale;
+            let db = 20.0 * magnitude.max(1e-12).log10();
+            let level = (1.0 + db / SPECTROGRAM_RANGE_DB).clamp(0.0, 1.0);
+            // The highest frequency is at the top.
+            let pixel = ((height - 1 - bin) * width + column) * 3;
+            pixels[pixel..pixel + 3].copy_from_slice(&color(level));
+        }
+    }
+
+    let file = fs::File::create(format!("{}/{}.{}", location, wav_file, extension))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Maps a level between 0 and 1 to a color from black through purple, red and yellow to
+/// white, similar to sox.
+fn color(level: f32) -> [u8; 3] {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 0.0],
+        [60.0, 0.0, 110.0],
+        [200.0, 0.0, 60.0],
+        [255.0, 150.0, 0.0],
+        [255.0, 255.0, 230.0],
+    ];
+
+    let position = level * (STOPS.len() - 1) as f32;
+    let index = (position as usize).min(STOPS.len() - 2);
+    let fraction = position - index as f32;
+    let (from, to) = (STOPS[index], STOPS[index + 1]);
+    [0, 1, 2].map(|i| (from[i] + (to[i] - from[i]) * fraction).round() as u8)
+}
+
+/// An in-place radix-2 FFT. The length must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 16000;
+    const FRAME_LEN: usize = (SAMPLE_RATE * FRAME_MS / 1000) as usize;
+    /// Changes of level are 100ms apart, on frame boundaries and zero crossings of the sine.
+    const BLOCK_LEN: usize = 1600;
+
+    /// A 400Hz sine whose level changes pseudo-randomly every block, so that its energy
+    /// envelope can be aligned.
+    fn sine(blocks: usize) -> Vec<f32> {
+        let mut seed = 1u32;
+        (0..blocks)
+            .flat_map(|_| {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                let amplitude = 0.1 + 0.5 * ((seed >> 16) % 100) as f32 / 100.0;
+                (0..BLOCK_LEN).map(move |i| {
+                    amplitude * (2.0 * PI * 400.0 * i as f32 / SAMPLE_RATE as f32).sin()
+                })
+            })
+            .collect()
+    }
+
+    /// Like [sine], but white noise, which only correlates with itself at one lag.
+    fn noise(blocks: usize) -> Vec<f32> {
+        let mut seed = 1u32;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) % 1000) as f32 / 1000.0
+        };
+        (0..blocks)
+            .flat_map(|_| {
+                let amplitude = 0.1 + 0.5 * next();
+                (0..BLOCK_LEN)
+                    .map(|_| amplitude * (2.0 * next() - 1.0))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn write_wav(path: &str, samples: &[f32]) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for sample in samples {
+            writer.write_sample((sample * 32767.0) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    fn analyze_samples(reference: &[f32], degraded: &[f32]) -> SignalQuality {
+        let dir = std::env::temp_dir().join(format!("call_sim_quality_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_str().unwrap();
+        write_wav(&format!("{}/ref.wav", path), reference);
+        write_wav(&format!("{}/degraded.wav", path), degraded);
+
+        let quality = analyze(
+            &AudioFiles {
+                degraded_path: path,
+                degraded_file: "degraded.wav",
+                ref_path: path,
+                ref_file: "ref.wav",
+            },
+            false,
+            "client_b",
+        );
+        fs::remove_dir_all(&dir).unwrap();
+        quality.unwrap()
+    }
+
+    #[test]
+    fn fft_of_impulse_is_flat() {
+        let mut re = vec![0.0; 16];
+        let mut im = vec![0.0; 16];
+        re[0] = 1.0;
+        fft(&mut re, &mut im);
+
+        for (re, im) in re.iter().zip(&im) {
+            assert!((re - 1.0).abs() < 1e-6);
+            assert!(im.abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn fft_matches_dft() {
+        let n = 64;
+        let input = noise(1)[..n].to_vec();
+        let mut re = input.clone();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im);
+
+        for k in 0..n {
+            let (dft_re, dft_im) = input.iter().enumerate().fold((0.0, 0.0), |(a, b), (i, x)| {
+                let angle = -2.0 * PI * (k * i) as f32 / n as f32;
+                (a + x * angle.cos(), b + x * angle.sin())
+            });
+            assert!((re[k] - dft_re).abs() < 1e-3, "re[{k}]");
+            assert!((im[k] - dft_im).abs() < 1e-3, "im[{k}]");
+        }
+    }
+
+    #[test]
+    fn fft_of_sine_peaks_at_its_bin() {
+        let n = 64;
+        let mut re = (0..n)
+            .map(|i| (2.0 * PI * 5.0 * i as f32 / n as f32).sin())
+            .collect::<Vec<_>>();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im);
+
+        for k in 0..n {
+            let magnitude = (re[k] * re[k] + im[k] * im[k]).sqrt();
+            let expected = if k == 5 || k == n - 5 {
+                n as f32 / 2.0
+            } else {
+                0.0
+            };
+            assert!((magnitude - expected).abs() < 1e-3, "bin {k}: {magnitude}");
+        }
+    }
+
+    #[test]
+    fn find_delay_of_delayed_signal() {
+        let reference = noise(30);
+
+        let delayed = [vec![0.0; 1234], reference.clone()].concat();
+        assert_eq!(find_delay(&reference, &delayed, FRAME_LEN), Some(1234));
+
+        let early = reference[500..].to_vec();
+        assert_eq!(find_delay(&reference, &early, FRAME_LEN), Some(-500));
+    }
+
+    #[test]
+    fn find_delay_of_silence() {
+        let reference = noise(10);
+        let silence = vec![0.0; reference.len()];
+        assert_eq!(find_delay(&reference, &silence, FRAME_LEN), None);
+    }
+
+    #[test]
+    fn analyze_identical_signals() {
+        let reference = sine(30);
+        let quality = analyze_samples(&reference, &reference);
+
+        assert_eq!(quality.glitches, 0);
+        assert_eq!(quality.silence_ratio, 0.0);
+        assert!(quality.snr > 30.0, "snr: {}", quality.snr);
+    }
+
+    #[test]
+    fn analyze_counts_dropouts() {
+        let reference = sine(30);
+        let mut degraded = reference.clone();
+        // Two dropouts, of 200ms and 100ms, as if concealed with silence.
+        degraded[10 * BLOCK_LEN..12 * BLOCK_LEN].fill(0.0);
+        degraded[20 * BLOCK_LEN..21 * BLOCK_LEN].fill(0.0);
+        let quality = analyze_samples(&reference, &degraded);
+
+        assert_eq!(quality.glitches, 2);
+        assert!(quality.silence_ratio > 0.05, "{}", quality.silence_ratio);
+        assert!(quality.silence_ratio < 0.15, "{}", quality.silence_ratio);
+    }
+}
@@ -20,9 +20,10 @@ use tokio::{
 };
 
 use crate::common::{
-    ChartDimension, GroupConfig, NetworkConfigWithOffset, NetworkProfile, TestCaseConfig,
+    ChartDimension, GroupConfig, MultiPartyClientConfig, MultiPartyTestCaseConfig,
+    NetworkConfigWithOffset, NetworkProfile, TestCaseConfig,
 };
-use crate::test::{AudioTestResults, GroupRun, Sound, TestCase};
+use crate::test::{AudioTestResults, GroupRun, MultiPartyRun, MultiPartyTestCase, Sound, TestCase};
 
 type ChartPoint = (f32, f32);
 
@@ -296,10 +297,10 @@ impl AnalysisReport {
 #[derive(Debug)]
 pub struct DockerStatsReport {
     timestamp: Vec<u64>,
-    cpu_usage: Stats,
-    mem_usage: Stats,
-    tx_bitrate: Stats,
-    rx_bitrate: Stats,
+    pub cpu_usage: Stats,
+    pub mem_usage: Stats,
+    pub tx_bitrate: Stats,
+    pub rx_bitrate: Stats,
     item_count: usize,
 }
 
@@ -1530,6 +1531,9 @@ impl Report {
                 line_chart_stats.push(stats);
             }
         }
+        if let Some(signal_quality) = &self.analysis_report.audio_test_results.signal_quality {
+            line_chart_stats.push(&signal_quality.segmental_snr);
+        }
 
         let mut set = JoinSet::new();
         for stats in line_chart_stats.into_iter() {
@@ -1595,6 +1599,9 @@ impl Report {
         if let AnalysisReportMos::Series(stats) = &self.analysis_report.audio_test_results.plc_mos {
             audio_core_stats.push(stats);
         }
+        if let Some(signal_quality) = &self.analysis_report.audio_test_results.signal_quality {
+            audio_core_stats.push(&signal_quality.segmental_snr);
+        }
 
         if !audio_core_stats.is_empty() {
             let audio_core_stats = Self::build_stats_rows(&html, &audio_core_stats);
@@ -2138,6 +2145,7 @@ impl Report {
         set_path: &str,
         time_started: &str,
         group_reports: &[GroupRun],
+        multi_party_runs: &[MultiPartyRun],
         sounds: &HashMap<String, Sound>,
     ) -> Result<()> {
         println!("\nCreating summary report for {}", set_name);
@@ -2264,6 +2272,20 @@ impl Report {
             );
         }
 
+        for (i, run) in multi_party_runs.iter().enumerate() {
+            buf.extend_from_slice(
+                html.accordion_section(
+                    &format!("multiParty_{}", i),
+                    vec![HtmlAccordionItem {
+                        label: format!("Multi-Party Group: {}", run.group_name),
+                        body: html.multi_party_summary_section(set_path, &run.reports),
+                        collapsed: false,
+                    }],
+                )
+                .as_bytes(),
+            );
+        }
+
         buf.extend_from_slice(
             html.accordion_section(
                 "mosReference",
@@ -2293,6 +2315,216 @@ impl Report {
     }
 }
 
+/// The report of one client in a multi-party test case.
+#[derive(Debug)]
+pub struct MultiPartyClientReport {
+    pub client_name: String,
+    pub docker_stats_report: DockerStatsReport,
+    pub client_log_report: ClientLogReport,
+    /// The demux IDs that the SFU assigned to the client, one for each time it joined.
+    pub demux_ids: Vec<u32>,
+}
+
+/// Averages of the streams that one or more clients received in a multi-party test case.
+/// Streams without data are left out, and so is a value if no stream had any data.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReceivedAverages {
+    pub audio_packet_loss: Option<f32>,
+    pub audio_jitter: Option<f32>,
+    pub audio_jitter_buffer_delay: Option<f32>,
+    pub audio_bitrate: Option<f32>,
+    pub video_packet_loss: Option<f32>,
+    pub video_bitrate: Option<f32>,
+    pub video_framerate: Option<f32>,
+}
+
+impl ReceivedAverages {
+    fn new<'a>(
+        audio: impl Iterator<Item = &'a AudioReceiveStats> + Clone,
+        video: impl Iterator<Item = &'a VideoReceiveStats> + Clone,
+    ) -> Self {
+        fn mean<'a, T: 'a>(
+            items: impl Iterator<Item = &'a T>,
+            stats: impl Fn(&'a T) -> &'a Stats,
+        ) -> Option<f32> {
+            let values = items
+                .map(stats)
+                // Without data in the filtered range, min is still above max.
+                .filter(|stats| stats.data.min <= stats.data.max)
+                .map(|stats| stats.data.ave)
+                .collect::<Vec<_>>();
+            (!values.is_empty()).then(|| average(&values))
+        }
+
+        Self {
+            audio_packet_loss: mean(audio.clone(), |stats| &stats.packet_loss_stats),
+            audio_jitter: mean(audio.clone(), |stats| &stats.jitter_stats),
+            audio_jitter_buffer_delay: mean(audio.clone(), |stats| {
+                &stats.jitter_buffer_delay_stats
+            }),
+            audio_bitrate: mean(audio, |stats| &stats.bitrate_stats),
+            video_packet_loss: mean(video.clone(), |stats| &stats.packet_loss_stats),
+            video_bitrate: mean(video.clone(), |stats| &stats.bitrate_stats),
+            video_framerate: mean(video, |stats| &stats.framerate_stats),
+        }
+    }
+}
+
+/// The report of a multi-party test case, with each client's view of the call.
+#[derive(Debug)]
+pub struct MultiPartyReport {
+    pub report_name: String,
+    pub test_path: String,
+    pub test_case_name: String,
+    pub clients: Vec<MultiPartyClientReport>,
+    /// Keep track of how many iterations were assigned for the test case.
+    pub iterations: u16,
+}
+
+impl MultiPartyReport {
+    pub async fn build(
+        test_case: &MultiPartyTestCase<'_>,
+        test_case_config: &MultiPartyTestCaseConfig,
+    ) -> Result<Self> {
+        let mut clients = Vec::with_capacity(test_case.clients.len());
+        for client in &test_case.clients {
+            let log_file = format!("{}/{}.log", test_case.test_path, client.name);
+            clients.push(MultiPartyClientReport {
+                client_name: client.name.clone(),
+                docker_stats_report: DockerStatsReport::build(
+                    &format!("{}/{}_stats.log", test_case.test_path, client.name),
+                    &client.name,
+                )
+                .await?,
+                client_log_report: ClientLogReport::build(&log_file, &client.name).await?,
+                demux_ids: Self::parse_demux_ids(&log_file).await?,
+            });
+        }
+
+        Ok(Self {
+            report_name: test_case.report_name.clone(),
+            test_path: test_case.test_path.clone(),
+            test_case_name: test_case.test_case_name.clone(),
+            clients,
+            iterations: test_case_config.iterations,
+        })
+    }
+
+    async fn parse_demux_ids(file_name: &str) -> Result<Vec<u32>> {
+        let file = File::open(file_name).await?;
+        let reader = BufReader::new(file);
+
+        // Example: New join state Joined(3252934736) for group call 1
+        let re_joined_line = Regex::new(r".*New join state Joined\((?P<demux_id>\d+)\)")?;
+
+        let mut demux_ids = vec![];
+        let mut lines = reader.lines();
+        while let Some(line) = lines.next_line().await? {
+            if let Some(cap) = re_joined_line.captures(&line) {
+                demux_ids.push(u32::from_str(&cap["demux_id"])?);
+            }
+        }
+
+        Ok(demux_ids)
+    }
+
+    /// Finds the client that sent a received stream. Demux IDs are multiples of 16 and the
+    /// SSRCs of a client's streams only differ from its demux ID in the lower 4 bits.
+    pub fn sender_name(&self, ssrc: &str) -> String {
+        let demux_id = u32::from_str(ssrc).ok().map(|ssrc| ssrc & !0xf);
+        self.clients
+            .iter()
+            .find(|client| demux_id.map_or(false, |id| client.demux_ids.contains(&id)))
+            .map_or_else(
+                || format!("unknown (ssrc={})", ssrc),
+                |client| client.client_name.clone(),
+            )
+    }
+
+    /// Averages of everything the client received.
+    pub fn received_by(client: &MultiPartyClientReport) -> ReceivedAverages {
+        let client_log_report = &client.client_log_report;
+        ReceivedAverages::new(
+            client_log_report.audio_receive_stats_list.iter(),
+            client_log_report.video_receive_stats_list.iter(),
+        )
+    }
+
+    /// Averages of what the client received from one sender.
+    pub fn received_from(
+        &self,
+        client: &MultiPartyClientReport,
+        sender_name: &str,
+    ) -> ReceivedAverages {
+        let client_log_report = &client.client_log_report;
+        ReceivedAverages::new(
+            client_log_report
+                .audio_receive_stats_list
+                .iter()
+                .filter(|stats| self.sender_name(&stats.ssrc) == sender_name),
+            client_log_report
+                .video_receive_stats_list
+                .iter()
+                .filter(|stats| self.sender_name(&stats.ssrc) == sender_name),
+        )
+    }
+
+    /// Averages of everything all clients received.
+    pub fn received_overall(&self) -> ReceivedAverages {
+        ReceivedAverages::new(
+            self.clients
+                .iter()
+                .flat_map(|client| &client.client_log_report.audio_receive_stats_list),
+            self.clients
+                .iter()
+                .flat_map(|client| &client.client_log_report.video_receive_stats_list),
+        )
+    }
+
+    pub async fn create_test_case_report(
+        &self,
+        set_name: &str,
+        test_case_config: &MultiPartyTestCaseConfig,
+    ) -> Result<()> {
+        let mut buf = vec![];
+        let html = Html::new();
+
+        buf.extend_from_slice(
+            html.header(&format!("{}/{} Report", set_name, self.report_name))
+                .as_bytes(),
+        );
+
+        buf.extend_from_slice(html.multi_party_heading(set_name, self).as_bytes());
+
+        let items = self
+            .clients
+            .iter()
+            .zip(&test_case_config.clients)
+            .map(|(client, client_config)| HtmlAccordionItem {
+                label: format!("Client: {}", client.client_name),
+                body: html.multi_party_client_section(self, client, client_config),
+                collapsed: false,
+            })
+            .collect();
+        buf.extend_from_slice(html.accordion_section("clients", items).as_bytes());
+
+        buf.extend_from_slice(html.footer().as_bytes());
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&format!("{}/report.html", self.test_path))
+            .await?;
+
+        if let Err(err) = file.write_all(buf.as_slice()).await {
+            println!("Error writing file! {err}");
+        }
+
+        Ok(())
+    }
+}
+
 /// A summary row can represent a single record, an aggregate item, or an aggregate average,
 /// calculated from 2 or more aggregate items.
 #[derive(Clone, Copy, Eq, PartialEq)]
@@ -2662,6 +2894,22 @@ impl Html {
             );
         }
 
+        if let Some(signal_quality) = &audio_test_results.signal_quality {
+            let _ = writeln!(
+                buf,
+                "<h3 class=\"text-right\">SNR: {:.1}dB Segmental SNR: {:.1}dB</h3>",
+                signal_quality.snr, signal_quality.segmental_snr.data.ave,
+            );
+            let _ = writeln!(
+                buf,
+                "<h3 class=\"text-right\">Glitches: {} ({:.1}/min) Silence: {:.1}% Delay: {:.0}ms</h3>",
+                signal_quality.glitches,
+                signal_quality.glitches_per_minute,
+                signal_quality.silence_ratio * 100.0,
+                signal_quality.delay_ms,
+            );
+        }
+
         buf.push_str("</div>\n");
         buf.push_str("</div>\n");
 
@@ -3247,6 +3495,216 @@ impl Html {
 
         buf
     }
+
+    pub fn multi_party_heading(&self, set_name: &str, report: &MultiPartyReport) -> String {
+        let mut buf = String::new();
+
+        let overall = report.received_overall();
+
+        buf.push_str("<div class=\"p-3 row\">\n");
+        buf.push_str("<div class=\"col-md-6\">\n");
+        let _ = writeln!(buf, "<h2>{}/{}</h2>", set_name, report.report_name);
+        let _ = writeln!(
+            buf,
+            "<h3 class=\"text-muted\">{} clients</h3>",
+            report.clients.len()
+        );
+        buf.push_str("</div>\n");
+        buf.push_str("<div class=\"col-md-6\">\n");
+        buf.push_str("<h3>Received by all clients (average)</h3>\n");
+        let _ = writeln!(
+            buf,
+            "<p>Audio: {}% loss, {} ms jitter, {} Kbps<br>Video: {}% loss, {} fps, {} Kbps</p>",
+            format_average(overall.audio_packet_loss),
+            format_average(overall.audio_jitter),
+            format_average(overall.audio_bitrate),
+            format_average(overall.video_packet_loss),
+            format_average(overall.video_framerate),
+            format_average(overall.video_bitrate),
+        );
+        buf.push_str("</div>\n");
+        buf.push_str("</div>\n");
+
+        buf
+    }
+
+    /// Shows what a client received from each of the others, along with its configuration.
+    pub fn multi_party_client_section(
+        &self,
+        report: &MultiPartyReport,
+        client: &MultiPartyClientReport,
+        client_config: &MultiPartyClientConfig,
+    ) -> String {
+        let mut buf = String::new();
+
+        buf.push_str("<div class=\"p-3 row\">\n");
+        buf.push_str("<div class=\"col-md-12\">\n");
+
+        buf.push_str("<table class=\"table table-bordered\">\n");
+        buf.push_str("<thead>\n");
+        buf.push_str("<tr>\n");
+        buf.push_str("<th rowspan=\"2\">Received From</th>\n");
+        buf.push_str("<th colspan=\"4\">Audio (average)</th>\n");
+        buf.push_str("<th colspan=\"3\">Video (average)</th>\n");
+        buf.push_str("</tr>\n");
+        buf.push_str("<tr>\n");
+        buf.push_str("<th>Loss %</th><th>Jitter ms</th><th>Jitter Buffer ms</th><th>Kbps</th>\n");
+        buf.push_str("<th>Loss %</th><th>fps</th><th>Kbps</th>\n");
+        buf.push_str("</tr>\n");
+        buf.push_str("</thead>\n");
+        buf.push_str("<tbody>\n");
+
+        let client_log_report = &client.client_log_report;
+        let sender_names = client_log_report
+            .audio_receive_stats_list
+            .iter()
+            .map(|stats| &stats.ssrc)
+            .chain(
+                client_log_report
+                    .video_receive_stats_list
+                    .iter()
+                    .map(|stats| &stats.ssrc),
+            )
+            .map(|ssrc| report.sender_name(ssrc))
+            .sorted()
+            .dedup();
+
+        let rows = sender_names
+            .map(|sender_name| {
+                let received = report.received_from(client, &sender_name);
+                (sender_name, received, "")
+            })
+            .chain([(
+                "All".to_string(),
+                MultiPartyReport::received_by(client),
+                "table-secondary",
+            )]);
+
+        for (sender_name, received, class) in rows {
+            let _ = writeln!(
+                buf,
+                "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                class,
+                sender_name,
+                format_average(received.audio_packet_loss),
+                format_average(received.audio_jitter),
+                format_average(received.audio_jitter_buffer_delay),
+                format_average(received.audio_bitrate),
+                format_average(received.video_packet_loss),
+                format_average(received.video_framerate),
+                format_average(received.video_bitrate),
+            );
+        }
+
+        buf.push_str("</tbody>\n");
+        buf.push_str("</table>\n");
+
+        let docker_stats = &client.docker_stats_report;
+        let _ = writeln!(
+            buf,
+            "<p>Container (average): {:.2}% CPU, {:.2} MiB, {:.2} Kbps sent, {:.2} Kbps received</p>",
+            docker_stats.cpu_usage.data.ave,
+            docker_stats.mem_usage.data.ave,
+            docker_stats.tx_bitrate.data.ave,
+            docker_stats.rx_bitrate.data.ave,
+        );
+        let _ = writeln!(buf, "<p>Demux IDs: {:?}</p>", client.demux_ids);
+
+        buf.push_str("<h4>Configuration</h4>\n");
+        let _ = writeln!(buf, "<p><code><pre>\n{:#?}</pre></code></p>", client_config);
+
+        buf.push_str("</div>\n");
+        buf.push_str("</div>\n");
+
+        buf
+    }
+
+    /// Shows one row per multi-party test case run, linked to its report.
+    pub fn multi_party_summary_section(
+        &self,
+        set_path: &str,
+        reports: &[Result<MultiPartyReport>],
+    ) -> String {
+        let mut buf = String::new();
+
+        buf.push_str("<div class=\"p-3 row\">\n");
+        buf.push_str("<div class=\"col-md-12\">\n");
+
+        buf.push_str("<table class=\"table table-hover table-bordered\">\n");
+        buf.push_str("<thead>\n");
+        buf.push_str("<tr>\n");
+        buf.push_str("<th rowspan=\"2\">Test Case</th>\n");
+        buf.push_str("<th rowspan=\"2\">Clients</th>\n");
+        buf.push_str("<th colspan=\"3\">Audio Received (average)</th>\n");
+        buf.push_str("<th colspan=\"2\">Video Received (average)</th>\n");
+        buf.push_str("<th colspan=\"2\">Container Stats (average)</th>\n");
+        buf.push_str("</tr>\n");
+        buf.push_str("<tr>\n");
+        buf.push_str("<th>Loss %</th><th>Jitter ms</th><th>Kbps</th>\n");
+        buf.push_str("<th>fps</th><th>Kbps</th>\n");
+        buf.push_str("<th>CPU %</th><th>Memory MiB</th>\n");
+        buf.push_str("</tr>\n");
+        buf.push_str("</thead>\n");
+        buf.push_str("<tbody>\n");
+
+        for report in reports {
+            match report {
+                Ok(report) => {
+                    let link = report
+                        .test_path
+                        .strip_prefix(set_path)
+                        .unwrap_or(&report.test_path)
+                        .trim_start_matches('/');
+                    let overall = report.received_overall();
+                    let cpu_usage = report
+                        .clients
+                        .iter()
+                        .map(|client| client.docker_stats_report.cpu_usage.data.ave)
+                        .collect::<Vec<_>>();
+                    let mem_usage = report
+                        .clients
+                        .iter()
+                        .map(|client| client.docker_stats_report.mem_usage.data.ave)
+                        .collect::<Vec<_>>();
+
+                    let _ = writeln!(
+                        buf,
+                        r#"<tr class="clickable" onclick="window.location='{}/report.html'">"#,
+                        link
+                    );
+                    let _ = writeln!(
+                        buf,
+                        "<td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.2}</td>",
+                        link,
+                        report.clients.len(),
+                        format_average(overall.audio_packet_loss),
+                        format_average(overall.audio_jitter),
+                        format_average(overall.audio_bitrate),
+                        format_average(overall.video_framerate),
+                        format_average(overall.video_bitrate),
+                        average(&cpu_usage),
+                        average(&mem_usage),
+                    );
+                    buf.push_str("</tr>\n");
+                }
+                Err(err) => {
+                    let _ = writeln!(
+                        buf,
+                        "<tr class=\"table-danger\"><td colspan=\"9\">Failed: {}</td></tr>",
+                        err
+                    );
+                }
+            }
+        }
+
+        buf.push_str("</tbody>\n");
+        buf.push_str("</table>\n");
+
+        buf.push_str("</div>\n");
+        buf.push_str("</div>\n");
+
+        buf
+    }
 }
 
 fn average(values: &[f32]) -> f32 {
@@ -3256,3 +3714,7 @@ fn average(values: &[f32]) -> f32 {
     let sum: f32 = values.iter().sum();
     sum / values.len() as f32
 }
+
+fn format_average(value: Option<f32>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{:.2}", value))
+}
@@ -0,0 +1,883 @@
+//
+// Copyright 2026 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Machine-readable test results. Alongside the html reports, every test case writes a
+//! `results.json` and every test set writes one for all of its groups. Two test sets can
+//! then be compared to catch regressions automatically.
+
+use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, fs, path::Path, str::FromStr};
+
+use crate::report::{AnalysisReportMos, MultiPartyReport, ReceivedAverages, Report, Stats};
+use crate::test::{GroupRun, MultiPartyRun};
+
+/// The file name used for results, both for test cases and test sets.
+const RESULTS_FILE_NAME: &str = "results.json";
+
+/// Aggregates of a [Stats] collection, over its filtered range.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StatsResults {
+    pub min: f32,
+    pub max: f32,
+    pub ave: f32,
+    /// The number of data points, including those outside the filtered range.
+    pub count: usize,
+}
+
+impl StatsResults {
+    /// Returns None if no data fell within the filtered range.
+    fn from_stats(stats: &Stats) -> Option<Self> {
+        let data = &stats.data;
+        (data.min <= data.max && data.ave.is_finite()).then(|| Self {
+            min: data.min,
+            max: data.max,
+            ave: data.ave,
+            count: data.points.len(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MosResults {
+    /// The single MOS value, or the average of a series.
+    pub mos: f32,
+    /// Set for MOS values that were calculated over time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series: Option<StatsResults>,
+}
+
+impl MosResults {
+    fn from_analysis(mos: &AnalysisReportMos) -> Option<Self> {
+        match mos {
+            AnalysisReportMos::None => None,
+            AnalysisReportMos::Single(mos) => Some(Self {
+                mos: *mos,
+                series: None,
+            }),
+            AnalysisReportMos::Series(stats) => Some(Self {
+                mos: stats.data.ave,
+                series: StatsResults::from_stats(stats),
+            }),
+        }
+        .filter(|results| results.mos.is_finite())
+    }
+}
+
+/// The results of one iteration of a test case, from client_b's perspective.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TestCaseResults {
+    pub report_name: String,
+    pub test_case_name: String,
+    pub network_profile: String,
+    pub sound_name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub video_name: String,
+    pub client_name: String,
+    /// MOS values by analysis, i.e. `visqol_mos_speech`. Missing analyses are left out.
+    pub mos: BTreeMap<String, MosResults>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vmaf: Option<f32>,
+    /// Results of the built-in signal analysis, i.e. `segmental_snr` or `glitches_per_minute`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub audio_quality: BTreeMap<String, f32>,
+    /// Aggregates of all collected stats, keyed by source and name, i.e. `container.cpu_usage`
+    /// or `audio_receive[0].jitter`.
+    pub stats: BTreeMap<String, StatsResults>,
+    /// Averages over several streams, i.e. `received.audio_packet_loss` for everything the
+    /// clients of a multi-party test case received.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub averages: BTreeMap<String, f32>,
+}
+
+impl TestCaseResults {
+    pub fn from_report(report: &Report) -> Self {
+        let audio = &report.analysis_report.audio_test_results;
+        let mos = [
+            ("visqol_mos_speech", &audio.visqol_mos_speech),
+            ("visqol_mos_audio", &audio.visqol_mos_audio),
+            ("visqol_mos_average", &audio.visqol_mos_average),
+            ("pesq_mos", &audio.pesq_mos),
+            ("plc_mos", &audio.plc_mos),
+        ]
+        .into_iter()
+        .filter_map(|(name, mos)| Some((name.to_string(), MosResults::from_analysis(mos)?)))
+        .collect();
+
+        let audio_quality = audio
+            .signal_quality
+            .iter()
+            .flat_map(|quality| {
+                [
+                    ("snr", quality.snr),
+                    ("segmental_snr", quality.segmental_snr.data.ave),
+                    ("glitches_per_minute", quality.glitches_per_minute),
+                    ("silence_ratio", quality.silence_ratio),
+                    ("delay_ms", quality.delay_ms),
+                ]
+            })
+            .filter(|(_, value)| value.is_finite())
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+
+        let mut stats = BTreeMap::new();
+        let mut add_stats = |prefix: &str, items: &[(&str, &Stats)]| {
+            for (name, item) in items {
+                if let Some(results) = StatsResults::from_stats(item) {
+                    stats.insert(format!("{prefix}.{name}"), results);
+                }
+            }
+        };
+
+        let docker_stats = &report.docker_stats_report;
+        add_stats(
+            "container",
+            &[
+                ("cpu_usage", &docker_stats.cpu_usage),
+                ("mem_usage", &docker_stats.mem_usage),
+                ("tx_bitrate", &docker_stats.tx_bitrate),
+                ("rx_bitrate", &docker_stats.rx_bitrate),
+            ],
+        );
+
+        let client_log = &report.client_log_report;
+        let connection = &client_log.connection_stats;
+        add_stats(
+            "connection",
+            &[
+                (
+                    "current_round_trip_time",
+                    &connection.current_round_trip_time_stats,
+                ),
+                (
+                    "available_outgoing_bitrate",
+                    &connection.available_outgoing_bitrate_stats,
+                ),
+            ],
+        );
+
+        let audio_send = &client_log.audio_send_stats;
+        add_stats(
+            "audio_send",
+            &[
+                ("packets_per_second", &audio_send.packets_per_second_stats),
+                ("average_packet_size", &audio_send.average_packet_size_stats),
+                ("bitrate", &audio_send.bitrate_stats),
+                ("remote_packet_loss", &audio_send.remote_packet_loss_stats),
+                ("remote_jitter", &audio_send.remote_jitter_stats),
+                (
+                    "remote_round_trip_time",
+                    &audio_send.remote_round_trip_time_stats,
+                ),
+                ("audio_energy", &audio_send.audio_energy_stats),
+            ],
+        );
+
+        for (i, audio_receive) in client_log.audio_receive_stats_list.iter().enumerate() {
+            add_stats(
+                &format!("audio_receive[{i}]"),
+                &[
+                    (
+                        "packets_per_second",
+                        &audio_receive.packets_per_second_stats,
+                    ),
+                    ("packet_loss", &audio_receive.packet_loss_stats),
+                    ("bitrate", &audio_receive.bitrate_stats),
+                    ("jitter", &audio_receive.jitter_stats),
+                    ("audio_energy", &audio_receive.audio_energy_stats),
+                    (
+                        "jitter_buffer_delay",
+                        &audio_receive.jitter_buffer_delay_stats,
+                    ),
+                ],
+            );
+        }
+
+        let audio_adaptation = &client_log.audio_adaptation;
+        add_stats(
+            "audio_adaptation",
+            &[
+                ("bitrate", &audio_adaptation.bitrate_stats),
+                ("packet_length", &audio_adaptation.packet_length_stats),
+            ],
+        );
+
+        if report.show_video {
+            for (i, video_send) in client_log.video_send_stats.iter().enumerate() {
+                add_stats(
+                    &format!("video_send[{i}]"),
+                    &[
+                        ("packets_per_second", &video_send.packets_per_second_stats),
+                        ("average_packet_size", &video_send.average_packet_size_stats),
+                        ("bitrate", &video_send.bitrate_stats),
+                        ("framerate", &video_send.framerate_stats),
+                        ("key_frames_encoded", &video_send.key_frames_encoded_stats),
+                        (
+                            "retransmitted_packets_sent",
+                            &video_send.retransmitted_packets_sent_stats,
+                        ),
+                        (
+                            "retransmitted_bitrate",
+                            &video_send.retransmitted_bitrate_stats,
+                        ),
+                        (
+                            "send_delay_per_packet",
+                            &video_send.send_delay_per_packet_stats,
+                        ),
+                        ("nack_count", &video_send.nack_count_stats),
+                        ("pli_count", &video_send.pli_count_stats),
+                        ("remote_packet_loss", &video_send.remote_packet_loss_stats),
+                        ("remote_jitter", &video_send.remote_jitter_stats),
+                        (
+                            "remote_round_trip_time",
+                            &video_send.remote_round_trip_time_stats,
+                        ),
+                    ],
+                );
+            }
+
+            for (i, video_receive) in client_log.video_receive_stats_list.iter().enumerate() {
+                add_stats(
+                    &format!("video_receive[{i}]"),
+                    &[
+                        (
+                            "packets_per_second",
+                            &video_receive.packets_per_second_stats,
+                        ),
+                        ("packet_loss", &video_receive.packet_loss_stats),
+                        ("bitrate", &video_receive.bitrate_stats),
+                        ("framerate", &video_receive.framerate_stats),
+                        (
+                            "key_frames_decoded",
+                            &video_receive.key_frames_decoded_stats,
+                        ),
+                    ],
+                );
+            }
+        }
+
+        Self {
+            report_name: report.report_name.clone(),
+            test_case_name: report.test_case_name.clone(),
+            network_profile: report.network_profile.get_name(),
+            sound_name: report.sound_name.clone(),
+            video_name: report.video_name.clone(),
+            client_name: report.client_name.clone(),
+            mos,
+            vmaf: report.analysis_report.vmaf.filter(|vmaf| vmaf.is_finite()),
+            audio_quality,
+            stats,
+            averages: BTreeMap::new(),
+        }
+    }
+
+    /// Multi-party test cases have no audio analysis, so the results are the stats of every
+    /// client, keyed by client name first, i.e. `client_2.container.cpu_usage`. Received
+    /// streams are keyed by their sender, i.e. `client_2.audio_receive.client_1.jitter`.
+    pub fn from_multi_party_report(report: &MultiPartyReport) -> Self {
+        let mut stats = BTreeMap::new();
+        let mut add_stats = |prefix: &str, items: &[(&str, &Stats)]| {
+            for (name, item) in items {
+                if let Some(results) = StatsResults::from_stats(item) {
+                    stats.insert(format!("{prefix}.{name}"), results);
+                }
+            }
+        };
+
+        for client in &report.clients {
+            let client_name = &client.client_name;
+
+            let docker_stats = &client.docker_stats_report;
+            add_stats(
+                &format!("{client_name}.container"),
+                &[
+                    ("cpu_usage", &docker_stats.cpu_usage),
+                    ("mem_usage", &docker_stats.mem_usage),
+                    ("tx_bitrate", &docker_stats.tx_bitrate),
+                    ("rx_bitrate", &docker_stats.rx_bitrate),
+                ],
+            );
+
+            let client_log = &client.client_log_report;
+            add_stats(
+                &format!("{client_name}.connection"),
+                &[(
+                    "current_round_trip_time",
+                    &client_log.connection_stats.current_round_trip_time_stats,
+                )],
+            );
+
+            // A sender that rejoined the call has several streams, so number the repeats.
+            let mut sender_keys = BTreeMap::<String, usize>::new();
+            let mut sender_key = |kind: &str, ssrc: &str| {
+                let sender_name = report.sender_name(ssrc);
+                let count = sender_keys
+                    .entry(format!("{kind}.{sender_name}"))
+                    .or_default();
+                *count += 1;
+                if *count == 1 {
+                    sender_name
+                } else {
+                    format!("{sender_name}[{}]", *count - 1)
+                }
+            };
+
+            for audio_receive in &client_log.audio_receive_stats_list {
+                let sender = sender_key("audio", &audio_receive.ssrc);
+                add_stats(
+                    &format!("{client_name}.audio_receive.{sender}"),
+                    &[
+                        ("packet_loss", &audio_receive.packet_loss_stats),
+                        ("bitrate", &audio_receive.bitrate_stats),
+                        ("jitter", &audio_receive.jitter_stats),
+                        (
+                            "jitter_buffer_delay",
+                            &audio_receive.jitter_buffer_delay_stats,
+                        ),
+                    ],
+                );
+            }
+
+            for video_receive in &client_log.video_receive_stats_list {
+                let sender = sender_key("video", &video_receive.ssrc);
+                add_stats(
+                    &format!("{client_name}.video_receive.{sender}"),
+                    &[
+                        ("packet_loss", &video_receive.packet_loss_stats),
+                        ("bitrate", &video_receive.bitrate_stats),
+                        ("framerate", &video_receive.framerate_stats),
+                    ],
+                );
+            }
+        }
+
+        let ReceivedAverages {
+            audio_packet_loss,
+            audio_jitter,
+            audio_jitter_buffer_delay,
+            audio_bitrate,
+            video_packet_loss,
+            video_bitrate,
+            video_framerate,
+        } = report.received_overall();
+        let averages = [
+            ("audio_packet_loss", audio_packet_loss),
+            ("audio_jitter", audio_jitter),
+            ("audio_jitter_buffer_delay", audio_jitter_buffer_delay),
+            ("audio_bitrate", audio_bitrate),
+            ("video_packet_loss", video_packet_loss),
+            ("video_bitrate", video_bitrate),
+            ("video_framerate", video_framerate),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((format!("received.{name}"), value?)))
+        .filter(|(_, value)| value.is_finite())
+        .collect();
+
+        Self {
+            report_name: report.report_name.clone(),
+            test_case_name: report.test_case_name.clone(),
+            // Every client has its own network profile and media.
+            network_profile: "per-client".to_string(),
+            sound_name: String::new(),
+            video_name: String::new(),
+            client_name: "all".to_string(),
+            mos: BTreeMap::new(),
+            vmaf: None,
+            audio_quality: BTreeMap::new(),
+            stats,
+            averages,
+        }
+    }
+
+    /// Flattens the results into named values that can be compared, i.e.
+    /// `mos.visqol_mos_speech` or `container.cpu_usage.ave`.
+    fn metrics(&self) -> impl Iterator<Item = (String, f32)> + '_ {
+        let mos = self
+            .mos
+            .iter()
+            .map(|(name, results)| (format!("mos.{name}"), results.mos));
+        let vmaf = self.vmaf.map(|vmaf| ("vmaf".to_string(), vmaf));
+        let audio_quality = self
+            .audio_quality
+            .iter()
+            .map(|(name, value)| (format!("audio_quality.{name}"), *value));
+        let stats = self.stats.iter().flat_map(|(name, results)| {
+            [
+                (format!("{name}.min"), results.min),
+                (format!("{name}.max"), results.max),
+                (format!("{name}.ave"), results.ave),
+            ]
+        });
+        let averages = self
+            .averages
+            .iter()
+            .map(|(name, value)| (name.clone(), *value));
+        mos.chain(vmaf)
+            .chain(audio_quality)
+            .chain(stats)
+            .chain(averages)
+    }
+
+    /// Writes the results to `results.json` in the test case's directory.
+    pub fn write(&self, test_path: &str) -> Result<()> {
+        write_json(&Path::new(test_path).join(RESULTS_FILE_NAME), self)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupResults {
+    pub group_name: String,
+    /// The test cases that ran successfully, with one entry per iteration.
+    pub test_cases: Vec<TestCaseResults>,
+    /// The number of test cases that failed and have no results.
+    pub failed: usize,
+}
+
+/// The results of all groups in a test set.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetResults {
+    pub set_name: String,
+    pub time_started: String,
+    pub groups: Vec<GroupResults>,
+}
+
+impl SetResults {
+    pub fn new(
+        set_name: &str,
+        time_started: &str,
+        group_runs: &[GroupRun],
+        multi_party_runs: &[MultiPartyRun],
+    ) -> Self {
+        let groups = group_runs
+            .iter()
+            .map(|group_run| GroupResults {
+                group_name: group_run.group_config.group_name.clone(),
+                test_cases: group_run
+                    .reports
+                    .iter()
+                    .flatten()
+                    .map(TestCaseResults::from_report)
+                    .collect(),
+                failed: group_run
+                    .reports
+                    .iter()
+                    .filter(|report| report.is_err())
+                    .count(),
+            })
+            .chain(multi_party_runs.iter().map(|run| {
+                GroupResults {
+                    group_name: run.group_name.clone(),
+                    test_cases: run
+                        .reports
+                        .iter()
+                        .flatten()
+                        .map(TestCaseResults::from_multi_party_report)
+                        .collect(),
+                    failed: run.reports.iter().filter(|report| report.is_err()).count(),
+                }
+            }))
+            .collect();
+
+        Self {
+            set_name: set_name.to_string(),
+            time_started: time_started.to_string(),
+            groups,
+        }
+    }
+
+    /// Writes the results to `results.json` in the test set's directory.
+    pub fn write(&self, set_path: &str) -> Result<()> {
+        write_json(&Path::new(set_path).join(RESULTS_FILE_NAME), self)
+    }
+
+    /// Reads results from a `results.json` file or from a test set's directory.
+    pub fn read(path: &Path) -> Result<Self> {
+        let path = if path.is_dir() {
+            path.join(RESULTS_FILE_NAME)
+        } else {
+            path.to_path_buf()
+        };
+        let file = fs::File::open(&path)
+            .with_context(|| format!("Failed to open results `{}`", path.display()))?;
+        serde_json::from_reader(file)
+            .with_context(|| format!("Failed to parse results `{}`", path.display()))
+    }
+
+    /// Returns the average of each metric over all iterations, keyed by group and report name.
+    fn averaged_metrics(&self) -> BTreeMap<(String, String), BTreeMap<String, f32>> {
+        let mut metrics = BTreeMap::new();
+        for group in &self.groups {
+            for (report_name, iterations) in &group
+                .test_cases
+                .iter()
+                .into_group_map_by(|test_case| test_case.report_name.clone())
+            {
+                let values = iterations
+                    .iter()
+                    .flat_map(|test_case| test_case.metrics())
+                    .into_group_map();
+                metrics.insert(
+                    (group.group_name.clone(), report_name.clone()),
+                    values
+                        .into_iter()
+                        .map(|(name, values)| {
+                            let average = values.iter().sum::<f32>() / values.len() as f32;
+                            (name, average)
+                        })
+                        .collect(),
+                );
+            }
+        }
+        metrics
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let file =
+        fs::File::create(path).with_context(|| format!("Failed to create `{}`", path.display()))?;
+    serde_json::to_writer_pretty(file, value)
+        .with_context(|| format!("Failed to write `{}`", path.display()))
+}
+
+/// The allowed change of a metric before it is considered a regression, parsed from
+/// `<metric>=<limit>`. The metric may end with `*` to match all metrics with that prefix. The
+/// limit is signed to give the direction of a regression and can be relative, so
+/// `mos.*=-0.1` allows MOS values to drop by 0.1 and `container.cpu_usage.ave=+25%` allows
+/// the average CPU usage to rise by 25%. If several thresholds match a metric, the last one
+/// applies.
+#[derive(Clone, Debug)]
+pub struct Threshold {
+    metric: String,
+    /// Whether decreases rather than increases are regressions.
+    decreasing: bool,
+    /// The allowed change, as an absolute value or a percentage of the baseline.
+    limit: f32,
+    relative: bool,
+}
+
+impl Threshold {
+    /// Used when no thresholds are given.
+    const DEFAULTS: &'static [&'static str] = &[
+        "mos.*=-0.1",
+        "vmaf=-2",
+        "audio_quality.segmental_snr=-2",
+        "container.cpu_usage.ave=+25%",
+        "container.mem_usage.ave=+25%",
+    ];
+
+    fn matches(&self, metric: &str) -> bool {
+        match self.metric.strip_suffix('*') {
+            Some(prefix) => metric.starts_with(prefix),
+            None => metric == self.metric,
+        }
+    }
+
+    /// Returns true if going from `baseline` to `current` exceeds the limit.
+    fn is_regression(&self, baseline: f32, current: f32) -> bool {
+        let change = if self.relative {
+            if baseline == 0.0 {
+                // Any change from zero is unlimited.
+                if current == 0.0 {
+                    0.0
+                } else {
+                    f32::INFINITY.copysign(current)
+                }
+            } else {
+                (current - baseline) / baseline.abs() * 100.0
+            }
+        } else {
+            current - baseline
+        };
+
+        if self.decreasing {
+            change < -self.limit
+        } else {
+            change > self.limit
+        }
+    }
+}
+
+impl FromStr for Threshold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (metric, limit) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected <metric>=<limit>, got `{s}`"))?;
+        if metric.is_empty() {
+            return Err(format!("missing metric in `{s}`"));
+        }
+        let (decreasing, limit) = if let Some(limit) = limit.strip_prefix('-') {
+            (true, limit)
+        } else if let Some(limit) = limit.strip_prefix('+') {
+            (false, limit)
+        } else {
+            return Err(format!(
+                "the limit in `{s}` needs a sign, `+` if increases are regressions or `-` if decreases are"
+            ));
+        };
+        let (limit, relative) = match limit.strip_suffix('%') {
+            Some(limit) => (limit, true),
+            None => (limit, false),
+        };
+        let limit = f32::from_str(limit)
+            .ok()
+            .filter(|limit| *limit >= 0.0)
+            .ok_or_else(|| format!("invalid limit in `{s}`"))?;
+
+        Ok(Self {
+            metric: metric.to_string(),
+            decreasing,
+            limit,
+            relative,
+        })
+    }
+}
+
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}={}{}{}",
+            self.metric,
+            if self.decreasing { "-" } else { "+" },
+            self.limit,
+            if self.relative { "%" } else { "" }
+        )
+    }
+}
+
+/// Compares the `current` results against the `baseline` for each test case, printing the
+/// metrics that have thresholds. Returns the number of regressions found, which includes
+/// test cases of the baseline that are missing or failed in the current results.
+pub fn compare(baseline: &SetResults, current: &SetResults, thresholds: &[Threshold]) -> usize {
+    println!(
+        "Comparing {} ({}) against baseline {} ({})",
+        current.set_name, current.time_started, baseline.set_name, baseline.time_started
+    );
+    println!(
+        "  Using thresholds: {}",
+        thresholds.iter().map(ToString::to_string).join(", ")
+    );
+
+    let baseline_metrics = baseline.averaged_metrics();
+    let current_metrics = current.averaged_metrics();
+    let mut regressions = 0;
+
+    for ((group_name, report_name), baseline_values) in &baseline_metrics {
+        println!("\n{group_name}/{report_name}");
+
+        let Some(current_values) = current_metrics.get(&(group_name.clone(), report_name.clone()))
+        else {
+            println!("  REGRESSION: missing from the current results");
+            regressions += 1;
+            continue;
+        };
+
+        for (metric, baseline_value) in baseline_values {
+            let Some(threshold) = thresholds.iter().rev().find(|t| t.matches(metric)) else {
+                continue;
+            };
+            let Some(current_value) = current_values.get(metric) else {
+                println!("  REGRESSION: {metric} is missing from the current results");
+                regressions += 1;
+                continue;
+            };
+
+            let is_regression = threshold.is_regression(*baseline_value, *current_value);
+            println!(
+                "  {:<12}{:<40}{:>12.3}{:>12.3}{:>+12.3}",
+                if is_regression { "REGRESSION:" } else { "" },
+                metric,
+                baseline_value,
+                current_value,
+                current_value - baseline_value,
+            );
+            if is_regression {
+                regressions += 1;
+            }
+        }
+    }
+
+    for (group_name, report_name) in current_metrics.keys() {
+        if !baseline_metrics.contains_key(&(group_name.clone(), report_name.clone())) {
+            println!("\n{group_name}/{report_name}\n  Not in the baseline, skipped");
+        }
+    }
+
+    let failed = current
+        .groups
+        .iter()
+        .map(|group| group.failed)
+        .sum::<usize>();
+    if failed > 0 {
+        println!("\n{failed} test case(s) failed in the current results");
+    }
+
+    println!("\nFound {regressions} regression(s)");
+    regressions
+}
+
+/// Parses thresholds given on the command line, falling back to [Threshold::DEFAULTS].
+pub fn thresholds_or_defaults(thresholds: Vec<Threshold>) -> Result<Vec<Threshold>> {
+    if !thresholds.is_empty() {
+        return Ok(thresholds);
+    }
+    Threshold::DEFAULTS
+        .iter()
+        .map(|threshold| Threshold::from_str(threshold).map_err(|e| anyhow!(e)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threshold(s: &str) -> Threshold {
+        Threshold::from_str(s).expect("threshold should parse")
+    }
+
+    fn set_results(mos: f32, cpu_usage: f32) -> SetResults {
+        SetResults {
+            set_name: "set".to_string(),
+            time_started: "2024-01-01".to_string(),
+            groups: vec![GroupResults {
+                group_name: "group".to_string(),
+                test_cases: vec![TestCaseResults {
+                    report_name: "report".to_string(),
+                    test_case_name: "test_case".to_string(),
+                    network_profile: "none".to_string(),
+                    sound_name: "normal_phrasing".to_string(),
+                    video_name: String::new(),
+                    client_name: "client_b".to_string(),
+                    mos: BTreeMap::from([(
+                        "visqol_mos_speech".to_string(),
+                        MosResults { mos, series: None },
+                    )]),
+                    vmaf: None,
+                    audio_quality: BTreeMap::new(),
+                    stats: BTreeMap::from([(
+                        "container.cpu_usage".to_string(),
+                        StatsResults {
+                            min: cpu_usage,
+                            max: cpu_usage,
+                            ave: cpu_usage,
+                            count: 1,
+                        },
+                    )]),
+                    averages: BTreeMap::new(),
+                }],
+                failed: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn threshold_from_str() {
+        let t = threshold("mos.*=-0.1");
+        assert_eq!(t.metric, "mos.*");
+        assert!(t.decreasing);
+        assert_eq!(t.limit, 0.1);
+        assert!(!t.relative);
+
+        let t = threshold("container.cpu_usage.ave=+25%");
+        assert_eq!(t.metric, "container.cpu_usage.ave");
+        assert!(!t.decreasing);
+        assert_eq!(t.limit, 25.0);
+        assert!(t.relative);
+        assert_eq!(t.to_string(), "container.cpu_usage.ave=+25%");
+
+        assert!(Threshold::from_str("vmaf").is_err());
+        assert!(Threshold::from_str("=-2").is_err());
+        assert!(Threshold::from_str("vmaf=2").is_err());
+        assert!(Threshold::from_str("vmaf=-x").is_err());
+        assert!(Threshold::from_str("vmaf=--2").is_err());
+    }
+
+    #[test]
+    fn threshold_defaults_parse() {
+        assert_eq!(
+            thresholds_or_defaults(vec![]).unwrap().len(),
+            Threshold::DEFAULTS.len()
+        );
+    }
+
+    #[test]
+    fn threshold_matches() {
+        let t = threshold("mos.*=-0.1");
+        assert!(t.matches("mos.visqol_mos_speech"));
+        assert!(!t.matches("vmaf"));
+
+        let t = threshold("vmaf=-2");
+        assert!(t.matches("vmaf"));
+        assert!(!t.matches("vmaf_neg"));
+    }
+
+    #[test]
+    fn threshold_is_regression() {
+        let t = threshold("mos.*=-0.1");
+        assert!(!t.is_regression(4.0, 3.95));
+        assert!(!t.is_regression(4.0, 4.5));
+        assert!(t.is_regression(4.0, 3.8));
+
+        let t = threshold("container.cpu_usage.ave=+25%");
+        assert!(!t.is_regression(40.0, 49.0));
+        assert!(!t.is_regression(40.0, 10.0));
+        assert!(t.is_regression(40.0, 51.0));
+        // Relative to the magnitude, so the direction holds for negative baselines.
+        assert!(t.is_regression(-40.0, -29.0));
+        assert!(!t.is_regression(-40.0, -51.0));
+    }
+
+    #[test]
+    fn threshold_is_regression_relative_from_zero() {
+        let increasing = threshold("container.cpu_usage.ave=+25%");
+        assert!(!increasing.is_regression(0.0, 0.0));
+        assert!(increasing.is_regression(0.0, 0.1));
+        assert!(!increasing.is_regression(0.0, -0.1));
+
+        let decreasing = threshold("audio_quality.snr=-10%");
+        assert!(!decreasing.is_regression(0.0, 0.0));
+        assert!(!decreasing.is_regression(0.0, 0.1));
+        assert!(decreasing.is_regression(0.0, -0.1));
+    }
+
+    #[test]
+    fn compare_counts_regressions() {
+        let thresholds = [
+            threshold("mos.*=-0.1"),
+            threshold("container.cpu_usage.ave=+25%"),
+        ];
+        let baseline = set_results(4.0, 40.0);
+
+        assert_eq!(compare(&baseline, &set_results(4.0, 40.0), &thresholds), 0);
+        assert_eq!(compare(&baseline, &set_results(3.5, 40.0), &thresholds), 1);
+        assert_eq!(compare(&baseline, &set_results(3.5, 60.0), &thresholds), 2);
+
+        let mut missing = set_results(4.0, 40.0);
+        missing.groups[0].test_cases.clear();
+        missing.groups[0].failed = 1;
+        assert_eq!(compare(&baseline, &missing, &thresholds), 1);
+    }
+
+    #[test]
+    fn compare_relative_from_zero() {
+        let thresholds = [threshold("container.cpu_usage.ave=+25%")];
+        let baseline = set_results(4.0, 0.0);
+
+        assert_eq!(compare(&baseline, &set_results(4.0, 0.0), &thresholds), 0);
+        assert_eq!(compare(&baseline, &set_results(4.0, 1.0), &thresholds), 1);
+    }
+
+    #[test]
+    fn compare_uses_last_matching_threshold() {
+        let thresholds = [threshold("mos.*=-0.1"), threshold("mos.*=-1")];
+        let baseline = set_results(4.0, 40.0);
+
+        assert_eq!(compare(&baseline, &set_results(3.5, 40.0), &thresholds), 0);
+    }
+}
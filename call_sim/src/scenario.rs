@@ -0,0 +1,600 @@
+//
+// Copyright 2026 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Declarative test sets. A scenario file describes the same groups, test cases and network
+//! profiles that the `run_*` functions in `main.rs` pass to [Test::run], so that new
+//! experiments don't need a code change.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::{collections::HashSet, fmt, fs, path::Path, time::Duration};
+
+use crate::common::{
+    AudioConfig, CallConfig, CallProfile, ChartDimension, DelayVariationStrategy, GeLossModel,
+    GroupActionConfig, GroupConfig, Loss, MarkovLossModel, MultiPartyTestCaseConfig, NetworkConfig,
+    NetworkProfile, SummaryReportColumns, TestCaseConfig, VideoConfig, GROUP_ACTIONS,
+};
+use crate::test::Test;
+
+/// A test set loaded from a TOML file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Runs the test set as a group call rather than a direct call.
+    #[serde(default)]
+    pub group_call: bool,
+    /// The groups of the test set, each with its own summary report.
+    #[serde(default, rename = "group")]
+    pub groups: Vec<ScenarioGroup>,
+    /// Groups of multi-party test cases, which need `group_call`.
+    #[serde(default, rename = "multi_party")]
+    pub multi_party_groups: Vec<ScenarioMultiPartyGroup>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioGroup {
+    /// See [GroupConfig::group_name].
+    pub group_name: String,
+    /// See [GroupConfig::chart_dimensions].
+    #[serde(default)]
+    pub chart_dimensions: Vec<ChartDimension>,
+    /// See [GroupConfig::x_labels].
+    #[serde(default)]
+    pub x_labels: Vec<String>,
+    /// See [GroupConfig::summary_report_columns].
+    #[serde(default)]
+    pub summary_report_columns: SummaryReportColumns,
+    /// The test cases, each of which is run over all the network profiles.
+    #[serde(rename = "test_case")]
+    pub test_cases: Vec<TestCaseConfig>,
+    /// The network profiles to run the test cases over. Defaults to no emulation.
+    #[serde(default = "default_network_profiles")]
+    pub network_profiles: Vec<NetworkProfile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioMultiPartyGroup {
+    pub group_name: String,
+    /// The test cases, each of which configures its clients' network emulation itself.
+    #[serde(rename = "test_case")]
+    pub test_cases: Vec<MultiPartyTestCaseConfig>,
+}
+
+fn default_network_profiles() -> Vec<NetworkProfile> {
+    vec![NetworkProfile::None]
+}
+
+impl Scenario {
+    /// Reads and validates the scenario file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read scenario file `{}`", path.display()))?;
+        let scenario: Scenario = toml::from_str(&contents)
+            .map_err(|e| anyhow!("Invalid scenario file `{}`: {}", path.display(), e))?;
+
+        let errors = scenario.validate();
+        if !errors.is_empty() {
+            return Err(anyhow!(
+                "Invalid scenario file `{}`:\n  {}",
+                path.display(),
+                errors.join("\n  ")
+            ));
+        }
+
+        Ok(scenario)
+    }
+
+    pub async fn run(self, test: &mut Test) -> Result<()> {
+        for group in self.groups {
+            test.run(
+                GroupConfig {
+                    group_name: group.group_name,
+                    chart_dimensions: group.chart_dimensions,
+                    x_labels: group.x_labels,
+                    summary_report_columns: group.summary_report_columns,
+                },
+                group.test_cases,
+                group.network_profiles,
+            )
+            .await?;
+        }
+
+        for group in self.multi_party_groups {
+            test.run_multi_party(&group.group_name, group.test_cases)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks what deserialization can't, returning an error for each offending field.
+    fn validate(&self) -> Vec<String> {
+        let mut v = Validator::default();
+
+        v.check(
+            !self.groups.is_empty() || !self.multi_party_groups.is_empty(),
+            "group",
+            "at least one group or multi_party group is needed",
+        );
+        v.check(
+            self.group_call || self.multi_party_groups.is_empty(),
+            "multi_party",
+            "needs group_call",
+        );
+
+        let mut group_names = HashSet::new();
+        for (i, group) in self.groups.iter().enumerate() {
+            let path = format!("group[{i}]");
+
+            v.check_name(&format!("{path}.group_name"), &group.group_name);
+            v.check(
+                group_names.insert(&group.group_name),
+                format!("{path}.group_name"),
+                "must be unique",
+            );
+            v.check(
+                !group.test_cases.is_empty(),
+                format!("{path}.test_case"),
+                "at least one test case is needed",
+            );
+            v.check(
+                !group.network_profiles.is_empty(),
+                format!("{path}.network_profiles"),
+                "at least one network profile is needed",
+            );
+
+            let mut test_case_names = HashSet::new();
+            for (j, test_case) in group.test_cases.iter().enumerate() {
+                let path = format!("{path}.test_case[{j}]");
+                v.check_name(&format!("{path}.test_case_name"), &test_case.test_case_name);
+                v.check(
+                    test_case_names.insert(&test_case.test_case_name),
+                    format!("{path}.test_case_name"),
+                    "must be unique within the group",
+                );
+                v.check(
+                    test_case.length_seconds > 0,
+                    format!("{path}.length_seconds"),
+                    "must be positive",
+                );
+                v.check(
+                    test_case.iterations > 0,
+                    format!("{path}.iterations"),
+                    "must be positive",
+                );
+                v.check_call_config(
+                    &format!("{path}.client_a_config"),
+                    &test_case.client_a_config,
+                );
+                v.check_call_config(
+                    &format!("{path}.client_b_config"),
+                    &test_case.client_b_config,
+                );
+            }
+
+            let mut profile_names = HashSet::new();
+            for (k, profile) in group.network_profiles.iter().enumerate() {
+                let path = format!("{path}.network_profiles[{k}]");
+                v.check(
+                    profile_names.insert(profile.get_name()),
+                    &path,
+                    "must be unique within the group",
+                );
+                v.check_network_profile(&path, profile);
+            }
+        }
+
+        for (i, group) in self.multi_party_groups.iter().enumerate() {
+            let path = format!("multi_party[{i}]");
+
+            v.check_name(&format!("{path}.group_name"), &group.group_name);
+            v.check(
+                group_names.insert(&group.group_name),
+                format!("{path}.group_name"),
+                "must be unique",
+            );
+            v.check(
+                !group.test_cases.is_empty(),
+                format!("{path}.test_case"),
+                "at least one test case is needed",
+            );
+
+            let mut test_case_names = HashSet::new();
+            for (j, test_case) in group.test_cases.iter().enumerate() {
+                let path = format!("{path}.test_case[{j}]");
+                v.check_name(&format!("{path}.test_case_name"), &test_case.test_case_name);
+                v.check(
+                    test_case_names.insert(&test_case.test_case_name),
+                    format!("{path}.test_case_name"),
+                    "must be unique within the group",
+                );
+                v.check(
+                    test_case.length_seconds > 0,
+                    format!("{path}.length_seconds"),
+                    "must be positive",
+                );
+                v.check(
+                    test_case.iterations > 0,
+                    format!("{path}.iterations"),
+                    "must be positive",
+                );
+                v.check(
+                    test_case.clients.len() >= 2,
+                    format!("{path}.client"),
+                    "at least two clients are needed",
+                );
+
+                let length = Duration::from_secs(test_case.length_seconds.into());
+                for (k, client) in test_case.clients.iter().enumerate() {
+                    let path = format!("{path}.client[{k}]");
+                    v.check_call_config(&format!("{path}.call_config"), &client.call_config);
+                    v.check_network_profile(
+                        &format!("{path}.network_profile"),
+                        &client.network_profile,
+                    );
+                    v.check(
+                        client.join_offset < length,
+                        format!("{path}.join_offset_secs"),
+                        "must be before the end of the test case",
+                    );
+                    if let Some(leave_offset) = client.leave_offset {
+                        v.check(
+                            client.join_offset < leave_offset && leave_offset <= length,
+                            format!("{path}.leave_offset_secs"),
+                            "must be after join_offset_secs and not after the end of the test case",
+                        );
+                    }
+                    for (l, action) in client.actions.iter().enumerate() {
+                        v.check_group_action(&format!("{path}.actions[{l}]"), action, length);
+                    }
+                }
+            }
+        }
+
+        v.errors
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<String>,
+}
+
+impl Validator {
+    fn check(&mut self, ok: bool, path: impl fmt::Display, message: &str) {
+        if !ok {
+            self.errors.push(format!("{path}: {message}"));
+        }
+    }
+
+    fn check_percentage(&mut self, path: impl fmt::Display, value: u8) {
+        self.check(value <= 100, path, "must be a percentage (0-100)");
+    }
+
+    /// Names end up in file paths of the test results.
+    fn check_name(&mut self, path: &str, name: &str) {
+        self.check(!name.is_empty(), path, "must not be empty");
+        self.check(
+            name.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'),
+            path,
+            "may only contain letters, digits, '_', '-' and '.'",
+        );
+    }
+
+    fn check_call_config(&mut self, path: &str, config: &CallConfig) {
+        self.check(
+            config.allowed_bitrate_kbps > 0,
+            format!("{path}.allowed_bitrate_kbps"),
+            "must be positive",
+        );
+        self.check(
+            config.stats_interval_secs > 0,
+            format!("{path}.stats_interval_secs"),
+            "must be positive",
+        );
+        self.check(
+            !config.force_relay || !config.relay_servers.is_empty(),
+            format!("{path}.force_relay"),
+            "needs relay_servers",
+        );
+        if let CallProfile::DeterministicLoss(loss) = config.profile {
+            self.check_percentage(format!("{path}.profile.deterministic_loss"), loss);
+        }
+        self.check_audio_config(&format!("{path}.audio"), &config.audio);
+        self.check_video_config(&format!("{path}.video"), &config.video);
+    }
+
+    fn check_audio_config(&mut self, path: &str, config: &AudioConfig) {
+        self.check(
+            !config.input_name.is_empty(),
+            format!("{path}.input_name"),
+            "must not be empty",
+        );
+        self.check(
+            config.min_packet_size_ms <= config.initial_packet_size_ms
+                && config.initial_packet_size_ms <= config.max_packet_size_ms,
+            format!("{path}.initial_packet_size_ms"),
+            "must be between min_packet_size_ms and max_packet_size_ms",
+        );
+        self.check(
+            config.min_bitrate_bps <= config.initial_bitrate_bps
+                && config.initial_bitrate_bps <= config.max_bitrate_bps,
+            format!("{path}.initial_bitrate_bps"),
+            "must be between min_bitrate_bps and max_bitrate_bps",
+        );
+        self.check(
+            (0..=10).contains(&config.complexity),
+            format!("{path}.complexity"),
+            "must be between 0 and 10",
+        );
+    }
+
+    fn check_video_config(&mut self, path: &str, config: &VideoConfig) {
+        // Mirrors VideoConfig::dimensions(), which would panic at runtime.
+        if let Some(input_name) = &config.input_name {
+            let has_dimensions = Path::new(input_name)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.rsplit_once('@'))
+                .and_then(|(_, dimensions)| dimensions.split_once('x'))
+                .map_or(false, |(width, height)| {
+                    width.parse::<u16>().is_ok() && height.parse::<u16>().is_ok()
+                });
+            self.check(
+                has_dimensions,
+                format!("{path}.input_name"),
+                "must end with the dimensions, i.e. `name@1280x720`",
+            );
+        }
+    }
+
+    fn check_group_action(&mut self, path: &str, action: &GroupActionConfig, length: Duration) {
+        self.check(
+            action.offset < length,
+            format!("{path}.offset_secs"),
+            "must be before the end of the test case",
+        );
+
+        let (name, value) = match action.action.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (action.action.as_str(), None),
+        };
+        let path = format!("{path}.action");
+        match (name, value) {
+            ("react", value) => self.check(
+                value.map_or(false, |value| !value.is_empty()),
+                path,
+                "needs a value, i.e. `react=👍`",
+            ),
+            ("request-video", value) => self.check(
+                value
+                    .and_then(|value| value.split_once('x'))
+                    .map_or(false, |(width, height)| {
+                        width.parse::<u16>().is_ok() && height.parse::<u16>().is_ok()
+                    }),
+                path,
+                "needs the dimensions, i.e. `request-video=640x360`",
+            ),
+            (name, value) => self.check(
+                value.is_none() && GROUP_ACTIONS.contains(&name),
+                path,
+                "must be one of the group actions, see GROUP_ACTIONS",
+            ),
+        }
+    }
+
+    fn check_network_profile(&mut self, path: &str, profile: &NetworkProfile) {
+        match profile {
+            NetworkProfile::SimpleLoss(loss) => {
+                self.check_percentage(format!("{path}.simple_loss"), *loss)
+            }
+            NetworkProfile::LimitedBandwidth(rate) => self.check(
+                *rate > 0,
+                format!("{path}.limited_bandwidth"),
+                "must be positive",
+            ),
+            NetworkProfile::Custom(name, schedule) => {
+                self.check_name(&format!("{path}.custom.name"), name);
+                self.check(
+                    !schedule.is_empty(),
+                    format!("{path}.custom.schedule"),
+                    "at least one entry is needed",
+                );
+                for (i, entry) in schedule.iter().enumerate() {
+                    let path = format!("{path}.custom.schedule[{i}]");
+                    if i > 0 {
+                        self.check(
+                            entry.offset > schedule[i - 1].offset,
+                            format!("{path}.offset_secs"),
+                            "must be after the previous entry",
+                        );
+                    }
+                    self.check_network_config(
+                        &format!("{path}.network_config"),
+                        &entry.network_config,
+                    );
+                }
+            }
+            NetworkProfile::None
+            | NetworkProfile::Default
+            | NetworkProfile::Moderate
+            | NetworkProfile::International
+            | NetworkProfile::SpikyLoss => {}
+        }
+    }
+
+    fn check_network_config(&mut self, path: &str, config: &NetworkConfig) {
+        self.check_percentage(format!("{path}.duplication"), config.duplication);
+        self.check_percentage(format!("{path}.corruption"), config.corruption);
+        self.check_percentage(format!("{path}.reorder"), config.reorder);
+        self.check_percentage(
+            format!("{path}.reorder_correlation"),
+            config.reorder_correlation,
+        );
+        // netem only reorders packets that it delays.
+        self.check(
+            config.reorder == 0 || config.delay > 0,
+            format!("{path}.reorder"),
+            "needs a delay",
+        );
+
+        if let Some(DelayVariationStrategy::Correlation(correlation)) =
+            config.delay_variation_strategy
+        {
+            self.check_percentage(
+                format!("{path}.delay_variation_strategy.correlation"),
+                correlation,
+            );
+        }
+
+        let (model_path, probabilities) = match config.loss {
+            None => return,
+            Some(Loss::Percentage(loss)) => {
+                self.check_percentage(format!("{path}.loss.percentage"), loss);
+                return;
+            }
+            Some(Loss::GeModel(GeLossModel::Bernoulli { p })) => {
+                ("ge_model.bernoulli", vec![("p", p)])
+            }
+            Some(Loss::GeModel(GeLossModel::SimpleGilbert { p, r })) => {
+                ("ge_model.simple_gilbert", vec![("p", p), ("r", r)])
+            }
+            Some(Loss::GeModel(GeLossModel::Gilbert { p, r, one_minus_h })) => (
+                "ge_model.gilbert",
+                vec![("p", p), ("r", r), ("one_minus_h", one_minus_h)],
+            ),
+            Some(Loss::GeModel(GeLossModel::GilbertElliot {
+                p,
+                r,
+                one_minus_h,
+                one_minus_k,
+            })) => (
+                "ge_model.gilbert_elliot",
+                vec![
+                    ("p", p),
+                    ("r", r),
+                    ("one_minus_h", one_minus_h),
+                    ("one_minus_k", one_minus_k),
+                ],
+            ),
+            Some(Loss::State(MarkovLossModel::Bernoulli { p13 })) => {
+                ("state.bernoulli", vec![("p13", p13)])
+            }
+            Some(Loss::State(MarkovLossModel::TwoState { p13, p31 })) => {
+                ("state.two_state", vec![("p13", p13), ("p31", p31)])
+            }
+            Some(Loss::State(MarkovLossModel::ThreeState { p13, p31, p32, p23 })) => (
+                "state.three_state",
+                vec![("p13", p13), ("p31", p31), ("p32", p32), ("p23", p23)],
+            ),
+            Some(Loss::State(MarkovLossModel::FourState {
+                p13,
+                p31,
+                p32,
+                p23,
+                p14,
+            })) => (
+                "state.four_state",
+                vec![
+                    ("p13", p13),
+                    ("p31", p31),
+                    ("p32", p32),
+                    ("p23", p23),
+                    ("p14", p14),
+                ],
+            ),
+        };
+        for (name, probability) in probabilities {
+            self.check_percentage(format!("{path}.loss.{model_path}.{name}"), probability);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(contents: &str) -> Vec<String> {
+        toml::from_str::<Scenario>(contents)
+            .expect("scenario should deserialize")
+            .validate()
+    }
+
+    #[test]
+    fn example_scenarios_are_valid() {
+        assert_eq!(
+            validate(include_str!("../scenarios/example.toml")),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            validate(include_str!("../scenarios/group_call.toml")),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn invalid_group_reports_field_paths() {
+        let errors = validate(
+            r#"
+            [[group]]
+            group_name = "bad name"
+            network_profiles = [{ simple_loss = 101 }]
+
+            [[group.test_case]]
+            test_case_name = "short"
+            length_seconds = 0
+            client_a_config.audio.input_name = ""
+            "#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "group[0].group_name: may only contain letters, digits, '_', '-' and '.'",
+                "group[0].test_case[0].length_seconds: must be positive",
+                "group[0].test_case[0].client_a_config.audio.input_name: must not be empty",
+                "group[0].network_profiles[0].simple_loss: must be a percentage (0-100)",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_multi_party_group_reports_field_paths() {
+        let errors = validate(
+            r#"
+            [[multi_party]]
+            group_name = "join_and_leave"
+
+            [[multi_party.test_case]]
+            test_case_name = "alone"
+            length_seconds = 10
+
+            [[multi_party.test_case.client]]
+            join_offset_secs = 5
+            leave_offset_secs = 2
+            actions = [{ offset_secs = 1, action = "wave" }]
+            "#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "multi_party: needs group_call",
+                "multi_party[0].test_case[0].client: at least two clients are needed",
+                "multi_party[0].test_case[0].client[0].leave_offset_secs: must be after join_offset_secs and not after the end of the test case",
+                "multi_party[0].test_case[0].client[0].actions[0].action: must be one of the group actions, see GROUP_ACTIONS",
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_scenario_is_invalid() {
+        assert_eq!(
+            validate(""),
+            vec!["group: at least one group or multi_party group is needed"]
+        );
+    }
+}
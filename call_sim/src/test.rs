@@ -7,14 +7,14 @@ pub mod calling {
     #![allow(clippy::derive_partial_eq_without_eq, clippy::enum_variant_names)]
     protobuf::include_call_sim_proto!();
 }
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use calling::{
     command_message::Command, test_management_client::TestManagementClient, CommandMessage, Empty,
 };
 use chrono::{DateTime, Local};
 use relative_path::RelativePath;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
     path::PathBuf,
     thread,
@@ -24,19 +24,23 @@ use tonic::transport::Channel;
 use tower::timeout::Timeout;
 
 use crate::common::{
-    AudioAnalysisMode, GroupConfig, NetworkConfigWithOffset, NetworkProfile, TestCaseConfig,
+    AudioAnalysisMode, GroupConfig, MultiPartyClientConfig, MultiPartyTestCaseConfig,
+    NetworkConfigWithOffset, NetworkProfile, TestCaseConfig,
 };
 use crate::docker::{
-    analyze_video, analyze_visqol_mos, clean_network, clean_up, convert_mp4_to_yuv,
-    convert_raw_to_wav, convert_wav_to_16khz_mono, convert_yuv_to_mp4, create_network,
-    emulate_network_change, emulate_network_start, generate_spectrogram, get_signaling_server_logs,
-    get_turn_server_logs, start_cli, start_client, start_signaling_server, start_tcp_dump,
-    start_turn_server, DockerStats,
+    self, analyze_video, analyze_visqol_mos, clean_network, clean_up, convert_mp4_to_yuv,
+    convert_yuv_to_mp4, create_network, emulate_network_change, emulate_network_start,
+    get_sfu_server_logs, get_signaling_server_logs, get_turn_server_logs, start_cli, start_client,
+    start_sfu_server, start_signaling_server, start_tcp_dump, start_turn_server, DockerStats,
+    LOCAL_SFU_URL,
 };
-use crate::report::{AnalysisReport, AnalysisReportMos, Report};
+use crate::report::{AnalysisReport, AnalysisReportMos, MultiPartyReport, Report};
+use crate::results::{SetResults, TestCaseResults};
 use crate::{
     audio::{chop_audio_and_analyze, get_audio_and_analyze, AudioFiles},
     common::ClientProfile,
+    local,
+    quality::{self, SignalQuality},
 };
 
 pub struct Client<'a> {
@@ -67,6 +71,8 @@ pub struct AudioTestResults {
     pub pesq_mos: AnalysisReportMos,
     /// MOS analysis using plc.
     pub plc_mos: AnalysisReportMos,
+    /// Metrics from the built-in signal analysis.
+    pub signal_quality: Option<SignalQuality>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -78,6 +84,25 @@ pub enum CallTypeConfig {
     Direct,
 }
 
+impl CallTypeConfig {
+    /// Whether the group call uses the SFU container instead of an external one.
+    pub fn uses_local_sfu(&self) -> bool {
+        matches!(self, Self::Group { sfu_url, .. } if sfu_url == LOCAL_SFU_URL)
+    }
+}
+
+/// Where the clients of a test run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestRunner {
+    /// Each client runs in its own container, with network emulation applied by netem.
+    Docker,
+    /// Both clients run in one local `call_sim-cli` process, which emulates the network
+    /// itself. Nothing needs Docker or root, but audio is only analyzed by the built-in
+    /// signal analysis and test cases that need other containers (video, relay servers,
+    /// etc.) are skipped.
+    InProcess { cli_path: String },
+}
+
 pub struct TestCase<'a> {
     pub report_name: String,
     pub test_path: String,
@@ -89,6 +114,23 @@ pub struct TestCase<'a> {
     pub client_b: &'a Client<'a>,
 }
 
+pub struct MultiPartyClient<'a> {
+    pub name: String,
+    pub config: &'a MultiPartyClientConfig,
+    pub sound: &'a Sound,
+    pub video: Option<&'a Video>,
+    pub output_raw: String,
+}
+
+pub struct MultiPartyTestCase<'a> {
+    pub report_name: String,
+    pub test_path: String,
+
+    pub test_case_name: String,
+
+    pub clients: Vec<MultiPartyClient<'a>>,
+}
+
 pub struct Sound {
     pub name: String,
     /// Optionally store the mos of the file vs. itself as a theoretical maximum.
@@ -133,6 +175,11 @@ pub struct GroupRun {
     pub reports: Vec<Result<Report>>,
 }
 
+pub struct MultiPartyRun {
+    pub group_name: String,
+    pub reports: Vec<Result<MultiPartyReport>>,
+}
+
 #[allow(dead_code)]
 pub struct Test {
     time_started: DateTime<Local>,
@@ -143,10 +190,12 @@ pub struct Test {
     media_path: String,
 
     group_runs: Vec<GroupRun>,
+    multi_party_runs: Vec<MultiPartyRun>,
 
     // TODO: maybe relocate to test case
     client_profiles: Vec<ClientProfile>,
     call_type: CallTypeConfig,
+    runner: TestRunner,
 
     // Keep track of all reference files used by copying them into the test
     // directory, converting them if necessary (and avoiding duplicates if
@@ -171,6 +220,7 @@ impl Test {
         set_name: &str,
         client_profiles: Vec<ClientProfile>,
         call_type: CallTypeConfig,
+        runner: TestRunner,
     ) -> Result<Self> {
         let time_started = chrono::Local::now();
 
@@ -200,14 +250,67 @@ impl Test {
             media_path: media_path.display().to_string(),
 
             group_runs: vec![],
+            multi_party_runs: vec![],
             sounds: HashMap::new(),
             videos: HashMap::new(),
 
             client_profiles,
             call_type,
+            runner,
         })
     }
 
+    fn is_in_process(&self) -> bool {
+        matches!(self.runner, TestRunner::InProcess { .. })
+    }
+
+    async fn convert_raw_to_wav(
+        &self,
+        location: &str,
+        raw_file: &str,
+        wav_file: &str,
+        length: Option<u16>,
+    ) -> Result<()> {
+        match self.runner {
+            TestRunner::Docker => {
+                docker::convert_raw_to_wav(location, raw_file, wav_file, length).await
+            }
+            TestRunner::InProcess { .. } => {
+                local::convert_raw_to_wav(location, raw_file, wav_file, length).await
+            }
+        }
+    }
+
+    async fn convert_wav_to_16khz_mono(
+        &self,
+        location: &str,
+        input_file: &str,
+        output_file: &str,
+    ) -> Result<()> {
+        match self.runner {
+            TestRunner::Docker => {
+                docker::convert_wav_to_16khz_mono(location, input_file, output_file).await
+            }
+            TestRunner::InProcess { .. } => {
+                local::convert_wav_to_16khz_mono(location, input_file, output_file).await
+            }
+        }
+    }
+
+    async fn generate_spectrogram(
+        &self,
+        location: &str,
+        wav_file: &str,
+        extension: &str,
+    ) -> Result<()> {
+        match self.runner {
+            TestRunner::Docker => docker::generate_spectrogram(location, wav_file, extension).await,
+            TestRunner::InProcess { .. } => {
+                quality::generate_spectrogram(location, wav_file, extension)
+            }
+        }
+    }
+
     async fn start_test_manager_client(&self) -> Result<TestManagementClient<Timeout<Channel>>> {
         let channel = Channel::from_static("http://localhost:9090")
             .connect_timeout(Duration::from_millis(500))
@@ -284,6 +387,7 @@ impl Test {
                 &test_case_config.client_b_config,
                 &self.client_profiles[0],
                 &self.call_type,
+                &[],
             )
             .await?;
 
@@ -299,6 +403,7 @@ impl Test {
                 &test_case_config.client_a_config,
                 &self.client_profiles[1],
                 &self.call_type,
+                &[],
             )
             .await?;
 
@@ -473,6 +578,190 @@ impl Test {
         Ok(())
     }
 
+    /// Like [Test::run_test], but for any number of clients in a group call. Each client
+    /// follows its own script and network emulation, with offsets counted from the start.
+    async fn run_multi_party_test(
+        &self,
+        test_case: &MultiPartyTestCase<'_>,
+        test_case_config: &MultiPartyTestCaseConfig,
+    ) -> Result<()> {
+        create_network().await?;
+        start_signaling_server().await?;
+
+        if self.call_type.uses_local_sfu() {
+            start_sfu_server().await?;
+        }
+
+        if test_case
+            .clients
+            .iter()
+            .any(|client| !client.config.call_config.relay_servers.is_empty())
+        {
+            start_turn_server().await?;
+        }
+
+        // Sleep here to allow the server(s) to get running. The SFU needs longer since it
+        // sets up its storage first.
+        let startup_time = if self.call_type.uses_local_sfu() {
+            Duration::from_secs(5)
+        } else {
+            Duration::from_secs(1)
+        };
+        tokio::time::sleep(startup_time).await;
+
+        println!("Connecting to test manager...");
+        let mut test_manager = self.start_test_manager_client().await?;
+        println!("Starting clients...");
+
+        // Sign-up for notifications from the signaling server.
+        let request = tonic::Request::new(Empty {});
+        let response = test_manager.notification(request).await;
+
+        if let Ok(response) = response {
+            let mut stream = response.into_inner();
+
+            for client in &test_case.clients {
+                start_client(&client.name, &test_case.test_path, &self.set_path).await?;
+            }
+
+            println!();
+
+            for (client, client_profile) in test_case.clients.iter().zip(&self.client_profiles) {
+                start_cli(
+                    &client.name,
+                    MediaFileIo {
+                        audio_input_file: client.sound.raw(),
+                        audio_output_file: client.output_raw.clone(),
+                        video_input_file: client.video.map(|v| v.raw()),
+                        // There is no single remote video to write out.
+                        video_output_file: None,
+                    },
+                    &client.config.call_config,
+                    &client.config.call_config,
+                    client_profile,
+                    &self.call_type,
+                    &client.config.group_actions(),
+                )
+                .await?;
+            }
+
+            // Merge the emulation schedules of all clients into one.
+            let mut network_changes = test_case
+                .clients
+                .iter()
+                .flat_map(|client| {
+                    client
+                        .config
+                        .network_profile
+                        .get_config()
+                        .into_iter()
+                        .map(|timed| (timed.offset, client.name.clone(), timed.network_config))
+                })
+                .collect::<Vec<_>>();
+            network_changes.sort_by_key(|(offset, _, _)| *offset);
+            let mut network_changes = network_changes.into_iter().peekable();
+            let mut emulation_started = HashSet::new();
+
+            println!("Waiting for clients...");
+
+            let mut done = false;
+            loop {
+                match stream.message().await {
+                    Ok(Some(event)) => {
+                        // We wait for all clients to indicate that they are ready and already
+                        // registered with the relay server.
+                        if !done && event.ready_count as usize == test_case.clients.len() {
+                            println!("\nRunning test...");
+
+                            while let Some((_, name, network_config)) =
+                                network_changes.next_if(|(offset, _, _)| offset.is_zero())
+                            {
+                                println!("  Setting up network emulation for `{}`.", name);
+                                emulate_network_start(&name, &network_config).await?;
+                                emulation_started.insert(name);
+                            }
+
+                            // Start monitoring docker stats. They will end when the associated container stops.
+                            let docker_stats = DockerStats::new().await?;
+                            for client in &test_case.clients {
+                                docker_stats.start(&client.name, &test_case.test_path)?;
+                            }
+
+                            // Start all clients, which then join the call when their scripts say so.
+                            for client in &test_case.clients {
+                                let request = tonic::Request::new(CommandMessage {
+                                    client: client.name.clone(),
+                                    command: Command::StartAsCaller.into(),
+                                });
+
+                                test_manager.send_command(request).await?;
+                            }
+
+                            println!("\nWaiting for the test to complete...");
+
+                            let start_time = Instant::now();
+
+                            for i in (1..=(test_case_config.length_seconds)).rev() {
+                                eprint!("\r{} seconds remaining...", i);
+                                tokio::time::sleep(Duration::from_secs(1)).await;
+
+                                while let Some((_, name, network_config)) = network_changes
+                                    .next_if(|(offset, _, _)| start_time.elapsed() >= *offset)
+                                {
+                                    let started = !emulation_started.insert(name.clone());
+
+                                    // Changing the network emulation takes time, so do it concurrently.
+                                    // For now we will be ignoring errors when changing the emulation settings.
+                                    tokio::spawn(async move {
+                                        eprint!(
+                                            "\n  Applying new emulated network settings for `{}`...",
+                                            name
+                                        );
+                                        let _ = if started {
+                                            emulate_network_change(&name, &network_config).await
+                                        } else {
+                                            emulate_network_start(&name, &network_config).await
+                                        };
+                                        eprintln!(" Done.");
+                                    });
+                                }
+                            }
+
+                            for client in &test_case.clients {
+                                let request = tonic::Request::new(CommandMessage {
+                                    client: client.name.clone(),
+                                    command: Command::Stop.into(),
+                                });
+
+                                test_manager.send_command(request).await?;
+                            }
+
+                            done = true;
+
+                            println!("\r  Test complete.");
+                            println!("\nWaiting for the clients to terminate...");
+                        } else if done && event.ready_count == 0 {
+                            println!("  Done.");
+                            break;
+                        }
+                    }
+                    Ok(None) => {
+                        println!("Received Message: None");
+                        break;
+                    }
+                    Err(err) => {
+                        println!("Error: {}", err);
+                        break;
+                    }
+                }
+            }
+        } else {
+            println!("Could not send notification() message: {:?}", response);
+        }
+
+        Ok(())
+    }
+
     /// Generates report artifacts by performing analysis on all media outputs. Performs
     /// the necessary conversions to do so.
     async fn generate_artifacts(
@@ -483,7 +772,7 @@ impl Test {
         let mut audio_test_results = AudioTestResults::default();
 
         // Perform conversions of audio data.
-        convert_raw_to_wav(
+        self.convert_raw_to_wav(
             &test_case.test_path,
             &test_case.client_a.output_raw,
             &test_case.client_a.output_wav,
@@ -492,7 +781,7 @@ impl Test {
         .await?;
 
         if test_case_config.client_a_config.audio.requires_speech() {
-            convert_wav_to_16khz_mono(
+            self.convert_wav_to_16khz_mono(
                 &test_case.test_path,
                 &test_case.client_a.output_wav,
                 &test_case.client_a.output_wav_speech,
@@ -500,7 +789,7 @@ impl Test {
             .await?;
         }
 
-        convert_raw_to_wav(
+        self.convert_raw_to_wav(
             &test_case.test_path,
             &test_case.client_b.output_raw,
             &test_case.client_b.output_wav,
@@ -509,7 +798,7 @@ impl Test {
         .await?;
 
         if test_case_config.client_b_config.audio.requires_speech() {
-            convert_wav_to_16khz_mono(
+            self.convert_wav_to_16khz_mono(
                 &test_case.test_path,
                 &test_case.client_b.output_wav,
                 &test_case.client_b.output_wav_speech,
@@ -535,6 +824,9 @@ impl Test {
             AudioAnalysisMode::None => {
                 // Do nothing, no analysis is requested.
             }
+            _ if self.is_in_process() => {
+                println!("\nSkipping MOS analysis, which needs Docker.");
+            }
             AudioAnalysisMode::Normal => {
                 get_audio_and_analyze(
                     &audio_files,
@@ -557,8 +849,19 @@ impl Test {
             }
         }
 
+        let audio_config = &test_case_config.client_b_config.audio;
+        if audio_config.analysis_mode != AudioAnalysisMode::None
+            && (audio_config.signal_analysis || self.is_in_process())
+        {
+            audio_test_results.signal_quality = Some(quality::analyze(
+                &audio_files,
+                audio_config.analysis_mode == AudioAnalysisMode::Chopped,
+                test_case.client_b.name,
+            )?);
+        }
+
         if test_case_config.client_b_config.audio.generate_spectrogram {
-            generate_spectrogram(
+            self.generate_spectrogram(
                 &test_case.test_path,
                 &test_case.client_b.output_wav,
                 test_case.client_b.sound.spectrogram_extension(),
@@ -644,6 +947,7 @@ impl Test {
                 test_case_config,
             )
             .await?;
+        TestCaseResults::from_report(&report).write(&test_case.test_path)?;
 
         Ok(report)
     }
@@ -672,12 +976,21 @@ impl Test {
             )?;
 
             // Make sure there is a wav version of the file available.
-            convert_raw_to_wav(&self.set_path, &raw_name, &wav_name, None).await?;
-            convert_wav_to_16khz_mono(&self.set_path, &wav_name, &wav_name_speech).await?;
+            self.convert_raw_to_wav(&self.set_path, &raw_name, &wav_name, None)
+                .await?;
+            self.convert_wav_to_16khz_mono(&self.set_path, &wav_name, &wav_name_speech)
+                .await?;
 
             // And a reference spectrogram. Since the speech wav files have a limited frequency
             // range, we will only generate spectrograms for the full-band audio files.
-            generate_spectrogram(&self.set_path, &wav_name, sound.spectrogram_extension()).await?;
+            self.generate_spectrogram(&self.set_path, &wav_name, sound.spectrogram_extension())
+                .await?;
+
+            // The remaining steps need Docker.
+            if self.is_in_process() {
+                self.sounds.insert(name.to_string(), sound);
+                return Ok(());
+            }
 
             if analyze {
                 let extension = "visqol_mos_audio.log".to_string();
@@ -776,12 +1089,92 @@ impl Test {
         Ok(())
     }
 
+    async fn generate_artifacts_and_report(
+        &self,
+        test_case: &TestCase<'_>,
+        test_case_config: &TestCaseConfig,
+        network_configs: &Vec<NetworkConfigWithOffset>,
+    ) -> Result<Report> {
+        match self.generate_artifacts(test_case, test_case_config).await {
+            Ok(test_results) => {
+                match self
+                    .generate_test_report(
+                        test_case,
+                        test_case_config,
+                        network_configs,
+                        test_results,
+                    )
+                    .await
+                {
+                    Ok(report) => Ok(report),
+                    Err(err) => {
+                        println!("Error generating test report: {}", err);
+                        Err(err)
+                    }
+                }
+            }
+            Err(err) => {
+                println!("Error generating artifacts: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Runs the test case without any containers, see [`TestRunner::InProcess`].
+    async fn run_test_case_in_process_and_get_report(
+        &self,
+        cli_path: &str,
+        test_case: &TestCase<'_>,
+        test_case_config: &TestCaseConfig,
+        network_configs: &Vec<NetworkConfigWithOffset>,
+    ) -> Result<Report> {
+        if let Err(err) = local::run_in_process(
+            cli_path,
+            &self.set_path,
+            test_case,
+            test_case_config,
+            network_configs,
+            MediaFileIo {
+                audio_input_file: test_case.client_a.sound.raw(),
+                audio_output_file: test_case.client_a.output_raw.clone(),
+                video_input_file: None,
+                video_output_file: None,
+            },
+            MediaFileIo {
+                audio_input_file: test_case.client_b.sound.raw(),
+                audio_output_file: test_case.client_b.output_raw.clone(),
+                video_input_file: None,
+                video_output_file: None,
+            },
+            &self.client_profiles,
+        )
+        .await
+        {
+            println!("Error running test: {}", err);
+            return Err(err);
+        }
+
+        self.generate_artifacts_and_report(test_case, test_case_config, network_configs)
+            .await
+    }
+
     async fn run_test_case_and_get_report(
         &self,
         test_case: &TestCase<'_>,
         test_case_config: &TestCaseConfig,
         network_configs: &Vec<NetworkConfigWithOffset>,
     ) -> Result<Report> {
+        if let TestRunner::InProcess { cli_path } = &self.runner {
+            return self
+                .run_test_case_in_process_and_get_report(
+                    cli_path,
+                    test_case,
+                    test_case_config,
+                    network_configs,
+                )
+                .await;
+        }
+
         match self
             .run_test(test_case, test_case_config, network_configs)
             .await
@@ -808,29 +1201,8 @@ impl Test {
                 .await?;
                 clean_network().await?;
 
-                match self.generate_artifacts(test_case, test_case_config).await {
-                    Ok(test_results) => {
-                        match self
-                            .generate_test_report(
-                                test_case,
-                                test_case_config,
-                                network_configs,
-                                test_results,
-                            )
-                            .await
-                        {
-                            Ok(report) => Ok(report),
-                            Err(err) => {
-                                println!("Error generating test report: {}", err);
-                                Err(err)
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        println!("Error generating artifacts: {}", err);
-                        Err(err)
-                    }
-                }
+                self.generate_artifacts_and_report(test_case, test_case_config, network_configs)
+                    .await
             }
             Err(err) => {
                 println!("Error running test: {}", err);
@@ -849,6 +1221,148 @@ impl Test {
         }
     }
 
+    async fn run_multi_party_test_case_and_get_report(
+        &self,
+        test_case: &MultiPartyTestCase<'_>,
+        test_case_config: &MultiPartyTestCaseConfig,
+    ) -> Result<MultiPartyReport> {
+        let result = self.run_multi_party_test(test_case, test_case_config).await;
+
+        if result.is_ok() {
+            // For debugging, dump the server logs.
+            get_signaling_server_logs(&test_case.test_path).await?;
+            if self.call_type.uses_local_sfu() {
+                get_sfu_server_logs(&test_case.test_path).await?;
+            }
+        }
+
+        // We are done with the containers.
+        let mut containers = test_case
+            .clients
+            .iter()
+            .map(|client| client.name.as_str())
+            .collect::<Vec<_>>();
+        containers.extend(["signaling_server", "sfu", "turn"]);
+        clean_up(containers).await?;
+        clean_network().await?;
+
+        if let Err(err) = result {
+            println!("Error running test: {}", err);
+            return Err(err);
+        }
+
+        match MultiPartyReport::build(test_case, test_case_config).await {
+            Ok(report) => {
+                report
+                    .create_test_case_report(&self.set_name, test_case_config)
+                    .await?;
+                TestCaseResults::from_multi_party_report(&report).write(&test_case.test_path)?;
+                Ok(report)
+            }
+            Err(err) => {
+                println!("Error generating test report: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Runs group calls with any number of clients. Unlike [Test::run], the audio is not
+    /// analyzed since every client hears a mix of the others. Instead, the report shows
+    /// what each client received from each of the others.
+    pub async fn run_multi_party(
+        &mut self,
+        group_name: &str,
+        tests: Vec<MultiPartyTestCaseConfig>,
+    ) -> Result<()> {
+        if self.call_type == CallTypeConfig::Direct {
+            return Err(anyhow!(
+                "Multi-party test cases need a group call, use a `group_` test set"
+            ));
+        }
+
+        let mut reports: Vec<Result<MultiPartyReport>> = vec![];
+
+        for test in tests {
+            if self.is_in_process() {
+                println!(
+                    "\nSkipping test case {} in-process: multi-party calls need an SFU",
+                    test.test_case_name
+                );
+                continue;
+            }
+
+            if test.clients.len() > self.client_profiles.len() {
+                return Err(anyhow!(
+                    "Test case {} has {} clients, but only {} client profiles are available",
+                    test.test_case_name,
+                    test.clients.len(),
+                    self.client_profiles.len()
+                ));
+            }
+
+            for client in &test.clients {
+                // Make sure the media is copied and converted.
+                self.process_sound(&client.call_config.audio.input_name, false)
+                    .await?;
+                if let Some(video) = &client.call_config.video.input_name {
+                    self.process_video(video).await?;
+                }
+            }
+
+            for i in 1..=test.iterations {
+                let report_name = test.test_case_name.clone();
+
+                let test_case_path = if test.iterations > 1 {
+                    println!("\nRunning test case: {}, iteration: {}", report_name, i);
+                    format!("{}/{}/{}_{}", self.set_path, group_name, report_name, i)
+                } else {
+                    println!("\nRunning test case: {}", report_name);
+                    format!("{}/{}/{}", self.set_path, group_name, report_name)
+                };
+                fs::create_dir_all(test_case_path.clone())?;
+
+                let test_case = MultiPartyTestCase {
+                    report_name,
+                    test_path: test_case_path,
+                    test_case_name: test.test_case_name.clone(),
+                    clients: test
+                        .clients
+                        .iter()
+                        .enumerate()
+                        .map(|(index, config)| {
+                            let name = format!("client_{}", index + 1);
+                            MultiPartyClient {
+                                output_raw: format!("{}_output.raw", name),
+                                name,
+                                config,
+                                // The media should have been processed.
+                                sound: &self.sounds[&config.call_config.audio.input_name],
+                                video: config
+                                    .call_config
+                                    .video
+                                    .input_name
+                                    .as_ref()
+                                    .map(|v| &self.videos[v]),
+                            }
+                        })
+                        .collect(),
+                };
+
+                reports.push(
+                    self.run_multi_party_test_case_and_get_report(&test_case, &test)
+                        .await,
+                );
+            }
+        }
+
+        self.multi_party_runs.push(MultiPartyRun {
+            group_name: group_name.to_string(),
+            reports,
+        });
+
+        Ok(())
+    }
+
     /// Runs the provided test permutations as individual test cases.
     pub async fn run(
         &mut self,
@@ -859,6 +1373,16 @@ impl Test {
         let mut reports: Vec<Result<Report>> = vec![];
 
         for test in tests {
+            if self.is_in_process() {
+                if let Some(reason) = local::unsupported_reason(&test, &self.call_type) {
+                    println!(
+                        "\nSkipping test case {} in-process: {}",
+                        test.test_case_name, reason
+                    );
+                    continue;
+                }
+            }
+
             let a_to_b_sound = test.client_a_config.audio.input_name.as_str();
             let b_to_a_sound = test.client_b_config.audio.input_name.as_str();
 
@@ -953,16 +1477,27 @@ impl Test {
 
     // Publish a report and clear history.
     pub async fn report(&mut self) -> Result<()> {
+        let time_started = self.time_started.format("%Y-%m-%d %H:%M:%S").to_string();
+
         Report::create_summary_report(
             &self.set_name,
             &self.set_path,
-            &self.time_started.format("%Y-%m-%d %H:%M:%S").to_string(),
+            &time_started,
             &self.group_runs,
+            &self.multi_party_runs,
             &self.sounds,
         )
         .await?;
+        SetResults::new(
+            &self.set_name,
+            &time_started,
+            &self.group_runs,
+            &self.multi_party_runs,
+        )
+        .write(&self.set_path)?;
 
         self.group_runs.clear();
+        self.multi_party_runs.clear();
 
         Ok(())
     }
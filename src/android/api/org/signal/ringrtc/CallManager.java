@@ -357,6 +357,58 @@ public class CallManager {
     ringrtcSetSelfUuid(nativeCallManager, Util.getBytesFromUuid(uuid));
   }
 
+  /**
+   *
+   * Updates how long 1:1 calls ring and how long received rings stay valid.
+   * Calls that are already ringing keep the timeout they started with.
+   *
+   * @param outgoingRingTimeoutMillis  how long an outgoing call rings before timing out, 0 for no timeout
+   * @param incomingRingTimeoutMillis  how long an incoming call rings before timing out, 0 for no timeout
+   * @param maxOfferAgeMillis          offers and group rings older than this are treated as expired
+   * @param groupRingExpiryMillis      how long a received group ring stays outstanding
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setRingPolicy(long outgoingRingTimeoutMillis,
+                            long incomingRingTimeoutMillis,
+                            long maxOfferAgeMillis,
+                            long groupRingExpiryMillis)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setRingPolicy():");
+
+    ringrtcSetRingPolicy(nativeCallManager,
+                         outgoingRingTimeoutMillis,
+                         incomingRingTimeoutMillis,
+                         maxOfferAgeMillis,
+                         groupRingExpiryMillis);
+  }
+
+  /**
+   *
+   * Enables or disables call waiting for 1:1 calls.
+   *
+   * When enabled, an offer from a different peer that arrives while the
+   * active call is connected is started as a waiting call instead of being
+   * rejected as busy. Accepting the waiting call puts the active call on hold.
+   *
+   * @param enabled  if true, then enable call waiting
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setCallWaitingEnabled(boolean enabled)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setCallWaitingEnabled(): " + enabled);
+    ringrtcSetCallWaitingEnabled(nativeCallManager, enabled);
+  }
+
   /**
    *
    * Indication from application to start a new outgoing call
@@ -380,6 +432,35 @@ public class CallManager {
     ringrtcCall(nativeCallManager, remote, callMediaType.ordinal(), localDeviceId);
   }
 
+  /**
+   *
+   * Indication from application to take over a call that another of the
+   * local user's devices has with the remote peer
+   *
+   * The call keeps its callId. Once it is connected, the other device ends
+   * its call with ENDED_HANDED_OFF_TO_ANOTHER_DEVICE.
+   *
+   * @param remote         remote side of the call
+   * @param callId         callId of the call being taken over
+   * @param callMediaType  used to specify an audio or video call
+   * @param localDeviceId  the local deviceId of the client
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void handoffCall(         Remote        remote,
+                          @NonNull CallId        callId,
+                          @NonNull CallMediaType callMediaType,
+                          @NonNull Integer       localDeviceId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "handoffCall(): " + callId);
+
+    ringrtcHandoffCall(nativeCallManager, remote, callId.longValue(), callMediaType.ordinal(), localDeviceId);
+  }
+
   /**
    *
    * Indication from application to proceed with call
@@ -415,10 +496,52 @@ public class CallManager {
                       @Nullable Integer                        audioLevelsIntervalMs,
                                 boolean                        enableCamera)
     throws CallException
+  {
+    proceed(callId, context, eglBase, audioProcessingMethod, useOboe, localSink, remoteSink, camera, iceServers, hideIp, dataMode, audioLevelsIntervalMs, enableCamera, new CandidatePolicy());
+  }
+
+  /**
+   *
+   * Indication from application to proceed with call
+   *
+   * @param callId                 callId for the call
+   * @param context                Call service context
+   * @param eglBase                eglBase to use for this Call
+   * @param audioProcessingMethod  the method to use for audio processing
+   * @param useOboe                whether to use the oboe-based audio device module, otherwise use java
+   * @param localSink              local video sink to use for this Call
+   * @param remoteSink             remote video sink to use for this Call
+   * @param camera                 camera control to use for this Call
+   * @param iceServers             list of ICE servers to use for this Call
+   * @param hideIp                 if true hide caller's IP by using a TURN server
+   * @param dataMode               desired data mode to start the session with
+   * @param audioLevelsIntervalMs  if greater than 0, enable audio levels with this interval (in milliseconds)
+   * @param enableCamera           if true, enable the local camera video track when created
+   * @param candidatePolicy        which ICE candidates the call may use
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void proceed(@NonNull  CallId                         callId,
+                      @NonNull  Context                        context,
+                      @NonNull  EglBase                        eglBase,
+                                AudioProcessingMethod          audioProcessingMethod,
+                                boolean                        useOboe,
+                      @NonNull  VideoSink                      localSink,
+                      @NonNull  VideoSink                      remoteSink,
+                      @NonNull  CameraControl                  camera,
+                      @NonNull  List<PeerConnection.IceServer> iceServers,
+                                boolean                        hideIp,
+                                DataMode                       dataMode,
+                      @Nullable Integer                        audioLevelsIntervalMs,
+                                boolean                        enableCamera,
+                      @NonNull  CandidatePolicy                candidatePolicy)
+    throws CallException
   {
     checkCallManagerExists();
 
     Log.i(TAG, "proceed(): callId: " + callId + ", hideIp: " + hideIp);
+    Log.i(TAG, "  candidatePolicy: " + candidatePolicy);
     for (PeerConnection.IceServer iceServer : iceServers) {
       for (String url : iceServer.urls) {
         Log.i(TAG, "  server: " + url);
@@ -434,7 +557,9 @@ public class CallManager {
                                               remoteSink,
                                               camera,
                                               iceServers,
-                                              hideIp);
+                                              // The relay-only policy is enforced by the ICE
+                                              // transport policy, like hideIp.
+                                              hideIp || candidatePolicy.relayOnly);
 
     callContext.setVideoEnabled(enableCamera);
 
@@ -443,7 +568,11 @@ public class CallManager {
                    callId.longValue(),
                    callContext,
                    dataMode.ordinal(),
-                   audioLevelsIntervalMillis);
+                   audioLevelsIntervalMillis,
+                   candidatePolicy.excludeHost,
+                   candidatePolicy.relayOnly,
+                   candidatePolicy.ipv4Only,
+                   candidatePolicy.excludedAdapterTypesBitMask());
   }
 
   /**
@@ -756,7 +885,8 @@ public class CallManager {
 
   /**
    *
-   * Indication from application to accept the active call.
+   * Indication from application to accept the active call, or the
+   * waiting call, which puts the active call on hold.
    *
    * @param callId  callId for the call
    *
@@ -847,6 +977,191 @@ public class CallManager {
     ringrtcHangup(nativeCallManager);
   }
 
+  /**
+   *
+   * Notification from application to hangup a specific call, which
+   * may be active, waiting or held.
+   *
+   * @param callId  callId for the call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void hangupCall(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "hangupCall(): " + callId);
+    ringrtcHangupCall(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Indication from application to resume the held call, putting the
+   * active call (if any) on hold.
+   *
+   * @param callId  callId for the held call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void resumeCall(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "resumeCall(): " + callId);
+    ringrtcResumeCall(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Indication from application to ask the remote peer to turn the active
+   * call into a video call.
+   *
+   * The answer is reported with the VIDEO_UPGRADE_ACCEPTED,
+   * VIDEO_UPGRADE_DECLINED or VIDEO_UPGRADE_TIMED_OUT event.
+   *
+   * @param callId  callId for the active call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void requestVideoUpgrade(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "requestVideoUpgrade(): " + callId);
+    ringrtcRequestVideoUpgrade(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Indication from application to accept the remote peer's request to
+   * turn the active call into a video call, which enables video in the
+   * sender status.
+   *
+   * @param callId  callId for the active call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void acceptVideoUpgrade(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "acceptVideoUpgrade(): " + callId);
+    ringrtcAcceptVideoUpgrade(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Indication from application to decline the remote peer's request to
+   * turn the active call into a video call.
+   *
+   * @param callId  callId for the active call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void declineVideoUpgrade(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "declineVideoUpgrade(): " + callId);
+    ringrtcDeclineVideoUpgrade(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Indication from application of conditions of the local devices that
+   * the user didn't choose, such as an unplugged microphone.
+   *
+   * The remote peer is notified with the REMOTE_MICROPHONE_DISCONNECTED,
+   * REMOTE_BACKGROUNDED and REMOTE_CAMERA_SWITCH_STARTED events (and
+   * their counterparts).
+   *
+   * @param callId                  callId for the active call
+   * @param microphoneDisconnected  whether the microphone is unavailable
+   * @param backgrounded            whether the app is in the background
+   * @param cameraSwitching         whether the camera is switching
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setLocalDeviceStatus(@NonNull CallId callId,
+                                   boolean         microphoneDisconnected,
+                                   boolean         backgrounded,
+                                   boolean         cameraSwitching)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setLocalDeviceStatus(): " + callId);
+    ringrtcSetLocalDeviceStatus(nativeCallManager, callId.longValue(), microphoneDisconnected, backgrounded, cameraSwitching);
+  }
+
+  /**
+   *
+   * Indication from application to move the active call to an existing
+   * call link call.
+   *
+   * Both peers are told to join the call link via
+   * {@link Observer#onEscalateToCallLink}, and the call ends once the
+   * remote peer has joined it.
+   *
+   * @param callId       callId for the active call
+   * @param linkRootKey  the root key for the call link
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void escalateToCallLink(@NonNull CallId          callId,
+                                 @NonNull CallLinkRootKey linkRootKey)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "escalateToCallLink(): " + callId);
+    ringrtcEscalateToCallLink(nativeCallManager, callId.longValue(), linkRootKey.getKeyBytes(), null, null, null, null, -1);
+  }
+
+  /**
+   *
+   * Indication from application to create a call link and move the active
+   * call to it.
+   *
+   * @param callId                       callId for the active call
+   * @param sfuUrl                       the URL to use when accessing the SFU
+   * @param createCredentialPresentation a serialized CreateCallLinkCredentialPresentation
+   * @param linkRootKey                  the root key for the call link
+   * @param adminPasskey                 the arbitrary passkey to use for the new room
+   * @param callLinkPublicParams         the serialized CallLinkPublicParams for the new room
+   * @param restrictions                 the restrictions for joining the new room
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void escalateToCallLink(
+    @NonNull CallId                     callId,
+    @NonNull String                     sfuUrl,
+    @NonNull byte[]                     createCredentialPresentation,
+    @NonNull CallLinkRootKey            linkRootKey,
+    @NonNull byte[]                     adminPasskey,
+    @NonNull byte[]                     callLinkPublicParams,
+    @NonNull CallLinkState.Restrictions restrictions)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "escalateToCallLink(): " + callId);
+    ringrtcEscalateToCallLink(nativeCallManager, callId.longValue(), linkRootKey.getKeyBytes(), sfuUrl, createCredentialPresentation, adminPasskey, callLinkPublicParams, restrictions.ordinal());
+  }
+
   /** Describes why a ring was cancelled. */
   public enum RingCancelReason {
     /** The user explicitly clicked "Decline". */
@@ -1492,6 +1807,16 @@ public class CallManager {
     observer.onLowBandwidthForVideo(remote, recovered);
   }
 
+  @CalledByNative
+  private void onEscalateToCallLink(Remote remote, long callId, @NonNull byte[] rootKey) {
+    Log.i(TAG, "onEscalateToCallLink():");
+    try {
+      observer.onEscalateToCallLink(remote, new CallId(callId), new CallLinkRootKey(rootKey));
+    } catch (CallException e) {
+      Log.w(TAG, "Invalid call link root key", e);
+    }
+  }
+
   // A faster version of PeerConnection.AdapterType.fromNativeIndex.
   // It also won't return null.
   @NonNull
@@ -1969,7 +2294,64 @@ public class CallManager {
     RECEIVED_OFFER_WHILE_ACTIVE,
 
     /** Received an offer while already handling an active call and glare was detected. */
-    RECEIVED_OFFER_WITH_GLARE;
+    RECEIVED_OFFER_WITH_GLARE,
+
+    /** The remote side has put the call on hold. */
+    REMOTE_HOLD_ENABLE,
+
+    /** The remote side has taken the call off hold. */
+    REMOTE_HOLD_DISABLE,
+
+    /** The call ended because the remote side moved it to another of our devices. */
+    ENDED_HANDED_OFF_TO_ANOTHER_DEVICE,
+
+    /** The remote side has joined the call link call the call is being escalated to. */
+    ESCALATION_REMOTE_JOINED,
+
+    /** The call could not be escalated to a call link call, but continues. */
+    ESCALATION_FAILED,
+
+    /** The call ended because both sides moved to a call link call. */
+    ENDED_ESCALATED_TO_CALL_LINK,
+
+    /** The remote side asked to turn the call into a video call. */
+    REMOTE_VIDEO_UPGRADE_REQUESTED,
+
+    /** The remote side withdrew its request to turn the call into a video call. */
+    REMOTE_VIDEO_UPGRADE_CANCELED,
+
+    /** The call is being turned into a video call, so the camera should be turned on. */
+    VIDEO_UPGRADE_ACCEPTED,
+
+    /** The remote side declined to turn the call into a video call. */
+    VIDEO_UPGRADE_DECLINED,
+
+    /** The remote side didn't answer the request to turn the call into a video call in time. */
+    VIDEO_UPGRADE_TIMED_OUT,
+
+    /** The remote side's microphone is unavailable, e.g. because it was unplugged. */
+    REMOTE_MICROPHONE_DISCONNECTED,
+
+    /** The remote side's microphone is available again. */
+    REMOTE_MICROPHONE_RECONNECTED,
+
+    /** The remote side's app went into the background, so its video is paused. */
+    REMOTE_BACKGROUNDED,
+
+    /** The remote side's app came back into the foreground. */
+    REMOTE_FOREGROUNDED,
+
+    /** The remote side is switching cameras, so its video is briefly paused. */
+    REMOTE_CAMERA_SWITCH_STARTED,
+
+    /** The remote side has finished switching cameras. */
+    REMOTE_CAMERA_SWITCH_ENDED,
+
+    /** The call was put on hold locally, because another call became active. */
+    LOCAL_HOLD_ENABLE,
+
+    /** The call became the active call, after waiting or being on hold. */
+    LOCAL_HOLD_DISABLE;
 
     @CalledByNative
     static CallEvent fromNativeIndex(int nativeIndex) {
@@ -2016,7 +2398,10 @@ public class CallManager {
     BUSY,
 
     /** Call needed permission on a different device. */
-    NEED_PERMISSION;
+    NEED_PERMISSION,
+
+    /** Call was handed off to a different device. */
+    HANDED_OFF;
 
     @CalledByNative
     static HangupType fromNativeIndex(int nativeIndex) {
@@ -2156,6 +2541,21 @@ public class CallManager {
      */
     void onLowBandwidthForVideo(Remote remote, boolean recovered);
 
+    /**
+     *
+     * Notification that the call is moving to a call link.
+     *
+     * This happens both when the local user escalated the call and when the
+     * remote peer did. The application should join the call link; the call
+     * ends with ENDED_ESCALATED_TO_CALL_LINK once the remote peer has joined
+     * it too.
+     *
+     * @param remote   remote peer of the call
+     * @param callId   callId for the call
+     * @param rootKey  the root key for the call link
+     */
+    void onEscalateToCallLink(Remote remote, CallId callId, CallLinkRootKey rootKey);
+
     /**
      *
      * Notification of that the call is completely concluded
@@ -2311,6 +2711,18 @@ public class CallManager {
     void ringrtcSetSelfUuid(long nativeCallManager, byte[] uuid)
     throws CallException;
 
+  private native
+    void ringrtcSetCallWaitingEnabled(long nativeCallManager, boolean enabled)
+    throws CallException;
+
+  private native
+    void ringrtcSetRingPolicy(long nativeCallManager,
+                              long outgoingRingTimeoutMillis,
+                              long incomingRingTimeoutMillis,
+                              long maxOfferAgeMillis,
+                              long groupRingExpiryMillis)
+    throws CallException;
+
   private native
     long ringrtcCreatePeerConnection(long                            nativePeerConnectionFactory,
                                      long                            nativeConnection,
@@ -2322,12 +2734,24 @@ public class CallManager {
     void ringrtcCall(long nativeCallManager, Remote remote, int callMediaType, int localDeviceId)
     throws CallException;
 
+  private native
+    void ringrtcHandoffCall(long   nativeCallManager,
+                            Remote remote,
+                            long   callId,
+                            int    callMediaType,
+                            int    localDeviceId)
+    throws CallException;
+
   private native
     void ringrtcProceed(long        nativeCallManager,
                         long        callId,
                         CallContext callContext,
                         int         dataMode,
-                        int         audioLevelsIntervalMillis)
+                        int         audioLevelsIntervalMillis,
+                        boolean     excludeHost,
+                        boolean     relayOnly,
+                        boolean     ipv4Only,
+                        int         excludedAdapterTypes)
     throws CallException;
 
   private native
@@ -2342,6 +2766,45 @@ public class CallManager {
     void ringrtcHangup(long nativeCallManager)
     throws CallException;
 
+  private native
+    void ringrtcHangupCall(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcResumeCall(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcRequestVideoUpgrade(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcAcceptVideoUpgrade(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcDeclineVideoUpgrade(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcSetLocalDeviceStatus(long    nativeCallManager,
+                                     long    callId,
+                                     boolean microphoneDisconnected,
+                                     boolean backgrounded,
+                                     boolean cameraSwitching)
+    throws CallException;
+
+  private native
+    void ringrtcEscalateToCallLink(long   nativeCallManager,
+                                   long   callId,
+                                   byte[] rootKeyBytes,
+                                   String sfuUrl,
+                                   byte[] createCredentialPresentation,
+                                   byte[] adminPasskey,
+                                   byte[] callLinkPublicParams,
+                                   int    restrictions)
+    throws CallException;
+
   private native
     void ringrtcCancelGroupRing(long nativeCallManager, byte[] groupId, long ringId, int reason)
     throws CallException;
@@ -0,0 +1,60 @@
+/*
+ * Copyright 2026 Signal Messenger, LLC
+ * SPDX-License-Identifier: AGPL-3.0-only
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.NonNull;
+
+import org.webrtc.PeerConnection;
+
+import java.util.Collections;
+import java.util.List;
+
+/**
+ *
+ * Which ICE candidates a 1:1 call may use. The default allows all.
+ *
+ */
+public class CandidatePolicy {
+  /** Drop host candidates, which reveal local IP addresses. */
+  public final boolean excludeHost;
+  /** Use relay (TURN) servers only, like hideIp. */
+  public final boolean relayOnly;
+  /** Drop candidates that don't have an IPv4 address. */
+  public final boolean ipv4Only;
+  /** Drop candidates on these types of network. CELLULAR matches all cellular types. */
+  @NonNull
+  public final List<PeerConnection.AdapterType> excludedAdapterTypes;
+
+  public CandidatePolicy() {
+    this(false, false, false, Collections.emptyList());
+  }
+
+  public CandidatePolicy(boolean                                   excludeHost,
+                         boolean                                   relayOnly,
+                         boolean                                   ipv4Only,
+                         @NonNull List<PeerConnection.AdapterType> excludedAdapterTypes) {
+    this.excludeHost          = excludeHost;
+    this.relayOnly            = relayOnly;
+    this.ipv4Only             = ipv4Only;
+    this.excludedAdapterTypes = excludedAdapterTypes;
+  }
+
+  int excludedAdapterTypesBitMask() {
+    int bitMask = 0;
+    for (PeerConnection.AdapterType adapterType : excludedAdapterTypes) {
+      bitMask |= adapterType.bitMask;
+    }
+    return bitMask;
+  }
+
+  @Override
+  public String toString() {
+    return "excludeHost: " + excludeHost +
+           ", relayOnly: " + relayOnly +
+           ", ipv4Only: " + ipv4Only +
+           ", excludedAdapterTypes: " + excludedAdapterTypes;
+  }
+}
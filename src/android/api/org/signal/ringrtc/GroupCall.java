@@ -1173,12 +1173,19 @@ public final class GroupCall {
      * A class used to store a reaction from a group member.
      */
     public static class Reaction {
+        /** The device that sent the reaction first. */
         public long demuxId;
         public @NonNull String value;
+        /** How many times the reaction was received since the last notification, at least 1. */
+        public int count;
+        /** The distinct devices that sent the reaction, in the order they were first received. */
+        public @NonNull long[] senderDemuxIds;
 
-        public Reaction(long demuxId, @NonNull String value) {
+        public Reaction(long demuxId, @NonNull String value, int count, @NonNull long[] senderDemuxIds) {
             this.demuxId = demuxId;
             this.value = value;
+            this.count = count;
+            this.senderDemuxIds = senderDemuxIds;
         }
     }
 
@@ -77,6 +77,23 @@ public struct CallLinkRootKey: CustomStringConvertible {
     }
 }
 
+/// The parameters needed to create a call link while escalating a 1:1 call.
+public struct CallLinkCreateParams {
+    public var sfuUrl: String
+    public var createCredentialPresentation: [UInt8]
+    public var adminPasskey: Data
+    public var callLinkPublicParams: [UInt8]
+    public var restrictions: CallLinkState.Restrictions
+
+    public init(sfuUrl: String, createCredentialPresentation: [UInt8], adminPasskey: Data, callLinkPublicParams: [UInt8], restrictions: CallLinkState.Restrictions) {
+        self.sfuUrl = sfuUrl
+        self.createCredentialPresentation = createCredentialPresentation
+        self.adminPasskey = adminPasskey
+        self.callLinkPublicParams = callLinkPublicParams
+        self.restrictions = restrictions
+    }
+}
+
 public struct CallLinkState {
     public enum Restrictions {
       case none, adminApproval, unknown
@@ -77,6 +77,44 @@ public enum CallManagerEvent: Int32 {
     case receivedOfferWhileActive
     /// Received an offer while already handling an active call and glare was detected.
     case receivedOfferWithGlare
+    /// The remote side has put the call on hold.
+    case remoteHoldEnable
+    /// The remote side has taken the call off hold.
+    case remoteHoldDisable
+    /// The call ended because the remote side moved it to another of our devices.
+    case endedHandedOffToAnotherDevice
+    /// The remote side has joined the call link call the call is being escalated to.
+    case escalationRemoteJoined
+    /// The call could not be escalated to a call link call, but continues.
+    case escalationFailed
+    /// The call ended because both sides moved to a call link call.
+    case endedEscalatedToCallLink
+    /// The remote side asked to turn the call into a video call.
+    case remoteVideoUpgradeRequested
+    /// The remote side withdrew its request to turn the call into a video call.
+    case remoteVideoUpgradeCanceled
+    /// The call is being turned into a video call, so the camera should be turned on.
+    case videoUpgradeAccepted
+    /// The remote side declined to turn the call into a video call.
+    case videoUpgradeDeclined
+    /// The remote side didn't answer the request to turn the call into a video call in time.
+    case videoUpgradeTimedOut
+    /// The remote side's microphone is unavailable, e.g. because it was unplugged.
+    case remoteMicrophoneDisconnected
+    /// The remote side's microphone is available again.
+    case remoteMicrophoneReconnected
+    /// The remote side's app went into the background, so its video is paused.
+    case remoteBackgrounded
+    /// The remote side's app came back into the foreground.
+    case remoteForegrounded
+    /// The remote side is switching cameras, so its video is briefly paused.
+    case remoteCameraSwitchStarted
+    /// The remote side has finished switching cameras.
+    case remoteCameraSwitchEnded
+    /// The call was put on hold locally, because another call became active.
+    case localHoldEnable
+    /// The call became the active call, after waiting or being on hold.
+    case localHoldDisable
 }
 
 // In sync with WebRTC's PeerConnection.AdapterType.
@@ -116,6 +154,26 @@ public struct NetworkRoute {
     }
 }
 
+/// Which ICE candidates a 1:1 call may use. The default allows all.
+@available(iOSApplicationExtension, unavailable)
+public struct CandidatePolicy {
+    /// Drop host candidates, which reveal local IP addresses.
+    public var excludeHost: Bool
+    /// Use relay (TURN) servers only, like hideIp.
+    public var relayOnly: Bool
+    /// Drop candidates that don't have an IPv4 address.
+    public var ipv4Only: Bool
+    /// Drop candidates on these types of network. Cellular matches all cellular types.
+    public var excludedAdapterTypes: [NetworkAdapterType]
+
+    public init(excludeHost: Bool = false, relayOnly: Bool = false, ipv4Only: Bool = false, excludedAdapterTypes: [NetworkAdapterType] = []) {
+        self.excludeHost = excludeHost
+        self.relayOnly = relayOnly
+        self.ipv4Only = ipv4Only
+        self.excludedAdapterTypes = excludedAdapterTypes
+    }
+}
+
 /// The data mode allows the client to limit the media bandwidth used.
 @available(iOSApplicationExtension, unavailable)
 public enum DataMode: Int32 {
@@ -140,6 +198,8 @@ public enum HangupType: Int32 {
     case busy = 3
     /// Call needed permission on a different device.
     case needPermission = 4
+    /// Call was handed off to a different device.
+    case handedOff = 5
 }
 
 @available(iOSApplicationExtension, unavailable)
@@ -227,6 +287,17 @@ public protocol CallManagerDelegate: AnyObject {
     @MainActor
     func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onLowBandwidthForVideoFor call: CallManagerDelegateCallType, recovered: Bool)
 
+    /**
+     * onEscalateToCallLinkFor will be invoked when the call is moving to the
+     * call link with the given root key, either because the local user asked
+     * for it or because the remote peer did.
+     *
+     * The application should join the call link; the 1:1 call ends with
+     * EndedEscalatedToCallLink once the remote peer has joined it too.
+     */
+    @MainActor
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, onEscalateToCallLinkFor call: CallManagerDelegateCallType, callId: UInt64, rootKey: CallLinkRootKey)
+
     /**
      * An Offer message should be sent to the given remote.
      * If there is any error, the UI can reset UI state and invoke the reset() API.
@@ -380,6 +451,40 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
+    /// Updates how long 1:1 calls ring and how long received rings stay valid.
+    ///
+    /// Calls that are already ringing keep the timeout they started with.
+    ///
+    /// - Parameters:
+    ///   - outgoingRingTimeoutMillis: How long an outgoing call rings before timing out, or 0 for no timeout
+    ///   - incomingRingTimeoutMillis: How long an incoming call rings before timing out, or 0 for no timeout
+    ///   - maxOfferAgeMillis: Offers and group rings older than this are treated as expired
+    ///   - groupRingExpiryMillis: How long a received group ring stays outstanding
+    @MainActor
+    public func setRingPolicy(outgoingRingTimeoutMillis: UInt64, incomingRingTimeoutMillis: UInt64, maxOfferAgeMillis: UInt64, groupRingExpiryMillis: UInt64) {
+        Logger.debug("setRingPolicy")
+
+        let retPtr = ringrtcSetRingPolicy(ringRtcCallManager, outgoingRingTimeoutMillis, incomingRingTimeoutMillis, maxOfferAgeMillis, groupRingExpiryMillis)
+        if retPtr == nil {
+            failDebug("setRingPolicy had an error")
+        }
+    }
+
+    /// Enables or disables call waiting for 1:1 calls.
+    ///
+    /// When enabled, an offer from a different peer that arrives while the active
+    /// call is connected is started as a waiting call instead of being rejected as
+    /// busy. Accepting the waiting call puts the active call on hold.
+    @MainActor
+    public func setCallWaitingEnabled(_ enabled: Bool) {
+        Logger.debug("setCallWaitingEnabled")
+
+        let retPtr = ringrtcSetCallWaitingEnabled(ringRtcCallManager, enabled)
+        if retPtr == nil {
+            failDebug("setCallWaitingEnabled had an error")
+        }
+    }
+
     deinit {
         // Close the RingRTC Call Manager.
         let retPtr = ringrtcClose(self.ringRtcCallManager)
@@ -413,6 +518,32 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         _ = unmanagedCall.retain()
     }
 
+    /// Take over a call that another of the local user's devices has with a
+    /// remote peer.
+    ///
+    /// The call keeps its callId. Once it is connected, the other device ends
+    /// its call with the endedHandedOffToAnotherDevice reason.
+    ///
+    /// - Parameters:
+    ///   - call: The application call context
+    ///   - callId: The callId of the call being taken over
+    ///   - callMediaType: The type of call (audio or video)
+    ///   - localDevice: The local device ID of the client (must be valid for lifetime of the call)
+    @MainActor
+    public func handoffCall(call: CallType, callId: UInt64, callMediaType: CallMediaType, localDevice: UInt32) throws {
+        Logger.debug("handoffCall")
+
+        let unmanagedCall: Unmanaged<CallType> = Unmanaged.passUnretained(call)
+
+        let retPtr = ringrtcHandoffCall(ringRtcCallManager, unmanagedCall.toOpaque(), callId, callMediaType.rawValue, localDevice)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "handoffCall() function failure")
+        }
+
+        // Keep the call reference around until rust says we're done with the call.
+        _ = unmanagedCall.retain()
+    }
+
     @MainActor
     public func accept(callId: UInt64) throws {
         Logger.debug("accept")
@@ -433,6 +564,111 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
+    /// Hang up a specific call, which may be active, waiting or held.
+    @MainActor
+    public func hangup(callId: UInt64) throws {
+        Logger.debug("hangup(callId:)")
+
+        let retPtr = ringrtcHangupCall(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "hangupCall() function failure")
+        }
+    }
+
+    /// Resume the held call, putting the active call (if any) on hold.
+    ///
+    /// To switch to a waiting call instead, accept it.
+    @MainActor
+    public func resumeCall(callId: UInt64) throws {
+        Logger.debug("resumeCall")
+
+        let retPtr = ringrtcResumeCall(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "resumeCall() function failure")
+        }
+    }
+
+    /// Ask the remote peer to turn the active call into a video call.
+    ///
+    /// The answer is reported with the videoUpgradeAccepted,
+    /// videoUpgradeDeclined or videoUpgradeTimedOut event.
+    @MainActor
+    public func requestVideoUpgrade(callId: UInt64) throws {
+        Logger.debug("requestVideoUpgrade")
+
+        let retPtr = ringrtcRequestVideoUpgrade(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "requestVideoUpgrade() function failure")
+        }
+    }
+
+    /// Accept the remote peer's request to turn the active call into a video
+    /// call, which enables video in the sender status.
+    @MainActor
+    public func acceptVideoUpgrade(callId: UInt64) throws {
+        Logger.debug("acceptVideoUpgrade")
+
+        let retPtr = ringrtcAcceptVideoUpgrade(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "acceptVideoUpgrade() function failure")
+        }
+    }
+
+    /// Decline the remote peer's request to turn the active call into a video call.
+    @MainActor
+    public func declineVideoUpgrade(callId: UInt64) throws {
+        Logger.debug("declineVideoUpgrade")
+
+        let retPtr = ringrtcDeclineVideoUpgrade(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "declineVideoUpgrade() function failure")
+        }
+    }
+
+    /// Tell the remote peer about conditions of the local devices that the
+    /// user didn't choose, such as an unplugged microphone.
+    ///
+    /// The remote peer is notified with the remoteMicrophoneDisconnected,
+    /// remoteBackgrounded and remoteCameraSwitchStarted events (and their
+    /// counterparts).
+    @MainActor
+    public func setLocalDeviceStatus(callId: UInt64, microphoneDisconnected: Bool, backgrounded: Bool, cameraSwitching: Bool) throws {
+        Logger.debug("setLocalDeviceStatus")
+
+        let retPtr = ringrtcSetLocalDeviceStatus(ringRtcCallManager, callId, microphoneDisconnected, backgrounded, cameraSwitching)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setLocalDeviceStatus() function failure")
+        }
+    }
+
+    /// Move the active call to a call link call.
+    ///
+    /// If `create` is given, the call link is created first; otherwise it must
+    /// already exist. Both peers are told to join the call link, and the 1:1
+    /// call ends once the remote peer has joined it.
+    @MainActor
+    public func escalateToCallLink(callId: UInt64, linkRootKey: CallLinkRootKey, create: CallLinkCreateParams?) throws {
+        Logger.debug("escalateToCallLink")
+
+        let rootKeySlice = allocatedAppByteSliceFromData(maybe_data: linkRootKey.bytes)
+        let sfuUrlSlice = allocatedAppByteSliceFromString(maybe_string: create?.sfuUrl)
+        let presentationSlice = allocatedAppByteSliceFromArray(maybe_bytes: create?.createCredentialPresentation)
+        let adminPasskeySlice = allocatedAppByteSliceFromData(maybe_data: create?.adminPasskey)
+        let publicParamsSlice = allocatedAppByteSliceFromArray(maybe_bytes: create?.callLinkPublicParams)
+        defer {
+            rootKeySlice.bytes?.deallocate()
+            sfuUrlSlice.bytes?.deallocate()
+            presentationSlice.bytes?.deallocate()
+            adminPasskeySlice.bytes?.deallocate()
+            publicParamsSlice.bytes?.deallocate()
+        }
+
+        let retPtr = ringrtcEscalateToCallLink(ringRtcCallManager, callId, rootKeySlice, sfuUrlSlice, presentationSlice, adminPasskeySlice, publicParamsSlice, create?.restrictions.toOrdinal() ?? -1)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "escalateToCallLink() function failure")
+        }
+    }
+
     @MainActor
     public func cancelGroupRing(groupId: Data, ringId: Int64, reason: RingCancelReason?) throws {
         Logger.debug("cancelGroupRing")
@@ -457,9 +693,11 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
     ///   - videoCaptureController: UI provided capturer interface
     ///   - dataMode: The desired data mode to start the session with
     ///   - audioLevelsIntervalMillis: If non-zero, the desired interval between audio level events (in milliseconds)
+    ///   - candidatePolicy: Which ICE candidates the call may use
     @MainActor
-    public func proceed(callId: UInt64, iceServers: [RTCIceServer], hideIp: Bool, videoCaptureController: VideoCaptureController, dataMode: DataMode, audioLevelsIntervalMillis: UInt64?) throws {
+    public func proceed(callId: UInt64, iceServers: [RTCIceServer], hideIp: Bool, videoCaptureController: VideoCaptureController, dataMode: DataMode, audioLevelsIntervalMillis: UInt64?, candidatePolicy: CandidatePolicy = CandidatePolicy()) throws {
         Logger.info("proceed(): callId: 0x\(String(callId, radix: 16)), hideIp: \(hideIp)")
+        Logger.info("  candidatePolicy: \(candidatePolicy)")
         for iceServer in iceServers {
             for url in iceServer.urlStrings {
                 Logger.info("  server: \(url)");
@@ -490,9 +728,11 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         // Create a call context object to hold on to some of
         // the settings needed by the application when actually
         // creating the connection.
-        let appCallContext = CallContext(iceServers: iceServers, hideIp: hideIp, audioSource: audioSource, audioTrack: audioTrack, videoSource: videoSource, videoTrack: videoTrack, videoCaptureController: videoCaptureController)
+        // The relay-only policy is enforced by the ICE transport policy, like hideIp.
+        let appCallContext = CallContext(iceServers: iceServers, hideIp: hideIp || candidatePolicy.relayOnly, audioSource: audioSource, audioTrack: audioTrack, videoSource: videoSource, videoTrack: videoTrack, videoCaptureController: videoCaptureController)
 
-        let retPtr = ringrtcProceed(ringRtcCallManager, callId, appCallContext.getWrapper(), dataMode.rawValue, audioLevelsIntervalMillis ?? 0)
+        let excludedAdapterTypes = candidatePolicy.excludedAdapterTypes.reduce(0) { $0 | UInt32($1.rawValue) }
+        let retPtr = ringrtcProceed(ringRtcCallManager, callId, appCallContext.getWrapper(), dataMode.rawValue, audioLevelsIntervalMillis ?? 0, candidatePolicy.excludeHost, candidatePolicy.relayOnly, candidatePolicy.ipv4Only, excludedAdapterTypes)
         if retPtr == nil {
             throw CallManagerError.apiFailed(description: "proceed() function failure")
         }
@@ -841,6 +1081,24 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
+    func onEscalateToCallLinkFor(remote: UnsafeRawPointer, callId: UInt64, rootKey: Data) {
+        Logger.debug("onEscalateToCallLink")
+
+        Task { @MainActor in
+            Logger.debug("onEscalateToCallLink - main.async")
+
+            guard let delegate = self.delegate else { return }
+
+            guard let rootKey = try? CallLinkRootKey(rootKey) else {
+                failDebug("rootKey was invalid")
+                return
+            }
+
+            let callReference: CallType = Unmanaged.fromOpaque(remote).takeUnretainedValue()
+            delegate.callManager(self, onEscalateToCallLinkFor: callReference, callId: callId, rootKey: rootKey)
+        }
+    }
+
     // MARK: - Signaling Observers
 
     func onSendOffer(callId: UInt64, remote: UnsafeRawPointer, destinationDeviceId: UInt32?, opaque: Data, callMediaType: CallMediaType) {
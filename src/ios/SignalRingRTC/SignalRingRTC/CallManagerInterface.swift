@@ -13,6 +13,7 @@ protocol CallManagerInterfaceDelegate: AnyObject {
     func onNetworkRouteChangedFor(remote: UnsafeRawPointer, networkRoute: NetworkRoute)
     func onAudioLevelsFor(remote: UnsafeRawPointer, capturedLevel: UInt16, receivedLevel: UInt16)
     func onLowBandwidthForVideoFor(remote: UnsafeRawPointer, recovered: Bool)
+    func onEscalateToCallLinkFor(remote: UnsafeRawPointer, callId: UInt64, rootKey: Data)
     func onSendOffer(callId: UInt64, remote: UnsafeRawPointer, destinationDeviceId: UInt32?, opaque: Data, callMediaType: CallMediaType)
     func onSendAnswer(callId: UInt64, remote: UnsafeRawPointer, destinationDeviceId: UInt32?, opaque: Data)
     func onSendIceCandidates(callId: UInt64, remote: UnsafeRawPointer, destinationDeviceId: UInt32?, candidates: [Data])
@@ -70,6 +71,7 @@ class CallManagerInterface {
             onNetworkRouteChanged: callManagerInterfaceOnNetworkRouteChanged,
             onAudioLevels: callManagerInterfaceOnAudioLevels,
             onLowBandwidthForVideo: callManagerInterfaceOnLowBandwidthForVideo,
+            onEscalateToCallLink: callManagerInterfaceOnEscalateToCallLink,
             onSendOffer: callManagerInterfaceOnSendOffer,
             onSendAnswer: callManagerInterfaceOnSendAnswer,
             onSendIceCandidates: callManagerInterfaceOnSendIceCandidates,
@@ -155,6 +157,14 @@ class CallManagerInterface {
         delegate.onLowBandwidthForVideoFor(remote: remote, recovered: recovered)
     }
 
+    func onEscalateToCallLinkFor(remote: UnsafeRawPointer, callId: UInt64, rootKey: Data) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.onEscalateToCallLinkFor(remote: remote, callId: callId, rootKey: rootKey)
+    }
+
     func onSendOffer(callId: UInt64, remote: UnsafeRawPointer, destinationDeviceId: UInt32?, opaque: Data, callMediaType: CallMediaType) {
         guard let delegate = self.callManagerObserverDelegate else {
             return
@@ -458,6 +468,27 @@ func callManagerInterfaceOnLowBandwidthForVideo(object: UnsafeMutableRawPointer?
     obj.onLowBandwidthForVideoFor(remote: remote, recovered: recovered)
 }
 
+@available(iOSApplicationExtension, unavailable)
+func callManagerInterfaceOnEscalateToCallLink(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, callId: UInt64, rootKey: AppByteSlice) {
+    guard let object = object else {
+        failDebug("object was unexpectedly nil")
+        return
+    }
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remote = remote else {
+        failDebug("remote was unexpectedly nil")
+        return
+    }
+
+    guard let rootKey = rootKey.asData() else {
+        failDebug("rootKey was unexpectedly nil")
+        return
+    }
+
+    obj.onEscalateToCallLinkFor(remote: remote, callId: callId, rootKey: rootKey)
+}
+
 @available(iOSApplicationExtension, unavailable)
 func callManagerInterfaceOnSendOffer(object: UnsafeMutableRawPointer?, callId: UInt64, remote: UnsafeRawPointer?, destinationDeviceId: UInt32, broadcast: Bool, opaque: AppByteSlice, mediaType: Int32) {
     guard let object = object else {
@@ -918,7 +949,12 @@ func callManagerInterfaceHandleReactions(object: UnsafeMutableRawPointer?, clien
             continue
         }
 
-        finalReactions.append(Reaction(demuxId: reaction.demuxId, value: value))
+        var senderDemuxIds: [UInt32] = []
+        for senderIndex in 0..<reaction.senderDemuxIdsCount {
+            senderDemuxIds.append(reaction.senderDemuxIds[senderIndex])
+        }
+
+        finalReactions.append(Reaction(demuxId: reaction.demuxId, value: value, count: reaction.count, senderDemuxIds: senderDemuxIds))
     }
 
     obj.handleReactions(clientId: clientId, reactions: finalReactions)
@@ -78,12 +78,19 @@ public class ReceivedAudioLevel {
 
 @available(iOSApplicationExtension, unavailable)
 public class Reaction {
+    /// The device that sent the reaction first.
     public let demuxId: UInt32
     public let value: String
+    /// How many times the reaction was received since the last notification, at least 1.
+    public let count: UInt32
+    /// The distinct devices that sent the reaction, in the order they were first received.
+    public let senderDemuxIds: [UInt32]
 
-    init(demuxId: UInt32, value: String) {
+    init(demuxId: UInt32, value: String, count: UInt32, senderDemuxIds: [UInt32]) {
         self.demuxId = demuxId
         self.value = value
+        self.count = count
+        self.senderDemuxIds = senderDemuxIds
     }
 }
 
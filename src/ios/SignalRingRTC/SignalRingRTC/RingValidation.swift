@@ -13,19 +13,30 @@ public enum CallMediaType: Int32 {
     case videoCall = 1
 }
 
-public func isValidOfferMessage(opaque: Data, messageAgeSec: UInt64, callMediaType: CallMediaType) -> Bool {
+/// The default for `maxOfferAgeMillis`, matching the default ring policy.
+public let defaultMaxOfferAgeMillis: UInt64 = 60_000
+
+/// Checks an offer without a CallManager, e.g. from a notification extension.
+///
+/// Pass the same `maxOfferAgeMillis` as given to `CallManager.setRingPolicy`.
+public func isValidOfferMessage(opaque: Data, messageAgeSec: UInt64, callMediaType: CallMediaType, maxOfferAgeMillis: UInt64 = defaultMaxOfferAgeMillis) -> Bool {
     Logger.debug("")
 
     return opaque.withUnsafeBytes { buffer in
         ringrtcIsValidOffer(AppByteSlice(bytes: buffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
                                          len: buffer.count),
                             messageAgeSec,
-                            callMediaType.rawValue)
+                            callMediaType.rawValue,
+                            maxOfferAgeMillis)
     }
 }
 
+/// Checks a group ring without a CallManager, e.g. from a notification extension.
+///
+/// Pass the same `maxOfferAgeMillis` as given to `CallManager.setRingPolicy`.
 public func isValidOpaqueRing(opaqueCallMessage: Data,
                               messageAgeSec: UInt64,
+                              maxOfferAgeMillis: UInt64 = defaultMaxOfferAgeMillis,
                               validateGroupRing: (_ groupId: Data, _ ringId: Int64) -> Bool) -> Bool {
     // Use a pointer to the argument to pass a closure down through a C-based interface;
     // withoutActuallyEscaping promises the compiler we won't persist it.
@@ -40,6 +51,7 @@ public func isValidOpaqueRing(opaqueCallMessage: Data,
                                                len: buffer.count)
                 return ringrtcIsCallMessageValidOpaqueRing(opaqueSlice,
                                                            messageAgeSec,
+                                                           maxOfferAgeMillis,
                                                            UnsafeMutableRawPointer(mutating: validateGroupRingPtr)) {
                     (groupId, ringId, context) in
                     let innerValidate = context!.assumingMemoryBound(to: CallbackType.self)
@@ -336,6 +336,65 @@ final class TestDelegate: CallManagerDelegate & HTTPDelegate {
         case .receivedOfferWithGlare:
             Logger.debug("TestDelegate:receivedOfferWithGlare")
             eventReceivedOfferWithGlare = true
+
+        case .remoteHoldEnable:
+            Logger.debug("TestDelegate:remoteHoldEnable")
+
+        case .remoteHoldDisable:
+            Logger.debug("TestDelegate:remoteHoldDisable")
+
+        case .endedHandedOffToAnotherDevice:
+            Logger.debug("TestDelegate:endedHandedOffToAnotherDevice")
+            eventGeneralEnded = true
+
+        case .escalationRemoteJoined:
+            Logger.debug("TestDelegate:escalationRemoteJoined")
+
+        case .escalationFailed:
+            Logger.debug("TestDelegate:escalationFailed")
+
+        case .endedEscalatedToCallLink:
+            Logger.debug("TestDelegate:endedEscalatedToCallLink")
+            eventGeneralEnded = true
+
+        case .remoteVideoUpgradeRequested:
+            Logger.debug("TestDelegate:remoteVideoUpgradeRequested")
+
+        case .remoteVideoUpgradeCanceled:
+            Logger.debug("TestDelegate:remoteVideoUpgradeCanceled")
+
+        case .videoUpgradeAccepted:
+            Logger.debug("TestDelegate:videoUpgradeAccepted")
+
+        case .videoUpgradeDeclined:
+            Logger.debug("TestDelegate:videoUpgradeDeclined")
+
+        case .videoUpgradeTimedOut:
+            Logger.debug("TestDelegate:videoUpgradeTimedOut")
+
+        case .remoteMicrophoneDisconnected:
+            Logger.debug("TestDelegate:remoteMicrophoneDisconnected")
+
+        case .remoteMicrophoneReconnected:
+            Logger.debug("TestDelegate:remoteMicrophoneReconnected")
+
+        case .remoteBackgrounded:
+            Logger.debug("TestDelegate:remoteBackgrounded")
+
+        case .remoteForegrounded:
+            Logger.debug("TestDelegate:remoteForegrounded")
+
+        case .remoteCameraSwitchStarted:
+            Logger.debug("TestDelegate:remoteCameraSwitchStarted")
+
+        case .remoteCameraSwitchEnded:
+            Logger.debug("TestDelegate:remoteCameraSwitchEnded")
+
+        case .localHoldEnable:
+            Logger.debug("TestDelegate:localHoldEnable")
+
+        case .localHoldDisable:
+            Logger.debug("TestDelegate:localHoldDisable")
         }
     }
 
@@ -351,6 +410,10 @@ final class TestDelegate: CallManagerDelegate & HTTPDelegate {
         Logger.debug("TestDelegate:onLowBandwidthForVideoFor - \(recovered)")
     }
 
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, onEscalateToCallLinkFor call: OpaqueCallData, callId: UInt64, rootKey: CallLinkRootKey) {
+        Logger.debug("TestDelegate:onEscalateToCallLinkFor - \(callId)")
+    }
+
     func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, shouldSendOffer callId: UInt64, call: OpaqueCallData, destinationDeviceId: UInt32?, opaque: Data, callMediaType: CallMediaType) {
         Logger.debug("TestDelegate:shouldSendOffer")
         generalInvocationDetected = true
@@ -8,16 +8,23 @@ import { RingRTCType } from './ringrtc/Service';
 export {
   AnswerMessage,
   AudioDevice,
+  AudioDevicesChange,
   DataMode,
   BusyMessage,
   Call,
   CallEndedReason,
+  CallEscalationState,
   CallId,
+  CallLinkCreateParams,
   CallLogLevel,
   CallMessageUrgency,
+  CallRemoteDeviceCondition,
   CallSettings,
   CallState,
+  CandidatePolicy,
+  CallVideoUpgradeState,
   CallingMessage,
+  ChatMessage,
   ConnectionState,
   DeviceId,
   GroupCall,
@@ -32,6 +39,8 @@ export {
   IceCandidateMessage,
   JoinState,
   LocalDeviceState,
+  LocalDeviceStatus,
+  NetworkAdapterType,
   OfferMessage,
   OfferType,
   OpaqueMessage,
@@ -39,10 +48,16 @@ export {
   PeekInfo,
   PeekStatusCodes,
   Reaction,
+  ReactionRateLimit,
   RemoteDeviceState,
+  RemoteSpeakingStats,
   RingCancelReason,
+  RingPolicy,
   RingRTCType,
   RingUpdate,
+  SpeakerTransition,
+  SpeakingStats,
+  SpeakingSummary,
   UserId,
   VideoCapturer,
   VideoRenderer,
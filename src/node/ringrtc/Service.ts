@@ -28,6 +28,8 @@ export function callIdFromRingId(ringId: bigint): CallId {
 class Config {
   field_trials: Record<string, string> | undefined;
   use_ringrtc_adm?: boolean;
+  // Only takes effect with the RingRTC ADM, on devices that support stereo.
+  use_stereo_audio?: boolean;
 }
 
 class NativeCallManager {
@@ -65,10 +67,15 @@ class NativeCallManager {
       get() {
         const use_ringrtc_adm =
           config.use_ringrtc_adm === undefined ? false : config.use_ringrtc_adm;
+        const use_stereo_audio =
+          config.use_stereo_audio === undefined
+            ? false
+            : config.use_stereo_audio;
         const callEndpoint = Native.createCallEndpoint(
           this,
           fieldTrialsString,
-          use_ringrtc_adm
+          use_ringrtc_adm,
+          use_stereo_audio
         );
 
         Object.defineProperty(this, Native.callEndpointPropertyKey, {
@@ -85,12 +92,28 @@ class NativeCallManager {
 // Mirror methods onto NativeCallManager.
 // This is done through direct assignment rather than wrapper methods to avoid indirection.
 (NativeCallManager.prototype as any).setSelfUuid = Native.cm_setSelfUuid;
+(NativeCallManager.prototype as any).setRingPolicy = Native.cm_setRingPolicy;
+(NativeCallManager.prototype as any).setCallWaitingEnabled =
+  Native.cm_setCallWaitingEnabled;
 (NativeCallManager.prototype as any).createOutgoingCall =
   Native.cm_createOutgoingCall;
+(NativeCallManager.prototype as any).handoffCall = Native.cm_handoffCall;
 (NativeCallManager.prototype as any).proceed = Native.cm_proceed;
 (NativeCallManager.prototype as any).accept = Native.cm_accept;
 (NativeCallManager.prototype as any).ignore = Native.cm_ignore;
 (NativeCallManager.prototype as any).hangup = Native.cm_hangup;
+(NativeCallManager.prototype as any).hangupCall = Native.cm_hangupCall;
+(NativeCallManager.prototype as any).resumeCall = Native.cm_resumeCall;
+(NativeCallManager.prototype as any).requestVideoUpgrade =
+  Native.cm_requestVideoUpgrade;
+(NativeCallManager.prototype as any).acceptVideoUpgrade =
+  Native.cm_acceptVideoUpgrade;
+(NativeCallManager.prototype as any).declineVideoUpgrade =
+  Native.cm_declineVideoUpgrade;
+(NativeCallManager.prototype as any).setLocalDeviceStatus =
+  Native.cm_setLocalDeviceStatus;
+(NativeCallManager.prototype as any).escalateToCallLink =
+  Native.cm_escalateToCallLink;
 (NativeCallManager.prototype as any).cancelGroupRing =
   Native.cm_cancelGroupRing;
 (NativeCallManager.prototype as any).signalingMessageSent =
@@ -133,6 +156,8 @@ class NativeCallManager {
 (NativeCallManager.prototype as any).disconnect = Native.cm_disconnect;
 (NativeCallManager.prototype as any).groupRing = Native.cm_groupRing;
 (NativeCallManager.prototype as any).groupReact = Native.cm_groupReact;
+(NativeCallManager.prototype as any).groupSendChatMessage =
+  Native.cm_groupSendChatMessage;
 (NativeCallManager.prototype as any).groupRaiseHand = Native.cm_groupRaiseHand;
 (NativeCallManager.prototype as any).setOutgoingAudioMuted =
   Native.cm_setOutgoingAudioMuted;
@@ -149,6 +174,9 @@ class NativeCallManager {
 (NativeCallManager.prototype as any).denyUser = Native.cm_denyUser;
 (NativeCallManager.prototype as any).removeClient = Native.cm_removeClient;
 (NativeCallManager.prototype as any).blockClient = Native.cm_blockClient;
+(NativeCallManager.prototype as any).lowerHand = Native.cm_lowerHand;
+(NativeCallManager.prototype as any).lowerAllHands = Native.cm_lowerAllHands;
+(NativeCallManager.prototype as any).requestMute = Native.cm_requestMute;
 (NativeCallManager.prototype as any).setGroupMembers =
   Native.cm_setGroupMembers;
 (NativeCallManager.prototype as any).setMembershipProof =
@@ -162,12 +190,24 @@ class NativeCallManager {
   Native.cm_peekCallLinkCall;
 (NativeCallManager.prototype as any).getAudioInputs = Native.cm_getAudioInputs;
 (NativeCallManager.prototype as any).setAudioInput = Native.cm_setAudioInput;
+(NativeCallManager.prototype as any).getActiveAudioInput =
+  Native.cm_getActiveAudioInput;
 (NativeCallManager.prototype as any).getAudioOutputs =
   Native.cm_getAudioOutputs;
 (NativeCallManager.prototype as any).setAudioOutput = Native.cm_setAudioOutput;
+(NativeCallManager.prototype as any).getActiveAudioOutput =
+  Native.cm_getActiveAudioOutput;
 (NativeCallManager.prototype as any).processEvents = Native.cm_processEvents;
 (NativeCallManager.prototype as any).setRtcStatsInterval =
   Native.cm_setRtcStatsInterval;
+(NativeCallManager.prototype as any).setConnectionRecoveryWindow =
+  Native.cm_setConnectionRecoveryWindow;
+(NativeCallManager.prototype as any).setReactionRateLimits =
+  Native.cm_setReactionRateLimits;
+(NativeCallManager.prototype as any).setReactionAggregationWindow =
+  Native.cm_setReactionAggregationWindow;
+(NativeCallManager.prototype as any).groupGetSpeakingSummary =
+  Native.cm_groupGetSpeakingSummary;
 
 type GroupId = Buffer;
 type GroupCallUserId = Buffer;
@@ -178,8 +218,73 @@ export interface PeekDeviceInfo {
 }
 
 export interface Reaction {
+  // The device that sent the reaction first.
   demuxId: number;
+  // A single emoji.
   value: string;
+  // How many times the reaction was received.
+  count: number;
+  // The distinct devices that sent it, in the order they were first received.
+  senderDemuxIds: Array<number>;
+}
+
+// How long 1:1 calls ring and how long received rings stay valid.
+// A ring timeout of 0 means no timeout.
+export interface RingPolicy {
+  outgoingRingTimeoutMillis: number;
+  incomingRingTimeoutMillis: number;
+  // Offers and group rings older than this are treated as expired.
+  maxOfferAgeMillis: number;
+  // How long a received group ring stays outstanding.
+  groupRingExpiryMillis: number;
+}
+
+// Allows up to burst reactions at once, with one more every intervalMillis.
+// An intervalMillis of 0 means no limit.
+export interface ReactionRateLimit {
+  burst: number;
+  intervalMillis: number;
+}
+
+export interface ChatMessage {
+  demuxId: number;
+  // Unique among the messages sent by the device with demuxId.
+  id: number;
+  // Milliseconds since the epoch, according to the sender's clock.
+  timestamp: number;
+  text: string;
+}
+
+export interface SpeakingStats {
+  // How long the device was the active speaker, according to the SFU.
+  activeSpeakerMillis: number;
+  // How many times the device became the active speaker.
+  turns: number;
+  // How long the device's audio level was high enough to count as speaking.
+  // Only accumulated while audio levels are being polled.
+  talkMillis: number;
+}
+
+export interface RemoteSpeakingStats extends SpeakingStats {
+  demuxId: number;
+  userId: GroupCallUserId;
+  // Milliseconds since the epoch; only set if the device has left the call.
+  leftTime?: number;
+}
+
+export interface SpeakerTransition {
+  demuxId: number;
+  isLocal: boolean;
+  // Milliseconds since the epoch.
+  time: number;
+}
+
+export interface SpeakingSummary {
+  local: SpeakingStats;
+  // Every remote device seen during the call, including those that have left.
+  remote: Array<RemoteSpeakingStats>;
+  // The most recent changes of active speaker, oldest first.
+  transitions: Array<SpeakerTransition>;
 }
 
 /** type returned by Rust */
@@ -227,7 +332,7 @@ export enum PeekStatusCodes {
 //   but it's because we bound to the default IP address (0.0.0.0)
 //   so it's probably the default adapter (wifi if available, for example)
 //   This is unlikely to happen in practice.
-enum NetworkAdapterType {
+export enum NetworkAdapterType {
   Unknown = 0,
   Ethernet = 1 << 0,
   Wifi = 1 << 1,
@@ -358,6 +463,8 @@ export type HttpResult<T> =
 export class RingRTCType {
   private readonly callManager: CallManager;
   private _call: Call | null;
+  // The waiting or held call when call waiting is enabled.
+  private _otherCall: Call | null;
   private _groupCallByClientId: Map<GroupCallClientId, GroupCall>;
   private _peekRequests: Requests<HttpResult<PeekInfo>>;
   private _callLinkRequests: Requests<HttpResult<CallLinkState>>;
@@ -442,9 +549,14 @@ export class RingRTCType {
 
   handleRtcStatsReport: ((reportJson: string) => void) | null = null;
 
+  handleAudioDevicesChanged:
+    | ((change: AudioDevicesChange) => void)
+    | null = null;
+
   constructor() {
     this.callManager = new NativeCallManager(this) as unknown as CallManager;
     this._call = null;
+    this._otherCall = null;
     this._groupCallByClientId = new Map();
     this._peekRequests = new Requests();
     this._callLinkRequests = new Requests();
@@ -461,6 +573,25 @@ export class RingRTCType {
     this.callManager.setSelfUuid(uuid);
   }
 
+  // Called by UX
+  // Calls that are already ringing keep the timeout they started with.
+  setRingPolicy(policy: RingPolicy): void {
+    this.callManager.setRingPolicy(
+      policy.outgoingRingTimeoutMillis,
+      policy.incomingRingTimeoutMillis,
+      policy.maxOfferAgeMillis,
+      policy.groupRingExpiryMillis
+    );
+  }
+
+  // Called by UX
+  // When enabled, an incoming call from someone else while connected to a
+  // call is started as a waiting call instead of being rejected as busy.
+  // Accepting it puts the current call on hold; see resumeCall.
+  setCallWaitingEnabled(enabled: boolean): void {
+    this.callManager.setCallWaitingEnabled(enabled);
+  }
+
   // Called by UX
   startOutgoingCall(
     remoteUserId: UserId,
@@ -486,6 +617,36 @@ export class RingRTCType {
     return call;
   }
 
+  // Called by UX
+  // Takes over a call that another of the user's devices has with the remote
+  // user. The call keeps its CallId, and the other device's call ends once
+  // this one is connected.
+  handoffCall(
+    remoteUserId: UserId,
+    callId: CallId,
+    isVideoCall: boolean,
+    localDeviceId: DeviceId
+  ): Call {
+    this.callManager.handoffCall(
+      remoteUserId,
+      callId,
+      isVideoCall,
+      localDeviceId
+    );
+    const isIncoming = false;
+    const call = new Call(
+      this.callManager,
+      remoteUserId,
+      callId,
+      isIncoming,
+      isVideoCall,
+      CallState.Prering
+    );
+    this._call = call;
+    call.outgoingVideoEnabled = isVideoCall;
+    return call;
+  }
+
   // Called by UX
   cancelGroupRing(
     groupId: GroupId,
@@ -568,7 +729,17 @@ export class RingRTCType {
       call.ignore();
       return;
     }
-    this._call = call;
+    if (
+      this._call &&
+      this._call.state !== CallState.Ended &&
+      this._call.remoteUserId !== remoteUserId
+    ) {
+      // RingRTC only starts a second call when call waiting is enabled.
+      call.waitingOrHeld = true;
+      this._otherCall = call;
+    } else {
+      this._call = call;
+    }
 
     handleIncomingCall(call)
       .then(success => {
@@ -606,15 +777,16 @@ export class RingRTCType {
         settings.iceServers,
         settings.hideIp,
         settings.dataMode,
-        settings.audioLevelsIntervalMillis || 0
+        settings.audioLevelsIntervalMillis || 0,
+        settings.candidatePolicy || {}
       );
     });
   }
 
   // Called by Rust
   onCallState(remoteUserId: UserId, state: CallState): void {
-    const call = this._call;
-    if (!call || call.remoteUserId !== remoteUserId) {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
       return;
     }
     call.state = state;
@@ -635,6 +807,20 @@ export class RingRTCType {
     };
     this._callInfoByCallId.delete(this.getCallInfoKey(callId));
 
+    const otherCall = this._otherCall;
+    if (
+      otherCall &&
+      otherCall.remoteUserId === remoteUserId &&
+      otherCall.callId.high === callId.high &&
+      otherCall.callId.low === callId.low
+    ) {
+      // The waiting or held call ended; the current call carries on.
+      this._otherCall = null;
+      otherCall.endedReason = reason;
+      otherCall.state = CallState.Ended;
+      return;
+    }
+
     const call = this._call;
     if (call && reason == CallEndedReason.ReceivedOfferWithGlare) {
       // The current call is the outgoing call.
@@ -692,11 +878,21 @@ export class RingRTCType {
     // call.handleStateChanged, which may look at call.endedReason.
     call.endedReason = reason;
     call.state = CallState.Ended;
+
+    if (otherCall) {
+      // A waiting call becomes the active call, but a held call stays on
+      // hold until it is resumed.
+      this._otherCall = null;
+      this._call = otherCall;
+      otherCall.waitingOrHeld =
+        otherCall.state === CallState.Accepted ||
+        otherCall.state === CallState.Reconnecting;
+    }
   }
 
   onRemoteAudioEnabled(remoteUserId: UserId, enabled: boolean): void {
-    const call = this._call;
-    if (!call || call.remoteUserId !== remoteUserId) {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
       return;
     }
 
@@ -707,8 +903,8 @@ export class RingRTCType {
   }
 
   onRemoteVideoEnabled(remoteUserId: UserId, enabled: boolean): void {
-    const call = this._call;
-    if (!call || call.remoteUserId !== remoteUserId) {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
       return;
     }
 
@@ -719,8 +915,8 @@ export class RingRTCType {
   }
 
   onRemoteSharingScreen(remoteUserId: UserId, enabled: boolean): void {
-    const call = this._call;
-    if (!call || call.remoteUserId !== remoteUserId) {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
       return;
     }
 
@@ -730,12 +926,79 @@ export class RingRTCType {
     }
   }
 
+  onRemoteHeld(remoteUserId: UserId, held: boolean): void {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
+      return;
+    }
+
+    call.remoteHeld = held;
+    if (call.handleRemoteHeld) {
+      call.handleRemoteHeld();
+    }
+  }
+
+  // Called by Rust when a call is put on hold or becomes the active call.
+  onLocalHeld(remoteUserId: UserId, held: boolean): void {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
+      return;
+    }
+
+    if (!held && call === this._otherCall) {
+      this.swapToOtherCall();
+    } else {
+      call.waitingOrHeld = held;
+    }
+  }
+
+  onEscalation(
+    remoteUserId: UserId,
+    state: CallEscalationState,
+    rootKey: Uint8Array | null
+  ): void {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
+      return;
+    }
+
+    if (call.handleEscalation) {
+      call.handleEscalation(state, rootKey || undefined);
+    }
+  }
+
+  onVideoUpgrade(remoteUserId: UserId, state: CallVideoUpgradeState): void {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
+      return;
+    }
+
+    if (call.handleVideoUpgrade) {
+      call.handleVideoUpgrade(state);
+    }
+  }
+
+  onRemoteDeviceCondition(
+    remoteUserId: UserId,
+    condition: CallRemoteDeviceCondition,
+    active: boolean
+  ): void {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
+      return;
+    }
+
+    if (call.handleRemoteDeviceCondition) {
+      call.handleRemoteDeviceCondition(condition, active);
+    }
+  }
+
   onNetworkRouteChanged(
     remoteUserId: UserId,
     localNetworkAdapterType: NetworkAdapterType
   ): void {
-    const call = this._call;
-    if (!call || call.remoteUserId !== remoteUserId) {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
       return;
     }
 
@@ -750,8 +1013,8 @@ export class RingRTCType {
     capturedLevel: RawAudioLevel,
     receivedLevel: RawAudioLevel
   ): void {
-    const call = this._call;
-    if (!call || call.remoteUserId !== remoteUserId) {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
       return;
     }
 
@@ -763,8 +1026,8 @@ export class RingRTCType {
   }
 
   onLowBandwidthForVideo(remoteUserId: UserId, recovered: boolean): void {
-    const call = this._call;
-    if (!call || call.remoteUserId !== remoteUserId) {
+    const call = this.getCallByRemoteUserId(remoteUserId);
+    if (!call) {
       return;
     }
 
@@ -773,6 +1036,23 @@ export class RingRTCType {
     }
   }
 
+  // Called by Rust
+  onAudioDevicesChanged(
+    outputs: Array<AudioDevice>,
+    inputs: Array<AudioDevice>,
+    outputFellBackToDefault: boolean,
+    inputFellBackToDefault: boolean
+  ): void {
+    if (this.handleAudioDevicesChanged) {
+      this.handleAudioDevicesChanged({
+        outputs,
+        inputs,
+        outputFellBackToDefault,
+        inputFellBackToDefault,
+      });
+    }
+  }
+
   renderVideoFrame(width: number, height: number, buffer: Buffer): void {
     const call = this._call;
     if (!call) {
@@ -1377,6 +1657,19 @@ export class RingRTCType {
     });
   }
 
+  // Called by Rust
+  handleChatMessages(
+    clientId: GroupCallClientId,
+    messages: Array<ChatMessage>
+  ): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleChatMessages(messages);
+      }
+    });
+  }
+
   // Called by Rust
   handleRaisedHands(
     clientId: GroupCallClientId,
@@ -1390,6 +1683,19 @@ export class RingRTCType {
     });
   }
 
+  // Called by Rust
+  handleRemoteMuteRequest(
+    clientId: GroupCallClientId,
+    muteSource: number
+  ): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleRemoteMuteRequest(muteSource);
+      }
+    });
+  }
+
   // Called by Rust
   handleRemoteDevicesChanged(
     clientId: GroupCallClientId,
@@ -1767,19 +2073,54 @@ export class RingRTCType {
     return this._call;
   }
 
+  // The waiting or held call, if call waiting is enabled.
+  get waitingOrHeldCall(): Call | null {
+    return this._otherCall;
+  }
+
   getCall(callId: CallId): Call | null {
-    const call = this.call;
+    for (const call of [this._call, this._otherCall]) {
+      if (
+        call &&
+        call.callId.high === callId.high &&
+        call.callId.low === callId.low
+      ) {
+        return call;
+      }
+    }
+    return null;
+  }
 
-    if (
-      call &&
-      call.callId.high === callId.high &&
-      call.callId.low === callId.low
-    ) {
-      return call;
+  private getCallByRemoteUserId(remoteUserId: UserId): Call | null {
+    for (const call of [this._call, this._otherCall]) {
+      if (call && call.remoteUserId === remoteUserId) {
+        return call;
+      }
     }
     return null;
   }
 
+  // Makes the waiting or held call the current call, following RingRTC
+  // putting the current call on hold.
+  private swapToOtherCall(): void {
+    const previousCall = this._call;
+    const otherCall = this._otherCall;
+    if (!otherCall) {
+      return;
+    }
+
+    otherCall.waitingOrHeld = false;
+    this._call = otherCall;
+    if (previousCall && previousCall.state !== CallState.Ended) {
+      previousCall.waitingOrHeld = true;
+      this._otherCall = previousCall;
+    } else {
+      this._otherCall = null;
+    }
+  }
+
+  // Accepting the waiting call puts the current call on hold, once RingRTC
+  // reports the change with onLocalHeld.
   accept(callId: CallId, asVideoCall: boolean): void {
     const call = this.getCall(callId);
     if (!call) {
@@ -1791,6 +2132,83 @@ export class RingRTCType {
     call.outgoingVideoEnabled = asVideoCall;
   }
 
+  // Takes the held call off hold, putting the current call (if any) on hold,
+  // once RingRTC reports the change with onLocalHeld.
+  resumeCall(callId: CallId): void {
+    sillyDeadlockProtection(() => {
+      this.callManager.resumeCall(callId);
+    });
+  }
+
+  // Moves the connected call to a call link call. If `create` is given, the
+  // call link is created first; otherwise it must already exist. Progress is
+  // reported to call.handleEscalation, and both sides are asked to join the
+  // call link call with the root key. Once both have joined, the call ends
+  // with CallEndedReason.EscalatedToCallLink.
+  escalateToCallLink(
+    callId: CallId,
+    linkRootKey: CallLinkRootKey,
+    create?: CallLinkCreateParams
+  ): void {
+    sillyDeadlockProtection(() => {
+      if (create) {
+        this.callManager.escalateToCallLink(
+          callId,
+          linkRootKey.bytes,
+          create.sfuUrl,
+          create.createCredentialPresentation,
+          create.adminPasskey,
+          create.callLinkPublicParams,
+          create.restrictions
+        );
+      } else {
+        this.callManager.escalateToCallLink(callId, linkRootKey.bytes);
+      }
+    });
+  }
+
+  // Asks the remote side to turn the connected call into a video call. The
+  // answer is reported to call.handleVideoUpgrade.
+  requestVideoUpgrade(callId: CallId): void {
+    sillyDeadlockProtection(() => {
+      this.callManager.requestVideoUpgrade(callId);
+    });
+  }
+
+  // Accepts the remote side's request to turn the call into a video call,
+  // which enables outgoing video.
+  acceptVideoUpgrade(callId: CallId): void {
+    const call = this.getCall(callId);
+    if (!call) {
+      return;
+    }
+
+    sillyDeadlockProtection(() => {
+      this.callManager.acceptVideoUpgrade(callId);
+    });
+    call.outgoingVideoEnabled = true;
+  }
+
+  declineVideoUpgrade(callId: CallId): void {
+    sillyDeadlockProtection(() => {
+      this.callManager.declineVideoUpgrade(callId);
+    });
+  }
+
+  // Tells the remote side about conditions of the local devices that the user
+  // didn't choose, such as an unplugged microphone. The remote side is
+  // notified with handleRemoteDeviceCondition.
+  setLocalDeviceStatus(callId: CallId, status: LocalDeviceStatus): void {
+    sillyDeadlockProtection(() => {
+      this.callManager.setLocalDeviceStatus(
+        callId,
+        status.microphoneDisconnected,
+        status.backgrounded,
+        status.cameraSwitching
+      );
+    });
+  }
+
   decline(callId: CallId): void {
     const call = this.getCall(callId);
     if (!call) {
@@ -1867,16 +2285,30 @@ export class RingRTCType {
     return this.callManager.getAudioInputs();
   }
 
-  setAudioInput(index: number): void {
-    this.callManager.setAudioInput(index);
+  // Selects either the input at an index returned by getAudioInputs, or the
+  // first present input from a list of preferred unique IDs, falling back to
+  // the default input if none of them are present.
+  setAudioInput(device: number | Array<string>): void {
+    this.callManager.setAudioInput(device);
+  }
+
+  getActiveAudioInput(): AudioDevice | undefined {
+    return this.callManager.getActiveAudioInput();
   }
 
   getAudioOutputs(): Array<AudioDevice> {
     return this.callManager.getAudioOutputs();
   }
 
-  setAudioOutput(index: number): void {
-    this.callManager.setAudioOutput(index);
+  // Selects either the output at an index returned by getAudioOutputs, or the
+  // first present output from a list of preferred unique IDs, falling back to
+  // the default output if none of them are present.
+  setAudioOutput(device: number | Array<string>): void {
+    this.callManager.setAudioOutput(device);
+  }
+
+  getActiveAudioOutput(): AudioDevice | undefined {
+    return this.callManager.getActiveAudioOutput();
   }
 }
 
@@ -1885,6 +2317,20 @@ export interface CallSettings {
   hideIp: boolean;
   dataMode: DataMode;
   audioLevelsIntervalMillis?: number;
+  candidatePolicy?: CandidatePolicy;
+}
+
+// Which ICE candidates a direct call may use. The default allows all.
+export interface CandidatePolicy {
+  // Drop host candidates, which reveal local IP addresses.
+  excludeHost?: boolean;
+  // Use relay (TURN) servers only, like hideIp.
+  relayOnly?: boolean;
+  // Drop candidates that don't have an IPv4 address.
+  ipv4Only?: boolean;
+  // Drop candidates on these types of network. Cellular matches all
+  // cellular types.
+  excludedAdapterTypes?: Array<NetworkAdapterType>;
 }
 
 interface IceServer {
@@ -1907,6 +2353,16 @@ export interface AudioDevice {
   i18nKey?: string;
 }
 
+// The current audio devices, reported when devices are added or removed.
+export interface AudioDevicesChange {
+  outputs: Array<AudioDevice>;
+  inputs: Array<AudioDevice>;
+  // The selected output device was removed and the default device is used instead.
+  outputFellBackToDefault: boolean;
+  // The selected input device was removed and the default device is used instead.
+  inputFellBackToDefault: boolean;
+}
+
 export interface VideoCapturer {
   enableCapture(): void;
   enableCaptureAndSend(
@@ -1937,6 +2393,9 @@ export class Call {
   outgoingAudioLevel: NormalizedAudioLevel = 0;
   remoteAudioLevel: NormalizedAudioLevel = 0;
   remoteSharingScreen = false;
+  remoteHeld = false;
+  // Whether this is a waiting or held call rather than the active call.
+  waitingOrHeld = false;
   networkRoute: NetworkRoute = new NetworkRoute();
   private _videoCapturer: VideoCapturer | null = null;
   private _videoRenderer: VideoRenderer | null = null;
@@ -1947,6 +2406,7 @@ export class Call {
   handleRemoteAudioEnabled?: () => void;
   handleRemoteVideoEnabled?: () => void;
   handleRemoteSharingScreen?: () => void;
+  handleRemoteHeld?: () => void;
   handleNetworkRouteChanged?: () => void;
   handleAudioLevels?: () => void;
 
@@ -1961,6 +2421,42 @@ export class Call {
    */
   handleLowBandwidthForVideo?: (recovered: boolean) => void;
 
+  /**
+   * Notification of progress moving the call to a call link call.
+   *
+   * When the state is Started, create a call link call client for the root
+   * key and join it. The call ends with EscalatedToCallLink once both sides
+   * have joined.
+   *
+   * @param state - the progress of the escalation
+   * @param rootKey - the root key of the call link, for Started only
+   */
+  handleEscalation?: (state: CallEscalationState, rootKey?: Uint8Array) => void;
+
+  /**
+   * Notification of progress turning the call into a video call.
+   *
+   * When the state is RemoteRequested, the user should be asked whether to
+   * turn on video. When it is Accepted, video has been enabled for the call
+   * and the camera should be turned on.
+   *
+   * @param state - the progress of the video upgrade
+   */
+  handleVideoUpgrade?: (state: CallVideoUpgradeState) => void;
+
+  /**
+   * Notification that a condition of the remote side's devices started or
+   * ended, such as its microphone being unplugged. These are not chosen by
+   * the remote user, so the UI may want to explain them.
+   *
+   * @param condition - the condition that changed
+   * @param active - whether the condition started (true) or ended (false)
+   */
+  handleRemoteDeviceCondition?: (
+    condition: CallRemoteDeviceCondition,
+    active: boolean
+  ) => void;
+
   // This callback should be set by the VideoCapturer,
   // But could also be set by the UX.
   renderVideoFrame?: (width: number, height: number, buffer: Buffer) => void;
@@ -2048,9 +2544,12 @@ export class Call {
     if (this._videoRenderer) {
       this._videoRenderer.disable();
     }
-    // This assumes we only have one active call.
     sillyDeadlockProtection(() => {
-      this._callManager.hangup();
+      if (this.waitingOrHeld) {
+        this._callManager.hangupCall(this.callId);
+      } else {
+        this._callManager.hangup();
+      }
     });
   }
 
@@ -2380,7 +2879,10 @@ export interface GroupCallObserver {
   onAudioLevels(groupCall: GroupCall): void;
   onLowBandwidthForVideo(groupCall: GroupCall, recovered: boolean): void;
   onReactions(groupCall: GroupCall, reactions: Array<Reaction>): void;
+  onChatMessages(groupCall: GroupCall, messages: Array<ChatMessage>): void;
   onRaisedHands(groupCall: GroupCall, raisedHands: Array<number>): void;
+  // An admin asked the local device to mute. Honour it with setOutgoingAudioMuted.
+  onRemoteMuteRequest(groupCall: GroupCall, muteSource: number): void;
   onPeekChanged(groupCall: GroupCall): void;
   onEnded(groupCall: GroupCall, reason: GroupCallEndReason): void;
 }
@@ -2477,6 +2979,11 @@ export class GroupCall {
     this._callManager.groupReact(this._clientId, value);
   }
 
+  // Called by UI
+  sendChatMessage(text: string): void {
+    this._callManager.groupSendChatMessage(this._clientId, text);
+  }
+
   // Called by UI
   raiseHand(raise: boolean): void {
     this._callManager.groupRaiseHand(this._clientId, raise);
@@ -2553,6 +3060,21 @@ export class GroupCall {
     this._callManager.blockClient(this._clientId, otherClientDemuxId);
   }
 
+  // Called by UI; only admins can lower other hands.
+  lowerHand(otherClientDemuxId: number): void {
+    this._callManager.lowerHand(this._clientId, otherClientDemuxId);
+  }
+
+  // Called by UI; only admins can lower other hands.
+  lowerAllHands(): void {
+    this._callManager.lowerAllHands(this._clientId);
+  }
+
+  // Called by UI; only admins can request that others mute.
+  requestMute(otherClientDemuxId: number): void {
+    this._callManager.requestMute(this._clientId, otherClientDemuxId);
+  }
+
   // Called by UI
   setGroupMembers(members: Array<GroupMemberInfo>): void {
     this._callManager.setGroupMembers(this._clientId, members);
@@ -2629,10 +3151,18 @@ export class GroupCall {
     this._observer.onReactions(this, reactions);
   }
 
+  handleChatMessages(messages: Array<ChatMessage>): void {
+    this._observer.onChatMessages(this, messages);
+  }
+
   handleRaisedHands(raisedHands: Array<number>): void {
     this._observer.onRaisedHands(this, raisedHands);
   }
 
+  handleRemoteMuteRequest(muteSource: number): void {
+    this._observer.onRemoteMuteRequest(this, muteSource);
+  }
+
   // Called by Rust via RingRTC object
   handleRemoteDevicesChanged(
     remoteDeviceStates: Array<RemoteDeviceState>
@@ -2698,6 +3228,36 @@ export class GroupCall {
   setRtcStatsInterval(intervalMillis: number): void {
     this._callManager.setRtcStatsInterval(this._clientId, intervalMillis);
   }
+
+  // Zero disables rejoining the SFU after the connection fails.
+  setConnectionRecoveryWindow(windowMillis: number): void {
+    this._callManager.setConnectionRecoveryWindow(this._clientId, windowMillis);
+  }
+
+  // Reactions sent by this device, or received from any one remote device,
+  // over the limit are dropped. Reactions aren't limited until this is called.
+  setReactionRateLimits(
+    send: ReactionRateLimit,
+    receive: ReactionRateLimit
+  ): void {
+    this._callManager.setReactionRateLimits(
+      this._clientId,
+      send.burst,
+      send.intervalMillis,
+      receive.burst,
+      receive.intervalMillis
+    );
+  }
+
+  // Received reactions are collected for this long, with identical ones
+  // combined, before being passed to onReactions. 0 passes them on right away.
+  setReactionAggregationWindow(windowMillis: number): void {
+    this._callManager.setReactionAggregationWindow(this._clientId, windowMillis);
+  }
+
+  getSpeakingSummary(): SpeakingSummary | undefined {
+    return this._callManager.groupGetSpeakingSummary(this._clientId);
+  }
 }
 
 // Implements VideoSource for use in CanvasVideoRenderer
@@ -2841,6 +3401,7 @@ export enum HangupType {
   Declined = 2,
   Busy = 3,
   NeedPermission = 4,
+  HandedOff = 5,
 }
 
 export enum DataMode {
@@ -2859,21 +3420,55 @@ export enum RingCancelReason {
 export interface CallManager {
   setConfig(config: Config): void;
   setSelfUuid(uuid: Buffer): void;
+  setRingPolicy(
+    outgoingRingTimeoutMillis: number,
+    incomingRingTimeoutMillis: number,
+    maxOfferAgeMillis: number,
+    groupRingExpiryMillis: number
+  ): void;
+  setCallWaitingEnabled(enabled: boolean): void;
   createOutgoingCall(
     remoteUserId: UserId,
     isVideoCall: boolean,
     localDeviceId: DeviceId
   ): CallId;
+  handoffCall(
+    remoteUserId: UserId,
+    callId: CallId,
+    isVideoCall: boolean,
+    localDeviceId: DeviceId
+  ): void;
   proceed(
     callId: CallId,
     iceServers: Array<IceServer>,
     hideIp: boolean,
     dataMode: DataMode,
-    audioLevelsIntervalMillis: number
+    audioLevelsIntervalMillis: number,
+    candidatePolicy: CandidatePolicy
   ): void;
   accept(callId: CallId): void;
   ignore(callId: CallId): void;
   hangup(): void;
+  hangupCall(callId: CallId): void;
+  resumeCall(callId: CallId): void;
+  requestVideoUpgrade(callId: CallId): void;
+  acceptVideoUpgrade(callId: CallId): void;
+  declineVideoUpgrade(callId: CallId): void;
+  setLocalDeviceStatus(
+    callId: CallId,
+    microphoneDisconnected: boolean,
+    backgrounded: boolean,
+    cameraSwitching: boolean
+  ): void;
+  escalateToCallLink(
+    callId: CallId,
+    linkRootKey: Buffer,
+    sfuUrl?: string,
+    createCredentialPresentation?: Buffer,
+    adminPasskey?: Buffer,
+    callLinkPublicParams?: Buffer,
+    restrictions?: number
+  ): void;
   cancelGroupRing(
     groupId: GroupId,
     ringId: string,
@@ -2974,6 +3569,7 @@ export interface CallManager {
   ): void;
   groupRing(clientId: GroupCallClientId, recipient: Buffer | undefined): void;
   groupReact(clientId: GroupCallClientId, value: string): void;
+  groupSendChatMessage(clientId: GroupCallClientId, text: string): void;
   groupRaiseHand(clientId: GroupCallClientId, raise: boolean): void;
   resendMediaKeys(clientId: GroupCallClientId): void;
   setDataMode(clientId: GroupCallClientId, dataMode: DataMode): void;
@@ -2986,6 +3582,9 @@ export interface CallManager {
   denyUser(clientId: GroupCallClientId, otherUserId: Buffer): void;
   removeClient(clientId: GroupCallClientId, otherClientDemuxId: number): void;
   blockClient(clientId: GroupCallClientId, otherClientDemuxId: number): void;
+  lowerHand(clientId: GroupCallClientId, otherClientDemuxId: number): void;
+  lowerAllHands(clientId: GroupCallClientId): void;
+  requestMute(clientId: GroupCallClientId, otherClientDemuxId: number): void;
   setGroupMembers(
     clientId: GroupCallClientId,
     members: Array<GroupMemberInfo>
@@ -3003,6 +3602,24 @@ export interface CallManager {
     clientId: GroupCallClientId,
     intervalMillis: number
   ): void;
+  setConnectionRecoveryWindow(
+    clientId: GroupCallClientId,
+    windowMillis: number
+  ): void;
+  setReactionRateLimits(
+    clientId: GroupCallClientId,
+    sendBurst: number,
+    sendIntervalMillis: number,
+    receiveBurst: number,
+    receiveIntervalMillis: number
+  ): void;
+  setReactionAggregationWindow(
+    clientId: GroupCallClientId,
+    windowMillis: number
+  ): void;
+  groupGetSpeakingSummary(
+    clientId: GroupCallClientId
+  ): SpeakingSummary | undefined;
   // Responses come back via handleCallLinkResponse
   readCallLink(
     requestId: number,
@@ -3052,9 +3669,11 @@ export interface CallManager {
   ): void;
 
   getAudioInputs(): Array<AudioDevice>;
-  setAudioInput(index: number): void;
+  setAudioInput(device: number | Array<string>): void;
+  getActiveAudioInput(): AudioDevice | undefined;
   getAudioOutputs(): Array<AudioDevice>;
-  setAudioOutput(index: number): void;
+  setAudioOutput(device: number | Array<string>): void;
+  getActiveAudioOutput(): AudioDevice | undefined;
 }
 
 export interface CallManagerCallbacks {
@@ -3074,6 +3693,25 @@ export interface CallManagerCallbacks {
   onRemoteAudioEnabled(remoteUserId: UserId, enabled: boolean): void;
   onRemoteVideoEnabled(remoteUserId: UserId, enabled: boolean): void;
   onRemoteSharingScreen(remoteUserId: UserId, enabled: boolean): void;
+  onRemoteHeld(remoteUserId: UserId, held: boolean): void;
+  onLocalHeld(remoteUserId: UserId, held: boolean): void;
+  onEscalation(
+    remoteUserId: UserId,
+    state: CallEscalationState,
+    rootKey: Uint8Array | null
+  ): void;
+  onVideoUpgrade(remoteUserId: UserId, state: CallVideoUpgradeState): void;
+  onRemoteDeviceCondition(
+    remoteUserId: UserId,
+    condition: CallRemoteDeviceCondition,
+    active: boolean
+  ): void;
+  onAudioDevicesChanged(
+    outputs: Array<AudioDevice>,
+    inputs: Array<AudioDevice>,
+    outputFellBackToDefault: boolean,
+    inputFellBackToDefault: boolean
+  ): void;
   onSendOffer(
     remoteUserId: UserId,
     remoteDeviceId: DeviceId,
@@ -3189,6 +3827,44 @@ export enum CallEndedReason {
   AcceptedOnAnotherDevice = 'AcceptedOnAnotherDevice',
   DeclinedOnAnotherDevice = 'DeclinedOnAnotherDevice',
   BusyOnAnotherDevice = 'BusyOnAnotherDevice',
+  HandedOffToAnotherDevice = 'HandedOffToAnotherDevice',
+  EscalatedToCallLink = 'EscalatedToCallLink',
+}
+
+// What's needed to create the call link that a call is escalated to; see
+// RingRTCType.createCallLink.
+export interface CallLinkCreateParams {
+  sfuUrl: string;
+  createCredentialPresentation: Buffer;
+  adminPasskey: Buffer;
+  callLinkPublicParams: Buffer;
+  restrictions: Exclude<CallLinkRestrictions, CallLinkRestrictions.Unknown>;
+}
+
+export enum CallEscalationState {
+  Started = 0,
+  RemoteJoined = 1,
+  Failed = 2,
+}
+
+export enum CallVideoUpgradeState {
+  RemoteRequested = 0,
+  RemoteCanceled = 1,
+  Accepted = 2,
+  Declined = 3,
+  TimedOut = 4,
+}
+
+export interface LocalDeviceStatus {
+  microphoneDisconnected: boolean;
+  backgrounded: boolean;
+  cameraSwitching: boolean;
+}
+
+export enum CallRemoteDeviceCondition {
+  MicrophoneDisconnected = 0,
+  Backgrounded = 1,
+  CameraSwitching = 2,
 }
 
 export enum CallLogLevel {
@@ -14,6 +14,7 @@ import {
   CallLinkRootKey,
   CallState,
   CallingMessage,
+  ChatMessage,
   GroupCall,
   GroupCallEndReason,
   GroupCallKind,
@@ -904,7 +905,9 @@ describe('RingRTC', () => {
       onAudioLevels(_call: GroupCall) {}
       onLowBandwidthForVideo(_call: GroupCall, _recovered: boolean) {}
       onReactions(_call: GroupCall, _reactions: Array<Reaction>) {}
+      onChatMessages(_call: GroupCall, _messages: Array<ChatMessage>) {}
       onRaisedHands(_call: GroupCall, _raisedHands: Array<number>) {}
+      onRemoteMuteRequest(_call: GroupCall, _muteSource: number) {}
       onPeekChanged(_call: GroupCall) {}
       onEnded(_call: GroupCall, _reason: GroupCallEndReason) {}
       /* eslint-enable @typescript-eslint/no-empty-function */
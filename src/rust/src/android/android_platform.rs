@@ -24,7 +24,7 @@ use crate::core::call::Call;
 use crate::core::connection::{Connection, ConnectionType};
 use crate::core::platform::{Platform, PlatformItem};
 use crate::core::{group_call, signaling};
-use crate::lite::call_links::{CallLinkRestrictions, CallLinkState, Empty};
+use crate::lite::call_links::{CallLinkRestrictions, CallLinkRootKey, CallLinkState, Empty};
 use crate::lite::{
     http, sfu,
     sfu::{DemuxId, PeekInfo, PeekResult, UserId},
@@ -448,6 +448,38 @@ impl Platform for AndroidPlatform {
         Ok(())
     }
 
+    fn on_escalate_to_call_link(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        root_key: &CallLinkRootKey,
+    ) -> Result<()> {
+        info!("on_escalate_to_call_link(): call_id: {}", call_id);
+
+        let env = &mut self.java_env()?;
+        let jni_call_manager = self.jni_call_manager.as_obj();
+
+        // Set a frame capacity of min (5) + objects (2).
+        let capacity = 7;
+        env.with_local_frame(capacity, |env| -> Result<()> {
+            let jni_remote = remote_peer.as_obj();
+            let call_id_jlong = u64::from(call_id) as jlong;
+            let jni_root_key = JObject::from(env.byte_array_from_slice(&root_key.bytes())?);
+
+            jni_call_method(
+                env,
+                jni_call_manager,
+                "onEscalateToCallLink",
+                jni_args!((
+                    jni_remote => org.signal.ringrtc.Remote,
+                    call_id_jlong => long,
+                    jni_root_key => [byte],
+                ) -> void),
+            )?;
+            Ok(())
+        })
+    }
+
     fn on_send_offer(
         &self,
         remote_peer: &Self::AppRemotePeer,
@@ -1117,8 +1149,8 @@ impl Platform for AndroidPlatform {
         );
 
         if let Ok(mut env) = self.java_env() {
-            // Set a frame capacity of min (5) + objects (1) + elements (N * 2 per reaction).
-            let capacity = (5 + 1 + reactions.len() * 2) as i32;
+            // Set a frame capacity of min (5) + objects (1) + elements (N * 3 per reaction).
+            let capacity = (5 + 1 + reactions.len() * 3) as i32;
             if let Err(e) = env.with_local_frame(capacity, |env| -> Result<()> {
                 // create Java List<GroupCall.Reaction>
                 let reaction_class = self.class_cache.get_class(REACTION_CLASS)?;
@@ -1128,9 +1160,19 @@ impl Platform for AndroidPlatform {
 
                 for reaction in reactions {
                     let jni_value = JObject::from(env.new_string(reaction.value)?);
+                    let sender_demux_ids: Vec<jlong> = reaction
+                        .sender_demux_ids
+                        .iter()
+                        .map(|demux_id| *demux_id as jlong)
+                        .collect();
+                    let jni_sender_demux_ids = env.new_long_array(sender_demux_ids.len() as i32)?;
+                    env.set_long_array_region(&jni_sender_demux_ids, 0, &sender_demux_ids)?;
+                    let jni_sender_demux_ids = JObject::from(jni_sender_demux_ids);
                     let args = jni_args!((
                         reaction.demux_id as jlong => long,
                         jni_value => java.lang.String,
+                        reaction.count as jint => int,
+                        jni_sender_demux_ids => [long],
                     ) -> void);
 
                     let reaction_obj = match env.new_object(reaction_class, args.sig, &args.args) {
@@ -18,6 +18,7 @@ use crate::android::call_manager;
 use crate::android::call_manager::AndroidCallManager;
 use crate::android::error;
 use crate::common::{CallConfig, CallMediaType, DataMode, DeviceId};
+use crate::core::candidate_policy::CandidatePolicy;
 use crate::core::connection::Connection;
 use crate::core::util::try_scoped;
 use crate::core::{group_call, signaling};
@@ -111,6 +112,50 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetSelfUuid(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetCallWaitingEnabled(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    enabled: jboolean,
+) {
+    match call_manager::set_call_waiting_enabled(
+        call_manager as *mut AndroidCallManager,
+        enabled != 0,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetRingPolicy(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    outgoing_ring_timeout_millis: jlong,
+    incoming_ring_timeout_millis: jlong,
+    max_offer_age_millis: jlong,
+    group_ring_expiry_millis: jlong,
+) {
+    match call_manager::set_ring_policy(
+        call_manager as *mut AndroidCallManager,
+        outgoing_ring_timeout_millis,
+        incoming_ring_timeout_millis,
+        max_offer_age_millis,
+        group_ring_expiry_millis,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCall(
@@ -135,6 +180,32 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCall(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHandoffCall(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    jni_remote: JObject,
+    call_id: jlong,
+    call_media_type: jint,
+    local_device: jint,
+) {
+    match call_manager::handoff_call(
+        &env,
+        call_manager as *mut AndroidCallManager,
+        jni_remote,
+        call_id,
+        CallMediaType::from_i32(call_media_type),
+        local_device as DeviceId,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcProceed(
@@ -145,19 +216,31 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcProceed(
     jni_call_context: JObject,
     data_mode: jint,
     audio_levels_interval_millis: jint,
+    exclude_host: jboolean,
+    relay_only: jboolean,
+    ipv4_only: jboolean,
+    excluded_adapter_types: jint,
 ) {
     let audio_levels_interval = if audio_levels_interval_millis <= 0 {
         None
     } else {
         Some(Duration::from_millis(audio_levels_interval_millis as u64))
     };
+    let candidate_policy = CandidatePolicy::from_flags(
+        exclude_host != 0,
+        relay_only != 0,
+        ipv4_only != 0,
+        excluded_adapter_types as u32,
+    );
 
     match call_manager::proceed(
         &env,
         call_manager as *mut AndroidCallManager,
         call_id,
         jni_call_context,
-        CallConfig::default().with_data_mode(DataMode::from_i32(data_mode)),
+        CallConfig::default()
+            .with_data_mode(DataMode::from_i32(data_mode))
+            .with_candidate_policy(candidate_policy),
         audio_levels_interval,
     ) {
         Ok(v) => v,
@@ -214,6 +297,146 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHangup(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHangupCall(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::hangup_call(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcResumeCall(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::resume_call(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcRequestVideoUpgrade(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::request_video_upgrade(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcAcceptVideoUpgrade(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::accept_video_upgrade(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcDeclineVideoUpgrade(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::decline_video_upgrade(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetLocalDeviceStatus(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    microphone_disconnected: jboolean,
+    backgrounded: jboolean,
+    camera_switching: jboolean,
+) {
+    let status = signaling::DeviceStatus {
+        microphone_disconnected: Some(microphone_disconnected != 0),
+        backgrounded: Some(backgrounded != 0),
+        camera_switching: Some(camera_switching != 0),
+    };
+    match call_manager::set_local_device_status(
+        call_manager as *mut AndroidCallManager,
+        call_id,
+        status,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcEscalateToCallLink(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    root_key: JByteArray,
+    sfu_url: JString,
+    create_credential_presentation: JByteArray,
+    admin_passkey: JByteArray,
+    call_link_public_params: JByteArray,
+    restrictions: jint,
+) {
+    match call_manager::escalate_to_call_link(
+        &mut env,
+        call_manager as *mut AndroidCallManager,
+        call_id,
+        root_key,
+        sfu_url,
+        create_credential_presentation,
+        admin_passkey,
+        call_link_public_params,
+        restrictions,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCancelGroupRing(
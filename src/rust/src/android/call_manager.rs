@@ -23,7 +23,7 @@ use crate::android::logging::init_logging;
 use crate::android::webrtc_peer_connection_factory::*;
 
 use crate::common::{CallConfig, CallId, CallMediaType, DataMode, DeviceId, Result};
-use crate::core::call_manager::CallManager;
+use crate::core::call_manager::{CallLinkCreateParams, CallManager, RingPolicy};
 use crate::core::connection::Connection;
 use crate::core::util::{ptr_as_box, ptr_as_mut};
 use crate::core::{group_call, signaling};
@@ -161,6 +161,32 @@ pub fn set_self_uuid(
     call_manager.set_self_uuid(env.convert_byte_array(uuid)?)
 }
 
+/// Application notification updating the ring timeouts and message age limits
+pub fn set_ring_policy(
+    call_manager: *mut AndroidCallManager,
+    outgoing_ring_timeout_millis: jlong,
+    incoming_ring_timeout_millis: jlong,
+    max_offer_age_millis: jlong,
+    group_ring_expiry_millis: jlong,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_ring_policy(RingPolicy {
+        outgoing_ring_timeout: Duration::from_millis(outgoing_ring_timeout_millis.try_into()?),
+        incoming_ring_timeout: Duration::from_millis(incoming_ring_timeout_millis.try_into()?),
+        max_offer_age: Duration::from_millis(max_offer_age_millis.try_into()?),
+        group_ring_expiry: Duration::from_millis(group_ring_expiry_millis.try_into()?),
+    })
+}
+
+/// Application notification enabling or disabling call waiting for 1:1 calls
+pub fn set_call_waiting_enabled(
+    call_manager: *mut AndroidCallManager,
+    enabled: bool,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_call_waiting_enabled(enabled)
+}
+
 /// Application notification to start a new call
 pub fn call(
     env: &JNIEnv,
@@ -174,6 +200,25 @@ pub fn call(
     call_manager.call(app_remote_peer, call_media_type, local_device_id)
 }
 
+/// Application notification to take over a call from another device
+pub fn handoff_call(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    jni_remote: JObject,
+    call_id: jlong,
+    call_media_type: CallMediaType,
+    local_device_id: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let app_remote_peer = env.new_global_ref(jni_remote)?;
+    call_manager.handoff_call(
+        app_remote_peer,
+        CallId::from(call_id),
+        call_media_type,
+        local_device_id,
+    )
+}
+
 /// Application notification to proceed with a new call
 pub fn proceed(
     env: &JNIEnv,
@@ -217,6 +262,91 @@ pub fn hangup(call_manager: *mut AndroidCallManager) -> Result<()> {
     call_manager.hangup()
 }
 
+/// Application notification of local hangup of a specific call, which may be
+/// active, waiting or held
+pub fn hangup_call(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.hangup_call(call_id)
+}
+
+/// Application notification to resume the held call, putting the active call
+/// (if any) on hold
+pub fn resume_call(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.resume_call(call_id)
+}
+
+/// Application notification to ask the remote peer to turn the active call
+/// into a video call.
+pub fn request_video_upgrade(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.request_video_upgrade(call_id)
+}
+
+/// Application notification accepting the remote peer's video upgrade
+/// request.
+pub fn accept_video_upgrade(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.accept_video_upgrade(call_id)
+}
+
+/// Application notification declining the remote peer's video upgrade
+/// request.
+pub fn decline_video_upgrade(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.decline_video_upgrade(call_id)
+}
+
+/// Application notification of conditions of the local devices, such as an
+/// unplugged microphone.
+pub fn set_local_device_status(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    status: signaling::DeviceStatus,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.set_local_device_status(call_id, status)
+}
+
+/// Application notification to move the active call to a call link call.
+///
+/// The call link is only created first if `sfu_url` is not null.
+#[allow(clippy::too_many_arguments)]
+pub fn escalate_to_call_link(
+    env: &mut JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    root_key: JByteArray,
+    sfu_url: JString,
+    create_credential_presentation: JByteArray,
+    admin_passkey: JByteArray,
+    call_link_public_params: JByteArray,
+    restrictions: jint,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    let root_key =
+        call_links::CallLinkRootKey::try_from(env.convert_byte_array(root_key)?.as_slice())?;
+    let create = if sfu_url.is_null() {
+        None
+    } else {
+        Some(CallLinkCreateParams {
+            sfu_url: env.get_string(&sfu_url)?.into(),
+            auth_presentation: env.convert_byte_array(create_credential_presentation)?,
+            admin_passkey: env.convert_byte_array(admin_passkey)?,
+            public_zkparams: env.convert_byte_array(call_link_public_params)?,
+            restrictions: jint_to_restrictions(restrictions),
+        })
+    };
+    call_manager.escalate_to_call_link(call_id, root_key, create)
+}
+
 /// Application notification cancelling a group call ring
 pub fn cancel_group_ring(
     env: &JNIEnv,
@@ -8,6 +8,7 @@ mod group_call_sim;
 
 use direct_call_sim::DirectCall;
 use group_call_sim::GroupCall;
+pub use group_call_sim::{GroupAction, ScheduledGroupAction};
 use log::*;
 use ringrtc::{
     common::{
@@ -25,6 +26,7 @@ use ringrtc::{
         sfu::{GroupMember, UserId},
     },
     native::{NativeCallContext, NativePlatform, PeerId, SignalingSender},
+    simnet::router::{LinkConfig, Router},
     webrtc::{
         media::{AudioTrack, VideoSink, VideoSource, VideoTrack},
         peer_connection_factory::{AudioConfig, IceServer, PeerConnectionFactory},
@@ -272,6 +274,51 @@ impl CallEndpoint {
         });
     }
 
+    /// Routes the endpoint's traffic for the given IP through a simulated router, which
+    /// applies `send_config` to outgoing and `receive_config` to incoming packets.
+    pub fn add_simulated_network(
+        &self,
+        router: &Router,
+        ip: &str,
+        send_config: &LinkConfig,
+        receive_config: &LinkConfig,
+    ) {
+        // To send across threads
+        let router = router.clone();
+        let ip = ip.to_owned();
+        let send_config = send_config.clone();
+        let receive_config = receive_config.clone();
+
+        self.actor.send(move |state| {
+            if let Some(ref mut network) = state.network {
+                network.add_router_interface(router, ip, send_config, receive_config);
+            } else {
+                error!("Error: Injectable network not set properly!");
+            }
+        });
+    }
+
+    /// Changes the links of a simulated network added with `add_simulated_network`.
+    pub fn set_simulated_link(
+        &self,
+        ip: &str,
+        send_config: &LinkConfig,
+        receive_config: &LinkConfig,
+    ) {
+        // To send across threads
+        let ip = ip.parse().expect("parse IP address");
+        let send_config = send_config.clone();
+        let receive_config = receive_config.clone();
+
+        self.actor.send(move |state| {
+            if let Some(ref network) = state.network {
+                network.set_router_link(ip, send_config, receive_config);
+            } else {
+                error!("Error: Injectable network not set properly!");
+            }
+        });
+    }
+
     pub fn stop_network(&self) {
         self.actor.send(move |state| {
             if let Some(ref network) = state.network {
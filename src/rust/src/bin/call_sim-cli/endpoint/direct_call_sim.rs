@@ -6,8 +6,14 @@
 use log::*;
 use ringrtc::{
     common::{CallConfig, CallId, Result},
-    native::{CallState, CallStateHandler, NativeCallContext},
-    webrtc::{peer_connection::AudioLevel, peer_connection_observer::NetworkRoute},
+    native::{
+        CallState, CallStateHandler, EscalationState, NativeCallContext, RemoteDeviceCondition,
+        VideoUpgradeState,
+    },
+    webrtc::{
+        peer_connection::AudioLevel, peer_connection_factory::AudioDevicesChanged,
+        peer_connection_observer::NetworkRoute,
+    },
 };
 
 use super::CallEndpoint;
@@ -109,6 +115,11 @@ impl CallStateHandler for CallEndpoint {
         Ok(())
     }
 
+    fn handle_audio_devices_changed(&self, change: AudioDevicesChanged) -> Result<()> {
+        info!("Audio devices changed for {}: {:?}", self.peer_id(), change);
+        Ok(())
+    }
+
     fn handle_remote_audio_state(&self, remote_peer_id: &str, enabled: bool) -> Result<()> {
         info!(
             "Audio State for {} => {}: {}",
@@ -138,4 +149,60 @@ impl CallStateHandler for CallEndpoint {
         );
         Ok(())
     }
+
+    fn handle_remote_held(&self, remote_peer_id: &str, held: bool) -> Result<()> {
+        info!(
+            "Held for {} => {}: {}",
+            self.peer_id(),
+            remote_peer_id,
+            held
+        );
+        Ok(())
+    }
+
+    fn handle_local_held(&self, remote_peer_id: &str, held: bool) -> Result<()> {
+        info!(
+            "Local held for {} => {}: {}",
+            self.peer_id(),
+            remote_peer_id,
+            held
+        );
+        Ok(())
+    }
+
+    fn handle_escalation(&self, remote_peer_id: &str, state: EscalationState) -> Result<()> {
+        info!(
+            "Escalation for {} => {}: {:?}",
+            self.peer_id(),
+            remote_peer_id,
+            state
+        );
+        Ok(())
+    }
+
+    fn handle_video_upgrade(&self, remote_peer_id: &str, state: VideoUpgradeState) -> Result<()> {
+        info!(
+            "Video upgrade for {} => {}: {:?}",
+            self.peer_id(),
+            remote_peer_id,
+            state
+        );
+        Ok(())
+    }
+
+    fn handle_remote_device_condition(
+        &self,
+        remote_peer_id: &str,
+        condition: RemoteDeviceCondition,
+        active: bool,
+    ) -> Result<()> {
+        info!(
+            "Remote device condition for {} => {}: {:?} {}",
+            self.peer_id(),
+            remote_peer_id,
+            condition,
+            active
+        );
+        Ok(())
+    }
 }
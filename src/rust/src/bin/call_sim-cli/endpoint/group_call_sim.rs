@@ -5,22 +5,109 @@
 
 use std::{
     collections::HashSet,
+    str::FromStr,
     time::{Duration, Instant},
 };
 
 use ringrtc::{
     common::Result,
-    core::group_call::{
-        self, ClientId, ConnectionState, GroupId, JoinState, Reaction, VideoRequest,
+    core::{
+        call_manager::CallManager,
+        group_call::{self, ClientId, ConnectionState, GroupId, JoinState, Reaction, VideoRequest},
     },
     lite::sfu::{DemuxId, MembershipProof, PeekInfo},
-    native::{GroupUpdate, GroupUpdateHandler},
+    native::{GroupUpdate, GroupUpdateHandler, NativePlatform},
     webrtc::peer_connection_observer::NetworkRoute,
 };
 
 use log::*;
 
-use super::CallEndpoint;
+use super::{CallEndpoint, CallEndpointState};
+
+/// The resolution requested for remote video until a scenario asks for another.
+const DEFAULT_VIDEO_REQUEST: (u16, u16) = (1280, 720);
+
+/// Something a client does in a group call, parsed from strings such as `join`,
+/// `mute-audio`, `react=👍` or `request-video=640x360`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GroupAction {
+    Join,
+    Leave,
+    SetAudioMuted(bool),
+    SetVideoMuted(bool),
+    /// Presenting also marks the outgoing video as screen content.
+    SetPresenting(bool),
+    SetHandRaised(bool),
+    React(String),
+    /// Requests video from all remote devices at the given resolution.
+    RequestVideo {
+        width: u16,
+        height: u16,
+    },
+}
+
+impl FromStr for GroupAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let action = match s.split_once('=') {
+            None => match s {
+                "join" => Self::Join,
+                "leave" => Self::Leave,
+                "mute-audio" => Self::SetAudioMuted(true),
+                "unmute-audio" => Self::SetAudioMuted(false),
+                "mute-video" => Self::SetVideoMuted(true),
+                "unmute-video" => Self::SetVideoMuted(false),
+                "present" => Self::SetPresenting(true),
+                "stop-presenting" => Self::SetPresenting(false),
+                "raise-hand" => Self::SetHandRaised(true),
+                "lower-hand" => Self::SetHandRaised(false),
+                _ => return Err(format!("unknown group action: {}", s)),
+            },
+            Some(("react", value)) if !value.is_empty() => Self::React(value.to_string()),
+            Some(("request-video", resolution)) => {
+                let (width, height) = resolution
+                    .split_once('x')
+                    .ok_or_else(|| format!("expected <width>x<height>, got: {}", resolution))?;
+                Self::RequestVideo {
+                    width: width
+                        .parse()
+                        .map_err(|e| format!("invalid width {}: {}", width, e))?,
+                    height: height
+                        .parse()
+                        .map_err(|e| format!("invalid height {}: {}", height, e))?,
+                }
+            }
+            _ => return Err(format!("unknown group action: {}", s)),
+        };
+        Ok(action)
+    }
+}
+
+/// A [`GroupAction`] to perform at an offset from the start of the call, given
+/// as `<offset_secs>:<action>`.
+#[derive(Clone, Debug)]
+pub struct ScheduledGroupAction {
+    pub offset: Duration,
+    pub action: GroupAction,
+}
+
+impl FromStr for ScheduledGroupAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (offset, action) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected <offset_secs>:<action>, got: {}", s))?;
+        let offset = offset
+            .parse()
+            .map_err(|e| format!("invalid offset {}: {}", offset, e))?;
+        Ok(Self {
+            offset: Duration::from_secs(offset),
+            action: action.parse()?,
+        })
+    }
+}
 
 impl Default for LocalDeviceState {
     fn default() -> Self {
@@ -48,6 +135,7 @@ pub struct GroupCall {
     pub peek_info: Option<PeekInfo>,
     pub reaction_log: Vec<(Instant, Vec<Reaction>)>,
     pub raised_hand_log: Vec<(Instant, Vec<DemuxId>)>,
+    pub video_request: (u16, u16),
 }
 
 impl GroupCall {
@@ -61,8 +149,25 @@ impl GroupCall {
             peek_info: None,
             reaction_log: vec![],
             raised_hand_log: vec![],
+            video_request: DEFAULT_VIDEO_REQUEST,
         }
     }
+
+    /// Requests video from every remote device at the current resolution.
+    fn request_video(&self, call_manager: &mut CallManager<NativePlatform>) {
+        let (width, height) = self.video_request;
+        let rendered_resolutions = self
+            .remote_device_state
+            .iter()
+            .map(|remote_state| VideoRequest {
+                demux_id: remote_state.demux_id,
+                width,
+                height,
+                framerate: None,
+            })
+            .collect();
+        call_manager.request_video(self.client_id, rendered_resolutions, height);
+    }
 }
 
 pub struct LocalDeviceState {
@@ -73,10 +178,7 @@ pub struct LocalDeviceState {
     audio_level: u16,
     network_route: Option<NetworkRoute>,
 
-    // TODO: add support for the following
-    #[allow(dead_code)]
     audio_muted: bool,
-    #[allow(dead_code)]
     video_muted: bool,
     #[allow(dead_code)]
     presenting: bool,
@@ -92,7 +194,16 @@ impl CallEndpoint {
         group_id: GroupId,
         membership_proof: MembershipProof,
     ) {
-        info!("Joining group call...");
+        self.join_group_call_after(Duration::ZERO, sfu_url, group_id, membership_proof);
+    }
+
+    pub fn join_group_call_after(
+        &self,
+        delay: Duration,
+        sfu_url: String,
+        group_id: GroupId,
+        membership_proof: MembershipProof,
+    ) {
         let our_uuid = self
             .user_id
             .as_ref()
@@ -100,7 +211,8 @@ impl CallEndpoint {
             .clone();
         let hkdf_extra_info = vec![];
 
-        self.actor.send(move |state| {
+        self.actor.send_delayed(delay, move |state| {
+            info!("Joining group call...");
             let client_id = state
                 .call_manager
                 .create_group_call_client(
@@ -115,34 +227,101 @@ impl CallEndpoint {
                 )
                 .expect("create group call client");
 
+            // Carry the local state over when rejoining, and clean up the
+            // client that was left.
+            let mut group_call = GroupCall::new(client_id, group_id, membership_proof.clone());
+            if let Some(previous) = state.group_call.take() {
+                group_call.local_device_state.audio_muted = previous.local_device_state.audio_muted;
+                group_call.local_device_state.video_muted = previous.local_device_state.video_muted;
+                group_call.video_request = previous.video_request;
+                state
+                    .call_manager
+                    .delete_group_call_client(previous.client_id);
+            }
+
             state
                 .call_manager
-                .set_outgoing_audio_muted(client_id, false);
+                .set_outgoing_audio_muted(client_id, group_call.local_device_state.audio_muted);
             state
                 .call_manager
-                .set_outgoing_video_muted(client_id, false);
+                .set_outgoing_video_muted(client_id, group_call.local_device_state.video_muted);
             let _ = state.call_manager.set_self_uuid(our_uuid.clone());
             state
                 .call_manager
-                .set_membership_proof(client_id, membership_proof.clone());
+                .set_membership_proof(client_id, membership_proof);
             state.call_manager.connect(client_id);
             state.call_manager.join(client_id);
             state
                 .call_manager
                 .set_rtc_stats_interval(client_id, Duration::from_secs(1));
-            let group_call = GroupCall::new(client_id, group_id, membership_proof);
             state.group_call = Some(group_call);
         });
     }
 
     pub fn hangup_group_call(&self) {
         info!("Starting to leave group call...");
-        self.actor.send(move |state| {
-            if let Some(ref group_call) = state.group_call {
-                state.call_manager.leave(group_call.client_id);
-                state.call_manager.disconnect(group_call.client_id);
-            } else {
-                warn!("Did not find group call to leave...");
+        self.actor.send(Self::leave_group_call);
+    }
+
+    fn leave_group_call(state: &mut CallEndpointState) {
+        if let Some(ref group_call) = state.group_call {
+            state.call_manager.leave(group_call.client_id);
+            state.call_manager.disconnect(group_call.client_id);
+        } else {
+            warn!("Did not find group call to leave...");
+        }
+    }
+
+    /// Performs a scripted action in the current group call after the given
+    /// delay. Joining needs the call details, so use
+    /// [`CallEndpoint::join_group_call_after`] for that instead.
+    pub fn perform_group_action_after(&self, delay: Duration, action: GroupAction) {
+        self.actor.send_delayed(delay, move |state| {
+            info!("Performing group action {:?}", action);
+            if action == GroupAction::Leave {
+                Self::leave_group_call(state);
+                return;
+            }
+
+            let Some(group_call) = state.group_call.as_mut() else {
+                warn!("Not in a group call, ignoring {:?}", action);
+                return;
+            };
+            let client_id = group_call.client_id;
+            let local_device_state = &mut group_call.local_device_state;
+
+            match action {
+                GroupAction::Join | GroupAction::Leave => {
+                    unreachable!("handled above or by join_group_call_after()")
+                }
+                GroupAction::SetAudioMuted(muted) => {
+                    local_device_state.audio_muted = muted;
+                    state
+                        .call_manager
+                        .set_outgoing_audio_muted(client_id, muted);
+                }
+                GroupAction::SetVideoMuted(muted) => {
+                    local_device_state.video_muted = muted;
+                    state
+                        .call_manager
+                        .set_outgoing_video_muted(client_id, muted);
+                }
+                GroupAction::SetPresenting(presenting) => {
+                    local_device_state.presenting = presenting;
+                    local_device_state.sharing_screen = presenting;
+                    state.call_manager.set_presenting(client_id, presenting);
+                    state.call_manager.set_sharing_screen(client_id, presenting);
+                }
+                GroupAction::SetHandRaised(raise) => {
+                    state.call_manager.raise_hand(client_id, raise);
+                }
+                GroupAction::React(value) => {
+                    state.call_manager.react(client_id, value);
+                }
+                GroupAction::RequestVideo { width, height } => {
+                    group_call.video_request = (width, height);
+                    group_call.request_video(&mut state.call_manager);
+                }
             }
         });
     }
@@ -192,33 +371,20 @@ impl GroupUpdateHandler for CallEndpoint {
                     }
                     group_call.local_device_state.join_state = join_state;
                 }
-                RemoteDeviceStatesChanged(client_id, remote_device_states) => {
+                RemoteDeviceStatesChanged(_client_id, remote_device_states) => {
                     let present: HashSet<u32> = group_call
                         .remote_device_state
                         .iter()
                         .map(|rds| rds.demux_id)
                         .collect();
-                    let added: Vec<_> = remote_device_states
+                    let added = remote_device_states
                         .iter()
-                        .filter(|rds| !present.contains(&rds.demux_id))
-                        .collect();
-
-                    if !added.is_empty() {
-                        let rendered_resolutions = added
-                            .iter()
-                            .map(|remote_state| VideoRequest {
-                                demux_id: remote_state.demux_id,
-                                width: 1280,
-                                height: 720,
-                                framerate: None,
-                            })
-                            .collect();
-                        state
-                            .call_manager
-                            .request_video(client_id, rendered_resolutions, 720);
-                    }
+                        .any(|rds| !present.contains(&rds.demux_id));
 
                     group_call.remote_device_state = remote_device_states;
+                    if added {
+                        group_call.request_video(&mut state.call_manager);
+                    }
                 }
                 PeekChanged {
                     client_id: _,
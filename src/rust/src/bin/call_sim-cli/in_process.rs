@@ -0,0 +1,217 @@
+//
+// Copyright 2026 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Runs a direct call between two endpoints in a single process, without containers or a
+//! test manager. Signaling goes through an in-memory relay and media through a simulated
+//! network, so this can be used wherever Docker is not available.
+
+use anyhow::{anyhow, Result};
+use log::*;
+use ringrtc::{
+    common::{actor::Stopper, CallConfig, CallId, CallMediaType, DeviceId},
+    lite::sfu::UserId,
+    native::PeerId,
+    simnet::router::Router,
+    webrtc::peer_connection_factory::IceServer,
+};
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    endpoint::{CallEndpoint, EventSync},
+    network::LinkSpec,
+    relay::InProcessSignalingRelay,
+};
+
+/// How long to wait for the call to ring and connect before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct InProcessClient {
+    pub name: String,
+    pub ip: String,
+    pub user_id: Option<UserId>,
+    pub device_id: DeviceId,
+    pub call_config: CallConfig,
+    /// Emulation for the client's uplink, ordered by offset. The downlink is unconstrained,
+    /// so the peer's uplink determines what this client receives.
+    pub links: Vec<LinkSpec>,
+}
+
+struct InProcessEndpoint {
+    endpoint: CallEndpoint,
+    ip: String,
+    ringing: Receiver<()>,
+    connected: Receiver<()>,
+}
+
+impl InProcessEndpoint {
+    fn start(
+        client: &InProcessClient,
+        call_config: CallConfig,
+        relay: &InProcessSignalingRelay,
+        router: &Router,
+        stopper: &Stopper,
+    ) -> Result<Self> {
+        let (ringing_tx, ringing) = channel();
+        let (connected_tx, connected) = channel();
+
+        let mut endpoint = CallEndpoint::new(
+            &client.name,
+            client.device_id,
+            client.user_id.clone(),
+            &call_config.audio_config,
+            Box::new(relay.clone()),
+            stopper,
+            EventSync {
+                ringing: Some(ringing_tx),
+                connected: Some(connected_tx),
+            },
+            None,
+            true,
+        )?;
+
+        let send_config = client
+            .links
+            .first()
+            .filter(|link| link.offset.is_zero())
+            .map_or_else(LinkSpec::unconstrained, LinkSpec::to_link_config);
+        endpoint.add_simulated_network(
+            router,
+            &client.ip,
+            &send_config,
+            &LinkSpec::unconstrained(),
+        );
+        endpoint.init_direct_settings(false, &IceServer::none(), call_config);
+
+        Ok(Self {
+            endpoint,
+            ip: client.ip.clone(),
+            ringing,
+            connected,
+        })
+    }
+
+    fn wait_for(&self, event: &Receiver<()>, what: &str) -> Result<()> {
+        info!("Waiting for {} to be {}...", self.endpoint.name, what);
+        event.recv_timeout(CONNECT_TIMEOUT).map_err(|_| {
+            anyhow!(
+                "{} was not {} within {:?}",
+                self.endpoint.name,
+                what,
+                CONNECT_TIMEOUT
+            )
+        })
+    }
+}
+
+/// Runs a call from `caller` to `callee` for `call_length`, applying the link changes of
+/// both clients at their offsets, then hangs up.
+///
+/// Both endpoints share the process' log, so only the callee posts stats. This matches
+/// the reports, which are built from the callee's perspective.
+pub fn run(caller: InProcessClient, callee: InProcessClient, call_length: Duration) -> Result<()> {
+    if caller.ip.is_empty() || callee.ip.is_empty() || caller.ip == callee.ip {
+        return Err(anyhow!(
+            "Each client needs its own --ip when running in-process"
+        ));
+    }
+
+    info!("Starting in-process scenario...");
+
+    let stopper = Stopper::new();
+    let relay = InProcessSignalingRelay::new(&stopper)?;
+    let router = Router::start(&stopper)?;
+
+    let mut caller_call_config = caller.call_config.clone();
+    caller_call_config.stats_interval_secs = u16::MAX;
+    caller_call_config.stats_initial_offset_secs = u16::MAX;
+
+    let caller_endpoint =
+        InProcessEndpoint::start(&caller, caller_call_config, &relay, &router, &stopper)?;
+    let callee_endpoint = InProcessEndpoint::start(
+        &callee,
+        callee.call_config.clone(),
+        &relay,
+        &router,
+        &stopper,
+    )?;
+
+    // Link changes from both clients, in the order they should be applied.
+    let mut link_changes: Vec<(&InProcessEndpoint, &LinkSpec)> = caller
+        .links
+        .iter()
+        .map(|link| (&caller_endpoint, link))
+        .chain(callee.links.iter().map(|link| (&callee_endpoint, link)))
+        .filter(|(_, link)| !link.offset.is_zero())
+        .collect();
+    link_changes.sort_by_key(|(_, link)| link.offset);
+
+    let result = run_call(
+        &caller_endpoint,
+        &callee_endpoint,
+        caller.device_id,
+        link_changes,
+        call_length,
+    );
+
+    caller_endpoint.endpoint.hangup();
+    callee_endpoint.endpoint.hangup();
+
+    // Then let the hangup settle.
+    thread::sleep(Duration::from_millis(100));
+
+    stopper.stop_all_and_join();
+
+    info!("Done with scenario.");
+    result
+}
+
+fn run_call(
+    caller: &InProcessEndpoint,
+    callee: &InProcessEndpoint,
+    caller_device_id: DeviceId,
+    link_changes: Vec<(&InProcessEndpoint, &LinkSpec)>,
+    call_length: Duration,
+) -> Result<()> {
+    // Like the managed scenario, the callee id doesn't need to be the actual id.
+    let call_id = CallId::new(0xCA111D);
+    let start_time = Instant::now();
+    caller.endpoint.create_outgoing_direct_call(
+        &PeerId::from("dummy"),
+        call_id,
+        CallMediaType::Audio,
+        caller_device_id,
+    );
+
+    // Wait to be in the ringing state before accepting an incoming call.
+    callee.wait_for(&callee.ringing, "ringing")?;
+    callee.endpoint.accept_incoming_direct_call(call_id);
+
+    caller.wait_for(&caller.connected, "connected")?;
+    callee.wait_for(&callee.connected, "connected")?;
+    info!("Now in the call...");
+
+    for (client, link) in link_changes {
+        if link.offset >= call_length {
+            break;
+        }
+        thread::sleep(link.offset.saturating_sub(start_time.elapsed()));
+        info!(
+            "Applying new emulated network settings for {}: {:?}",
+            client.endpoint.name, link
+        );
+        client.endpoint.set_simulated_link(
+            &client.ip,
+            &link.to_link_config(),
+            &LinkSpec::unconstrained(),
+        );
+    }
+
+    thread::sleep(call_length.saturating_sub(start_time.elapsed()));
+    Ok(())
+}
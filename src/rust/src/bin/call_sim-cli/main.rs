@@ -4,13 +4,14 @@
 //
 
 mod endpoint;
+mod in_process;
 mod network;
 mod relay;
 mod scenario;
 mod util;
 mod video;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use base64::prelude::*;
 use clap::Parser;
 use fern::Dispatch;
@@ -28,9 +29,12 @@ use ringrtc::{
     },
 };
 use scenario::ScenarioCallTypeConfig;
-use std::ffi::CString;
+use std::{ffi::CString, iter, time::Duration};
 
-use crate::scenario::ScenarioManager;
+use crate::{
+    endpoint::ScheduledGroupAction, in_process::InProcessClient, network::LinkSpec,
+    scenario::ScenarioManager,
+};
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -131,6 +135,10 @@ struct Args {
     #[arg(long, action = clap::ArgAction::Set, default_value = "true")]
     fec: bool,
 
+    /// Whether to capture, play out and encode audio in stereo.
+    #[arg(long, action = clap::ArgAction::Set, default_value = "false")]
+    stereo: bool,
+
     /// Whether to use adaptation when encoding audio. Set to 0 to disable (default).
     #[arg(long, default_value_t = 0)]
     adaptation: i32,
@@ -234,12 +242,47 @@ struct Args {
     /// Formatted as `{userId}:{memberId}`.
     #[arg(short = 'm', long, value_delimiter = ',', value_parser = parse_group_member_info)]
     pub group_member_info: Option<Vec<GroupMember>>,
+
+    /// Scripts the group call, formatted as `<offset_secs>:<action>` with the offset counted
+    /// from the start of the call. Actions are `join`, `leave`, `mute-audio`, `unmute-audio`,
+    /// `mute-video`, `unmute-video`, `present`, `stop-presenting`, `raise-hand`, `lower-hand`,
+    /// `react=<value>` and `request-video=<width>x<height>`. Without a `join` action, the
+    /// client joins as soon as the call starts.
+    #[arg(long)]
+    group_action: Vec<ScheduledGroupAction>,
+
+    /// Runs a direct call with a peer in this process instead of waiting for commands from
+    /// the test manager. The peer is configured by the arguments following `--`. Signaling
+    /// is relayed in memory and media is routed over a simulated network, so no containers
+    /// or servers are needed.
+    #[arg(long)]
+    in_process: bool,
+
+    /// How long the call lasts when running in-process.
+    #[arg(long, default_value = "30")]
+    call_length_secs: u64,
+
+    /// Emulates the client's uplink when running in-process, formatted as comma-separated
+    /// `key=value` pairs of `offset`, `delay`, `jitter` (ms), `loss`, `burst_loss` (%),
+    /// `rate` (kbps) and `limit` (packets). Repeat with later offsets to change the
+    /// emulation during the call.
+    #[arg(long)]
+    link: Vec<LinkSpec>,
+
+    /// The peer's arguments when running in-process.
+    #[arg(last = true)]
+    peer_args: Vec<String>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    let peer_args = args.in_process.then(|| {
+        Args::parse_from(
+            iter::once("call_sim-cli").chain(args.peer_args.iter().map(String::as_str)),
+        )
+    });
 
-    let fern_logger = Dispatch::new()
+    let mut fern_logger = Dispatch::new()
         .format(|out, message, record| {
             out.finish(format_args!(
                 "[{} {} {}:{}] {}",
@@ -252,8 +295,13 @@ fn main() -> Result<()> {
         })
         .level(LevelFilter::Debug);
 
-    if let Some(log_file) = args.log_file {
-        fern_logger.chain(fern::log_file(log_file)?).apply()?;
+    if let Some(log_file) = &args.log_file {
+        fern_logger = fern_logger.chain(fern::log_file(log_file)?);
+        // The peer shares the process, so its log file gets the same output.
+        if let Some(peer_log_file) = peer_args.as_ref().and_then(|peer| peer.log_file.as_ref()) {
+            fern_logger = fern_logger.chain(fern::log_file(peer_log_file)?);
+        }
+        fern_logger.apply()?;
     } else {
         fern_logger.chain(std::io::stdout()).apply()?;
     }
@@ -264,6 +312,28 @@ fn main() -> Result<()> {
     info!("Setting field trials to {}", &args.field_trials);
     ringrtc::webrtc::field_trial::init(&args.field_trials).expect("no null characters");
 
+    if let Some(peer_args) = peer_args {
+        for client_args in [&args, &peer_args] {
+            if client_args.is_group_call
+                || client_args.input_video_file.is_some()
+                || !client_args.relay_servers.is_empty()
+                || client_args.deterministic_loss.is_some()
+            {
+                return Err(anyhow!(
+                    "Only audio calls without relay servers or deterministic loss can run in-process"
+                ));
+            }
+        }
+
+        return in_process::run(
+            in_process_client(&args),
+            in_process_client(&peer_args),
+            Duration::from_secs(args.call_length_secs),
+        );
+    }
+
+    let call_config = call_config(&args);
+
     let ice_server = if args.relay_servers.is_empty() {
         IceServer::none()
     } else {
@@ -281,8 +351,49 @@ fn main() -> Result<()> {
         )
     };
 
-    // Create a call configuration that should be used for the call.
-    let call_config = CallConfig {
+    let mut scenario = ScenarioManager::new()?;
+    let call_type_config = if args.is_group_call {
+        ScenarioCallTypeConfig::GroupCallConfig {
+            sfu_url: args.sfu_url.expect("sfu url should be provided"),
+            group_id: args.group_id.expect("group_id should be provided"),
+            membership_proof: args
+                .membership_proof
+                .expect("membership proof should be provided"),
+            group_member_info: args
+                .group_member_info
+                .expect("group_member_info should be provided"),
+            actions: args.group_action,
+        }
+    } else {
+        ScenarioCallTypeConfig::DirectCallConfig {
+            ice_server,
+            force_relay: args.force_relay,
+        }
+    };
+    scenario.run(
+        &args.name,
+        &args.ip,
+        args.user_id,
+        args.device_id,
+        call_config,
+        scenario::ScenarioConfig {
+            video_width: args.input_video_width,
+            video_height: args.input_video_height,
+            video_input: args.input_video_file.map(Into::into),
+            output_video_width: args.output_video_width,
+            output_video_height: args.output_video_height,
+            video_output: args.output_video_file.map(Into::into),
+            deterministic_loss: args.deterministic_loss,
+            call_type_config,
+        },
+    );
+
+    Ok(())
+}
+
+/// Creates a call configuration that should be used for the call.
+fn call_config(args: &Args) -> CallConfig {
+    CallConfig {
         // This configuration is currently the same as `Normal`.
         data_mode: DataMode::Custom {
             max_bitrate: units::DataRate::from_kbps(args.allowed_bitrate_kbps as u64),
@@ -293,13 +404,16 @@ fn main() -> Result<()> {
         audio_config: AudioConfig {
             audio_device_module_type: RffiAudioDeviceModuleType::File,
             file_based_adm_config: Some(FileBasedAdmConfig {
-                input_file: CString::new(args.input_file).expect("CString::new failed"),
-                output_file: CString::new(args.output_file).expect("CString::new failed"),
+                input_file: CString::new(args.input_file.as_str()).expect("CString::new failed"),
+                output_file: CString::new(args.output_file.as_str()).expect("CString::new failed"),
             }),
             high_pass_filter_enabled: args.high_pass_filter,
             aec_enabled: args.aec,
             ns_enabled: args.ns,
             agc_enabled: args.agc,
+            stereo_enabled: args.stereo,
+            #[cfg(all(not(feature = "sim"), feature = "virtual_audio"))]
+            virtual_audio_device: None,
         },
         audio_encoder_config: AudioEncoderConfig {
             initial_packet_size_ms: args
@@ -317,6 +431,7 @@ fn main() -> Result<()> {
             enable_cbr: args.cbr,
             enable_dtx: args.dtx,
             enable_fec: args.fec,
+            enable_stereo: args.stereo,
         },
         enable_tcc_audio: args.tcc,
         enable_red_audio: args.red,
@@ -328,45 +443,23 @@ fn main() -> Result<()> {
         },
         audio_rtcp_report_interval_ms: args.audio_rtcp_report_interval_ms,
         enable_vp9: args.vp9,
-    };
-
-    let mut scenario = ScenarioManager::new()?;
-    let call_type_config = if args.is_group_call {
-        ScenarioCallTypeConfig::GroupCallConfig {
-            sfu_url: args.sfu_url.expect("sfu url should be provided"),
-            group_id: args.group_id.expect("group_id should be provided"),
-            membership_proof: args
-                .membership_proof
-                .expect("membership proof should be provided"),
-            group_member_info: args
-                .group_member_info
-                .expect("group_member_info should be provided"),
-        }
-    } else {
-        ScenarioCallTypeConfig::DirectCallConfig {
-            ice_server,
-            force_relay: args.force_relay,
-        }
-    };
-    scenario.run(
-        &args.name,
-        &args.ip,
-        args.user_id,
-        args.device_id,
-        call_config,
-        scenario::ScenarioConfig {
-            video_width: args.input_video_width,
-            video_height: args.input_video_height,
-            video_input: args.input_video_file.map(Into::into),
-            output_video_width: args.output_video_width,
-            output_video_height: args.output_video_height,
-            video_output: args.output_video_file.map(Into::into),
-            deterministic_loss: args.deterministic_loss,
-            call_type_config,
-        },
-    );
+        candidate_policy: Default::default(),
+        video_upgrade_timeout: CallConfig::default().video_upgrade_timeout,
+    }
+}
 
-    Ok(())
+fn in_process_client(args: &Args) -> InProcessClient {
+    let mut links = args.link.clone();
+    links.sort_by_key(|link| link.offset);
+
+    InProcessClient {
+        name: args.name.clone(),
+        ip: args.ip.clone(),
+        user_id: args.user_id.clone(),
+        device_id: args.device_id,
+        call_config: call_config(args),
+        links,
+    }
 }
 
 fn parse_base64(s: &str) -> Result<GroupId, String> {
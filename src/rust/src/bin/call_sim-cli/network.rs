@@ -9,21 +9,32 @@ use bitvec::{
     prelude::{BitSlice, LocalBits, Lsb0},
 };
 use log::*;
-use ringrtc::common::Result;
+use ringrtc::common::{
+    units::{DataRate, DataSize},
+    Result,
+};
 use std::{
     iter::{Cycle, StepBy},
+    str::FromStr,
     thread,
+    time::Duration,
 };
 
-use ringrtc::webrtc::{
-    injectable_network::{self, InjectableNetwork},
-    network::NetworkInterfaceType,
+use ringrtc::{
+    simnet::router::{self, LinkConfig, Router},
+    webrtc::{
+        injectable_network::{self, InjectableNetwork},
+        network::NetworkInterfaceType,
+    },
 };
 use std::{
     io,
-    net::{SocketAddr, UdpSocket},
+    net::{IpAddr, SocketAddr, UdpSocket},
 };
 
+/// The packet size assumed when converting a queue limit in packets to a queue size.
+const MAX_PACKET_SIZE_BYTES: u64 = 1500;
+
 pub struct DeterministicLoss {
     pre_delay: u8,
     ignore_last_n: u8,
@@ -119,10 +130,104 @@ impl DeterministicLoss {
     }
 }
 
-/// Wrapper around InjectableNetwork that allows adding DeterministicLoss
+/// Emulation settings for one client's uplink, applied from `offset` into the call.
+///
+/// Parsed from comma-separated `key=value` pairs, for example
+/// `offset=10,delay=50,jitter=10,loss=2,burst_loss=30,rate=256,limit=100`. Times are in ms,
+/// losses in percent, the rate in kbps (0 is unlimited), and the limit in packets.
+#[derive(Clone, Debug)]
+pub struct LinkSpec {
+    pub offset: Duration,
+    pub delay_ms: u64,
+    pub jitter_ms: u64,
+    pub loss_percent: f64,
+    /// The loss probability for a packet following a lost one. Defaults to `loss_percent`.
+    pub burst_loss_percent: Option<f64>,
+    pub rate_kbps: u64,
+    pub limit_packets: u64,
+}
+
+impl Default for LinkSpec {
+    fn default() -> Self {
+        Self {
+            offset: Duration::ZERO,
+            delay_ms: 0,
+            jitter_ms: 0,
+            loss_percent: 0.0,
+            burst_loss_percent: None,
+            rate_kbps: 0,
+            // The default used by netem.
+            limit_packets: 1000,
+        }
+    }
+}
+
+impl FromStr for LinkSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        fn percent(value: &str) -> std::result::Result<f64, String> {
+            match value.parse::<f64>() {
+                Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent),
+                _ => Err(format!("invalid percentage `{value}`")),
+            }
+        }
+
+        let mut spec = Self::default();
+        for pair in s.split(',').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected `key=value`, got `{pair}`"))?;
+            let number = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|e| format!("invalid value for `{key}`: {e}"))
+            };
+            match key {
+                "offset" => spec.offset = Duration::from_millis(number()?),
+                "delay" => spec.delay_ms = number()?,
+                "jitter" => spec.jitter_ms = number()?,
+                "loss" => spec.loss_percent = percent(value)?,
+                "burst_loss" => spec.burst_loss_percent = Some(percent(value)?),
+                "rate" => spec.rate_kbps = number()?,
+                "limit" => spec.limit_packets = number()?,
+                _ => return Err(format!("unknown link setting `{key}`")),
+            }
+        }
+        Ok(spec)
+    }
+}
+
+impl LinkSpec {
+    pub fn to_link_config(&self) -> LinkConfig {
+        let rate = if self.rate_kbps == 0 {
+            DataRate::from_mbps(1000)
+        } else {
+            DataRate::from_kbps(self.rate_kbps)
+        };
+        LinkConfig {
+            delay_min: Duration::from_millis(self.delay_ms.saturating_sub(self.jitter_ms)),
+            // The delay is sampled from a half-open range, so it must not be empty.
+            delay_max: Duration::from_millis(self.delay_ms + self.jitter_ms + 1),
+            loss_probability: self.loss_percent / 100.0,
+            repeated_loss_probability: self.burst_loss_percent.unwrap_or(self.loss_percent) / 100.0,
+            rate,
+            queue_size: DataSize::from_bytes(MAX_PACKET_SIZE_BYTES * self.limit_packets.max(1)),
+        }
+    }
+
+    /// A link that doesn't noticeably delay, drop or limit anything.
+    pub fn unconstrained() -> LinkConfig {
+        Self::default().to_link_config()
+    }
+}
+
+/// Wrapper around InjectableNetwork that allows adding DeterministicLoss or routing
+/// packets through a simulated network.
 pub struct DeterministicLossNetwork {
     injectable_network: InjectableNetwork,
     socket: Option<UdpSocket>,
+    router: Option<Router>,
 }
 
 impl DeterministicLossNetwork {
@@ -130,6 +235,7 @@ impl DeterministicLossNetwork {
         Self {
             injectable_network,
             socket: None,
+            router: None,
         }
     }
 
@@ -194,6 +300,61 @@ impl DeterministicLossNetwork {
         });
     }
 
+    /// Sends and receives packets for the given IP through a simulated router, which can
+    /// be shared with other endpoints in the same process.
+    pub fn add_router_interface(
+        &mut self,
+        router: Router,
+        ip: String,
+        send_config: LinkConfig,
+        receive_config: LinkConfig,
+    ) {
+        let ip = ip.parse().expect("parse IP address");
+
+        let router_as_sender = router.clone();
+        self.injectable_network
+            .set_sender(Box::new(move |packet: injectable_network::Packet| {
+                router_as_sender.send_packet(router::Packet {
+                    source: packet.source,
+                    dest: packet.dest,
+                    data: packet.data,
+                });
+            }));
+
+        // Adding it to the network causes the PeerConnections to learn about it through
+        // the NetworkMonitor. For our tests, we just assume "wifi" for simplicity.
+        self.injectable_network
+            .add_interface("wifi", NetworkInterfaceType::Wifi, ip, 1);
+
+        self.router = Some(router);
+        self.set_router_link(ip, send_config, receive_config);
+    }
+
+    /// Replaces the links used for the IP's interface on the simulated router. Packets
+    /// already in flight are still delivered according to the old links.
+    pub fn set_router_link(&self, ip: IpAddr, send_config: LinkConfig, receive_config: LinkConfig) {
+        let Some(router) = &self.router else {
+            error!("Error: No router interface has been added!");
+            return;
+        };
+
+        let network_as_receiver = self.injectable_network.clone();
+        router
+            .add_interface(
+                ip,
+                send_config,
+                receive_config,
+                Box::new(move |packet: router::Packet| {
+                    network_as_receiver.receive_udp(injectable_network::Packet {
+                        source: packet.source,
+                        dest: packet.dest,
+                        data: packet.data,
+                    });
+                }),
+            )
+            .expect("add router interface");
+    }
+
     pub fn stop_network(&self) {
         if let Some(socket) = &self.socket {
             socket
@@ -387,3 +387,90 @@ impl SignalingRelay for CallSimSignalingRelayClient {
         });
     }
 }
+
+/// Relays signaling between endpoints running in the same process. Like the call_sim
+/// signaling server, every message is delivered to all other registered endpoints.
+#[derive(Clone)]
+pub struct InProcessSignalingRelay {
+    actor: Actor<InProcessSignalingRelayState>,
+}
+
+struct InProcessSignalingRelayState {
+    endpoints: Vec<CallEndpoint>,
+}
+
+impl InProcessSignalingRelay {
+    pub fn new(stopper: &Stopper) -> Result<Self> {
+        Ok(Self {
+            actor: Actor::start("InProcessRelay", stopper.clone(), move |_actor| {
+                Ok(InProcessSignalingRelayState { endpoints: vec![] })
+            })?,
+        })
+    }
+
+    fn for_each_other_endpoint(
+        &self,
+        sender_id: &PeerId,
+        sender_device_id: DeviceId,
+        deliver: impl Fn(&CallEndpoint) + Send + 'static,
+    ) {
+        // To send across threads
+        let sender_id = sender_id.clone();
+
+        self.actor.send(move |state| {
+            for endpoint in &state.endpoints {
+                if endpoint.peer_id() != sender_id || endpoint.device_id != sender_device_id {
+                    deliver(endpoint);
+                }
+            }
+        });
+    }
+}
+
+impl SignalingRelay for InProcessSignalingRelay {
+    fn register(&self, endpoint: &CallEndpoint) {
+        // To send across threads
+        let endpoint = endpoint.clone();
+
+        self.actor.send(move |state| {
+            info!("InProcessRelay: Registered {}", endpoint.relay_id());
+            state.endpoints.push(endpoint);
+        });
+    }
+
+    fn send_signaling(
+        &self,
+        sender_id: &PeerId,
+        sender_device_id: DeviceId,
+        recipient_id: &PeerId,
+        call_id: CallId,
+        msg: Message,
+    ) {
+        info!("send(): Message to {}", recipient_id);
+
+        let from = sender_id.clone();
+        self.for_each_other_endpoint(sender_id, sender_device_id, move |endpoint| {
+            endpoint.receive_signaling(&from, sender_device_id, call_id, msg.clone());
+        });
+    }
+
+    fn send_call_message(
+        &self,
+        sender_id: &PeerId,
+        sender_device_id: DeviceId,
+        recipient_id: Vec<u8>,
+        opaque_message: Vec<u8>,
+    ) {
+        info!("send_opaque_message(): Message to {:?}", recipient_id);
+
+        let from = sender_id.clone();
+        self.for_each_other_endpoint(sender_id, sender_device_id, move |endpoint| {
+            endpoint.receive_call_message(
+                &from,
+                sender_device_id,
+                Instant::now(),
+                opaque_message.clone(),
+            );
+        });
+    }
+}
@@ -26,7 +26,7 @@ use tonic::transport::Channel;
 use tower::timeout::Timeout;
 
 use crate::{
-    endpoint::{CallEndpoint, EventSync},
+    endpoint::{CallEndpoint, EventSync, GroupAction, ScheduledGroupAction},
     relay::CallSimSignalingRelayClient,
     video::{self, I420Source},
 };
@@ -70,6 +70,7 @@ pub enum ScenarioCallTypeConfig {
         group_id: GroupId,
         membership_proof: MembershipProof,
         group_member_info: Vec<GroupMember>,
+        actions: Vec<ScheduledGroupAction>,
     },
 }
 
@@ -171,6 +172,7 @@ impl ScenarioManager {
                 group_id,
                 membership_proof: _,
                 group_member_info,
+                actions: _,
             } => {
                 client.init_group_settings(HashMap::from([(
                     group_id.clone(),
@@ -382,6 +384,7 @@ impl ScenarioManager {
             group_id,
             membership_proof,
             group_member_info: _,
+            actions,
         } = group_scenario_config
         else {
             panic!("expected a group call config")
@@ -396,46 +399,29 @@ impl ScenarioManager {
                     match Command::try_from(message.command) {
                         Ok(Command::StartAsCaller) => {
                             info!("command_message::Command::StartAsCaller");
-                            client.join_group_call(
-                                sfu_url.clone(),
-                                group_id.clone(),
-                                membership_proof.clone(),
+                            Self::start_group(
+                                &client,
+                                &client_sync,
+                                &mut video_input,
+                                &sfu_url,
+                                &group_id,
+                                &membership_proof,
+                                &actions,
                             );
-
-                            info!("Waiting to be connected...");
-                            let _ = client_sync.connected.recv();
-                            info!("Now in the group call...");
-
-                            if let Some(video_input) = video_input.take() {
-                                client.send_video(
-                                    video_input,
-                                    video::FRAME_INTERVAL_30FPS,
-                                    Duration::from_secs(1),
-                                )
-                            }
-
                             info!("finished command_message::Command::StartAsCaller");
                         }
                         Ok(Command::StartAsCallee) => {
                             info!("command_message::Command::StartAsCallee");
                             // TODO: implement wait for group ring
-                            client.join_group_call(
-                                sfu_url.clone(),
-                                group_id.clone(),
-                                membership_proof.clone(),
+                            Self::start_group(
+                                &client,
+                                &client_sync,
+                                &mut video_input,
+                                &sfu_url,
+                                &group_id,
+                                &membership_proof,
+                                &actions,
                             );
-
-                            info!("Waiting to be connected...");
-                            let _ = client_sync.connected.recv();
-                            info!("Now in the call...");
-
-                            if let Some(video_input) = video_input.take() {
-                                client.send_video(
-                                    video_input,
-                                    video::FRAME_INTERVAL_30FPS,
-                                    Duration::from_secs(1),
-                                )
-                            }
                             info!("finished command_message::Command::StartAsCallee");
                         }
                         Ok(Command::Stop) => {
@@ -466,4 +452,57 @@ impl ScenarioManager {
         }
         info!("Done with scenario.");
     }
+
+    /// Joins the group call and schedules the scripted actions, counting their
+    /// offsets from now. Without a scripted join, the client joins right away
+    /// and waits to be connected.
+    fn start_group(
+        client: &CallEndpoint,
+        client_sync: &ClientSync,
+        video_input: &mut Option<I420Source<File>>,
+        sfu_url: &str,
+        group_id: &GroupId,
+        membership_proof: &MembershipProof,
+        actions: &[ScheduledGroupAction],
+    ) {
+        let first_join = actions
+            .iter()
+            .filter(|scheduled| scheduled.action == GroupAction::Join)
+            .map(|scheduled| scheduled.offset)
+            .min();
+
+        if first_join.is_none() {
+            client.join_group_call(
+                sfu_url.to_string(),
+                group_id.clone(),
+                membership_proof.clone(),
+            );
+        }
+
+        for ScheduledGroupAction { offset, action } in actions {
+            match action {
+                GroupAction::Join => client.join_group_call_after(
+                    *offset,
+                    sfu_url.to_string(),
+                    group_id.clone(),
+                    membership_proof.clone(),
+                ),
+                action => client.perform_group_action_after(*offset, action.clone()),
+            }
+        }
+
+        let video_delay = match first_join {
+            Some(offset) => offset + Duration::from_secs(1),
+            None => {
+                info!("Waiting to be connected...");
+                let _ = client_sync.connected.recv();
+                info!("Now in the group call...");
+                Duration::from_secs(1)
+            }
+        };
+
+        if let Some(video_input) = video_input.take() {
+            client.send_video(video_input, video::FRAME_INTERVAL_30FPS, video_delay)
+        }
+    }
 }
@@ -19,8 +19,9 @@ use ringrtc::{
         sfu::{DemuxId, UserId},
     },
     native::{
-        CallState, CallStateHandler, GroupUpdate, GroupUpdateHandler, NativeCallContext,
-        NativePlatform, PeerId, SignalingSender,
+        CallState, CallStateHandler, EscalationState, GroupUpdate, GroupUpdateHandler,
+        NativeCallContext, NativePlatform, PeerId, RemoteDeviceCondition, SignalingSender,
+        VideoUpgradeState,
     },
     simnet::{
         router,
@@ -33,7 +34,8 @@ use ringrtc::{
         network::NetworkInterfaceType,
         peer_connection::AudioLevel,
         peer_connection_factory::{
-            self as pcf, IceServer, PeerConnectionFactory, RffiAudioDeviceModuleType,
+            self as pcf, AudioDevicesChanged, IceServer, PeerConnectionFactory,
+            RffiAudioDeviceModuleType,
         },
         peer_connection_observer::NetworkRoute,
     },
@@ -655,6 +657,11 @@ impl CallStateHandler for CallEndpoint {
         Ok(())
     }
 
+    fn handle_audio_devices_changed(&self, change: AudioDevicesChanged) -> Result<()> {
+        info!("Audio devices changed for {}: {:?}", self.peer_id, change);
+        Ok(())
+    }
+
     fn handle_remote_audio_state(&self, remote_peer_id: &str, enabled: bool) -> Result<()> {
         info!(
             "Audio State for {} => {}: {}",
@@ -678,6 +685,48 @@ impl CallStateHandler for CallEndpoint {
         );
         Ok(())
     }
+
+    fn handle_remote_held(&self, remote_peer_id: &str, held: bool) -> Result<()> {
+        info!("Held for {} => {}: {}", self.peer_id, remote_peer_id, held);
+        Ok(())
+    }
+
+    fn handle_local_held(&self, remote_peer_id: &str, held: bool) -> Result<()> {
+        info!(
+            "Local held for {} => {}: {}",
+            self.peer_id, remote_peer_id, held
+        );
+        Ok(())
+    }
+
+    fn handle_escalation(&self, remote_peer_id: &str, state: EscalationState) -> Result<()> {
+        info!(
+            "Escalation for {} => {}: {:?}",
+            self.peer_id, remote_peer_id, state
+        );
+        Ok(())
+    }
+
+    fn handle_video_upgrade(&self, remote_peer_id: &str, state: VideoUpgradeState) -> Result<()> {
+        info!(
+            "Video upgrade for {} => {}: {:?}",
+            self.peer_id, remote_peer_id, state
+        );
+        Ok(())
+    }
+
+    fn handle_remote_device_condition(
+        &self,
+        remote_peer_id: &str,
+        condition: RemoteDeviceCondition,
+        active: bool,
+    ) -> Result<()> {
+        info!(
+            "Remote device condition for {} => {}: {:?} {}",
+            self.peer_id, remote_peer_id, condition, active
+        );
+        Ok(())
+    }
 }
 
 impl GroupUpdateHandler for CallEndpoint {
@@ -10,7 +10,7 @@ use std::sync::{Arc, Mutex};
 
 use log::info;
 
-use ringrtc::core::group_call::Reaction;
+use ringrtc::core::group_call::{ChatMessage, Reaction};
 use ringrtc::{
     common::units::DataRate,
     core::{
@@ -139,10 +139,18 @@ impl group_call::Observer for Observer {
         // ignore
     }
 
+    fn handle_chat_messages(&self, _client_id: ClientId, _messages: Vec<ChatMessage>) {
+        // ignore
+    }
+
     fn handle_raised_hands(&self, _client_id: ClientId, raised_hands: Vec<DemuxId>) {
         info!("Raised hands changed to {:?}", raised_hands);
     }
 
+    fn handle_remote_mute_request(&self, _client_id: ClientId, mute_source: DemuxId) {
+        info!("Mute requested by {}", mute_source);
+    }
+
     fn handle_rtc_stats_report(&self, _report_json: String) {
         // ignore
     }
@@ -10,7 +10,9 @@ pub mod jni_signature;
 pub mod units;
 
 use std::fmt;
+use std::time::Duration;
 
+use crate::core::candidate_policy::CandidatePolicy;
 use crate::webrtc::{
     media::AudioEncoderConfig,
     peer_connection_factory::{AudioConfig, AudioJitterBufferConfig},
@@ -400,6 +402,72 @@ pub enum ApplicationEvent {
     /// Received an offer while already handling an active call and glare
     /// was detected.
     ReceivedOfferWithGlare,
+
+    /// The remote side has put the call on hold.
+    RemoteHoldEnable,
+
+    /// The remote side has taken the call off hold.
+    RemoteHoldDisable,
+
+    /// The call ended because the remote side moved it to another of the
+    /// local user's devices.
+    EndedHandedOffToAnotherDevice,
+
+    /// The remote side has joined the call link call the call is being
+    /// escalated to.
+    EscalationRemoteJoined,
+
+    /// The call could not be escalated to a call link call, but continues.
+    EscalationFailed,
+
+    /// The call ended because both sides moved to a call link call.
+    EndedEscalatedToCallLink,
+
+    /// The remote side asked to turn the call into a video call. Answer with
+    /// `accept_video_upgrade` or `decline_video_upgrade`.
+    RemoteVideoUpgradeRequested,
+
+    /// The remote side withdrew its request to turn the call into a video call.
+    RemoteVideoUpgradeCanceled,
+
+    /// The call is being upgraded to a video call: the remote side accepted the
+    /// local request, or asked for the same thing. Video is now enabled in the
+    /// sender status, so the local camera should be turned on.
+    VideoUpgradeAccepted,
+
+    /// The remote side declined to turn the call into a video call.
+    VideoUpgradeDeclined,
+
+    /// The remote side didn't answer the request to turn the call into a video
+    /// call in time, and the request was canceled.
+    VideoUpgradeTimedOut,
+
+    /// The remote side's microphone is unavailable, e.g. because it was
+    /// unplugged.
+    RemoteMicrophoneDisconnected,
+
+    /// The remote side's microphone is available again.
+    RemoteMicrophoneReconnected,
+
+    /// The remote side's app went into the background, so its video is
+    /// paused by the OS.
+    RemoteBackgrounded,
+
+    /// The remote side's app came back into the foreground.
+    RemoteForegrounded,
+
+    /// The remote side is switching cameras, so its video is briefly paused.
+    RemoteCameraSwitchStarted,
+
+    /// The remote side has finished switching cameras.
+    RemoteCameraSwitchEnded,
+
+    /// The call was put on hold locally, because another call became the
+    /// active call.
+    LocalHoldEnable,
+
+    /// The call became the active call, after waiting or being on hold.
+    LocalHoldDisable,
 }
 
 impl fmt::Display for ApplicationEvent {
@@ -777,6 +845,13 @@ pub struct CallConfig {
     pub audio_rtcp_report_interval_ms: i32,
 
     pub enable_vp9: bool,
+
+    /// Which ICE candidates 1:1 calls may use.
+    pub candidate_policy: CandidatePolicy,
+
+    /// How long the remote side of a 1:1 call has to answer a video upgrade
+    /// request before it is canceled.
+    pub video_upgrade_timeout: Duration,
 }
 
 impl Default for CallConfig {
@@ -792,6 +867,8 @@ impl Default for CallConfig {
             audio_jitter_buffer_config: Default::default(),
             audio_rtcp_report_interval_ms: 5000,
             enable_vp9: true,
+            candidate_policy: Default::default(),
+            video_upgrade_timeout: Duration::from_secs(30),
         }
     }
 }
@@ -801,6 +878,22 @@ impl CallConfig {
         self.data_mode = data_mode;
         self
     }
+
+    /// Uses stereo Opus for outgoing audio.
+    pub fn with_stereo_audio(mut self, enabled: bool) -> Self {
+        self.audio_encoder_config.enable_stereo = enabled;
+        self
+    }
+
+    pub fn with_candidate_policy(mut self, candidate_policy: CandidatePolicy) -> Self {
+        self.candidate_policy = candidate_policy;
+        self
+    }
+
+    pub fn with_video_upgrade_timeout(mut self, video_upgrade_timeout: Duration) -> Self {
+        self.video_upgrade_timeout = video_upgrade_timeout;
+        self
+    }
 }
 
 // Benchmarking component list.
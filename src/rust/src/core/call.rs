@@ -13,8 +13,6 @@ use std::sync::{
 use std::thread;
 use std::time::Duration;
 
-use x25519_dalek::StaticSecret;
-
 use crate::common::actor::{Actor, Stopper};
 use crate::common::{
     ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, CallState, DeviceId, Result,
@@ -22,7 +20,7 @@ use crate::common::{
 use crate::core::call_fsm::{CallEvent, CallStateMachine};
 use crate::core::call_manager::CallManager;
 use crate::core::call_mutex::CallMutex;
-use crate::core::connection::{Connection, ConnectionObserverEvent, ConnectionType};
+use crate::core::connection::{Connection, ConnectionObserverEvent, ConnectionType, OfferSecrets};
 use crate::core::platform::Platform;
 use crate::core::signaling;
 use crate::error::RingRtcError;
@@ -48,8 +46,9 @@ where
 {
     parent_connection: Connection<T>,
     // Used to negotiate SRTP keys with
-    // the remote public key derived from the remote local secret.
-    local_secret: StaticSecret,
+    // the remote public key derived from the remote local secret
+    // (and the remote KEM ciphertext for V5).
+    local_secrets: OfferSecrets,
     ice_gatherer: IceGatherer,
     offer: signaling::Offer,
 }
@@ -70,6 +69,9 @@ where
     media_type: CallMediaType,
     /// The local DeviceId of the client.
     local_device_id: DeviceId,
+    /// Whether this outgoing call takes over a call with the same CallId
+    /// from another of the local user's devices.
+    handoff: bool,
     /// The application specific remote peer of this call
     app_remote_peer: Arc<CallMutex<<T as Platform>::AppRemotePeer>>,
     /// The application specific context for this call
@@ -78,6 +80,9 @@ where
     state: Arc<CallMutex<CallState>>,
     /// The actively connected connection.
     active_device_id: Arc<CallMutex<Option<DeviceId>>>,
+    /// The remote device that is taking over the call from the active
+    /// device, if a handoff is in progress.
+    handoff_device_id: Arc<CallMutex<Option<DeviceId>>>,
     /// Pending remote offer and associated data.  Incoming calls only.
     pending_call: Arc<CallMutex<Option<PendingCall>>>,
     /// Injects events into the [CallStateMachine](../call_fsm/struct.CallStateMachine.html).
@@ -165,10 +170,12 @@ where
             direction: self.direction,
             media_type: self.media_type,
             local_device_id: self.local_device_id,
+            handoff: self.handoff,
             app_remote_peer: Arc::clone(&self.app_remote_peer),
             app_call_context: Arc::clone(&self.app_call_context),
             state: Arc::clone(&self.state),
             active_device_id: Arc::clone(&self.active_device_id),
+            handoff_device_id: Arc::clone(&self.handoff_device_id),
             pending_call: Arc::clone(&self.pending_call),
             fsm_sender: self.fsm_sender.clone(),
             timeout_stopper: self.timeout_stopper.clone(),
@@ -194,6 +201,7 @@ where
         direction: CallDirection,
         media_type: CallMediaType,
         local_device_id: DeviceId,
+        handoff: bool,
         call_manager: CallManager<T>,
     ) -> Result<Self> {
         info!("new(): call_id: {}", call_id);
@@ -211,10 +219,12 @@ where
             direction,
             media_type,
             local_device_id,
+            handoff,
             app_remote_peer: Arc::new(CallMutex::new(app_remote_peer, "app_remote_peer")),
             app_call_context: Arc::new(CallMutex::new(None, "app_call_context")),
             state: Arc::new(CallMutex::new(CallState::NotYetStarted, "state")),
             active_device_id: Arc::new(CallMutex::new(None, "active_device_id")),
+            handoff_device_id: Arc::new(CallMutex::new(None, "handoff_device_id")),
             pending_call: Arc::new(CallMutex::new(None, "pending_call")),
             fsm_sender,
             timeout_stopper: Stopper::new(),
@@ -255,6 +265,20 @@ where
         self.direction
     }
 
+    /// Return true if this outgoing call takes over a call from another of
+    /// the local user's devices.
+    pub fn is_handoff(&self) -> bool {
+        self.handoff
+    }
+
+    /// Return true if the given remote device is taking over this call.
+    pub fn is_handoff_device(&self, remote_device: DeviceId) -> bool {
+        match self.handoff_device_id.lock() {
+            Ok(handoff_device_id) => *handoff_device_id == Some(remote_device),
+            Err(_) => false,
+        }
+    }
+
     /// Return the current Call state.
     pub fn state(&self) -> Result<CallState> {
         let state = self.state.lock()?;
@@ -405,6 +429,107 @@ where
         Ok(())
     }
 
+    /// Start a connection with another device of the remote user that is
+    /// taking over this call. The active connection stays up until the new
+    /// one is connected.
+    pub fn start_handoff(&self, received: signaling::ReceivedOffer) -> Result<()> {
+        let remote_device_id = received.sender_device_id;
+        info!("start_handoff(): remote_device: {}", remote_device_id);
+
+        if self.connection_map.lock()?.contains_key(&remote_device_id) {
+            info!("start_handoff(): already connected to device, ignoring");
+            return Ok(());
+        }
+        if let Some(handoff_device_id) = *self.handoff_device_id.lock()? {
+            info!(
+                "start_handoff(): already handing off to device {}, ignoring",
+                handoff_device_id
+            );
+            return Ok(());
+        }
+
+        let active_connection = self.active_connection()?;
+        let mut call_manager = self.call_manager()?;
+        let mut connection = call_manager.create_connection(
+            self,
+            remote_device_id,
+            ConnectionType::Incoming,
+            received.offer.latest_version(),
+            active_connection.call_config().clone(),
+            active_connection.audio_levels_interval(),
+        )?;
+        let answer = connection.start_incoming(received, Vec::new())?;
+        call_manager.send_answer(
+            self.clone(),
+            connection.clone(),
+            signaling::SendAnswer {
+                receiver_device_id: remote_device_id,
+                answer,
+            },
+        )?;
+
+        self.connection_map
+            .lock()?
+            .insert(remote_device_id, connection);
+        *self.handoff_device_id.lock()? = Some(remote_device_id);
+        Ok(())
+    }
+
+    /// Switch media over to the device taking over the call and let the
+    /// previously active device know that it can hang up.
+    pub fn complete_handoff(&self, remote_device: DeviceId) -> Result<()> {
+        info!("complete_handoff(): remote_device: {}", remote_device);
+
+        let old_device_id = self.active_device_id()?;
+        let (mut new_connection, old_connection) = {
+            let connection_map = self.connection_map.lock()?;
+            match connection_map.get(&remote_device) {
+                Some(v) => (v.clone(), connection_map.get(&old_device_id).cloned()),
+                None => return Err(RingRtcError::ConnectionNotFound(remote_device).into()),
+            }
+        };
+
+        new_connection.inject_accept()?;
+        new_connection.enable_media()?;
+        new_connection.start_tick()?;
+
+        // The old connection is kept until the old device hangs up so that
+        // the hangup keeps being resent over RTP data.
+        if let Some(old_connection) = old_connection {
+            old_connection.disable_media()?;
+            old_connection.send_hangup_via_rtp_data(
+                signaling::Hangup::HandedOffToAnotherDevice(remote_device),
+            )?;
+        }
+
+        *self.active_device_id.lock()? = Some(remote_device);
+        *self.handoff_device_id.lock()? = None;
+        self.notify_network_route_changed(new_connection.network_route()?)
+    }
+
+    /// Terminate the connection to a device that has left the call without
+    /// ending it, such as after a handoff.
+    pub fn drop_connection(&self, remote_device: DeviceId) -> Result<()> {
+        info!("drop_connection(): remote_device: {}", remote_device);
+
+        let connection = self.connection_map.lock()?.remove(&remote_device);
+        if let Some(mut connection) = connection {
+            connection.terminate()?;
+        }
+
+        let mut handoff_device_id = self.handoff_device_id.lock()?;
+        if *handoff_device_id == Some(remote_device) {
+            *handoff_device_id = None;
+        }
+        Ok(())
+    }
+
+    /// Put the active connection on hold (or take it off hold).
+    pub fn set_held(&self, held: bool) -> Result<()> {
+        let mut connection = self.active_connection()?;
+        connection.set_held(held)
+    }
+
     /// Notify application of an event.
     ///
     /// This is a pass through to the CallManager.
@@ -584,17 +709,22 @@ where
                     self,
                     0,
                     ConnectionType::OutgoingParent,
-                    signaling::Version::V4,
+                    signaling::Version::V5,
                     call_config,
                     audio_levels_interval,
                 )?;
-                let (local_secret, ice_gatherer, offer) =
+                let (local_secrets, ice_gatherer, offer) =
                     parent_connection.start_outgoing_parent(self.media_type)?;
+                let offer = if self.handoff {
+                    offer.into_handoff()?
+                } else {
+                    offer
+                };
 
                 // Keep around so that it's not closed until all the connections are closed.
                 *(self.forking.lock()?) = Some(ForkingState {
                     parent_connection: parent_connection.clone(),
-                    local_secret,
+                    local_secrets,
                     ice_gatherer,
                     offer: offer.clone(),
                 });
@@ -636,7 +766,7 @@ where
                     audio_levels_interval,
                 )?;
                 child_connection.start_outgoing_child(
-                    &forking.local_secret,
+                    &forking.local_secrets,
                     &forking.ice_gatherer,
                     &forking.offer,
                     &received,
@@ -1020,6 +1150,14 @@ where
         self.inject_event(CallEvent::ReceivedIce(received))
     }
 
+    /// Inject a `ReceivedHandoffOffer` event into the FSM
+    pub fn inject_received_handoff_offer(
+        &mut self,
+        received: signaling::ReceivedOffer,
+    ) -> Result<()> {
+        self.inject_event(CallEvent::ReceivedHandoffOffer(received))
+    }
+
     /// Inject a `ReceivedHangup` event into the FSM
     pub fn inject_received_hangup(&mut self, received: signaling::ReceivedHangup) -> Result<()> {
         self.inject_event(CallEvent::ReceivedHangup(received))
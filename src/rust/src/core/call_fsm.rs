@@ -86,6 +86,9 @@ pub enum CallEvent {
     ReceivedIce(signaling::ReceivedIce),
     /// Received hangup signal message from remote peer.
     ReceivedHangup(signaling::ReceivedHangup),
+    /// Received offer from another device of the remote peer that is taking
+    /// over the call.
+    ReceivedHandoffOffer(signaling::ReceivedOffer),
 
     /// Connection observer event
     ConnectionObserverEvent(ConnectionObserverEvent, DeviceId),
@@ -129,6 +132,12 @@ impl fmt::Display for CallEvent {
                 "ReceivedHangup, device: {} hangup: {}",
                 received.sender_device_id, received.hangup
             ),
+            CallEvent::ReceivedHandoffOffer(received) => {
+                format!(
+                    "ReceivedHandoffOffer, device: {}",
+                    received.sender_device_id
+                )
+            }
             CallEvent::ConnectionObserverEvent(e, d) => {
                 format!("ConnectionObserverEvent, event: {}, device: {}", e, d)
             }
@@ -386,6 +395,9 @@ where
             CallEvent::ReceivedHangup(received) => {
                 self.handle_received_hangup(call, state, received)
             }
+            CallEvent::ReceivedHandoffOffer(received) => {
+                self.handle_received_handoff_offer(call, state, received)
+            }
             CallEvent::ConnectionObserverEvent(event, remote_device_id) => {
                 self.handle_connection_observer_event(call, state, event, remote_device_id)
             }
@@ -538,6 +550,25 @@ where
         Ok(())
     }
 
+    fn handle_received_handoff_offer(
+        &mut self,
+        call: Call<T>,
+        state: CallState,
+        received: signaling::ReceivedOffer,
+    ) -> Result<()> {
+        // Only a call that is already up can be taken over.
+        if state.active() {
+            self.schedule_work_until_terminating(
+                call,
+                "Handle Received Handoff Offer failed",
+                move |call| call.start_handoff(received),
+            );
+        } else {
+            self.unexpected_state(state, "HandleReceivedHandoffOffer");
+        }
+        Ok(())
+    }
+
     fn handle_received_hangup(
         &mut self,
         call: Call<T>,
@@ -555,14 +586,33 @@ where
 
         // If the callee that originated the hangup, ignore messages that are propagated
         // back to us from the caller.
-        if direction == CallDirection::Incoming && Some(call.local_device_id()) == hangup_device_id
+        // (A handoff names a device of the other user, so it doesn't apply there.)
+        if direction == CallDirection::Incoming
+            && hangup_type != signaling::HangupType::HandedOffToAnotherDevice
+            && Some(call.local_device_id()) == hangup_device_id
         {
             info!("handle_received_hangup(): Ignoring hangup message originated by this device");
             return Ok(());
         }
 
         // If already connected to device A, ignore hangup messages from device B.
+        // The exception is a device that is handing off the call (or taking it over):
+        // drop its connection, but keep the call going.
         if let Ok(active_device_id) = call.active_device_id() {
+            if sender_device_id != active_device_id
+                && (hangup_type == signaling::HangupType::HandedOffToAnotherDevice
+                    || call.is_handoff_device(sender_device_id))
+            {
+                info!(
+                    "handle_received_hangup(): Dropping connection with device that left the call"
+                );
+                self.schedule_work_until_terminating(
+                    call,
+                    "Dropping connection failed",
+                    move |call| call.drop_connection(sender_device_id),
+                );
+                return Ok(());
+            }
             if sender_device_id != active_device_id {
                 info!("handle_received_hangup(): Ignoring hangup message from devices we aren't connected with");
                 return Ok(());
@@ -613,6 +663,11 @@ where
                 app_event_without_propagation(ApplicationEvent::EndedRemoteHangupBusy)
             }
 
+            // Either side gets handed off: the call continues on another device, so end quietly.
+            (signaling::HangupType::HandedOffToAnotherDevice, _) => {
+                app_event_without_propagation(ApplicationEvent::EndedHandedOffToAnotherDevice)
+            }
+
             // Everything else is unexpected: warn, and mostly treat like normal, no propagation.
             // TODO: Isn't NeedPermission for incoming normal because it's propagated above?
             // Should we make this no_app_event_and_no_propagation?
@@ -645,6 +700,12 @@ where
             }
         }
 
+        // Say goodbye with the same hangup so that the remote side drops this
+        // device's connection rather than ending the call.
+        if hangup_type == signaling::HangupType::HandedOffToAnotherDevice {
+            call.send_hangup_via_rtp_data_and_signaling_to_all_except(received.hangup, 0)?;
+        }
+
         // Send a Hangup event to the UX, if a call is being remotely hungup, the user
         // should always know.
         self.schedule_work_even_when_terminating(
@@ -781,6 +842,25 @@ where
                         // In Call::handle_accept_call, the CallState is set to ConnectedAndAccepted
                         // before the ConnectionState is set to ConnectedAndAccepted, so there's nothing to do here.
                     }
+                    (
+                        _,
+                        CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted,
+                        ConnectionState::ConnectedBeforeAccepted,
+                    ) if call.is_handoff_device(remote_device_id) => {
+                        info!(
+                            "handle_connection_observer_event(): Handing off to {}",
+                            remote_device_id
+                        );
+                        if state == CallState::ReconnectingAfterAccepted {
+                            call.set_state(CallState::ConnectedAndAccepted)?;
+                            self.notify_application(call.clone(), ApplicationEvent::Reconnected);
+                        }
+                        self.schedule_work_until_terminating(
+                            call,
+                            "Completing handoff failed",
+                            move |call| call.complete_handoff(remote_device_id),
+                        );
+                    }
                     (
                         _,
                         CallState::ConnectedAndAccepted,
@@ -944,11 +1024,87 @@ where
                     }
                     if let Some(audio_enabled) = status.audio_enabled {
                         if audio_enabled {
-                            self.notify_application(call, ApplicationEvent::RemoteAudioEnable)
+                            self.notify_application(
+                                call.clone(),
+                                ApplicationEvent::RemoteAudioEnable,
+                            )
                         } else {
-                            self.notify_application(call, ApplicationEvent::RemoteAudioDisable)
+                            self.notify_application(
+                                call.clone(),
+                                ApplicationEvent::RemoteAudioDisable,
+                            )
                         }
                     }
+                    if let Some(held) = status.held {
+                        if held {
+                            self.notify_application(
+                                call.clone(),
+                                ApplicationEvent::RemoteHoldEnable,
+                            )
+                        } else {
+                            self.notify_application(
+                                call.clone(),
+                                ApplicationEvent::RemoteHoldDisable,
+                            )
+                        }
+                    }
+                    if let Some(device_status) = status.device_status {
+                        self.handle_remote_device_status(call, device_status);
+                    }
+                } else {
+                    info!(
+                        "call_id: {} remote_device_id: {} Ignoring event: {}, from inactive connection.",
+                        call_id, remote_device_id, event
+                    );
+                }
+                Ok(())
+            }
+            ConnectionObserverEvent::RemoteVideoUpgrade(upgrade_type) => {
+                if state.active() && call.active_device_id()? == remote_device_id {
+                    let app_event = match upgrade_type {
+                        signaling::VideoUpgradeType::Request => {
+                            ApplicationEvent::RemoteVideoUpgradeRequested
+                        }
+                        signaling::VideoUpgradeType::Accept => {
+                            ApplicationEvent::VideoUpgradeAccepted
+                        }
+                        signaling::VideoUpgradeType::Decline => {
+                            ApplicationEvent::VideoUpgradeDeclined
+                        }
+                        signaling::VideoUpgradeType::Cancel => {
+                            ApplicationEvent::RemoteVideoUpgradeCanceled
+                        }
+                    };
+                    self.notify_application(call, app_event);
+                } else {
+                    info!(
+                        "call_id: {} remote_device_id: {} Ignoring event: {}, from inactive connection.",
+                        call_id, remote_device_id, event
+                    );
+                }
+                Ok(())
+            }
+            ConnectionObserverEvent::VideoUpgradeTimedOut => {
+                if state.active() && call.active_device_id()? == remote_device_id {
+                    self.notify_application(call, ApplicationEvent::VideoUpgradeTimedOut);
+                } else {
+                    info!(
+                        "call_id: {} remote_device_id: {} Ignoring event: {}, from inactive connection.",
+                        call_id, remote_device_id, event
+                    );
+                }
+                Ok(())
+            }
+            ConnectionObserverEvent::RemoteEscalationChanged(escalation) => {
+                if state.active() && call.active_device_id()? == remote_device_id {
+                    self.schedule_work_until_terminating(
+                        call,
+                        "Processing remote escalation failed",
+                        move |call| {
+                            call.call_manager()?
+                                .received_escalation(call.call_id(), escalation)
+                        },
+                    );
                 } else {
                     info!(
                         "call_id: {} remote_device_id: {} Ignoring event: {}, from inactive connection.",
@@ -989,6 +1145,31 @@ where
         }
     }
 
+    fn handle_remote_device_status(&mut self, call: Call<T>, status: signaling::DeviceStatus) {
+        let events = [
+            (
+                status.microphone_disconnected,
+                ApplicationEvent::RemoteMicrophoneDisconnected,
+                ApplicationEvent::RemoteMicrophoneReconnected,
+            ),
+            (
+                status.backgrounded,
+                ApplicationEvent::RemoteBackgrounded,
+                ApplicationEvent::RemoteForegrounded,
+            ),
+            (
+                status.camera_switching,
+                ApplicationEvent::RemoteCameraSwitchStarted,
+                ApplicationEvent::RemoteCameraSwitchEnded,
+            ),
+        ];
+        for (value, if_true, if_false) in events {
+            if let Some(value) = value {
+                self.notify_application(call.clone(), if value { if_true } else { if_false });
+            }
+        }
+    }
+
     fn handle_internal_error(&mut self, call: Call<T>, error: anyhow::Error) -> Result<()> {
         info!("handle_internal_error():");
         self.worker_spawn(move || {
@@ -28,13 +28,14 @@ use crate::common::{
 use crate::core::call::Call;
 use crate::core::call_mutex::CallMutex;
 use crate::core::connection::{Connection, ConnectionType};
-use crate::core::group_call::{HttpSfuClient, Observer, Reaction};
+use crate::core::group_call::{HttpSfuClient, Observer, Reaction, ReactionRateLimit};
 use crate::core::platform::Platform;
 use crate::core::signaling::ReceivedOffer;
+use crate::core::speaking_stats::SpeakingSummary;
 use crate::core::util::{try_scoped, uuid_to_string};
 use crate::core::{group_call, signaling};
 use crate::error::RingRtcError;
-use crate::lite::call_links::{self, CallLinkRootKey};
+use crate::lite::call_links::{self, CallLinkRestrictions, CallLinkRootKey};
 use crate::lite::{
     http, sfu,
     sfu::{DemuxId, GroupMember, MembershipProof, PeekInfo, UserId},
@@ -59,6 +60,35 @@ lazy_static! {
             .unwrap_or(TIME_OUT_PERIOD);
 }
 
+/// How long 1:1 calls ring and how long received rings stay valid.
+///
+/// Set per CallManager with [`CallManager::set_ring_policy`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RingPolicy {
+    /// How long an outgoing 1:1 call may take to be accepted before it
+    /// ends with [`ApplicationEvent::EndedTimeout`]. Zero means no timeout.
+    pub outgoing_ring_timeout: Duration,
+    /// How long an incoming (or waiting) 1:1 call may ring before it ends
+    /// with [`ApplicationEvent::EndedTimeout`]. Zero means no timeout.
+    pub incoming_ring_timeout: Duration,
+    /// Offers and group rings older than this when received are treated
+    /// as expired.
+    pub max_offer_age: Duration,
+    /// How long a received group ring stays outstanding before it expires.
+    pub group_ring_expiry: Duration,
+}
+
+impl Default for RingPolicy {
+    fn default() -> Self {
+        Self {
+            outgoing_ring_timeout: TIME_OUT_PERIOD,
+            incoming_ring_timeout: TIME_OUT_PERIOD,
+            max_offer_age: MAX_MESSAGE_AGE,
+            group_ring_expiry: *INCOMING_GROUP_CALL_RING_TIME,
+        }
+    }
+}
+
 /// Spawns a task on the worker thread to handle an API
 /// request with error handling.
 ///
@@ -136,6 +166,21 @@ enum MessageSendResult {
 
 type MessageClosure<T> = Box<dyn FnOnce(&CallManager<T>) -> Result<MessageSendResult> + Send>;
 
+/// What a message item sends when its turn comes.
+enum MessageContent<T>
+where
+    T: Platform,
+{
+    /// The closure to be called which will send the message.
+    Closure(MessageClosure<T>),
+    /// The buffered local ICE candidates of the connections, taken when
+    /// the message is sent and combined into one message.
+    Ice {
+        connections: Vec<Connection<T>>,
+        broadcast: bool,
+    },
+}
+
 /// A structure to hold messages in the message_queue, identified by their CallId.
 pub struct SignalingMessageItem<T>
 where
@@ -143,10 +188,100 @@ where
 {
     /// The CallId of the Call that the message belongs to.
     call_id: CallId,
+    /// The Call that the message belongs to.
+    call: Call<T>,
     /// The type of message the item corresponds to.
     message_type: signaling::MessageType,
-    /// The closure to be called which will send the message.
-    message_closure: MessageClosure<T>,
+    /// What the item sends.
+    content: MessageContent<T>,
+    /// When the item was queued.
+    queued: Instant,
+}
+
+impl<T> SignalingMessageItem<T>
+where
+    T: Platform,
+{
+    fn new(
+        call: Call<T>,
+        message_type: signaling::MessageType,
+        content: MessageContent<T>,
+    ) -> Self {
+        Self {
+            call_id: call.call_id(),
+            call,
+            message_type,
+            content,
+            queued: Instant::now(),
+        }
+    }
+
+    /// Hangup and Busy messages are sent even after their call has ended,
+    /// and ahead of any queued ICE messages.
+    fn is_urgent(&self) -> bool {
+        matches!(
+            self.message_type,
+            signaling::MessageType::Hangup | signaling::MessageType::Busy
+        )
+    }
+
+    /// Adds the connections of `other` to this item if both are ICE
+    /// messages for the same call and receiver.
+    ///
+    /// Returns `other` back if the items can't be merged.
+    fn coalesce(&mut self, other: Self) -> std::result::Result<(), Self> {
+        if self.call_id != other.call_id {
+            return Err(other);
+        }
+        match (&mut self.content, &other.content) {
+            (
+                MessageContent::Ice {
+                    connections,
+                    broadcast,
+                },
+                MessageContent::Ice {
+                    connections: other_connections,
+                    broadcast: other_broadcast,
+                },
+            ) if broadcast == other_broadcast
+                && (*broadcast
+                    || connections.first().map(Connection::remote_device_id)
+                        == other_connections.first().map(Connection::remote_device_id)) =>
+            {
+                for other_connection in other_connections {
+                    if !connections
+                        .iter()
+                        .any(|c| c.connection_id() == other_connection.connection_id())
+                    {
+                        connections.push(other_connection.clone());
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(other),
+        }
+    }
+}
+
+/// Statistics about the signaling message queue.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SignalingMessageQueueStats {
+    /// Messages currently waiting to be sent.
+    pub depth: usize,
+    /// The most messages that have been waiting at once.
+    pub max_depth: usize,
+    /// Messages handed to the application to send.
+    pub sent: u64,
+    /// ICE messages merged into an ICE message that was already queued.
+    pub coalesced: u64,
+    /// Messages dropped because their call ended before they were sent.
+    pub dropped: u64,
+    /// How long the last sent message waited in the queue.
+    pub last_latency: Duration,
+    /// The longest any sent message waited in the queue.
+    pub max_latency: Duration,
+    /// How long all sent messages waited in the queue, in total.
+    pub total_latency: Duration,
 }
 
 /// A structure implementing a message queue used to control the
@@ -166,6 +301,8 @@ where
     /// of being sent). We will only send one at a time to the
     /// application.
     messages_in_flight: bool,
+    /// Statistics about the queue; `depth` is filled in when read.
+    stats: SignalingMessageQueueStats,
 }
 
 impl<T> SignalingMessageQueue<T>
@@ -178,8 +315,54 @@ where
             queue: VecDeque::new(),
             last_sent_message_type: None,
             messages_in_flight: false,
+            stats: SignalingMessageQueueStats::default(),
         })
     }
+
+    /// Queue a message item.
+    ///
+    /// An ICE message directly following a queued ICE message for the same
+    /// call and receiver is merged into it. Urgent messages go ahead of any
+    /// queued ICE messages.
+    fn push(&mut self, message_item: SignalingMessageItem<T>) {
+        let message_item = match self.queue.back_mut() {
+            Some(last) => match last.coalesce(message_item) {
+                Ok(()) => {
+                    self.stats.coalesced += 1;
+                    return;
+                }
+                Err(message_item) => message_item,
+            },
+            None => message_item,
+        };
+
+        if message_item.is_urgent() {
+            let index = self
+                .queue
+                .iter()
+                .position(|x| x.message_type == signaling::MessageType::Ice)
+                .unwrap_or(self.queue.len());
+            self.queue.insert(index, message_item);
+        } else {
+            self.queue.push_back(message_item);
+        }
+        self.stats.max_depth = self.stats.max_depth.max(self.queue.len());
+    }
+
+    fn record_sent(&mut self, queued: Instant) {
+        let latency = queued.elapsed();
+        self.stats.sent += 1;
+        self.stats.last_latency = latency;
+        self.stats.max_latency = self.stats.max_latency.max(latency);
+        self.stats.total_latency += latency;
+    }
+
+    fn stats(&self) -> SignalingMessageQueueStats {
+        SignalingMessageQueueStats {
+            depth: self.queue.len(),
+            ..self.stats
+        }
+    }
 }
 
 /// Information about a received group ring that hasn't yet been accepted or cancelled.
@@ -190,8 +373,8 @@ struct OutstandingGroupRing {
 }
 
 impl OutstandingGroupRing {
-    fn has_expired(&self) -> bool {
-        self.received.elapsed() >= TIME_OUT_PERIOD
+    fn has_expired(&self, expiry: Duration) -> bool {
+        self.received.elapsed() >= expiry
     }
 }
 
@@ -201,6 +384,9 @@ enum ReceivedOfferCollision {
     None,
     /// An active call with a different user, so act busy
     Busy,
+    /// An active call with a different user, but call waiting is enabled so
+    /// let the incoming call wait until it is accepted or ended
+    Waiting,
     /// An active call with the same user, but we win so ignore the incoming call
     GlareWinner,
     /// An active call with the same user, but we lose so drop our call
@@ -295,11 +481,13 @@ pub enum OfferValidationError {
     Expired,
 }
 
-/// Statelessly evaluate the given offer.
+/// Statelessly evaluate the given offer against `ring_policy`, which should
+/// match the one given to [`CallManager::set_ring_policy`].
 pub fn validate_offer(
     received: &signaling::ReceivedOffer,
+    ring_policy: &RingPolicy,
 ) -> std::result::Result<(), OfferValidationError> {
-    if received.age > MAX_MESSAGE_AGE {
+    if received.age > ring_policy.max_offer_age {
         return Err(OfferValidationError::Expired);
     }
     Ok(())
@@ -312,9 +500,13 @@ pub enum OpaqueRingValidationError {
     RejectedByCallback,
 }
 
+/// Statelessly evaluate the given message as a group ring against
+/// `ring_policy`, which should match the one given to
+/// [`CallManager::set_ring_policy`].
 pub fn validate_call_message_as_opaque_ring(
     message: &protobuf::signaling::CallMessage,
     message_age: Duration,
+    ring_policy: &RingPolicy,
     validate_group_ring: impl FnOnce(group_call::GroupIdRef, group_call::RingId) -> bool,
 ) -> std::result::Result<(), OpaqueRingValidationError> {
     match message {
@@ -332,7 +524,7 @@ pub fn validate_call_message_as_opaque_ring(
             if IntentionType::try_from(*ring_type) != Ok(IntentionType::Ring) {
                 return Err(OpaqueRingValidationError::NotARing);
             }
-            if message_age > MAX_MESSAGE_AGE {
+            if message_age > ring_policy.max_offer_age {
                 return Err(OpaqueRingValidationError::Expired);
             }
             if !validate_group_ring(group_id, group_call::RingId::from(*ring_id)) {
@@ -357,6 +549,42 @@ struct GroupCallClient {
     active: bool,
 }
 
+/// The parameters needed to create the call link that a direct call is
+/// escalated to. See [`CallManager::escalate_to_call_link`].
+pub struct CallLinkCreateParams {
+    pub sfu_url: String,
+    pub auth_presentation: Vec<u8>,
+    pub admin_passkey: Vec<u8>,
+    pub public_zkparams: Vec<u8>,
+    pub restrictions: Option<CallLinkRestrictions>,
+}
+
+/// The state of moving the active direct call to a call link call.
+struct CallLinkEscalation {
+    /// The direct call being escalated.
+    call_id: CallId,
+    root_key: CallLinkRootKey,
+    /// The call link call client, once the application has created it.
+    client_id: Option<group_call::ClientId>,
+    /// Set when the call link call client takes over busy to join.
+    busy_handed_over: bool,
+    local_joined: bool,
+    remote_joined: bool,
+}
+
+impl CallLinkEscalation {
+    fn new(call_id: CallId, root_key: CallLinkRootKey) -> Self {
+        Self {
+            call_id,
+            root_key,
+            client_id: None,
+            busy_handed_over: false,
+            local_joined: false,
+            remote_joined: false,
+        }
+    }
+}
+
 pub struct CallManager<T>
 where
     T: Platform,
@@ -369,6 +597,17 @@ where
     call_by_call_id: Arc<CallMutex<HashMap<CallId, Call<T>>>>,
     /// CallId of the active call.
     active_call_id: Arc<CallMutex<Option<CallId>>>,
+    /// Whether an incoming call from another peer should wait rather than
+    /// being rejected as busy while a call is active.
+    call_waiting_enabled: Arc<CallMutex<bool>>,
+    /// CallId of an incoming call waiting while the active call continues.
+    waiting_call_id: Arc<CallMutex<Option<CallId>>>,
+    /// CallId of the call that has been put on hold.
+    held_call_id: Arc<CallMutex<Option<CallId>>>,
+    /// Escalation of the active call to a call link call, if in progress.
+    escalation: Arc<CallMutex<Option<CallLinkEscalation>>>,
+    /// Ring timeouts and message age limits.
+    ring_policy: Arc<CallMutex<RingPolicy>>,
     /// 1:1 call messages that arrived before the Offer for a particular call.
     pending_call_messages: Arc<CallMutex<PendingCallMessages>>,
     /// Map of all group calls.
@@ -442,6 +681,11 @@ where
             self_uuid: Arc::clone(&self.self_uuid),
             call_by_call_id: Arc::clone(&self.call_by_call_id),
             active_call_id: Arc::clone(&self.active_call_id),
+            call_waiting_enabled: Arc::clone(&self.call_waiting_enabled),
+            waiting_call_id: Arc::clone(&self.waiting_call_id),
+            held_call_id: Arc::clone(&self.held_call_id),
+            escalation: Arc::clone(&self.escalation),
+            ring_policy: Arc::clone(&self.ring_policy),
             pending_call_messages: Arc::clone(&self.pending_call_messages),
             group_call_by_client_id: Arc::clone(&self.group_call_by_client_id),
             next_group_call_client_id: Arc::clone(&self.next_group_call_client_id),
@@ -477,6 +721,11 @@ where
             self_uuid: Arc::new(CallMutex::new(None, "self_uuid")),
             call_by_call_id: Arc::new(CallMutex::new(HashMap::new(), "call_by_call_id")),
             active_call_id: Arc::new(CallMutex::new(None, "active_call_id")),
+            call_waiting_enabled: Arc::new(CallMutex::new(false, "call_waiting_enabled")),
+            waiting_call_id: Arc::new(CallMutex::new(None, "waiting_call_id")),
+            held_call_id: Arc::new(CallMutex::new(None, "held_call_id")),
+            escalation: Arc::new(CallMutex::new(None, "escalation")),
+            ring_policy: Arc::new(CallMutex::new(RingPolicy::default(), "ring_policy")),
             pending_call_messages: Arc::new(CallMutex::new(
                 PendingCallMessages::None,
                 "pending_individual_call_messages",
@@ -511,6 +760,31 @@ where
         Ok(())
     }
 
+    /// Enables or disables call waiting for 1:1 calls.
+    ///
+    /// When enabled, an offer from a different peer that arrives while the
+    /// active call is connected is started as a waiting call instead of
+    /// being rejected as busy. Accepting the waiting call puts the active
+    /// call on hold.
+    pub fn set_call_waiting_enabled(&mut self, enabled: bool) -> Result<()> {
+        info!("set_call_waiting_enabled(): {}", enabled);
+        *self.call_waiting_enabled.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Updates the ring timeouts and message age limits.
+    ///
+    /// Calls that are already ringing keep the timeout they started with.
+    pub fn set_ring_policy(&mut self, policy: RingPolicy) -> Result<()> {
+        info!("set_ring_policy(): {:?}", policy);
+        *self.ring_policy.lock()? = policy;
+        Ok(())
+    }
+
+    fn ring_policy(&self) -> Result<RingPolicy> {
+        Ok(*self.ring_policy.lock()?)
+    }
+
     /// Create an outgoing call.
     pub fn call(
         &mut self,
@@ -536,21 +810,58 @@ where
         let mut call_manager = self.clone();
         self.worker_spawn(move || {
             let remote_peer_error = remote_peer.clone();
-            if let Err(err) =
-                call_manager.handle_call(remote_peer, call_id, call_media_type, local_device_id)
-            {
+            if let Err(err) = call_manager.handle_call(
+                remote_peer,
+                call_id,
+                call_media_type,
+                local_device_id,
+                false,
+            ) {
                 error!("Handle call failed: {}", err);
                 call_manager.internal_create_api_error(&remote_peer_error, call_id, err);
             }
         })
     }
 
+    /// Take over an active call that another of the local user's devices
+    /// has with the remote peer.
+    ///
+    /// The call keeps its CallId. The remote side switches media over to this
+    /// device once it is connected, and the other device then ends its call
+    /// with [`ApplicationEvent::EndedHandedOffToAnotherDevice`].
+    pub fn handoff_call(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        call_media_type: CallMediaType,
+        local_device_id: DeviceId,
+    ) -> Result<()> {
+        info!("API:handoff_call({}):", call_id);
+
+        let mut call_manager = self.clone();
+        self.worker_spawn(move || {
+            let remote_peer_error = remote_peer.clone();
+            if let Err(err) = call_manager.handle_call(
+                remote_peer,
+                call_id,
+                call_media_type,
+                local_device_id,
+                true,
+            ) {
+                error!("Handle handoff call failed: {}", err);
+                call_manager.internal_create_api_error(&remote_peer_error, call_id, err);
+            }
+        })
+    }
+
     /// Accept an incoming call.
+    ///
+    /// If the call is waiting, the active call is put on hold.
     pub fn accept_call(&mut self, call_id: CallId) -> Result<()> {
         handle_active_call_api!(self, CallManager::handle_accept_call, call_id)
     }
 
-    /// Drop the active call.
+    /// Drop the active, waiting or held call.
     pub fn drop_call(&mut self, call_id: CallId) -> Result<()> {
         handle_active_call_api!(self, CallManager::handle_drop_call, call_id)
     }
@@ -588,6 +899,82 @@ where
         handle_active_call_api!(self, CallManager::handle_hangup)
     }
 
+    /// Local hangup of a specific call, which may be active, waiting or held.
+    pub fn hangup_call(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_hangup_call, call_id)
+    }
+
+    /// Resume the held call, putting the active call (if any) on hold.
+    pub fn resume_call(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_resume_call, call_id)
+    }
+
+    /// Move the active call to a call link call.
+    ///
+    /// The call link is created first if `create` is given; otherwise the
+    /// call link for `root_key` must already exist. Both sides are then told
+    /// to join it via [`Platform::on_escalate_to_call_link`], and once both
+    /// have joined the call ends with [`ApplicationEvent::EndedEscalatedToCallLink`].
+    pub fn escalate_to_call_link(
+        &mut self,
+        call_id: CallId,
+        root_key: CallLinkRootKey,
+        create: Option<CallLinkCreateParams>,
+    ) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_escalate_to_call_link,
+            call_id,
+            root_key,
+            create
+        )
+    }
+
+    /// Ask the remote side to turn the active call into a video call.
+    ///
+    /// The answer is reported with [`ApplicationEvent::VideoUpgradeAccepted`],
+    /// [`ApplicationEvent::VideoUpgradeDeclined`] or
+    /// [`ApplicationEvent::VideoUpgradeTimedOut`].
+    pub fn request_video_upgrade(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_request_video_upgrade, call_id)
+    }
+
+    /// Accept the remote side's request to turn the active call into a video
+    /// call, which enables video in the sender status.
+    pub fn accept_video_upgrade(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_respond_to_video_upgrade,
+            call_id,
+            true
+        )
+    }
+
+    /// Decline the remote side's request to turn the active call into a video call.
+    pub fn decline_video_upgrade(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_respond_to_video_upgrade,
+            call_id,
+            false
+        )
+    }
+
+    /// Tell the remote side about conditions of the local devices, such as
+    /// an unplugged microphone. Unset fields are left as they were.
+    pub fn set_local_device_status(
+        &mut self,
+        call_id: CallId,
+        status: signaling::DeviceStatus,
+    ) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_set_local_device_status,
+            call_id,
+            status
+        )
+    }
+
     fn remove_outstanding_group_ring(
         &mut self,
         group_id: group_call::GroupIdRef,
@@ -722,6 +1109,14 @@ where
         )
     }
 
+    /// Audio devices were added or removed.
+    ///
+    /// Can be called from any thread, e.g. the callback given to
+    /// PeerConnectionFactory::set_audio_devices_changed_callback.
+    pub fn audio_devices_changed(&mut self) -> Result<()> {
+        handle_api!(self, CallManager::handle_audio_devices_changed)
+    }
+
     /// Received a HTTP response from the application.
     pub fn received_http_response(&mut self, request_id: u32, response: Option<http::Response>) {
         let _ = handle_api!(
@@ -798,6 +1193,24 @@ where
         }
     }
 
+    /// Check if call_id refers to a waiting or held call.
+    pub fn call_is_waiting_or_held(&self, call_id: CallId) -> Result<bool> {
+        Ok(*self.waiting_call_id.lock()? == Some(call_id)
+            || *self.held_call_id.lock()? == Some(call_id))
+    }
+
+    /// Returns the waiting Call
+    pub fn waiting_call(&self) -> Result<Call<T>> {
+        let waiting_call_id = *self.waiting_call_id.lock()?;
+        self.secondary_call(waiting_call_id)
+    }
+
+    /// Returns the held Call
+    pub fn held_call(&self) -> Result<Call<T>> {
+        let held_call_id = *self.held_call_id.lock()?;
+        self.secondary_call(held_call_id)
+    }
+
     /// Checks if the CallManager is busy with either a 1:1 or group call.
     #[cfg(feature = "sim")]
     pub fn busy(&self) -> bool {
@@ -924,14 +1337,117 @@ where
         Ok(())
     }
 
-    /// Releases busy so another call can begin
+    /// Releases busy so another call can begin, unless a waiting or
+    /// held call still exists.
     fn release_busy(&mut self) -> Result<()> {
         let mut busy = self.busy.lock()?;
-        *busy = false;
+        *busy = self.active_call_id.lock()?.is_some() || self.secondary_call_exists()?;
+
+        Ok(())
+    }
+
+    /// Releases busy from the direct call being escalated to a call link
+    /// call, unless another active, waiting or held call still needs it.
+    fn hand_over_busy(&mut self, call_id: CallId) -> Result<()> {
+        let is_other_call = |other: Option<CallId>| other.is_some_and(|other| other != call_id);
+        if is_other_call(*self.active_call_id.lock()?)
+            || is_other_call(*self.waiting_call_id.lock()?)
+            || is_other_call(*self.held_call_id.lock()?)
+        {
+            info!("hand_over_busy(): staying busy for another call");
+            return Ok(());
+        }
+
+        let mut escalation = self.escalation.lock()?;
+        if let Some(escalation) = escalation.as_mut().filter(|v| v.call_id == call_id) {
+            escalation.busy_handed_over = true;
+            *self.busy.lock()? = false;
+        }
 
         Ok(())
     }
 
+    /// Returns true if there is a waiting or held call.
+    fn secondary_call_exists(&self) -> Result<bool> {
+        Ok(self.waiting_call_id.lock()?.is_some() || self.held_call_id.lock()?.is_some())
+    }
+
+    /// Looks up a waiting or held call in the call map.
+    fn secondary_call(&self, call_id: Option<CallId>) -> Result<Call<T>> {
+        match call_id {
+            Some(call_id) => match self.call_by_call_id.lock()?.get(&call_id) {
+                Some(call) => Ok(call.clone()),
+                None => Err(RingRtcError::CallIdNotFound(call_id).into()),
+            },
+            None => Err(RingRtcError::NoActiveCall.into()),
+        }
+    }
+
+    /// Returns the waiting or held call with the given call_id, if any.
+    fn waiting_or_held_call(&self, call_id: CallId) -> Result<Option<Call<T>>> {
+        if !self.call_is_waiting_or_held(call_id)? {
+            return Ok(None);
+        }
+        Ok(self.call_by_call_id.lock()?.get(&call_id).cloned())
+    }
+
+    /// Makes the waiting call, if any, the active call. Used when the
+    /// active call ends while another call is still waiting.
+    fn promote_waiting_call(&mut self) -> Result<()> {
+        let mut active_call_id = self.active_call_id.lock()?;
+        if active_call_id.is_none() {
+            if let Some(waiting_call_id) = self.waiting_call_id.lock()?.take() {
+                info!("promote_waiting_call(): call_id: {}", waiting_call_id);
+                *active_call_id = Some(waiting_call_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the given waiting or held call the active call, putting the
+    /// current active call (if any) on hold.
+    fn swap_active_call(&mut self, call_id: CallId) -> Result<Call<T>> {
+        let (call, previous_call) = {
+            let mut active_call_id = self.active_call_id.lock()?;
+            let mut waiting_call_id = self.waiting_call_id.lock()?;
+            let mut held_call_id = self.held_call_id.lock()?;
+            let call_map = self.call_by_call_id.lock()?;
+
+            let call = match call_map.get(&call_id) {
+                Some(v) => v.clone(),
+                None => return Err(RingRtcError::CallIdNotFound(call_id).into()),
+            };
+
+            if *waiting_call_id == Some(call_id) {
+                *waiting_call_id = None;
+            } else if *held_call_id == Some(call_id) {
+                *held_call_id = None;
+            } else {
+                return Err(RingRtcError::CallIdNotFound(call_id).into());
+            }
+
+            let previous_call = match active_call_id.replace(call_id) {
+                Some(previous_call_id) => {
+                    *held_call_id = Some(previous_call_id);
+                    call_map.get(&previous_call_id).cloned()
+                }
+                None => None,
+            };
+            (call, previous_call)
+        };
+
+        if let Some(previous_call) = previous_call {
+            info!(
+                "swap_active_call(): holding call_id: {}",
+                previous_call.call_id()
+            );
+            previous_call.set_held(true)?;
+            previous_call.notify_application(ApplicationEvent::LocalHoldEnable)?;
+        }
+        call.notify_application(ApplicationEvent::LocalHoldDisable)?;
+        Ok(call)
+    }
+
     /// Terminates Call and optionally notifies application of the reason why.
     /// Also removes/drops it from the map.
     fn terminate_and_drop_call(&mut self, call_id: CallId) -> Result<()> {
@@ -955,6 +1471,7 @@ where
     ) -> Result<()> {
         info!("send_hangup(): call_id: {}", call_id);
 
+        let call_for_item = call.clone();
         let hangup_closure = Box::new(move |cm: &CallManager<T>| {
             ringbench!(
                 RingBench::Cm,
@@ -970,11 +1487,11 @@ where
             Ok(MessageSendResult::Sent)
         });
 
-        let message_item = SignalingMessageItem {
-            call_id,
-            message_type: signaling::MessageType::Hangup,
-            message_closure: hangup_closure,
-        };
+        let message_item = SignalingMessageItem::new(
+            call_for_item,
+            signaling::MessageType::Hangup,
+            MessageContent::Closure(hangup_closure),
+        );
 
         self.send_next_message(Some(message_item))
     }
@@ -997,6 +1514,17 @@ where
 
         info!("conclude_call(): call_id: {}", call_id);
 
+        {
+            let mut waiting_call_id = self.waiting_call_id.lock()?;
+            if *waiting_call_id == Some(call_id) {
+                *waiting_call_id = None;
+            }
+            let mut held_call_id = self.held_call_id.lock()?;
+            if *held_call_id == Some(call_id) {
+                *held_call_id = None;
+            }
+        }
+
         self.trim_messages(call_id)?;
 
         if let Some(event) = event {
@@ -1034,6 +1562,41 @@ where
         })
     }
 
+    /// Clears the active call so that another call can begin, concluding any
+    /// escalation of it to a call link call. Returns the event to end the
+    /// call with.
+    fn release_active_call(
+        &mut self,
+        call_id: CallId,
+        event: ApplicationEvent,
+    ) -> Result<ApplicationEvent> {
+        let escalation = {
+            let mut escalation = self.escalation.lock()?;
+            match escalation.as_ref() {
+                Some(v) if v.call_id == call_id => escalation.take(),
+                _ => None,
+            }
+        };
+
+        self.clear_active_call()?;
+        self.promote_waiting_call()?;
+
+        match escalation {
+            Some(escalation) if escalation.busy_handed_over => {
+                // The call link call client now holds busy.
+                if escalation.local_joined {
+                    Ok(ApplicationEvent::EndedEscalatedToCallLink)
+                } else {
+                    Ok(event)
+                }
+            }
+            _ => {
+                self.release_busy()?;
+                Ok(event)
+            }
+        }
+    }
+
     /// Terminates the active call.
     fn terminate_active_call(&mut self, send_hangup: bool, event: ApplicationEvent) -> Result<()> {
         info!("terminate_active_call():");
@@ -1044,8 +1607,7 @@ where
         }
 
         let call = self.active_call()?;
-        self.clear_active_call()?;
-        self.release_busy()?;
+        let event = self.release_active_call(call.call_id(), event)?;
 
         let hangup = if send_hangup {
             Some(signaling::Hangup::Normal)
@@ -1056,6 +1618,36 @@ where
         self.terminate_call(call, hangup, Some(event))
     }
 
+    /// Terminates the call with the given call_id, whether it is the
+    /// active call or a waiting or held call.
+    fn terminate_direct_call(
+        &mut self,
+        call_id: CallId,
+        send_hangup: bool,
+        event: ApplicationEvent,
+    ) -> Result<()> {
+        if self.call_is_active(call_id)? {
+            return self.terminate_active_call(send_hangup, event);
+        }
+
+        let call = match self.waiting_or_held_call(call_id)? {
+            Some(v) => v,
+            None => {
+                info!("terminate_direct_call(): skipping, no such call");
+                return Ok(());
+            }
+        };
+
+        let hangup = if send_hangup {
+            Some(signaling::Hangup::Normal)
+        } else {
+            None
+        };
+
+        self.terminate_call(call, hangup, Some(event))?;
+        self.release_busy()
+    }
+
     /// Handle call() API from application.
     fn handle_call(
         &mut self,
@@ -1063,13 +1655,14 @@ where
         call_id: CallId,
         call_media_type: CallMediaType,
         local_device_id: DeviceId,
+        handoff: bool,
     ) -> Result<()> {
         ringbench!(
             RingBench::App,
             RingBench::Cm,
             format!(
-                "call()\t{}\t{}\t{}",
-                call_id, call_media_type, local_device_id
+                "call()\t{}\t{}\t{}\thandoff={}",
+                call_id, call_media_type, local_device_id, handoff
             )
         );
 
@@ -1088,6 +1681,7 @@ where
                         CallDirection::Outgoing,
                         call_media_type,
                         local_device_id,
+                        handoff,
                         self.clone(),
                     )?;
 
@@ -1099,7 +1693,7 @@ where
 
                     *busy = true;
                     *active_call_id = Some(call_id);
-                    call.start_timeout_timer(TIME_OUT_PERIOD)?;
+                    call.start_timeout_timer(self.ring_policy()?.outgoing_ring_timeout)?;
                     call.inject_start_call()
                 }
             }
@@ -1114,13 +1708,303 @@ where
             format!("accept()\t{}", call_id)
         );
 
+        if *self.waiting_call_id.lock()? == Some(call_id) {
+            // Don't put the active call on hold unless the waiting call
+            // is actually ready to be accepted.
+            if !self.waiting_call()?.state()?.can_be_accepted_locally() {
+                ringbenchx!(RingBench::Cm, RingBench::App, "waiting call not connected");
+                return Ok(());
+            }
+            let mut waiting_call = self.swap_active_call(call_id)?;
+            return waiting_call.inject_accept_call();
+        }
+
         let mut active_call = check_active_call!(self, "handle_accept_call");
         if active_call.call_id() != call_id {
             ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
             return Ok(());
         }
-
-        active_call.inject_accept_call()
+
+        active_call.inject_accept_call()
+    }
+
+    /// Handle resume_call() API from application.
+    fn handle_resume_call(&mut self, call_id: CallId) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("resume()\t{}", call_id)
+        );
+
+        if *self.held_call_id.lock()? != Some(call_id) {
+            ringbenchx!(RingBench::Cm, RingBench::App, "not held call_id");
+            return Ok(());
+        }
+
+        let held_call = self.swap_active_call(call_id)?;
+        held_call.set_held(false)
+    }
+
+    /// Handle hangup_call() API from application.
+    fn handle_hangup_call(&mut self, call_id: CallId) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("hangup()\t{}", call_id)
+        );
+
+        self.terminate_direct_call(call_id, true, ApplicationEvent::EndedLocalHangup)
+    }
+
+    /// Handle request_video_upgrade() API from application.
+    fn handle_request_video_upgrade(&mut self, call_id: CallId) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("request_video_upgrade()\t{}", call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_request_video_upgrade");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+        if !active_call.state()?.active() {
+            warn!("request_video_upgrade(): the call is not connected");
+            return Ok(());
+        }
+
+        active_call.active_connection()?.request_video_upgrade()
+    }
+
+    /// Handle accept_video_upgrade() and decline_video_upgrade() API from
+    /// application.
+    fn handle_respond_to_video_upgrade(&mut self, call_id: CallId, accept: bool) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("respond_to_video_upgrade()\t{}\taccept={}", call_id, accept)
+        );
+
+        let active_call = check_active_call!(self, "handle_respond_to_video_upgrade");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+        if !active_call.state()?.active() {
+            warn!("respond_to_video_upgrade(): the call is not connected");
+            return Ok(());
+        }
+
+        active_call
+            .active_connection()?
+            .respond_to_video_upgrade(accept)
+    }
+
+    /// Handle set_local_device_status() API from application.
+    fn handle_set_local_device_status(
+        &mut self,
+        call_id: CallId,
+        status: signaling::DeviceStatus,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("set_local_device_status()\t{}\t{:?}", call_id, status)
+        );
+
+        let active_call = check_active_call!(self, "handle_set_local_device_status");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+        if !active_call.state()?.active() {
+            warn!("set_local_device_status(): the call is not connected");
+            return Ok(());
+        }
+
+        active_call
+            .active_connection()?
+            .update_sender_status(signaling::SenderStatus {
+                device_status: Some(status),
+                ..Default::default()
+            })
+    }
+
+    /// Handle escalate_to_call_link() API from application.
+    fn handle_escalate_to_call_link(
+        &mut self,
+        call_id: CallId,
+        root_key: CallLinkRootKey,
+        create: Option<CallLinkCreateParams>,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!(
+                "escalate_to_call_link()\t{}\tcreate={}",
+                call_id,
+                create.is_some()
+            )
+        );
+
+        let active_call = check_active_call!(self, "handle_escalate_to_call_link");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+        if !active_call.state()?.active() {
+            warn!("escalate_to_call_link(): the call is not connected");
+            let remote_peer = active_call.remote_peer()?.clone();
+            return self.notify_application(
+                &remote_peer,
+                call_id,
+                ApplicationEvent::EscalationFailed,
+            );
+        }
+
+        {
+            let mut escalation = self.escalation.lock()?;
+            if escalation.is_some() {
+                warn!("escalate_to_call_link(): escalation already in progress");
+                return Ok(());
+            }
+            *escalation = Some(CallLinkEscalation::new(call_id, root_key.clone()));
+        }
+
+        match create {
+            None => self.send_escalation(call_id),
+            Some(params) => {
+                let mut call_manager = self.clone();
+                call_links::create_call_link(
+                    self.http_client(),
+                    &params.sfu_url,
+                    root_key,
+                    &params.auth_presentation,
+                    &params.admin_passkey,
+                    &params.public_zkparams,
+                    params.restrictions,
+                    Box::new(move |result| {
+                        let mut cm = call_manager.clone();
+                        let _ = call_manager.worker_spawn(move || {
+                            let result = match result {
+                                Ok(_) => cm.send_escalation(call_id),
+                                Err(status) => {
+                                    warn!("Failed to create call link for escalation: {}", status);
+                                    cm.fail_escalation(call_id)
+                                }
+                            };
+                            if let Err(err) = result {
+                                error!("Escalating to call link failed: {}", err);
+                            }
+                        });
+                    }),
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Sends the root key of the call link to the remote side and tells the
+    /// application to join the call link call.
+    fn send_escalation(&mut self, call_id: CallId) -> Result<()> {
+        let (root_key, joined) = match self.escalation.lock()?.as_ref() {
+            Some(escalation) if escalation.call_id == call_id => {
+                (escalation.root_key.clone(), escalation.local_joined)
+            }
+            _ => {
+                info!("send_escalation(): escalation no longer in progress");
+                return Ok(());
+            }
+        };
+        if !self.call_is_active(call_id)? {
+            info!("send_escalation(): ignoring for inactive call");
+            return Ok(());
+        }
+
+        let active_call = self.active_call()?;
+        active_call
+            .active_connection()?
+            .send_escalation_via_rtp_data(signaling::Escalation {
+                root_key: root_key.bytes(),
+                joined,
+            })?;
+
+        let remote_peer = active_call.remote_peer()?.clone();
+        self.notify_escalate_to_call_link(&remote_peer, call_id, &root_key)
+    }
+
+    /// Abandons the escalation, leaving the direct call as it is.
+    fn fail_escalation(&mut self, call_id: CallId) -> Result<()> {
+        {
+            let mut escalation = self.escalation.lock()?;
+            match escalation.as_ref() {
+                Some(v) if v.call_id == call_id => {
+                    let _ = escalation.take();
+                }
+                _ => return Ok(()),
+            }
+        }
+
+        if let Some(call) = self.call_by_call_id.lock()?.get(&call_id).cloned() {
+            let remote_peer = call.remote_peer()?.clone();
+            self.notify_application(&remote_peer, call_id, ApplicationEvent::EscalationFailed)?;
+        }
+        Ok(())
+    }
+
+    /// Follows the join state of the call link call client that the active
+    /// call is being escalated to.
+    fn handle_escalation_join_state_changed(
+        &mut self,
+        client_id: group_call::ClientId,
+        join_state: group_call::JoinState,
+    ) -> Result<()> {
+        match join_state {
+            group_call::JoinState::Joined(_) => {
+                let (call_id, root_key, remote_joined) = {
+                    let mut escalation = self.escalation.lock()?;
+                    match escalation.as_mut() {
+                        Some(v) if v.client_id == Some(client_id) && !v.local_joined => {
+                            v.local_joined = true;
+                            (v.call_id, v.root_key.bytes(), v.remote_joined)
+                        }
+                        _ => return Ok(()),
+                    }
+                };
+                if !self.call_is_active(call_id)? {
+                    return Ok(());
+                }
+
+                info!("escalation joined: call_id: {}", call_id);
+                self.active_call()?
+                    .active_connection()?
+                    .send_escalation_via_rtp_data(signaling::Escalation {
+                        root_key,
+                        joined: true,
+                    })?;
+                if remote_joined {
+                    self.terminate_active_call(true, ApplicationEvent::EndedEscalatedToCallLink)?;
+                }
+                Ok(())
+            }
+            group_call::JoinState::NotJoined(_) => {
+                let call_id = {
+                    let escalation = self.escalation.lock()?;
+                    match escalation.as_ref() {
+                        Some(v) if v.client_id == Some(client_id) && v.busy_handed_over => {
+                            v.call_id
+                        }
+                        _ => return Ok(()),
+                    }
+                };
+                // The call link call ended before the direct call did, so
+                // take busy back for the direct call.
+                self.fail_escalation(call_id)?;
+                self.release_busy()
+            }
+            group_call::JoinState::Joining | group_call::JoinState::Pending(_) => Ok(()),
+        }
     }
 
     fn handle_terminate_active_call(
@@ -1129,8 +2013,7 @@ where
         hangup: Option<signaling::Hangup>,
         event: ApplicationEvent,
     ) -> Result<()> {
-        self.clear_active_call()?;
-        self.release_busy()?;
+        let event = self.release_active_call(active_call.call_id(), event)?;
         self.terminate_call(active_call, hangup, Some(event))
     }
 
@@ -1142,6 +2025,14 @@ where
             format!("drop()\t{}", call_id)
         );
 
+        if self.call_is_waiting_or_held(call_id)? {
+            return self.terminate_direct_call(
+                call_id,
+                false,
+                ApplicationEvent::EndedAppDroppedCall,
+            );
+        }
+
         let active_call = check_active_call!(self, "handle_drop_call");
         if active_call.call_id() != call_id {
             ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
@@ -1165,6 +2056,12 @@ where
             format!("proceed()\t{}", call_id)
         );
 
+        if *self.waiting_call_id.lock()? == Some(call_id) {
+            let mut waiting_call = self.waiting_call()?;
+            waiting_call.set_call_context(app_call_context)?;
+            return waiting_call.inject_proceed(call_config, audio_levels_interval);
+        }
+
         let mut active_call = check_active_call!(self, "handle_proceed");
         if active_call.call_id() != call_id {
             ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
@@ -1192,10 +2089,15 @@ where
         let mut is_active_call = false;
         let mut should_handle = true;
 
-        if let Ok(active_call) = self.active_call() {
-            if active_call.call_id() == call_id {
+        // Waiting and held calls are handled the same as the active call.
+        let call = match self.waiting_or_held_call(call_id) {
+            Ok(Some(call)) => Ok(call),
+            _ => self.active_call(),
+        };
+        if let Ok(call) = call {
+            if call.call_id() == call_id {
                 is_active_call = true;
-                if let Ok(state) = active_call.state() {
+                if let Ok(state) = call.state() {
                     if state.connected_or_reconnecting() {
                         // Get the last sent message type and see if it was for ICE.
                         // Since we are in a connected state, don't handle it if so.
@@ -1218,7 +2120,11 @@ where
                     call_id
                 );
 
-                let _ = self.terminate_active_call(true, ApplicationEvent::EndedSignalingFailure);
+                let _ = self.terminate_direct_call(
+                    call_id,
+                    true,
+                    ApplicationEvent::EndedSignalingFailure,
+                );
             } else {
                 // See if the associated call is in the call map.
                 let mut call = None;
@@ -1295,7 +2201,8 @@ where
             )
         );
 
-        if let Err(e) = validate_offer(&received) {
+        let ring_policy = self.ring_policy()?;
+        if let Err(e) = validate_offer(&received, &ring_policy) {
             match e {
                 OfferValidationError::Expired => {
                     ringbenchx!(RingBench::Cm, RingBench::App, "offer expired");
@@ -1324,6 +2231,15 @@ where
             }
         };
 
+        if received.offer.is_handoff() {
+            return self.handle_received_handoff_offer(
+                active_call,
+                remote_peer,
+                incoming_call_id,
+                received,
+            );
+        }
+
         // Create the call object so that it will either be used as the
         // active call or properly concluded if dropped.
         let mut incoming_call = Call::new(
@@ -1332,6 +2248,7 @@ where
             CallDirection::Incoming,
             received.offer.call_media_type,
             received.receiver_device_id,
+            false,
             self.clone(),
         )?;
 
@@ -1360,6 +2277,7 @@ where
             Ignore(ApplicationEvent),
             RejectAsBusy(ApplicationEvent),
             Start,
+            Wait,
         }
 
         let (active_call_action, incoming_call_action) = match collision {
@@ -1370,6 +2288,9 @@ where
                 ActiveCallAction::DontTerminate,
                 IncomingCallAction::RejectAsBusy(ApplicationEvent::ReceivedOfferWhileActive),
             ),
+            ReceivedOfferCollision::Waiting => {
+                (ActiveCallAction::DontTerminate, IncomingCallAction::Wait)
+            }
             ReceivedOfferCollision::GlareWinner => (
                 ActiveCallAction::DontTerminate,
                 IncomingCallAction::Ignore(ApplicationEvent::ReceivedOfferWithGlare),
@@ -1394,7 +2315,7 @@ where
             ActiveCallAction::DontTerminate => {}
             ActiveCallAction::TerminateAndSendHangup(app_event) => {
                 self.clear_active_call()?;
-                *busy = false;
+                *busy = self.secondary_call_exists()?;
                 self.terminate_call(
                     active_call.unwrap(),
                     Some(signaling::Hangup::Normal),
@@ -1403,7 +2324,7 @@ where
             }
             ActiveCallAction::TerminateWithoutSendingHangup(app_event) => {
                 self.clear_active_call()?;
-                *busy = false;
+                *busy = self.secondary_call_exists()?;
                 self.terminate_call(active_call.unwrap(), None, Some(app_event))?;
             }
         }
@@ -1430,29 +2351,84 @@ where
 
                 *busy = true;
                 *active_call_id = Some(incoming_call_id);
-                incoming_call.start_timeout_timer(TIME_OUT_PERIOD)?;
+                incoming_call.start_timeout_timer(ring_policy.incoming_ring_timeout)?;
                 incoming_call.handle_received_offer(received)?;
                 incoming_call.inject_start_call()?;
 
-                match std::mem::take(&mut *self.pending_call_messages.lock()?) {
-                    PendingCallMessages::None => {}
-                    PendingCallMessages::IceCandidates { call_id, received }
-                        if call_id == incoming_call_id =>
-                    {
-                        for received in received {
-                            incoming_call.inject_received_ice(received)?;
-                        }
-                    }
-                    PendingCallMessages::Hangup { call_id, received }
-                        if call_id == incoming_call_id =>
-                    {
-                        incoming_call.inject_received_hangup(received)?;
-                    }
-                    PendingCallMessages::IceCandidates { call_id, .. }
-                    | PendingCallMessages::Hangup { call_id, .. } => {
-                        info!("dropping pending messages for {}", call_id);
-                    }
+                self.inject_pending_call_messages(&mut incoming_call)?;
+            }
+            IncomingCallAction::Wait => {
+                let mut waiting_call_id = self.waiting_call_id.lock()?;
+                if let Some(waiting_call_id) = *waiting_call_id {
+                    return Err(RingRtcError::CallAlreadyInProgress(waiting_call_id).into());
                 }
+
+                let mut call_map = self.call_by_call_id.lock()?;
+                call_map.insert(incoming_call_id, incoming_call.clone());
+
+                // Busy is already set by the active call.
+                *waiting_call_id = Some(incoming_call_id);
+                incoming_call.start_timeout_timer(ring_policy.incoming_ring_timeout)?;
+                incoming_call.handle_received_offer(received)?;
+                incoming_call.inject_start_call()?;
+
+                self.inject_pending_call_messages(&mut incoming_call)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle an offer from another device of the remote peer that is taking
+    /// over the active call.
+    fn handle_received_handoff_offer(
+        &mut self,
+        active_call: Option<Call<T>>,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        received: ReceivedOffer,
+    ) -> Result<()> {
+        match active_call {
+            Some(mut active_call)
+                if active_call.call_id() == call_id
+                    && self.remote_peer_equals_active(&active_call, &remote_peer) =>
+            {
+                info!(
+                    "Handoff of the active call to device {}",
+                    received.sender_device_id
+                );
+                active_call.inject_received_handoff_offer(received)
+            }
+            Some(active_call) if active_call.call_id() == call_id => {
+                warn!("Handoff offer for the active call from a different peer, ignoring");
+                Ok(())
+            }
+            _ => {
+                // The call isn't on this device, so there's nothing to take over
+                // and nothing to ring for.
+                info!("No matching call for handoff offer, ignoring");
+                self.notify_call_concluded(&remote_peer, call_id)
+            }
+        }
+    }
+
+    /// Injects any messages that arrived before the offer for the incoming call.
+    fn inject_pending_call_messages(&self, incoming_call: &mut Call<T>) -> Result<()> {
+        let incoming_call_id = incoming_call.call_id();
+        match std::mem::take(&mut *self.pending_call_messages.lock()?) {
+            PendingCallMessages::None => {}
+            PendingCallMessages::IceCandidates { call_id, received }
+                if call_id == incoming_call_id =>
+            {
+                for received in received {
+                    incoming_call.inject_received_ice(received)?;
+                }
+            }
+            PendingCallMessages::Hangup { call_id, received } if call_id == incoming_call_id => {
+                incoming_call.inject_received_hangup(received)?;
+            }
+            PendingCallMessages::IceCandidates { call_id, .. }
+            | PendingCallMessages::Hangup { call_id, .. } => {
+                info!("dropping pending messages for {}", call_id);
             }
         }
         Ok(())
@@ -1501,6 +2477,10 @@ where
             )
         );
 
+        if let Some(mut call) = self.waiting_or_held_call(call_id)? {
+            return call.inject_received_ice(received);
+        }
+
         match self.active_call() {
             Ok(mut active_call) if active_call.call_id() == call_id => {
                 active_call.inject_received_ice(received)?;
@@ -1543,6 +2523,10 @@ where
             )
         );
 
+        if let Some(mut call) = self.waiting_or_held_call(call_id)? {
+            return call.inject_received_hangup(received);
+        }
+
         match self.active_call() {
             Ok(mut active_call) if active_call.call_id() == call_id => {
                 active_call.inject_received_hangup(received)?;
@@ -1631,7 +2615,7 @@ where
                     (Some(group_id), Some(ring_type), Some(ring_id)) => {
                         let ring_update = match ring_type {
                             IntentionType::Ring => {
-                                if message_age > MAX_MESSAGE_AGE {
+                                if message_age > self.ring_policy()?.max_offer_age {
                                     group_call::RingUpdate::ExpiredRequest
                                 } else if *self.busy.lock()? {
                                     // Let your other devices know.
@@ -1756,11 +2740,12 @@ where
         ring_id: group_call::RingId,
         sender_uuid: UserId,
     ) -> Result<()> {
+        let group_ring_expiry = self.ring_policy()?.group_ring_expiry;
         {
             let mut outstanding_group_rings = self.outstanding_group_rings.lock()?;
             // Take this opportunity to clear the outstanding rings table
             // (which should be small).
-            outstanding_group_rings.retain(|_group_id, ring| !ring.has_expired());
+            outstanding_group_rings.retain(|_group_id, ring| !ring.has_expired(group_ring_expiry));
             // If there's an existing, non-expired ring, replace it so that the
             // newly received ring will get cancelled upon joining.
             outstanding_group_rings.insert(
@@ -1786,22 +2771,21 @@ where
         }
 
         let mut self_for_timeout = self.clone();
-        self.worker
-            .send_delayed(*INCOMING_GROUP_CALL_RING_TIME, move |_| {
-                let result = try_scoped(|| {
-                    self_for_timeout.remove_outstanding_group_ring(&group_id, ring_id)?;
-                    self_for_timeout.platform.lock()?.group_call_ring_update(
-                        group_id,
-                        ring_id,
-                        sender_uuid,
-                        group_call::RingUpdate::ExpiredRequest,
-                    );
-                    Ok(())
-                });
-                if let Err(err) = result {
-                    error!("error handling group ring timeout: {}", err)
-                }
+        self.worker.send_delayed(group_ring_expiry, move |_| {
+            let result = try_scoped(|| {
+                self_for_timeout.remove_outstanding_group_ring(&group_id, ring_id)?;
+                self_for_timeout.platform.lock()?.group_call_ring_update(
+                    group_id,
+                    ring_id,
+                    sender_uuid,
+                    group_call::RingUpdate::ExpiredRequest,
+                );
+                Ok(())
             });
+            if let Err(err) = result {
+                error!("error handling group ring timeout: {}", err)
+            }
+        });
 
         Ok(())
     }
@@ -1813,6 +2797,11 @@ where
         }
     }
 
+    /// Handle audio devices being added or removed.
+    fn handle_audio_devices_changed(&mut self) -> Result<()> {
+        self.platform.lock()?.on_audio_devices_changed()
+    }
+
     /// Handle receiving an HTTP response from the application.
     fn handle_received_http_response(
         &mut self,
@@ -1848,6 +2837,7 @@ where
         }
 
         self.clear_active_call()?;
+        let _ = self.escalation.lock()?.take();
         self.release_busy()?;
 
         // clear out the message queue, the app gave up on everything
@@ -1863,6 +2853,7 @@ where
         let call_id = call.call_id();
         info!("send_busy(): call_id: {}", call_id);
 
+        let call_for_item = call.clone();
         let busy_closure = Box::new(move |cm: &CallManager<T>| {
             ringbench!(
                 RingBench::Cm,
@@ -1878,11 +2869,11 @@ where
             Ok(MessageSendResult::Sent)
         });
 
-        let message_item = SignalingMessageItem {
-            call_id,
-            message_type: signaling::MessageType::Busy,
-            message_closure: busy_closure,
-        };
+        let message_item = SignalingMessageItem::new(
+            call_for_item,
+            signaling::MessageType::Busy,
+            MessageContent::Closure(busy_closure),
+        );
 
         self.send_next_message(Some(message_item))
     }
@@ -1939,6 +2930,9 @@ where
                     info!("Glare, not yet connected so no active device");
                     glare_tiebreaker()
                 }
+            } else if self.call_waiting_allowed(active_call_state) {
+                info!("Active call exists, call waiting for received offer");
+                ReceivedOfferCollision::Waiting
             } else {
                 info!("Active call exists, sending busy for received offer");
                 ReceivedOfferCollision::Busy
@@ -1949,6 +2943,23 @@ where
         }
     }
 
+    /// Check if an incoming call from a different peer may wait while the
+    /// active call continues. Only one waiting or held call is supported.
+    fn call_waiting_allowed(&self, active_call_state: CallState) -> bool {
+        let enabled = self
+            .call_waiting_enabled
+            .lock()
+            .map(|enabled| *enabled)
+            .unwrap_or(false);
+        let secondary_call_exists = self.secondary_call_exists().unwrap_or(true);
+        enabled
+            && !secondary_call_exists
+            && matches!(
+                active_call_state,
+                CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted
+            )
+    }
+
     /// Check if the remote_peer matches the remote_peer in the active
     /// call.
     fn remote_peer_equals_active(
@@ -2034,7 +3045,7 @@ where
         if let Some(message_item) = message_item_option {
             match self.message_queue.lock() {
                 Ok(mut message_queue) => {
-                    message_queue.queue.push_back(message_item);
+                    message_queue.push(message_item);
                 }
                 Err(e) => {
                     return Err(e);
@@ -2061,14 +3072,41 @@ where
 
                     match message_queue.queue.pop_front() {
                         Some(message_item) => {
+                            if !message_item.is_urgent()
+                                && matches!(
+                                    message_item.call.state(),
+                                    Ok(CallState::Terminating | CallState::Terminated)
+                                )
+                            {
+                                info!(
+                                    "send_next_message(): dropping {:?} for ended call_id: {}",
+                                    message_item.message_type, message_item.call_id
+                                );
+                                message_queue.stats.dropped += 1;
+                                continue;
+                            }
+
                             info!(
                                 "send_next_message(): sending message, len: {}",
                                 message_queue.queue.len()
                             );
 
-                            // Execute the closure and match its return value.
-                            match (message_item.message_closure)(self) {
+                            let queued = message_item.queued;
+                            let result = match message_item.content {
+                                MessageContent::Closure(message_closure) => message_closure(self),
+                                MessageContent::Ice {
+                                    connections,
+                                    broadcast,
+                                } => self.send_ice(&message_item.call, connections, broadcast),
+                            };
+
+                            // Match the result of sending the message.
+                            match result {
                                 Ok(message_is_in_flight) => {
+                                    if message_is_in_flight == MessageSendResult::Sent {
+                                        message_queue.record_sent(queued);
+                                    }
+
                                     // We have attempted to deliver the message. If a message
                                     // is actually in flight, set the in flight flag. But
                                     // check to see if the platform overrides it (in which
@@ -2114,6 +3152,55 @@ where
         }
     }
 
+    /// Send the buffered local ICE candidates of the connections as
+    /// one message.
+    fn send_ice(
+        &self,
+        call: &Call<T>,
+        connections: Vec<Connection<T>>,
+        broadcast: bool,
+    ) -> Result<MessageSendResult> {
+        let call_id = call.call_id();
+
+        let mut local_candidates = Vec::new();
+        for connection in &connections {
+            local_candidates.extend(connection.take_buffered_local_ice_candidates()?);
+        }
+
+        if local_candidates.is_empty() {
+            return Ok(MessageSendResult::NotSent);
+        }
+
+        ringbench!(
+            RingBench::Cm,
+            RingBench::App,
+            format!(
+                "send_ice_candidates({})\t{}",
+                local_candidates.len(),
+                call_id,
+            )
+        );
+
+        let remote_peer = call.remote_peer()?;
+
+        let platform = self.platform.lock()?;
+        platform.on_send_ice(
+            &remote_peer,
+            call_id,
+            signaling::SendIce {
+                receiver_device_id: if broadcast {
+                    None
+                } else {
+                    connections.first().map(Connection::remote_device_id)
+                },
+                ice: signaling::Ice {
+                    candidates: local_candidates,
+                },
+            },
+        )?;
+        Ok(MessageSendResult::Sent)
+    }
+
     /// Remove all messages in the queue by call_id. Ignore Busy
     /// messages as they might have been sent on behalf of the
     /// call before termination. Also ignore Hangup messages, since
@@ -2123,21 +3210,20 @@ where
         let mut message_queue = self.message_queue.lock()?;
         let mq = &mut *message_queue;
 
-        debug!(
-            "trim_messages(): start id: {} len: {}",
-            call_id,
-            mq.queue.len()
-        );
-        mq.queue.retain(|x| {
-            (x.call_id != call_id)
-                || (x.message_type == signaling::MessageType::Busy)
-                || (x.message_type == signaling::MessageType::Hangup)
-        });
+        let len = mq.queue.len();
+        debug!("trim_messages(): start id: {} len: {}", call_id, len);
+        mq.queue.retain(|x| (x.call_id != call_id) || x.is_urgent());
+        mq.stats.dropped += (len - mq.queue.len()) as u64;
         debug!("trim_messages(): end len: {}", mq.queue.len());
 
         Ok(())
     }
 
+    /// Returns statistics about the signaling message queue.
+    pub fn signaling_message_queue_stats(&self) -> Result<SignalingMessageQueueStats> {
+        Ok(self.message_queue.lock()?.stats())
+    }
+
     ////////////////////////////////////////////////////////////////////////
     // Module level public functions start here
     ////////////////////////////////////////////////////////////////////////
@@ -2213,6 +3299,23 @@ where
         platform.on_low_bandwidth_for_video(remote_peer, recovered)
     }
 
+    /// Notify application that the call is being escalated to a call link
+    fn notify_escalate_to_call_link(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        root_key: &CallLinkRootKey,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::Cm,
+            RingBench::App,
+            format!("escalate_to_call_link()\t{}", call_id)
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_escalate_to_call_link(remote_peer, call_id, root_key)
+    }
+
     /// Create a new connection to a remote device
     pub(super) fn create_connection(
         &self,
@@ -2264,6 +3367,71 @@ where
         platform.disconnect_incoming_media(app_call_context)
     }
 
+    /// Received the progress of escalating to a call link from remote for the active call.
+    pub(super) fn received_escalation(
+        &mut self,
+        call_id: CallId,
+        received: signaling::Escalation,
+    ) -> Result<()> {
+        info!(
+            "received_escalation(): call_id: {} joined: {}",
+            call_id, received.joined
+        );
+
+        if !self.call_is_active(call_id)? {
+            info!("received_escalation(): ignoring for inactive call");
+            return Ok(());
+        }
+        let active_call = self.active_call()?;
+        let remote_peer = active_call.remote_peer()?.clone();
+
+        let (started, newly_joined, completed) = {
+            let mut escalation = self.escalation.lock()?;
+            match escalation.as_mut() {
+                Some(v) if v.call_id == call_id && v.root_key.bytes() == received.root_key => {
+                    let newly_joined = received.joined && !v.remote_joined;
+                    v.remote_joined |= received.joined;
+                    (None, newly_joined, newly_joined && v.local_joined)
+                }
+                // If both sides escalate at the same time, the caller's call link wins.
+                Some(v)
+                    if v.call_id == call_id
+                        && !v.busy_handed_over
+                        && active_call.direction() == CallDirection::Outgoing =>
+                {
+                    info!("received_escalation(): ignoring, using our own call link");
+                    return Ok(());
+                }
+                Some(v) if v.call_id == call_id && v.busy_handed_over => {
+                    warn!("received_escalation(): ignoring, already joining another call link");
+                    return Ok(());
+                }
+                _ => {
+                    let root_key = CallLinkRootKey::try_from(&received.root_key[..])?;
+                    let mut started = CallLinkEscalation::new(call_id, root_key.clone());
+                    started.remote_joined = received.joined;
+                    *escalation = Some(started);
+                    (Some(root_key), received.joined, false)
+                }
+            }
+        };
+
+        if let Some(root_key) = started {
+            self.notify_escalate_to_call_link(&remote_peer, call_id, &root_key)?;
+        }
+        if newly_joined {
+            self.notify_application(
+                &remote_peer,
+                call_id,
+                ApplicationEvent::EscalationRemoteJoined,
+            )?;
+        }
+        if completed {
+            self.terminate_active_call(true, ApplicationEvent::EndedEscalatedToCallLink)?;
+        }
+        Ok(())
+    }
+
     /// Received hangup from remote for the active call.
     pub(super) fn remote_hangup(
         &mut self,
@@ -2272,11 +3440,9 @@ where
     ) -> Result<()> {
         info!("remote_hangup(): call_id: {}", call_id);
 
-        if self.call_is_active(call_id)? {
-            match app_event_override {
-                Some(event) => self.terminate_active_call(false, event),
-                None => self.terminate_active_call(false, ApplicationEvent::EndedRemoteHangup),
-            }
+        if self.call_is_active(call_id)? || self.call_is_waiting_or_held(call_id)? {
+            let event = app_event_override.unwrap_or(ApplicationEvent::EndedRemoteHangup);
+            self.terminate_direct_call(call_id, false, event)
         } else {
             info!("remote_hangup(): ignoring for inactive call");
             Ok(())
@@ -2320,8 +3486,8 @@ where
     pub(super) fn timeout(&mut self, call_id: CallId) -> Result<()> {
         info!("timeout(): call_id: {}", call_id);
 
-        if self.call_is_active(call_id)? {
-            self.terminate_active_call(true, ApplicationEvent::EndedTimeout)
+        if self.call_is_active(call_id)? || self.call_is_waiting_or_held(call_id)? {
+            self.terminate_direct_call(call_id, true, ApplicationEvent::EndedTimeout)
         } else {
             info!("timeout(): ignoring for inactive call");
             Ok(())
@@ -2332,8 +3498,8 @@ where
     pub(super) fn connection_failure(&mut self, call_id: CallId) -> Result<()> {
         info!("call_failed(): call_id: {}", call_id);
 
-        if self.call_is_active(call_id)? {
-            self.terminate_active_call(true, ApplicationEvent::EndedConnectionFailure)
+        if self.call_is_active(call_id)? || self.call_is_waiting_or_held(call_id)? {
+            self.terminate_direct_call(call_id, true, ApplicationEvent::EndedConnectionFailure)
         } else {
             info!("call_failed(): ignoring for inactive call");
             Ok(())
@@ -2347,8 +3513,8 @@ where
     pub(super) fn internal_error(&mut self, call_id: CallId, error: anyhow::Error) -> Result<()> {
         info!("internal_error(): call_id: {}, error: {}", call_id, error);
 
-        if self.call_is_active(call_id)? {
-            self.terminate_active_call(true, ApplicationEvent::EndedInternalFailure)
+        if self.call_is_active(call_id)? || self.call_is_waiting_or_held(call_id)? {
+            self.terminate_direct_call(call_id, true, ApplicationEvent::EndedInternalFailure)
         } else {
             info!("internal_error(): ignoring for inactive call");
             Ok(())
@@ -2365,6 +3531,7 @@ where
         let call_id = call.call_id();
         info!("send_offer(): call_id: {}", call_id);
 
+        let call_for_item = call.clone();
         let offer_closure = Box::new(move |cm: &CallManager<T>| {
             ringbench!(
                 RingBench::Cm,
@@ -2383,11 +3550,11 @@ where
             }
         });
 
-        let message_item = SignalingMessageItem {
-            call_id,
-            message_type: signaling::MessageType::Offer,
-            message_closure: offer_closure,
-        };
+        let message_item = SignalingMessageItem::new(
+            call_for_item,
+            signaling::MessageType::Offer,
+            MessageContent::Closure(offer_closure),
+        );
 
         self.send_next_message(Some(message_item))
     }
@@ -2402,6 +3569,7 @@ where
         let call_id = call.call_id();
         info!("send_answer(): call_id: {}", call_id);
 
+        let call_for_item = call.clone();
         let answer_closure = Box::new(move |cm: &CallManager<T>| {
             ringbench!(
                 RingBench::Cm,
@@ -2424,11 +3592,11 @@ where
             }
         });
 
-        let message_item = SignalingMessageItem {
-            call_id,
-            message_type: signaling::MessageType::Answer,
-            message_closure: answer_closure,
-        };
+        let message_item = SignalingMessageItem::new(
+            call_for_item,
+            signaling::MessageType::Answer,
+            MessageContent::Closure(answer_closure),
+        );
 
         self.send_next_message(Some(message_item))
     }
@@ -2443,48 +3611,14 @@ where
         let call_id = call.call_id();
         info!("send_ice_candidates(): call_id: {}", call_id);
 
-        let ice_closure = Box::new(move |cm: &CallManager<T>| {
-            let local_candidates = connection.take_buffered_local_ice_candidates()?;
-
-            if local_candidates.is_empty() {
-                return Ok(MessageSendResult::NotSent);
-            }
-
-            ringbench!(
-                RingBench::Cm,
-                RingBench::App,
-                format!(
-                    "send_ice_candidates({})\t{}",
-                    local_candidates.len(),
-                    call_id,
-                )
-            );
-
-            let remote_peer = call.remote_peer()?;
-
-            let platform = cm.platform.lock()?;
-            platform.on_send_ice(
-                &remote_peer,
-                call_id,
-                signaling::SendIce {
-                    receiver_device_id: if broadcast {
-                        None
-                    } else {
-                        Some(connection.remote_device_id())
-                    },
-                    ice: signaling::Ice {
-                        candidates: local_candidates,
-                    },
-                },
-            )?;
-            Ok(MessageSendResult::Sent)
-        });
-
-        let message_item = SignalingMessageItem {
-            call_id,
-            message_type: signaling::MessageType::Ice,
-            message_closure: ice_closure,
-        };
+        let message_item = SignalingMessageItem::new(
+            call,
+            signaling::MessageType::Ice,
+            MessageContent::Ice {
+                connections: vec![connection],
+                broadcast,
+            },
+        );
 
         self.send_next_message(Some(message_item))
     }
@@ -2554,6 +3688,26 @@ where
     ) {
         info!("handle_join_state_changed():");
         platform_handler!(self, handle_join_state_changed, client_id, join_state);
+
+        let is_escalation_client = match self.escalation.lock() {
+            Ok(escalation) => escalation
+                .as_ref()
+                .map_or(false, |v| v.client_id == Some(client_id)),
+            Err(error) => {
+                error!("{}", error);
+                false
+            }
+        };
+        if is_escalation_client {
+            let mut call_manager = self.clone();
+            let _ = self.clone().worker_spawn(move || {
+                if let Err(err) =
+                    call_manager.handle_escalation_join_state_changed(client_id, join_state)
+                {
+                    error!("Handling escalation join state failed: {}", err);
+                }
+            });
+        }
     }
 
     fn handle_remote_devices_changed(
@@ -2633,11 +3787,25 @@ where
         platform_handler!(self, handle_reactions, client_id, reactions);
     }
 
+    fn handle_chat_messages(
+        &self,
+        client_id: group_call::ClientId,
+        messages: Vec<group_call::ChatMessage>,
+    ) {
+        info!("handle_chat_messages(): {}", messages.len());
+        platform_handler!(self, handle_chat_messages, client_id, messages);
+    }
+
     fn handle_raised_hands(&self, client_id: group_call::ClientId, raised_hands: Vec<DemuxId>) {
         info!("handle_raised_hands(): {:?}", raised_hands);
         platform_handler!(self, handle_raised_hands, client_id, raised_hands);
     }
 
+    fn handle_remote_mute_request(&self, client_id: group_call::ClientId, mute_source: DemuxId) {
+        info!("handle_remote_mute_request(): {}", mute_source);
+        platform_handler!(self, handle_remote_mute_request, client_id, mute_source);
+    }
+
     fn handle_rtc_stats_report(&self, report_json: String) {
         platform_handler!(self, handle_rtc_stats_report, report_json);
     }
@@ -2781,9 +3949,10 @@ where
         };
 
         let ring_id = {
+            let group_ring_expiry = self.ring_policy()?.group_ring_expiry;
             let mut outstanding_group_rings = self.outstanding_group_rings.lock()?;
             // Take this opportunity to clear the outstanding rings table (which should be small).
-            outstanding_group_rings.retain(|_group_id, ring| !ring.has_expired());
+            outstanding_group_rings.retain(|_group_id, ring| !ring.has_expired(group_ring_expiry));
             outstanding_group_rings
                 .get(&group_id)
                 .map(|ring| ring.ring_id)
@@ -2909,6 +4078,16 @@ where
 
         info!("Call Link Client created with id: {}", client_id);
 
+        if let Some(escalation) = self.escalation.lock()?.as_mut() {
+            if escalation.root_key.bytes() == root_key.bytes() && !escalation.busy_handed_over {
+                info!(
+                    "Call Link Client is for escalating call_id: {}",
+                    escalation.call_id
+                );
+                escalation.client_id = Some(client_id);
+            }
+        }
+
         Ok(client_id)
     }
 
@@ -2986,9 +4165,9 @@ where
     }
 
     forward_group_call_api!(connect());
-    forward_group_call_api!(join());
     forward_group_call_api!(leave());
     forward_group_call_api!(react(value: String));
+    forward_group_call_api!(send_chat_message(text: String));
     forward_group_call_api!(raise_hand(raise: bool));
     forward_group_call_api!(group_ring => ring(recipient: Option<UserId>));
     forward_group_call_api!(set_outgoing_audio_muted(muted: bool));
@@ -3005,9 +4184,50 @@ where
     forward_group_call_api!(deny_user(user_id: UserId));
     forward_group_call_api!(remove_client(other_client_id: DemuxId));
     forward_group_call_api!(block_client(other_client_id: DemuxId));
+    forward_group_call_api!(lower_hand(other_client_id: DemuxId));
+    forward_group_call_api!(lower_all_hands());
+    forward_group_call_api!(request_mute(other_client_id: DemuxId));
     forward_group_call_api!(set_group_members(members: Vec<GroupMember>));
     forward_group_call_api!(set_membership_proof(proof: Vec<u8>));
     forward_group_call_api!(set_rtc_stats_interval(interval: Duration));
+    forward_group_call_api!(set_connection_recovery_window(window: Duration));
+    forward_group_call_api!(set_reaction_rate_limits(
+        send: ReactionRateLimit,
+        receive: ReactionRateLimit
+    ));
+    forward_group_call_api!(set_reaction_aggregation_window(window: Duration));
+
+    pub fn join(&mut self, client_id: group_call::ClientId) {
+        info!("join(): id: {}", client_id);
+
+        // The direct call being escalated keeps the CallManager busy, so hand
+        // busy over to the call link call client for it to be able to join.
+        let escalated_call_id = match self.escalation.lock() {
+            Ok(escalation) => escalation
+                .as_ref()
+                .filter(|v| v.client_id == Some(client_id) && !v.busy_handed_over)
+                .map(|v| v.call_id),
+            Err(error) => {
+                error!("{}", error);
+                None
+            }
+        };
+        if let Some(call_id) = escalated_call_id {
+            if let Err(error) = self.hand_over_busy(call_id) {
+                error!("{}", error);
+            }
+        }
+
+        self.with_group_call(client_id, |group_call| group_call.join());
+    }
+
+    pub fn speaking_summary(&mut self, client_id: group_call::ClientId) -> Option<SpeakingSummary> {
+        let mut summary = None;
+        self.with_group_call(client_id, |group_call| {
+            summary = Some(group_call.speaking_summary())
+        });
+        summary
+    }
 
     pub fn disconnect(&mut self, client_id: group_call::ClientId) {
         info!("disconnect(): id: {}", client_id);
@@ -3057,13 +4277,31 @@ mod tests {
             }
         }
 
-        validate_offer(&offer_with_age(Duration::ZERO)).expect("valid");
-        validate_offer(&offer_with_age(MAX_MESSAGE_AGE - Duration::from_secs(1))).expect("valid");
-        validate_offer(&offer_with_age(MAX_MESSAGE_AGE)).expect("valid");
+        let policy = RingPolicy::default();
+        validate_offer(&offer_with_age(Duration::ZERO), &policy).expect("valid");
+        validate_offer(
+            &offer_with_age(MAX_MESSAGE_AGE - Duration::from_secs(1)),
+            &policy,
+        )
+        .expect("valid");
+        validate_offer(&offer_with_age(MAX_MESSAGE_AGE), &policy).expect("valid");
         assert!(matches!(
-            validate_offer(&offer_with_age(MAX_MESSAGE_AGE + Duration::from_secs(1))),
+            validate_offer(
+                &offer_with_age(MAX_MESSAGE_AGE + Duration::from_secs(1)),
+                &policy
+            ),
             Err(OfferValidationError::Expired)
         ));
+
+        let long_policy = RingPolicy {
+            max_offer_age: MAX_MESSAGE_AGE * 2,
+            ..Default::default()
+        };
+        validate_offer(
+            &offer_with_age(MAX_MESSAGE_AGE + Duration::from_secs(1)),
+            &long_policy,
+        )
+        .expect("valid");
     }
 
     #[test]
@@ -3085,21 +4323,25 @@ mod tests {
             true
         }
 
+        let policy = RingPolicy::default();
         validate_call_message_as_opaque_ring(
             &valid_message,
             Duration::ZERO,
+            &policy,
             check_group_and_ring_id,
         )
         .expect("valid");
         validate_call_message_as_opaque_ring(
             &valid_message,
             MAX_MESSAGE_AGE - Duration::from_secs(1),
+            &policy,
             check_group_and_ring_id,
         )
         .expect("valid");
         validate_call_message_as_opaque_ring(
             &valid_message,
             MAX_MESSAGE_AGE,
+            &policy,
             check_group_and_ring_id,
         )
         .expect("valid");
@@ -3108,7 +4350,22 @@ mod tests {
             validate_call_message_as_opaque_ring(
                 &valid_message,
                 MAX_MESSAGE_AGE + Duration::from_secs(1),
-                check_group_and_ring_id,
+                &policy,
+                check_group_and_ring_id
+            ),
+            Err(OpaqueRingValidationError::Expired)
+        ));
+
+        let short_policy = RingPolicy {
+            max_offer_age: Duration::from_secs(10),
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_call_message_as_opaque_ring(
+                &valid_message,
+                Duration::from_secs(11),
+                &short_policy,
+                check_group_and_ring_id
             ),
             Err(OpaqueRingValidationError::Expired)
         ));
@@ -3125,11 +4382,17 @@ mod tests {
             ..Default::default()
         };
 
-        validate_call_message_as_opaque_ring(&valid_message, Duration::ZERO, |_, _| true)
+        let policy = RingPolicy::default();
+        validate_call_message_as_opaque_ring(&valid_message, Duration::ZERO, &policy, |_, _| true)
             .expect("valid");
 
         assert!(matches!(
-            validate_call_message_as_opaque_ring(&valid_message, Duration::ZERO, |_, _| { false }),
+            validate_call_message_as_opaque_ring(
+                &valid_message,
+                Duration::ZERO,
+                &policy,
+                |_, _| { false }
+            ),
             Err(OpaqueRingValidationError::RejectedByCallback)
         ));
     }
@@ -3138,9 +4401,15 @@ mod tests {
     fn test_validate_group_ring_intention_for_non_rings() {
         #[track_caller]
         fn assert_rejected(message: CallMessage, description: &str) {
+            let policy = RingPolicy::default();
             assert!(
                 matches!(
-                    validate_call_message_as_opaque_ring(&message, Duration::ZERO, |_, _| { true }),
+                    validate_call_message_as_opaque_ring(
+                        &message,
+                        Duration::ZERO,
+                        &policy,
+                        |_, _| { true }
+                    ),
                     Err(OpaqueRingValidationError::NotARing)
                 ),
                 "{}",
@@ -0,0 +1,315 @@
+//
+// Copyright 2026 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Filtering of the ICE candidates used by 1:1 calls.
+
+use std::net::IpAddr;
+
+use crate::core::signaling;
+use crate::webrtc::peer_connection_observer::NetworkAdapterType;
+
+/// The type of an ICE candidate, from its `typ` attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateType {
+    Host,
+    ServerReflexive,
+    PeerReflexive,
+    Relay,
+}
+
+/// The parts of an ICE candidate that a [`CandidatePolicy`] looks at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CandidateInfo {
+    pub candidate_type: CandidateType,
+    /// None if the address is a hostname, such as an mDNS name.
+    pub ip: Option<IpAddr>,
+    pub network_cost: Option<u16>,
+}
+
+impl CandidateInfo {
+    /// Parses an SDP candidate attribute, such as
+    /// `candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host network-cost 10`.
+    pub fn from_sdp(sdp: &str) -> Option<Self> {
+        let sdp = sdp.trim().strip_prefix("a=").unwrap_or(sdp.trim());
+        let fields: Vec<&str> = sdp.split_ascii_whitespace().collect();
+        if fields.len() < 8 || !fields[0].starts_with("candidate:") || fields[6] != "typ" {
+            return None;
+        }
+        let candidate_type = match fields[7] {
+            "host" => CandidateType::Host,
+            "srflx" => CandidateType::ServerReflexive,
+            "prflx" => CandidateType::PeerReflexive,
+            "relay" => CandidateType::Relay,
+            _ => return None,
+        };
+        let network_cost = fields[8..]
+            .chunks_exact(2)
+            .find(|pair| pair[0] == "network-cost")
+            .and_then(|pair| pair[1].parse().ok());
+        Some(Self {
+            candidate_type,
+            ip: fields[4].parse().ok(),
+            network_cost,
+        })
+    }
+
+    /// The type of network adapter the candidate was gathered on, as far as
+    /// its network cost tells.
+    ///
+    /// Candidates only carry their adapter type through the cost WebRTC gives
+    /// each type of network (see rtc_base/network_constants.h), plus one for
+    /// networks under a VPN.
+    pub fn adapter_type(&self) -> Option<NetworkAdapterType> {
+        let cost = self.network_cost?;
+        if cost % 2 == 1 {
+            return Some(NetworkAdapterType::Vpn);
+        }
+        match cost {
+            0 => Some(NetworkAdapterType::Ethernet),
+            10 => Some(NetworkAdapterType::Wifi),
+            250 => Some(NetworkAdapterType::Cellular5G),
+            500 => Some(NetworkAdapterType::Cellular4G),
+            900 => Some(NetworkAdapterType::Cellular),
+            910 => Some(NetworkAdapterType::Cellular3G),
+            980 => Some(NetworkAdapterType::Cellular2G),
+            _ => None,
+        }
+    }
+}
+
+fn is_cellular(adapter_type: NetworkAdapterType) -> bool {
+    matches!(
+        adapter_type,
+        NetworkAdapterType::Cellular
+            | NetworkAdapterType::Cellular2G
+            | NetworkAdapterType::Cellular3G
+            | NetworkAdapterType::Cellular4G
+            | NetworkAdapterType::Cellular5G
+    )
+}
+
+/// Which ICE candidates a 1:1 call may use.
+///
+/// The policy applies both to the candidates gathered locally, which are then
+/// never sent, and to the candidates received from the remote side, which are
+/// then never used. The default allows all candidates.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CandidatePolicy {
+    /// Drop host candidates, which reveal local IP addresses. Meant for
+    /// calls with people who aren't contacts.
+    pub exclude_host: bool,
+    /// Drop all but relay candidates. The PeerConnection is also created
+    /// with a relay-only ICE transport policy, so that nothing else is
+    /// gathered, and the remote side doesn't learn the local public address
+    /// from connectivity checks.
+    pub relay_only: bool,
+    /// Drop candidates that don't have an IPv4 address.
+    pub ipv4_only: bool,
+    /// Drop candidates on these types of network. Cellular matches all
+    /// cellular types.
+    pub excluded_adapter_types: Vec<NetworkAdapterType>,
+}
+
+impl CandidatePolicy {
+    /// Only host candidates are dropped.
+    pub fn no_host() -> Self {
+        Self {
+            exclude_host: true,
+            ..Default::default()
+        }
+    }
+
+    /// Only relay candidates are used.
+    pub fn relay_only() -> Self {
+        Self {
+            relay_only: true,
+            ..Default::default()
+        }
+    }
+
+    /// Builds a policy from the values passed through the platform bindings,
+    /// where the excluded adapter types are a bitmask of
+    /// [`NetworkAdapterType`] values.
+    pub fn from_flags(
+        exclude_host: bool,
+        relay_only: bool,
+        ipv4_only: bool,
+        excluded_adapter_types: u32,
+    ) -> Self {
+        use NetworkAdapterType::*;
+        Self {
+            exclude_host,
+            relay_only,
+            ipv4_only,
+            excluded_adapter_types: [
+                Ethernet, Wifi, Cellular, Vpn, Loopback, Default, Cellular2G, Cellular3G,
+                Cellular4G, Cellular5G,
+            ]
+            .into_iter()
+            .filter(|adapter_type| excluded_adapter_types & (*adapter_type as u32) != 0)
+            .collect(),
+        }
+    }
+
+    /// Returns why the candidate must not be used, or None if it can be.
+    ///
+    /// Removals of candidates are always allowed. Candidates that can't be
+    /// parsed are only allowed by the default policy.
+    pub fn check(&self, candidate: &signaling::IceCandidate) -> Option<&'static str> {
+        if *self == Self::default() || candidate.removed_address().is_some() {
+            return None;
+        }
+        match candidate.v3_sdp() {
+            Some(sdp) => self.check_sdp(&sdp),
+            None => Some("not parsable"),
+        }
+    }
+
+    /// Like [`CandidatePolicy::check`], for a candidate in SDP form.
+    pub fn check_sdp(&self, sdp: &str) -> Option<&'static str> {
+        if *self == Self::default() {
+            return None;
+        }
+        let Some(info) = CandidateInfo::from_sdp(sdp) else {
+            return Some("not parsable");
+        };
+        if self.relay_only && info.candidate_type != CandidateType::Relay {
+            return Some("not relay");
+        }
+        if self.exclude_host && info.candidate_type == CandidateType::Host {
+            return Some("host");
+        }
+        if self.ipv4_only && !matches!(info.ip, Some(IpAddr::V4(_))) {
+            return Some("not IPv4");
+        }
+        if let Some(adapter_type) = info.adapter_type() {
+            if self.excluded_adapter_types.iter().any(|excluded| {
+                *excluded == adapter_type
+                    || (*excluded == NetworkAdapterType::Cellular && is_cellular(adapter_type))
+            }) {
+                return Some("excluded adapter type");
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST: &str = "candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host generation 0 ufrag abcd network-id 1 network-cost 10";
+    const HOST_IPV6: &str =
+        "candidate:2 1 udp 2122262783 2001:db8::1 50001 typ host generation 0 network-cost 0";
+    const SRFLX_CELLULAR: &str = "a=candidate:3 1 udp 1686052607 198.51.100.1 50002 typ srflx raddr 10.0.0.1 rport 50002 generation 0 network-cost 900";
+    const RELAY_VPN: &str = "candidate:4 1 udp 41885439 203.0.113.1 50003 typ relay raddr 198.51.100.1 rport 50002 generation 0 network-cost 11";
+
+    #[test]
+    fn parse_candidates() {
+        assert_eq!(
+            Some(CandidateInfo {
+                candidate_type: CandidateType::Host,
+                ip: Some("192.0.2.1".parse().unwrap()),
+                network_cost: Some(10),
+            }),
+            CandidateInfo::from_sdp(HOST)
+        );
+        let srflx = CandidateInfo::from_sdp(SRFLX_CELLULAR).unwrap();
+        assert_eq!(CandidateType::ServerReflexive, srflx.candidate_type);
+        assert_eq!(Some(NetworkAdapterType::Cellular), srflx.adapter_type());
+        let relay = CandidateInfo::from_sdp(RELAY_VPN).unwrap();
+        assert_eq!(CandidateType::Relay, relay.candidate_type);
+        assert_eq!(Some(NetworkAdapterType::Vpn), relay.adapter_type());
+        let mdns = CandidateInfo::from_sdp(
+            "candidate:5 1 udp 2122260223 a1b2c3.local 50004 typ host generation 0",
+        )
+        .unwrap();
+        assert_eq!(None, mdns.ip);
+        assert_eq!(None, mdns.adapter_type());
+        assert_eq!(None, CandidateInfo::from_sdp("ICE-CANDIDATE-1"));
+    }
+
+    #[test]
+    fn default_policy_allows_all() {
+        let policy = CandidatePolicy::default();
+        for sdp in [
+            HOST,
+            HOST_IPV6,
+            SRFLX_CELLULAR,
+            RELAY_VPN,
+            "ICE-CANDIDATE-1",
+        ] {
+            assert_eq!(None, policy.check_sdp(sdp), "{}", sdp);
+        }
+    }
+
+    #[test]
+    fn restrictive_policies() {
+        let no_host = CandidatePolicy::no_host();
+        assert_eq!(Some("host"), no_host.check_sdp(HOST));
+        assert_eq!(None, no_host.check_sdp(SRFLX_CELLULAR));
+        assert_eq!(Some("not parsable"), no_host.check_sdp("ICE-CANDIDATE-1"));
+
+        let relay_only = CandidatePolicy::relay_only();
+        assert_eq!(Some("not relay"), relay_only.check_sdp(SRFLX_CELLULAR));
+        assert_eq!(None, relay_only.check_sdp(RELAY_VPN));
+
+        let ipv4_only = CandidatePolicy {
+            ipv4_only: true,
+            ..Default::default()
+        };
+        assert_eq!(Some("not IPv4"), ipv4_only.check_sdp(HOST_IPV6));
+        assert_eq!(None, ipv4_only.check_sdp(HOST));
+
+        let no_vpn_or_cellular = CandidatePolicy {
+            excluded_adapter_types: vec![NetworkAdapterType::Vpn, NetworkAdapterType::Cellular],
+            ..Default::default()
+        };
+        assert_eq!(
+            Some("excluded adapter type"),
+            no_vpn_or_cellular.check_sdp(SRFLX_CELLULAR)
+        );
+        assert_eq!(
+            Some("excluded adapter type"),
+            no_vpn_or_cellular.check_sdp(RELAY_VPN)
+        );
+        assert_eq!(
+            Some("excluded adapter type"),
+            no_vpn_or_cellular.check_sdp(
+                "candidate:6 1 udp 1686052607 198.51.100.2 50005 typ srflx network-cost 500"
+            )
+        );
+        assert_eq!(None, no_vpn_or_cellular.check_sdp(HOST));
+    }
+
+    #[test]
+    fn from_flags() {
+        assert_eq!(
+            CandidatePolicy::default(),
+            CandidatePolicy::from_flags(false, false, false, 0)
+        );
+        assert_eq!(
+            CandidatePolicy {
+                relay_only: true,
+                excluded_adapter_types: vec![NetworkAdapterType::Cellular, NetworkAdapterType::Vpn],
+                ..Default::default()
+            },
+            CandidatePolicy::from_flags(
+                false,
+                true,
+                false,
+                NetworkAdapterType::Vpn as u32 | NetworkAdapterType::Cellular as u32
+            )
+        );
+    }
+
+    #[test]
+    fn removals_are_always_allowed() {
+        let removal =
+            signaling::IceCandidate::from_removed_address("192.0.2.1:50000".parse().unwrap())
+                .unwrap();
+        assert_eq!(None, CandidatePolicy::relay_only().check(&removal));
+    }
+}
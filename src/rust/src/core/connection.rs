@@ -10,13 +10,16 @@ use std::net::SocketAddr;
 use std::sync::mpsc::{Receiver, SyncSender};
 use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use bytes::{BufMut, BytesMut};
 
 use prost::Message;
 
 use hkdf::Hkdf;
+use ml_kem::kem::{Decapsulate, Encapsulate};
+use ml_kem::{Ciphertext, Encoded, EncodedSizeUser, KemCore, MlKem768};
+use mrp::{MrpReceiveError, MrpStream};
 use rand::rngs::OsRng;
 use sha2::Sha256;
 use x25519_dalek::{PublicKey, StaticSecret};
@@ -40,6 +43,7 @@ use crate::webrtc;
 use crate::webrtc::ice_gatherer::IceGatherer;
 use crate::webrtc::media::{MediaStream, VideoFrame, VideoFrameMetadata, VideoSink};
 use crate::webrtc::peer_connection::{AudioLevel, PeerConnection, SendRates};
+use crate::webrtc::peer_connection_factory::AudioDeviceStatsTracker;
 use crate::webrtc::peer_connection_observer::{
     IceConnectionState, NetworkAdapterType, NetworkRoute, PeerConnectionObserverTrait,
     TransportProtocol,
@@ -59,6 +63,14 @@ const SEND_RTP_DATA_MESSAGE_INTERVAL_MILLIS: u64 = 1000;
 const SEND_RTP_DATA_MESSAGE_INTERVAL_TICKS: u64 =
     SEND_RTP_DATA_MESSAGE_INTERVAL_MILLIS / TICK_INTERVAL_MILLIS;
 
+/// How many unacknowledged reliable RTP data messages to buffer.
+const RELIABLE_RTP_DATA_BUFFER_SIZE: usize = 64;
+/// How long to wait for an ack before resending a reliable RTP data message.
+const RELIABLE_RTP_DATA_RESEND_INTERVAL: Duration = Duration::from_millis(500);
+/// How many times to resend before the remote peer has acked anything, in case
+/// it doesn't understand MRP headers (about 10 seconds).
+const RELIABLE_RTP_DATA_MAX_UNCONFIRMED_RESENDS: usize = 20;
+
 /// How often to check the latest bandwidth estimate from WebRTC
 const CHECK_BWE_INTERVAL_MILLIS: u64 = 1000;
 const CHECK_BWE_INTERVAL_TICKS: u64 = CHECK_BWE_INTERVAL_MILLIS / TICK_INTERVAL_MILLIS;
@@ -90,6 +102,17 @@ pub enum ConnectionObserverEvent {
     /// and the value changed.
     RemoteSenderStatusChanged(signaling::SenderStatus),
 
+    /// The remote side sent an escalation message via RTP data
+    /// and the value changed.
+    RemoteEscalationChanged(signaling::Escalation),
+
+    /// The remote side requested a video upgrade, or answered or canceled
+    /// one, via RTP data.
+    RemoteVideoUpgrade(signaling::VideoUpgradeType),
+
+    /// The remote side didn't answer the local video upgrade request in time.
+    VideoUpgradeTimedOut,
+
     /// The remote side sent a hangup message via RTP data
     /// or via signaling.
     ReceivedHangup(signaling::Hangup),
@@ -143,6 +166,8 @@ where
     app_connection: Option<<T as Platform>::AppConnection>,
     /// Boxed copy of the stats collector object shared for callbacks.
     stats_observer: Option<Box<StatsObserver>>,
+    /// Local audio device stats to log along with the WebRTC stats.
+    audio_device_stats: Option<AudioDeviceStatsTracker>,
 }
 
 // Send and Sync needed to share *const pointer types across threads.
@@ -237,6 +262,36 @@ struct TickState {
     actor: Actor<TickState>,
 }
 
+/// One-off RTP data messages (accepted, hangup, ...) are also sent on
+/// their own over an MrpStream, so they are acknowledged and retransmitted
+/// rather than only repeated as part of the latest state.
+struct ReliableRtpData {
+    stream: MrpStream<protobuf::rtp_data::Message, protobuf::rtp_data::Message>,
+    /// Set once the remote peer has sent us a header of its own. Peers that
+    /// don't understand MRP headers never ack, so until then we only resend
+    /// up to [RELIABLE_RTP_DATA_MAX_UNCONFIRMED_RESENDS] times.
+    remote_supports_mrp: bool,
+    unconfirmed_resends: usize,
+}
+
+impl From<&protobuf::rtp_data::MrpHeader> for mrp::MrpHeader {
+    fn from(value: &protobuf::rtp_data::MrpHeader) -> Self {
+        Self {
+            seqnum: value.seqnum,
+            ack_num: value.ack_num,
+        }
+    }
+}
+
+impl From<mrp::MrpHeader> for protobuf::rtp_data::MrpHeader {
+    fn from(value: mrp::MrpHeader) -> Self {
+        Self {
+            seqnum: value.seqnum,
+            ack_num: value.ack_num,
+        }
+    }
+}
+
 // Don't send below this, even if the remote side requests lower.
 const MIN_SEND_RATE: DataRate = DataRate::from_kbps(30);
 // When a network route is relayed, don't send more than this.
@@ -375,6 +430,8 @@ where
     tick_context: Actor<TickState>,
     /// The accumulated state of sending messages over RTP data
     accumulated_rtp_data_message: Arc<CallMutex<protobuf::rtp_data::Message>>,
+    /// Acknowledged delivery of one-off messages over RTP data
+    reliable_rtp_data: Arc<CallMutex<ReliableRtpData>>,
     /// We use this to drop out-of-order messages.
     last_received_rtp_data_timestamp: Arc<CallMutex<rtp::Timestamp>>,
     // If set, all of the video frames will go here.
@@ -464,6 +521,7 @@ where
             connection_type: self.connection_type,
             tick_context: self.tick_context.clone(),
             accumulated_rtp_data_message: Arc::clone(&self.accumulated_rtp_data_message),
+            reliable_rtp_data: Arc::clone(&self.reliable_rtp_data),
             last_received_rtp_data_timestamp: Arc::clone(&self.last_received_rtp_data_timestamp),
             incoming_video_sink: self.incoming_video_sink.clone(),
             bwe_callback_state: self.bwe_callback_state,
@@ -497,6 +555,7 @@ where
             incoming_media: None,
             app_connection: None,
             stats_observer: None,
+            audio_device_stats: None,
         };
 
         let poll_stats_config = PollStatsConfig::new(
@@ -546,6 +605,14 @@ where
                 protobuf::rtp_data::Message::default(),
                 "accumulated_rtp_data_message",
             )),
+            reliable_rtp_data: Arc::new(CallMutex::new(
+                ReliableRtpData {
+                    stream: MrpStream::new(RELIABLE_RTP_DATA_BUFFER_SIZE),
+                    remote_supports_mrp: false,
+                    unconfirmed_resends: 0,
+                },
+                "reliable_rtp_data",
+            )),
             last_received_rtp_data_timestamp: Arc::new(CallMutex::new(
                 0,
                 "last_received_rtp_data_timestamp",
@@ -585,7 +652,7 @@ where
     pub fn start_outgoing_parent(
         &mut self,
         call_media_type: CallMediaType,
-    ) -> Result<(StaticSecret, IceGatherer, signaling::Offer)> {
+    ) -> Result<(OfferSecrets, IceGatherer, signaling::Offer)> {
         let result = (|| {
             self.set_state(ConnectionState::Starting)?;
 
@@ -604,6 +671,7 @@ where
 
             // We have to do this before we pass ownership of offer_sdi into set_local_description.
             let (local_secret, local_public_key) = generate_local_secret_and_public_key()?;
+            let (kem_decapsulation_key, kem_encapsulation_key) = MlKem768::generate(&mut OsRng);
             let v4_offer = offer.to_v4(
                 local_public_key.as_bytes().to_vec(),
                 &self.call_config,
@@ -628,10 +696,21 @@ where
             peer_connection.set_local_description(observer.as_ref(), offer);
             observer.get_result()?;
 
-            let offer = signaling::Offer::from_v4(call_media_type, v4_offer)?;
+            // V4-only receivers ignore the V5 parameters and answer with V4.
+            let v5_offer = protobuf::signaling::ConnectionParametersV5 {
+                kem_encapsulation_key: Some(kem_encapsulation_key.as_bytes().to_vec()),
+                kem_ciphertext: None,
+            };
+            let offer =
+                signaling::Offer::from_v4_and_v5(call_media_type, v4_offer, Some(v5_offer))?;
+
+            let local_secrets = OfferSecrets {
+                dh_secret: local_secret,
+                kem_decapsulation_key,
+            };
 
             self.set_state(ConnectionState::IceGathering)?;
-            Ok((local_secret, ice_gatherer, offer))
+            Ok((local_secrets, ice_gatherer, offer))
         })();
 
         // Always start the FSM no matter what happened above because
@@ -647,7 +726,7 @@ where
     // 3. Make sure no media flows except for incoming RTP until a remote accepts.
     pub fn start_outgoing_child(
         &mut self,
-        local_secret: &StaticSecret,
+        local_secrets: &OfferSecrets,
         ice_gatherer: &IceGatherer,
         offer: &signaling::Offer,
         received: &signaling::ReceivedAnswer,
@@ -660,8 +739,11 @@ where
             let mut webrtc = self.webrtc.lock()?;
 
             // Create a stats observer object.
-            let stats_observer =
-                create_stats_observer(self.call_id(), self.poll_stats_config.get_initial_offset());
+            let stats_observer = create_stats_observer(
+                self.call_id(),
+                self.poll_stats_config.get_initial_offset(),
+                webrtc.audio_device_stats.clone(),
+            );
             webrtc.stats_observer = Some(stats_observer);
 
             let peer_connection = webrtc.peer_connection()?;
@@ -696,14 +778,27 @@ where
                 };
 
             if let Some(remote_public_key) = remote_public_key {
+                // An answer without V5 parameters comes from a V4-only callee.
+                let kem_shared_secret = match received.answer.to_v5() {
+                    Some(v5_answer) => Some(decapsulate_kem_shared_secret(
+                        &local_secrets.kem_decapsulation_key,
+                        v5_answer.kem_ciphertext.as_deref(),
+                    )?),
+                    None => None,
+                };
+                info!(
+                    "Negotiating SRTP keys using signaling {}",
+                    received.answer.latest_version()
+                );
                 let callee_identity_key = &received.sender_identity_key;
                 let caller_identity_key = &received.receiver_identity_key;
                 let NegotiatedSrtpKeys {
                     offer_key,
                     answer_key,
                 } = negotiate_srtp_keys(
-                    local_secret,
+                    &local_secrets.dh_secret,
                     &remote_public_key,
+                    kem_shared_secret.as_deref(),
                     caller_identity_key,
                     callee_identity_key,
                 )?;
@@ -764,8 +859,11 @@ where
             let mut webrtc = self.webrtc.lock()?;
 
             // Create a stats observer object.
-            let stats_observer =
-                create_stats_observer(self.call_id(), self.poll_stats_config.get_initial_offset());
+            let stats_observer = create_stats_observer(
+                self.call_id(),
+                self.poll_stats_config.get_initial_offset(),
+                webrtc.audio_device_stats.clone(),
+            );
             webrtc.stats_observer = Some(stats_observer);
 
             let peer_connection = webrtc.peer_connection()?;
@@ -788,9 +886,24 @@ where
             };
 
             let (local_secret, local_public_key) = generate_local_secret_and_public_key()?;
-            let answer_key = match remote_public_key {
-                None => None,
+            let (answer_key, kem_ciphertext) = match remote_public_key {
+                None => (None, None),
                 Some(remote_public_key) => {
+                    // Answer a V5 offer with V5 and anything else with V4.
+                    let (kem_ciphertext, kem_shared_secret) = match received.offer.to_v5() {
+                        Some(v5_offer) => {
+                            let (kem_ciphertext, kem_shared_secret) =
+                                encapsulate_kem_shared_secret(
+                                    v5_offer.kem_encapsulation_key.as_deref(),
+                                )?;
+                            (Some(kem_ciphertext), Some(kem_shared_secret))
+                        }
+                        None => (None, None),
+                    };
+                    info!(
+                        "Negotiating SRTP keys using signaling {}",
+                        received.offer.latest_version()
+                    );
                     let caller_identity_key = &received.sender_identity_key;
                     let callee_identity_key = &received.receiver_identity_key;
                     let NegotiatedSrtpKeys {
@@ -799,11 +912,12 @@ where
                     } = negotiate_srtp_keys(
                         &local_secret,
                         &remote_public_key,
+                        kem_shared_secret.as_deref(),
                         caller_identity_key,
                         callee_identity_key,
                     )?;
                     offer.disable_dtls_and_set_srtp_key(&offer_key)?;
-                    Some(answer_key)
+                    (Some(answer_key), kem_ciphertext)
                 }
             };
 
@@ -838,7 +952,13 @@ where
                 if let Some(answer_key) = &answer_key {
                     answer.disable_dtls_and_set_srtp_key(answer_key)?;
                 }
-                signaling::Answer::from_v4(v4_answer)?
+                let v5_answer = kem_ciphertext.map(|kem_ciphertext| {
+                    protobuf::signaling::ConnectionParametersV5 {
+                        kem_encapsulation_key: None,
+                        kem_ciphertext: Some(kem_ciphertext),
+                    }
+                });
+                signaling::Answer::from_v4_and_v5(v4_answer, v5_answer)?
             } else {
                 return Err(RingRtcError::UnknownSignaledProtocolVersion.into());
             };
@@ -955,6 +1075,16 @@ where
         Ok(())
     }
 
+    /// Set the local audio device stats to log along with the WebRTC stats.
+    pub fn set_audio_device_stats(
+        &self,
+        audio_device_stats: AudioDeviceStatsTracker,
+    ) -> Result<()> {
+        let mut webrtc = self.webrtc.lock()?;
+        webrtc.audio_device_stats = Some(audio_device_stats);
+        Ok(())
+    }
+
     /// Return the call configuration used for this connection.
     pub fn call_config(&self) -> &CallConfig {
         &self.call_config
@@ -1137,6 +1267,7 @@ where
         if ticks_elapsed % SEND_RTP_DATA_MESSAGE_INTERVAL_TICKS == 0 {
             self.send_latest_rtp_data_message(&mut webrtc)?;
         }
+        self.send_reliable_rtp_data_acks_and_resends(&mut webrtc)?;
 
         if ticks_elapsed % self.poll_stats_config.poll_stats_interval_ticks
             == self.poll_stats_config.poll_stats_initial_offset_ticks
@@ -1301,7 +1432,15 @@ where
                 // the backwards-compatibility mechanism we have that contains a dummy
                 // candidate.
             } else if let Some(sdp) = candidate.v3_sdp() {
-                added_sdps.push(sdp);
+                if let Some(reason) = self.call_config.candidate_policy.check_sdp(&sdp) {
+                    info!(
+                        "Remote ICE candidate filtered ({}): {}",
+                        reason,
+                        redact_string(&sdp)
+                    );
+                } else {
+                    added_sdps.push(sdp);
+                }
             }
         }
 
@@ -1345,7 +1484,73 @@ where
         };
 
         let mut webrtc = self.webrtc.lock()?;
-        self.update_and_send_rtp_data_message(&mut webrtc, move |data| data.hangup = Some(hangup))
+        self.update_and_send_rtp_data_message(&mut webrtc, |data| data.hangup = Some(hangup))?;
+        self.send_reliable_rtp_data_message(&mut webrtc, move |data| data.hangup = Some(hangup))
+    }
+
+    /// Send the progress of escalating to a call link to the remote peer via RTP data.
+    pub fn send_escalation_via_rtp_data(&self, escalation: signaling::Escalation) -> Result<()> {
+        ringbench!(
+            RingBench::Conn,
+            RingBench::WebRtc,
+            format!("dc(escalation/{:?})\t{}", escalation, self.connection_id)
+        );
+
+        let escalation = protobuf::rtp_data::Escalation {
+            id: Some(u64::from(self.call_id)),
+            root_key: Some(escalation.root_key.to_vec()),
+            joined: Some(escalation.joined),
+        };
+
+        let mut webrtc = self.webrtc.lock()?;
+        self.update_and_send_rtp_data_message(&mut webrtc, |data| {
+            data.escalation = Some(escalation.clone())
+        })?;
+        self.send_reliable_rtp_data_message(&mut webrtc, move |data| {
+            data.escalation = Some(escalation)
+        })
+    }
+
+    /// Send a video upgrade request or response to the remote peer via RTP data.
+    ///
+    /// Accepting a request also enables video in the sender status, in the
+    /// same message.
+    pub fn send_video_upgrade_via_rtp_data(&self, upgrade: signaling::VideoUpgrade) -> Result<()> {
+        ringbench!(
+            RingBench::Conn,
+            RingBench::WebRtc,
+            format!("dc(video_upgrade/{:?})\t{}", upgrade, self.connection_id)
+        );
+
+        use protobuf::rtp_data::video_upgrade::Type;
+        let upgrade_type = match upgrade.upgrade_type {
+            signaling::VideoUpgradeType::Request => Type::VideoUpgradeRequest,
+            signaling::VideoUpgradeType::Accept => Type::VideoUpgradeAccept,
+            signaling::VideoUpgradeType::Decline => Type::VideoUpgradeDecline,
+            signaling::VideoUpgradeType::Cancel => Type::VideoUpgradeCancel,
+        };
+        let video_upgrade = protobuf::rtp_data::VideoUpgrade {
+            id: Some(u64::from(self.call_id)),
+            r#type: Some(upgrade_type.into()),
+            request_id: Some(upgrade.request_id),
+        };
+
+        let mut webrtc = self.webrtc.lock()?;
+        self.update_and_send_rtp_data_message(&mut webrtc, |data| {
+            data.video_upgrade = Some(video_upgrade);
+            if upgrade.upgrade_type == signaling::VideoUpgradeType::Accept {
+                self.merge_sender_status(
+                    data,
+                    signaling::SenderStatus {
+                        video_enabled: Some(true),
+                        ..Default::default()
+                    },
+                );
+            }
+        })?;
+        self.send_reliable_rtp_data_message(&mut webrtc, move |data| {
+            data.video_upgrade = Some(video_upgrade)
+        })
     }
 
     /// Send an accepted message to the remote peer via RTP data.
@@ -1361,9 +1566,8 @@ where
         };
 
         let mut webrtc = self.webrtc.lock()?;
-        self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
-            data.accepted = Some(accepted)
-        })
+        self.update_and_send_rtp_data_message(&mut webrtc, |data| data.accepted = Some(accepted))?;
+        self.send_reliable_rtp_data_message(&mut webrtc, move |data| data.accepted = Some(accepted))
     }
 
     fn update_bandwidth_controller(
@@ -1408,22 +1612,51 @@ where
     pub fn update_sender_status_from_fsm(&self, updated: signaling::SenderStatus) -> Result<()> {
         let mut webrtc = self.webrtc.lock()?;
         self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
-            let previous = data.sender_status.as_ref();
-            let previous_video_enabled =
-                previous.and_then(|sender_status| sender_status.video_enabled);
-            let previous_sharing_screen =
-                previous.and_then(|sender_status| sender_status.sharing_screen);
-            let previous_audio_enabled =
-                previous.and_then(|sender_status| sender_status.audio_enabled);
-            data.sender_status = Some(protobuf::rtp_data::SenderStatus {
-                id: Some(u64::from(self.call_id)),
-                video_enabled: updated.video_enabled.or(previous_video_enabled),
-                sharing_screen: updated.sharing_screen.or(previous_sharing_screen),
-                audio_enabled: updated.audio_enabled.or(previous_audio_enabled),
-            });
+            self.merge_sender_status(data, updated)
         })
     }
 
+    /// Merges the updated fields into the sender status of the message.
+    fn merge_sender_status(
+        &self,
+        data: &mut protobuf::rtp_data::Message,
+        updated: signaling::SenderStatus,
+    ) {
+        let previous = data.sender_status.as_ref();
+        let previous_video_enabled = previous.and_then(|sender_status| sender_status.video_enabled);
+        let previous_sharing_screen =
+            previous.and_then(|sender_status| sender_status.sharing_screen);
+        let previous_audio_enabled = previous.and_then(|sender_status| sender_status.audio_enabled);
+        let previous_held = previous.and_then(|sender_status| sender_status.held);
+        let previous_device_status = previous.and_then(|sender_status| sender_status.device_status);
+        let device_status = match updated.device_status {
+            None => previous_device_status,
+            Some(updated) => {
+                let previous_microphone_disconnected = previous_device_status
+                    .and_then(|device_status| device_status.microphone_disconnected);
+                let previous_backgrounded =
+                    previous_device_status.and_then(|device_status| device_status.backgrounded);
+                let previous_camera_switching =
+                    previous_device_status.and_then(|device_status| device_status.camera_switching);
+                Some(protobuf::rtp_data::DeviceStatus {
+                    microphone_disconnected: updated
+                        .microphone_disconnected
+                        .or(previous_microphone_disconnected),
+                    backgrounded: updated.backgrounded.or(previous_backgrounded),
+                    camera_switching: updated.camera_switching.or(previous_camera_switching),
+                })
+            }
+        };
+        data.sender_status = Some(protobuf::rtp_data::SenderStatus {
+            id: Some(u64::from(self.call_id)),
+            video_enabled: updated.video_enabled.or(previous_video_enabled),
+            sharing_screen: updated.sharing_screen.or(previous_sharing_screen),
+            audio_enabled: updated.audio_enabled.or(previous_audio_enabled),
+            held: updated.held.or(previous_held),
+            device_status,
+        });
+    }
+
     /// Populates a message using the supplied closure and sends it via RTP data.
     fn update_and_send_rtp_data_message<F>(
         &self,
@@ -1440,7 +1673,10 @@ where
             state.seqnum = Some(state.seqnum.unwrap_or(0) + 1);
             state
         };
-        info!("Sending RTP data message: {:?}", message);
+        info!(
+            "Sending RTP data message: {:?}",
+            redact_rtp_data_message(&message)
+        );
         self.send_via_rtp_data(webrtc_data, &message)
     }
 
@@ -1458,6 +1694,83 @@ where
         }
     }
 
+    /// Populates a standalone message using the supplied closure and sends it
+    /// reliably via RTP data.
+    ///
+    /// This should follow an update of the latest state with the same fields.
+    /// The message shares that update's seqnum, so a receiver handles whichever
+    /// copy arrives first and drops the other one.
+    fn send_reliable_rtp_data_message<F>(
+        &self,
+        webrtc_data: &mut std::sync::MutexGuard<WebRtcData<T>>,
+        populate: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&mut protobuf::rtp_data::Message),
+    {
+        let mut message = protobuf::rtp_data::Message {
+            seqnum: self.accumulated_rtp_data_message.lock()?.seqnum,
+            ..Default::default()
+        };
+        populate(&mut message);
+
+        let mut reliable = self.reliable_rtp_data.lock()?;
+        if let Err(err) = reliable.stream.try_send(|header| {
+            message.mrp_header = Some(header.into());
+            self.send_via_rtp_data(webrtc_data, &message)?;
+            Ok((message, Instant::now() + RELIABLE_RTP_DATA_RESEND_INTERVAL))
+        }) {
+            // The latest state has already been sent, so the message isn't lost
+            // unless that is lost too.
+            warn!("Could not send reliable RTP data message: {:?}", err);
+        }
+        Ok(())
+    }
+
+    /// Acknowledges received reliable messages and resends our own messages
+    /// that haven't been acknowledged in time.
+    fn send_reliable_rtp_data_acks_and_resends(
+        &self,
+        webrtc_data: &mut std::sync::MutexGuard<WebRtcData<T>>,
+    ) -> Result<()> {
+        let mut reliable = self.reliable_rtp_data.lock()?;
+        let ReliableRtpData {
+            stream,
+            remote_supports_mrp,
+            unconfirmed_resends,
+        } = &mut *reliable;
+
+        if let Err(err) = stream.try_send_ack(|header| {
+            let ack = protobuf::rtp_data::Message {
+                mrp_header: Some(header.into()),
+                ..Default::default()
+            };
+            self.send_via_rtp_data(webrtc_data, &ack)
+        }) {
+            warn!("Failed to send reliable RTP data ack: {:?}", err);
+        }
+
+        // The first message may well be ours (i.e. the callee's accepted), so
+        // resend it even before we know that the remote peer will ack.
+        if *remote_supports_mrp || *unconfirmed_resends < RELIABLE_RTP_DATA_MAX_UNCONFIRMED_RESENDS
+        {
+            if let Err(err) = stream.try_resend(Instant::now(), |message| {
+                info!(
+                    "Resending reliable RTP data message: {:?}",
+                    redact_rtp_data_message(message)
+                );
+                self.send_via_rtp_data(webrtc_data, message)?;
+                if !*remote_supports_mrp {
+                    *unconfirmed_resends += 1;
+                }
+                Ok(Instant::now() + RELIABLE_RTP_DATA_RESEND_INTERVAL)
+            }) {
+                warn!("Failed to resend reliable RTP data message: {:?}", err);
+            }
+        }
+        Ok(())
+    }
+
     /// Send data via RTP data.
     fn send_via_rtp_data(
         &self,
@@ -1567,6 +1880,43 @@ where
         call.connect_incoming_media(incoming_media)
     }
 
+    /// Stop media flowing through the connection, such as when the call has
+    /// moved to a different connection.
+    ///
+    /// Audio playout and recording are left alone since the call is still
+    /// using them.
+    pub fn disable_media(&self) -> Result<()> {
+        info!("disable_media(): id: {}", self.connection_id);
+
+        let webrtc = self.webrtc.lock()?;
+        let pc = webrtc.peer_connection()?;
+        pc.set_incoming_media_enabled(false);
+        pc.set_outgoing_media_enabled(false);
+        Ok(())
+    }
+
+    /// Put the connection on hold (or take it off hold). While held, no
+    /// media is sent or received, but RTP data keeps flowing so that the
+    /// remote side is told about the hold via the sender status.
+    ///
+    /// Audio playout and recording are left alone since they are shared
+    /// with any other call that is in progress.
+    pub fn set_held(&mut self, held: bool) -> Result<()> {
+        info!("set_held(): id: {}, held: {}", self.connection_id, held);
+
+        {
+            let webrtc = self.webrtc.lock()?;
+            let pc = webrtc.peer_connection()?;
+            pc.set_incoming_media_enabled(!held);
+            pc.set_outgoing_media_enabled(!held);
+        }
+
+        self.update_sender_status(signaling::SenderStatus {
+            held: Some(held),
+            ..Default::default()
+        })
+    }
+
     /// Send a ConnectionEvent to the internal FSM.
     fn inject_event(&mut self, event: ConnectionEvent) -> Result<()> {
         self.fsm_sender
@@ -1708,6 +2058,19 @@ where
             return Ok(());
         }
 
+        // For relay-only, the PeerConnection's ICE transport policy already
+        // keeps other candidates from being gathered; this is a second line
+        // of defence.
+        if let Some(reason) = self.call_config.candidate_policy.check(&candidate) {
+            info!(
+                "Local ICE candidate filtered ({}): {}; {}",
+                reason,
+                candidate.to_info_string(),
+                redact_string(sdp_for_logging)
+            );
+            return Ok(());
+        }
+
         if let Some(relay_protocol) = relay_protocol {
             info!(
                 "Local ICE candidate: {}; {}; relay_protocol={:?}",
@@ -1820,7 +2183,7 @@ where
             return;
         }
 
-        let message = match protobuf::rtp_data::Message::decode(bytes) {
+        let mut message = match protobuf::rtp_data::Message::decode(bytes) {
             Ok(v) => v,
             Err(e) => {
                 warn!("unable to parse rx protobuf: {}", e);
@@ -1828,8 +2191,52 @@ where
             }
         };
 
-        debug!("Received RTP data message: {:?}", message);
+        debug!(
+            "Received RTP data message: {:?}",
+            redact_rtp_data_message(&message)
+        );
+
+        if let Some(mrp_header) = message.mrp_header.take() {
+            // We're on the WebRTC network thread, and the reliable stream's lock is held
+            // while sending RTP, which blocks on this thread. So hop off of it first.
+            let mut connection = self.clone();
+            self.tick_context.send(move |_| {
+                connection
+                    .handle_received_reliable_rtp_data_message(&mrp_header, message)
+                    .unwrap_or_else(|e| warn!("unable to handle reliable RTP data: {}", e));
+            });
+        } else {
+            self.handle_received_rtp_data_message(message);
+        }
+    }
 
+    fn handle_received_reliable_rtp_data_message(
+        &mut self,
+        mrp_header: &protobuf::rtp_data::MrpHeader,
+        message: protobuf::rtp_data::Message,
+    ) -> Result<()> {
+        let ready_messages = {
+            let mut reliable = self.reliable_rtp_data.lock()?;
+            reliable.remote_supports_mrp = true;
+            reliable.stream.receive(&mrp_header.into(), message)
+        };
+        match ready_messages {
+            Ok(ready_messages) => {
+                for message in ready_messages {
+                    self.handle_received_rtp_data_message(message);
+                }
+            }
+            err @ Err(MrpReceiveError::ReceiveWindowFull(_)) => {
+                warn!(
+                    "Error when receiving reliable RTP data message, discarding. {:?}",
+                    err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_received_rtp_data_message(&mut self, message: protobuf::rtp_data::Message) {
         let mut message_handled = false;
         if let Some(accepted) = message.accepted {
             if let CallDirection::Outgoing = self.direction() {
@@ -1847,6 +2254,41 @@ where
             };
             message_handled = true;
         };
+        if let (Some(escalation), Some(seqnum)) = (&message.escalation, message.seqnum) {
+            match <[u8; 16]>::try_from(escalation.root_key()) {
+                Ok(root_key) => self
+                    .inject_received_escalation_via_rtp_data(
+                        CallId::new(escalation.id()),
+                        signaling::Escalation {
+                            root_key,
+                            joined: escalation.joined(),
+                        },
+                        seqnum,
+                    )
+                    .unwrap_or_else(|e| warn!("unable to inject remote escalation event: {}", e)),
+                Err(_) => warn!("Invalid root key in escalation message"),
+            }
+            message_handled = true;
+        };
+        if let (Some(video_upgrade), Some(seqnum)) = (&message.video_upgrade, message.seqnum) {
+            use protobuf::rtp_data::video_upgrade::Type;
+            let upgrade_type = match video_upgrade.r#type() {
+                Type::VideoUpgradeRequest => signaling::VideoUpgradeType::Request,
+                Type::VideoUpgradeAccept => signaling::VideoUpgradeType::Accept,
+                Type::VideoUpgradeDecline => signaling::VideoUpgradeType::Decline,
+                Type::VideoUpgradeCancel => signaling::VideoUpgradeType::Cancel,
+            };
+            self.inject_received_video_upgrade_via_rtp_data(
+                CallId::new(video_upgrade.id()),
+                signaling::VideoUpgrade {
+                    request_id: video_upgrade.request_id(),
+                    upgrade_type,
+                },
+                seqnum,
+            )
+            .unwrap_or_else(|e| warn!("unable to inject remote video upgrade event: {}", e));
+            message_handled = true;
+        };
         if let Some(hangup) = message.hangup {
             self.inject_received_hangup(
                 CallId::new(hangup.id()),
@@ -1866,6 +2308,14 @@ where
                     video_enabled: sender_status.video_enabled,
                     sharing_screen: sender_status.sharing_screen,
                     audio_enabled: sender_status.audio_enabled,
+                    held: sender_status.held,
+                    device_status: sender_status.device_status.map(|device_status| {
+                        signaling::DeviceStatus {
+                            microphone_disconnected: device_status.microphone_disconnected,
+                            backgrounded: device_status.backgrounded,
+                            camera_switching: device_status.camera_switching,
+                        }
+                    }),
                 },
                 seqnum,
             )
@@ -1882,7 +2332,10 @@ where
             message_handled = true;
         };
         if !message_handled {
-            info!("Unhandled RTP data message: {:?}", message);
+            info!(
+                "Unhandled RTP data message: {:?}",
+                redact_rtp_data_message(&message)
+            );
         }
     }
 
@@ -1927,6 +2380,44 @@ where
         ))
     }
 
+    /// Inject a `ReceivedEscalationViaRtpData` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `escalation` - The escalation progress of the remote peer.
+    pub fn inject_received_escalation_via_rtp_data(
+        &mut self,
+        call_id: CallId,
+        escalation: signaling::Escalation,
+        seqnum: u64,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedEscalationViaRtpData(
+            call_id, escalation, seqnum,
+        ))
+    }
+
+    /// Inject a `ReceivedVideoUpgradeViaRtpData` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `upgrade` - The video upgrade request or response of the remote peer.
+    pub fn inject_received_video_upgrade_via_rtp_data(
+        &mut self,
+        call_id: CallId,
+        upgrade: signaling::VideoUpgrade,
+        seqnum: u64,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedVideoUpgradeViaRtpData(
+            call_id, upgrade, seqnum,
+        ))
+    }
+
     /// Inject a `ReceivedReceiverStatusViaRtpData` event into the FSM.
     ///
     /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
@@ -1971,6 +2462,34 @@ where
         self.inject_event(ConnectionEvent::UpdateSenderStatus(status))
     }
 
+    /// Inject a `RequestVideoUpgrade` event into the FSM.
+    ///
+    /// `Called By:` Call manager.
+    pub fn request_video_upgrade(&mut self) -> Result<()> {
+        self.inject_event(ConnectionEvent::RequestVideoUpgrade)
+    }
+
+    /// Inject a `RespondToVideoUpgrade` event into the FSM.
+    ///
+    /// `Called By:` Call manager.
+    ///
+    /// * `accept` - Whether to accept the remote peer's video upgrade request.
+    pub fn respond_to_video_upgrade(&mut self, accept: bool) -> Result<()> {
+        self.inject_event(ConnectionEvent::RespondToVideoUpgrade(accept))
+    }
+
+    /// Inject a `VideoUpgradeTimeout` event into the FSM.
+    ///
+    /// `Called By:` Connection FSM timer.
+    pub fn inject_video_upgrade_timeout(&mut self, request_id: u32) -> Result<()> {
+        self.inject_event(ConnectionEvent::VideoUpgradeTimeout(request_id))
+    }
+
+    /// How long the remote peer has to answer a video upgrade request.
+    pub fn video_upgrade_timeout(&self) -> Duration {
+        self.call_config.video_upgrade_timeout
+    }
+
     /// Inject a `UpdateDataMode` event into the FSM.
     ///
     /// `Called By:` Local application.
@@ -2075,6 +2594,23 @@ where
             .unwrap()
             .sender_status
     }
+
+    #[cfg(feature = "sim")]
+    pub fn last_sent_escalation(&self) -> Option<protobuf::rtp_data::Escalation> {
+        self.accumulated_rtp_data_message
+            .lock()
+            .unwrap()
+            .escalation
+            .clone()
+    }
+
+    #[cfg(feature = "sim")]
+    pub fn last_sent_video_upgrade(&self) -> Option<protobuf::rtp_data::VideoUpgrade> {
+        self.accumulated_rtp_data_message
+            .lock()
+            .unwrap()
+            .video_upgrade
+    }
 }
 
 #[cfg(feature = "sim")]
@@ -2187,12 +2723,59 @@ where
     }
 }
 
+/// Returns a copy of the message that is safe to log, without the call link root key.
+fn redact_rtp_data_message(message: &protobuf::rtp_data::Message) -> protobuf::rtp_data::Message {
+    let mut message = message.clone();
+    if let Some(escalation) = message.escalation.as_mut() {
+        escalation.root_key = None;
+    }
+    message
+}
+
 fn generate_local_secret_and_public_key() -> Result<(StaticSecret, PublicKey)> {
     let secret = StaticSecret::random_from_rng(OsRng);
     let public = PublicKey::from(&secret);
     Ok((secret, public))
 }
 
+type KemDecapsulationKey = <MlKem768 as KemCore>::DecapsulationKey;
+type KemEncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+
+/// The secrets an outgoing parent keeps so that it can negotiate SRTP keys
+/// with each answer it receives.
+pub struct OfferSecrets {
+    dh_secret: StaticSecret,
+    // Only used if the answer is V5.
+    kem_decapsulation_key: KemDecapsulationKey,
+}
+
+/// Returns (ciphertext, shared_secret).
+fn encapsulate_kem_shared_secret(
+    remote_encapsulation_key: Option<&[u8]>,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let remote_encapsulation_key = remote_encapsulation_key
+        .and_then(|key| Encoded::<KemEncapsulationKey>::try_from(key).ok())
+        .ok_or(RingRtcError::SrtpKeyNegotiationFailure)?;
+    let remote_encapsulation_key = KemEncapsulationKey::from_bytes(&remote_encapsulation_key);
+    let (ciphertext, shared_secret) = remote_encapsulation_key
+        .encapsulate(&mut OsRng)
+        .map_err(|_| RingRtcError::SrtpKeyNegotiationFailure)?;
+    Ok((ciphertext.to_vec(), shared_secret.to_vec()))
+}
+
+fn decapsulate_kem_shared_secret(
+    local_decapsulation_key: &KemDecapsulationKey,
+    remote_ciphertext: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    let remote_ciphertext = remote_ciphertext
+        .and_then(|ciphertext| Ciphertext::<MlKem768>::try_from(ciphertext).ok())
+        .ok_or(RingRtcError::SrtpKeyNegotiationFailure)?;
+    let shared_secret = local_decapsulation_key
+        .decapsulate(&remote_ciphertext)
+        .map_err(|_| RingRtcError::SrtpKeyNegotiationFailure)?;
+    Ok(shared_secret.to_vec())
+}
+
 struct NegotiatedSrtpKeys {
     pub offer_key: SrtpKey,
     pub answer_key: SrtpKey,
@@ -2201,6 +2784,8 @@ struct NegotiatedSrtpKeys {
 fn negotiate_srtp_keys(
     local_secret: &StaticSecret,
     remote_public_key: &[u8],
+    // Present for V5 and absent for V4.
+    kem_shared_secret: Option<&[u8]>,
     caller_identity_key: &[u8],
     callee_identity_key: &[u8],
 ) -> Result<NegotiatedSrtpKeys> {
@@ -2215,15 +2800,26 @@ fn negotiate_srtp_keys(
 
     let shared_secret = local_secret.diffie_hellman(&remote_public_key);
 
+    // V5 derives the keys from both shared secrets so they stay secret as long as either does.
+    let (hkdf_info_prefix, hkdf_ikm) = match kem_shared_secret {
+        None => (
+            "Signal_Calling_20200807_SignallingDH_SRTPKey_KDF",
+            shared_secret.as_bytes().to_vec(),
+        ),
+        Some(kem_shared_secret) => (
+            "Signal_Calling_20261018_SignallingDH_MLKEM768_SRTPKey_KDF",
+            [shared_secret.as_bytes(), kem_shared_secret].concat(),
+        ),
+    };
+
     let hkdf_salt = vec![0u8; 32];
-    let hkdf_info_prefix = "Signal_Calling_20200807_SignallingDH_SRTPKey_KDF";
     let mut hkdf_info = Vec::with_capacity(
         hkdf_info_prefix.len() + caller_identity_key.len() + callee_identity_key.len(),
     );
     hkdf_info.extend_from_slice(hkdf_info_prefix.as_bytes());
     hkdf_info.extend_from_slice(caller_identity_key);
     hkdf_info.extend_from_slice(callee_identity_key);
-    let hkdf = Hkdf::<Sha256>::new(Some(&hkdf_salt), shared_secret.as_bytes());
+    let hkdf = Hkdf::<Sha256>::new(Some(&hkdf_salt), &hkdf_ikm);
 
     const SUITE: SrtpCryptoSuite = SrtpCryptoSuite::AeadAes256Gcm;
     const KEY_SIZE: usize = SUITE.key_size();
@@ -2304,4 +2900,58 @@ mod tests {
         assert_eq!(expect(300_000), compute(Low, 1_000_000, true));
         assert_eq!(expect(300_000), compute(Low, 300_000, true));
     }
+
+    #[test]
+    fn negotiate_srtp_keys_v4_and_v5() {
+        let caller_identity_key = b"caller";
+        let callee_identity_key = b"callee";
+        let (caller_secret, caller_public_key) = generate_local_secret_and_public_key().unwrap();
+        let (callee_secret, callee_public_key) = generate_local_secret_and_public_key().unwrap();
+        let (kem_decapsulation_key, kem_encapsulation_key) = MlKem768::generate(&mut OsRng);
+
+        let (kem_ciphertext, callee_kem_shared_secret) =
+            encapsulate_kem_shared_secret(Some(&kem_encapsulation_key.as_bytes())).unwrap();
+        let caller_kem_shared_secret =
+            decapsulate_kem_shared_secret(&kem_decapsulation_key, Some(&kem_ciphertext)).unwrap();
+        assert_eq!(caller_kem_shared_secret, callee_kem_shared_secret);
+
+        let negotiate = |local_secret, remote_public_key: &PublicKey, kem_shared_secret| {
+            negotiate_srtp_keys(
+                local_secret,
+                remote_public_key.as_bytes(),
+                kem_shared_secret,
+                caller_identity_key,
+                callee_identity_key,
+            )
+            .unwrap()
+        };
+        let caller_v4 = negotiate(&caller_secret, &callee_public_key, None);
+        let callee_v4 = negotiate(&callee_secret, &caller_public_key, None);
+        let caller_v5 = negotiate(
+            &caller_secret,
+            &callee_public_key,
+            Some(&caller_kem_shared_secret),
+        );
+        let callee_v5 = negotiate(
+            &callee_secret,
+            &caller_public_key,
+            Some(&callee_kem_shared_secret),
+        );
+
+        assert_eq!(caller_v4.offer_key, callee_v4.offer_key);
+        assert_eq!(caller_v4.answer_key, callee_v4.answer_key);
+        assert_eq!(caller_v5.offer_key, callee_v5.offer_key);
+        assert_eq!(caller_v5.answer_key, callee_v5.answer_key);
+        assert_ne!(caller_v4.offer_key, caller_v5.offer_key);
+        assert_ne!(caller_v4.answer_key, caller_v5.answer_key);
+
+        // Malformed or missing KEM values fail rather than falling back to V4.
+        assert!(encapsulate_kem_shared_secret(None).is_err());
+        assert!(encapsulate_kem_shared_secret(Some(&[1, 2, 3])).is_err());
+        assert!(decapsulate_kem_shared_secret(&kem_decapsulation_key, None).is_err());
+        assert!(
+            decapsulate_kem_shared_secret(&kem_decapsulation_key, Some(&kem_ciphertext[1..]))
+                .is_err()
+        );
+    }
 }
@@ -20,6 +20,8 @@
 //! - AnswerCall
 //! - LocalHangup
 //! - UpdateSenderStatus
+//! - RequestVideoUpgrade
+//! - RespondToVideoUpgrade
 //! - SendReceiverStatusViaRtpData
 //! - SendBusy
 //! - ReceivedIce
@@ -34,6 +36,8 @@
 //! - ReceivedIncomingMedia
 //! - ReceivedAcceptedViaRtpData
 //! - ReceivedSenderStatusViaRtpData
+//! - ReceivedEscalationViaRtpData
+//! - ReceivedVideoUpgradeViaRtpData
 //! - ReceivedReceiverStatusViaRtpData
 //! - ReceivedHangup
 //!
@@ -87,6 +91,14 @@ pub enum ConnectionEvent {
     /// Source: RTP data
     /// Action: Bubble up to app, which should change the "in call" screen.
     ReceivedSenderStatusViaRtpData(CallId, signaling::SenderStatus, u64),
+    /// Receive escalation progress from remote peer.
+    /// Source: RTP data
+    /// Action: Bubble up to the CallManager, which joins or completes the escalation.
+    ReceivedEscalationViaRtpData(CallId, signaling::Escalation, u64),
+    /// Receive a video upgrade request or response from remote peer.
+    /// Source: RTP data
+    /// Action: Track the pending request and bubble up to app.
+    ReceivedVideoUpgradeViaRtpData(CallId, signaling::VideoUpgrade, u64),
     /// Receive receiver status change from remote peer.
     /// Source: RTP data
     /// Action: Make adjustments in connection if necessary.
@@ -95,6 +107,18 @@ pub enum ConnectionEvent {
     /// Source: app (user action)
     /// Action: Accumulate and send a sender status message via RTP data.
     UpdateSenderStatus(signaling::SenderStatus),
+    /// Ask the remote peer to turn the call into a video call.
+    /// Source: app (user action)
+    /// Action: Send a video upgrade request via RTP data and wait for the answer.
+    RequestVideoUpgrade,
+    /// Answer the pending video upgrade request of the remote peer.
+    /// Source: app (user action)
+    /// Action: Send the answer via RTP data, enabling video if accepted.
+    RespondToVideoUpgrade(bool),
+    /// The remote peer didn't answer a video upgrade request in time.
+    /// Source: timer
+    /// Action: Cancel the request and bubble up to app.
+    VideoUpgradeTimeout(u32),
     /// Set data mode
     /// Source: app (user setting)
     /// Action: Update and send bitrate via a receiver status message via RTP data.
@@ -153,6 +177,18 @@ impl fmt::Display for ConnectionEvent {
                     id, status, seqnum
                 )
             }
+            ConnectionEvent::ReceivedEscalationViaRtpData(id, escalation, seqnum) => {
+                format!(
+                    "ReceivedEscalationViaRtpData, call_id: {}, escalation: {:?}, seqnum: {:?}",
+                    id, escalation, seqnum
+                )
+            }
+            ConnectionEvent::ReceivedVideoUpgradeViaRtpData(id, upgrade, seqnum) => {
+                format!(
+                    "ReceivedVideoUpgradeViaRtpData, call_id: {}, upgrade: {:?}, seqnum: {:?}",
+                    id, upgrade, seqnum
+                )
+            }
             ConnectionEvent::ReceivedReceiverStatusViaRtpData(id, max_bitrate, seqnum) => {
                 format!(
                     "ReceivedReceiverStatusViaRtpData, call_id: {}, max_bitrate: {:?}, seqnum: {:?}",
@@ -166,6 +202,13 @@ impl fmt::Display for ConnectionEvent {
             ConnectionEvent::UpdateSenderStatus(status) => {
                 format!("UpdateSenderStatus, status: {:?}", status)
             }
+            ConnectionEvent::RequestVideoUpgrade => "RequestVideoUpgrade".to_string(),
+            ConnectionEvent::RespondToVideoUpgrade(accept) => {
+                format!("RespondToVideoUpgrade, accept: {}", accept)
+            }
+            ConnectionEvent::VideoUpgradeTimeout(request_id) => {
+                format!("VideoUpgradeTimeout, request_id: {}", request_id)
+            }
             ConnectionEvent::UpdateDataMode(mode) => {
                 format!("UpdateDataMode, mode: {:?}", mode)
             }
@@ -192,6 +235,15 @@ impl fmt::Debug for ConnectionEvent {
     }
 }
 
+/// A video upgrade request that hasn't been answered yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PendingVideoUpgrade {
+    /// The local side asked the remote side to turn on video.
+    Local(u32),
+    /// The remote side asked the local side to turn on video.
+    Remote(u32),
+}
+
 /// ConnectionStateMachine Object.
 ///
 /// The ConnectionStateMachine object consumes incoming ConnectionEvents and
@@ -220,6 +272,16 @@ where
     /// We process remote sender status messages larger than the seqnum
     /// and fire events when the status changes.
     last_remote_sender_status: Option<(u64, signaling::SenderStatus)>,
+    /// The sequence number and last received remote escalation, handled
+    /// the same way as the remote sender status.
+    last_remote_escalation: Option<(u64, signaling::Escalation)>,
+    /// The sequence number and last received remote video upgrade message,
+    /// handled the same way as the remote sender status.
+    last_remote_video_upgrade: Option<(u64, signaling::VideoUpgrade)>,
+    /// The video upgrade request waiting for an answer, if any.
+    pending_video_upgrade: Option<PendingVideoUpgrade>,
+    /// The ID of the next local video upgrade request.
+    next_video_upgrade_request_id: u32,
     /// The sequence number of the last received remote receiver bitrate.
     /// We process remote receiver status messages larger than the seqnum
     /// and use the bitrate when it changes.
@@ -246,6 +308,10 @@ where
             worker_thread: Actor::start("connection-fsm-worker", Stopper::new(), |_| Ok(()))?,
             notify_thread: Actor::start("connection-fsm-notify", Stopper::new(), |_| Ok(()))?,
             last_remote_sender_status: None,
+            last_remote_escalation: None,
+            last_remote_video_upgrade: None,
+            pending_video_upgrade: None,
+            next_video_upgrade_request_id: 1,
             last_remote_receiver_status: None,
         })
     }
@@ -264,6 +330,14 @@ where
                     ConnectionState::ConnectedAndAccepted,
                     ConnectionEvent::ReceivedSenderStatusViaRtpData(_, _, _),
                 )
+                | (
+                    ConnectionState::ConnectedAndAccepted,
+                    ConnectionEvent::ReceivedEscalationViaRtpData(_, _, _),
+                )
+                | (
+                    ConnectionState::ConnectedAndAccepted,
+                    ConnectionEvent::ReceivedVideoUpgradeViaRtpData(_, _, _),
+                )
                 | (
                     ConnectionState::ConnectedAndAccepted,
                     ConnectionEvent::ReceivedReceiverStatusViaRtpData(_, _, _),
@@ -304,6 +378,16 @@ where
         }
     }
 
+    /// Spawn a task on the worker thread after a delay if it is still running.
+    fn worker_spawn_delayed<F>(&mut self, delay: Duration, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if !self.worker_thread.stopper().has_been_stopped() {
+            self.worker_thread.send_delayed(delay, move |_| f());
+        }
+    }
+
     /// Spawn a task on the notify thread if it is still running.
     fn notify_spawn<F>(&mut self, f: F)
     where
@@ -361,6 +445,10 @@ where
             }
             ConnectionEvent::ReceivedSenderStatusViaRtpData(id, status, seqnum) => self
                 .handle_received_sender_status_via_rtp_data(connection, state, id, status, seqnum),
+            ConnectionEvent::ReceivedEscalationViaRtpData(id, escalation, seqnum) => self
+                .handle_received_escalation_via_rtp_data(connection, state, id, escalation, seqnum),
+            ConnectionEvent::ReceivedVideoUpgradeViaRtpData(id, upgrade, seqnum) => self
+                .handle_received_video_upgrade_via_rtp_data(connection, state, id, upgrade, seqnum),
             ConnectionEvent::ReceivedReceiverStatusViaRtpData(id, max_bitrate, seqnum) => self
                 .handle_received_receiver_status_via_rtp_data(
                     connection,
@@ -373,6 +461,15 @@ where
             ConnectionEvent::UpdateSenderStatus(status) => {
                 self.handle_update_sender_status(connection, state, status)
             }
+            ConnectionEvent::RequestVideoUpgrade => {
+                self.handle_request_video_upgrade(connection, state)
+            }
+            ConnectionEvent::RespondToVideoUpgrade(accept) => {
+                self.handle_respond_to_video_upgrade(connection, state, accept)
+            }
+            ConnectionEvent::VideoUpgradeTimeout(request_id) => {
+                self.handle_video_upgrade_timeout(connection, request_id)
+            }
             ConnectionEvent::UpdateDataMode(mode) => {
                 self.handle_update_data_mode(connection, state, mode)
             }
@@ -602,6 +699,175 @@ where
         Ok(())
     }
 
+    fn handle_received_escalation_via_rtp_data(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        call_id: CallId,
+        escalation: signaling::Escalation,
+        seqnum: u64,
+    ) -> Result<()> {
+        debug!(
+            "handle_received_escalation_via_rtp_data(): escalation: {:?}, seqnum: {:?}",
+            escalation, seqnum
+        );
+
+        if connection.call_id() != call_id {
+            warn!("Remote escalation for non-active call");
+            return Ok(());
+        }
+
+        let changed = match self.last_remote_escalation {
+            None => true,
+            Some((last_seqnum, last_escalation)) => {
+                if seqnum <= last_seqnum {
+                    // Ignore retransmits and out of order messages.
+                    return Ok(());
+                }
+                last_escalation != escalation
+            }
+        };
+        self.last_remote_escalation = Some((seqnum, escalation));
+
+        match state {
+            ConnectionState::ConnectedAndAccepted | ConnectionState::ReconnectingAfterAccepted => {
+                if changed {
+                    self.notify_observer(
+                        connection,
+                        ConnectionObserverEvent::RemoteEscalationChanged(escalation),
+                    );
+                }
+            }
+            ConnectionState::ConnectingBeforeAccepted
+            | ConnectionState::ConnectingAfterAccepted
+            | ConnectionState::ConnectedBeforeAccepted => {
+                // Only an accepted call can be escalated.
+            }
+            ConnectionState::NotYetStarted
+            | ConnectionState::Starting
+            | ConnectionState::IceGathering
+            | ConnectionState::IceFailed
+            | ConnectionState::Terminating
+            | ConnectionState::Terminated => {
+                self.unexpected_state(state, "ReceivedEscalationViaRtpData");
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_received_video_upgrade_via_rtp_data(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        call_id: CallId,
+        upgrade: signaling::VideoUpgrade,
+        seqnum: u64,
+    ) -> Result<()> {
+        debug!(
+            "handle_received_video_upgrade_via_rtp_data(): upgrade: {:?}, seqnum: {:?}",
+            upgrade, seqnum
+        );
+
+        if connection.call_id() != call_id {
+            warn!("Remote video upgrade for non-active call");
+            return Ok(());
+        }
+
+        let changed = match self.last_remote_video_upgrade {
+            None => true,
+            Some((last_seqnum, last_upgrade)) => {
+                if seqnum <= last_seqnum {
+                    // Ignore retransmits and out of order messages.
+                    return Ok(());
+                }
+                last_upgrade != upgrade
+            }
+        };
+        self.last_remote_video_upgrade = Some((seqnum, upgrade));
+
+        match state {
+            ConnectionState::ConnectedAndAccepted | ConnectionState::ReconnectingAfterAccepted => {
+                if changed {
+                    self.handle_remote_video_upgrade_changed(connection, upgrade);
+                }
+            }
+            ConnectionState::ConnectingBeforeAccepted
+            | ConnectionState::ConnectingAfterAccepted
+            | ConnectionState::ConnectedBeforeAccepted => {
+                // Only an accepted call can be upgraded.
+            }
+            ConnectionState::NotYetStarted
+            | ConnectionState::Starting
+            | ConnectionState::IceGathering
+            | ConnectionState::IceFailed
+            | ConnectionState::Terminating
+            | ConnectionState::Terminated => {
+                self.unexpected_state(state, "ReceivedVideoUpgradeViaRtpData");
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_remote_video_upgrade_changed(
+        &mut self,
+        connection: Connection<T>,
+        upgrade: signaling::VideoUpgrade,
+    ) {
+        use signaling::VideoUpgradeType;
+
+        let request_id = upgrade.request_id;
+        match (upgrade.upgrade_type, self.pending_video_upgrade) {
+            (VideoUpgradeType::Request, Some(PendingVideoUpgrade::Local(_))) => {
+                // Both sides asked at the same time, so each side takes the
+                // other's request as accepting its own.
+                self.answer_video_upgrade(connection.clone(), request_id, true);
+                self.notify_observer(
+                    connection,
+                    ConnectionObserverEvent::RemoteVideoUpgrade(VideoUpgradeType::Accept),
+                );
+            }
+            (VideoUpgradeType::Request, _) => {
+                self.pending_video_upgrade = Some(PendingVideoUpgrade::Remote(request_id));
+                self.notify_observer(
+                    connection,
+                    ConnectionObserverEvent::RemoteVideoUpgrade(VideoUpgradeType::Request),
+                );
+            }
+            (VideoUpgradeType::Accept, Some(PendingVideoUpgrade::Local(pending_id)))
+                if pending_id == request_id =>
+            {
+                self.pending_video_upgrade = None;
+                self.update_sender_status(
+                    connection.clone(),
+                    signaling::SenderStatus {
+                        video_enabled: Some(true),
+                        ..Default::default()
+                    },
+                );
+                self.notify_observer(
+                    connection,
+                    ConnectionObserverEvent::RemoteVideoUpgrade(VideoUpgradeType::Accept),
+                );
+            }
+            (VideoUpgradeType::Decline, Some(PendingVideoUpgrade::Local(pending_id)))
+            | (VideoUpgradeType::Cancel, Some(PendingVideoUpgrade::Remote(pending_id)))
+                if pending_id == request_id =>
+            {
+                self.pending_video_upgrade = None;
+                self.notify_observer(
+                    connection,
+                    ConnectionObserverEvent::RemoteVideoUpgrade(upgrade.upgrade_type),
+                );
+            }
+            _ => {
+                info!(
+                    "Ignoring remote video upgrade {:?}, pending: {:?}",
+                    upgrade, self.pending_video_upgrade
+                );
+            }
+        }
+    }
+
     fn handle_received_receiver_status_via_rtp_data(
         &mut self,
         connection: Connection<T>,
@@ -715,29 +981,168 @@ where
 
     fn handle_update_sender_status(
         &mut self,
-        mut connection: Connection<T>,
+        connection: Connection<T>,
         state: ConnectionState,
         sender_status: signaling::SenderStatus,
     ) -> Result<()> {
         if state.connected_or_reconnecting() {
             // notify the peer via an RTP data message.
-            self.worker_spawn(move || {
-                let result = try_scoped(|| {
-                    if connection.terminating()? {
-                        return Ok(());
-                    }
-                    connection.update_sender_status_from_fsm(sender_status)
-                });
-                if let Err(err) = result {
-                    connection.inject_internal_error(err, "Sending local sender status failed");
-                }
-            });
+            self.update_sender_status(connection, sender_status);
         } else {
             self.unexpected_state(state, "UpdateSenderStatus");
         };
         Ok(())
     }
 
+    fn handle_request_video_upgrade(
+        &mut self,
+        mut connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        if !matches!(
+            state,
+            ConnectionState::ConnectedAndAccepted | ConnectionState::ReconnectingAfterAccepted
+        ) {
+            self.unexpected_state(state, "RequestVideoUpgrade");
+            return Ok(());
+        }
+
+        match self.pending_video_upgrade {
+            Some(PendingVideoUpgrade::Local(request_id)) => {
+                info!("Video upgrade {} already requested", request_id);
+            }
+            Some(PendingVideoUpgrade::Remote(request_id)) => {
+                // The remote side already asked for the same thing.
+                self.answer_video_upgrade(connection.clone(), request_id, true);
+                self.notify_observer(
+                    connection,
+                    ConnectionObserverEvent::RemoteVideoUpgrade(
+                        signaling::VideoUpgradeType::Accept,
+                    ),
+                );
+            }
+            None => {
+                let request_id = self.next_video_upgrade_request_id;
+                self.next_video_upgrade_request_id = request_id.wrapping_add(1);
+                self.pending_video_upgrade = Some(PendingVideoUpgrade::Local(request_id));
+                self.send_video_upgrade(
+                    connection.clone(),
+                    signaling::VideoUpgrade {
+                        request_id,
+                        upgrade_type: signaling::VideoUpgradeType::Request,
+                    },
+                );
+                let timeout = connection.video_upgrade_timeout();
+                self.worker_spawn_delayed(timeout, move || {
+                    if let Err(err) = connection.inject_video_upgrade_timeout(request_id) {
+                        warn!("Unable to time out video upgrade: {}", err);
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_respond_to_video_upgrade(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        accept: bool,
+    ) -> Result<()> {
+        if !matches!(
+            state,
+            ConnectionState::ConnectedAndAccepted | ConnectionState::ReconnectingAfterAccepted
+        ) {
+            self.unexpected_state(state, "RespondToVideoUpgrade");
+            return Ok(());
+        }
+
+        match self.pending_video_upgrade {
+            Some(PendingVideoUpgrade::Remote(request_id)) => {
+                self.answer_video_upgrade(connection, request_id, accept);
+            }
+            _ => {
+                warn!("No remote video upgrade request to respond to");
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_video_upgrade_timeout(
+        &mut self,
+        connection: Connection<T>,
+        request_id: u32,
+    ) -> Result<()> {
+        if self.pending_video_upgrade != Some(PendingVideoUpgrade::Local(request_id)) {
+            // Already answered.
+            return Ok(());
+        }
+
+        self.pending_video_upgrade = None;
+        self.send_video_upgrade(
+            connection.clone(),
+            signaling::VideoUpgrade {
+                request_id,
+                upgrade_type: signaling::VideoUpgradeType::Cancel,
+            },
+        );
+        self.notify_observer(connection, ConnectionObserverEvent::VideoUpgradeTimedOut);
+        Ok(())
+    }
+
+    /// Answers the remote video upgrade request, which is no longer pending.
+    fn answer_video_upgrade(&mut self, connection: Connection<T>, request_id: u32, accept: bool) {
+        self.pending_video_upgrade = None;
+        let upgrade_type = if accept {
+            signaling::VideoUpgradeType::Accept
+        } else {
+            signaling::VideoUpgradeType::Decline
+        };
+        self.send_video_upgrade(
+            connection,
+            signaling::VideoUpgrade {
+                request_id,
+                upgrade_type,
+            },
+        );
+    }
+
+    fn send_video_upgrade(
+        &mut self,
+        mut connection: Connection<T>,
+        upgrade: signaling::VideoUpgrade,
+    ) {
+        self.worker_spawn(move || {
+            let result = try_scoped(|| {
+                if connection.terminating()? {
+                    return Ok(());
+                }
+                connection.send_video_upgrade_via_rtp_data(upgrade)
+            });
+            if let Err(err) = result {
+                connection.inject_internal_error(err, "Sending video upgrade failed");
+            }
+        });
+    }
+
+    fn update_sender_status(
+        &mut self,
+        mut connection: Connection<T>,
+        sender_status: signaling::SenderStatus,
+    ) {
+        self.worker_spawn(move || {
+            let result = try_scoped(|| {
+                if connection.terminating()? {
+                    return Ok(());
+                }
+                connection.update_sender_status_from_fsm(sender_status)
+            });
+            if let Err(err) = result {
+                connection.inject_internal_error(err, "Sending local sender status failed");
+            }
+        });
+    }
+
     fn handle_update_data_mode(
         &mut self,
         mut connection: Connection<T>,
@@ -5,7 +5,7 @@
 
 use anyhow;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     convert::TryInto,
     hash::{Hash, Hasher},
     iter::FromIterator,
@@ -34,7 +34,11 @@ use crate::{
         units::DataRate,
         DataMode, Result,
     },
-    core::{call_mutex::CallMutex, crypto as frame_crypto, signaling},
+    core::{
+        call_mutex::CallMutex,
+        crypto as frame_crypto, signaling,
+        speaking_stats::{SpeakingStatsTracker, SpeakingSummary},
+    },
     error::RingRtcError,
     lite::{
         http, sfu,
@@ -274,8 +278,14 @@ pub trait Observer {
 
     fn handle_reactions(&self, client_id: ClientId, reactions: Vec<Reaction>);
 
+    fn handle_chat_messages(&self, client_id: ClientId, messages: Vec<ChatMessage>);
+
     fn handle_raised_hands(&self, client_id: ClientId, raised_hands: Vec<DemuxId>);
 
+    // An admin asked the local device to mute its audio. The client doesn't mute
+    // itself; the app is expected to honour the request with set_outgoing_audio_muted.
+    fn handle_remote_mute_request(&self, client_id: ClientId, mute_source: DemuxId);
+
     fn handle_rtc_stats_report(&self, report_json: String);
 
     // This will be the last callback.
@@ -483,11 +493,290 @@ pub enum EndReason {
 
 const ADMIN_LOG_TAG: &str = "AdminAction";
 
+/// Identical reactions received close together are delivered as one, with a count
+/// of how many were received and from which devices.
 #[repr(C)]
 #[derive(Clone, Debug)]
 pub struct Reaction {
+    /// The device that sent the reaction first.
     pub demux_id: DemuxId,
+    /// A single emoji, normalized (see `normalize_reaction`).
     pub value: String,
+    /// How many times the reaction was received, at least 1.
+    pub count: u32,
+    /// The distinct devices that sent the reaction, in the order they were first received.
+    pub sender_demux_ids: Vec<DemuxId>,
+}
+
+/// Limits the reactions sent by a single device: up to `burst` at once, with one more
+/// allowed every `interval`. A zero `interval` means no limit.
+///
+/// Reactions aren't limited unless the application opts in with
+/// `Client::set_reaction_rate_limits`. The default is the recommended limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReactionRateLimit {
+    pub burst: u32,
+    pub interval: Duration,
+}
+
+impl ReactionRateLimit {
+    pub const UNLIMITED: Self = Self {
+        burst: 0,
+        interval: Duration::ZERO,
+    };
+}
+
+impl Default for ReactionRateLimit {
+    fn default() -> Self {
+        Self {
+            burst: 5,
+            interval: Duration::from_millis(500),
+        }
+    }
+}
+
+// A token bucket enforcing a ReactionRateLimit.
+#[derive(Debug)]
+struct ReactionRateLimiter {
+    available: u32,
+    last_refill: Instant,
+}
+
+impl ReactionRateLimiter {
+    fn new(limit: &ReactionRateLimit, now: Instant) -> Self {
+        Self {
+            available: limit.burst,
+            last_refill: now,
+        }
+    }
+
+    fn try_take(&mut self, limit: &ReactionRateLimit, now: Instant) -> bool {
+        if limit.interval.is_zero() {
+            return true;
+        }
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refills =
+            u32::try_from(elapsed.as_nanos() / limit.interval.as_nanos()).unwrap_or(u32::MAX);
+        if self.available.saturating_add(refills) >= limit.burst {
+            self.available = limit.burst;
+            self.last_refill = now;
+        } else {
+            self.available += refills;
+            self.last_refill += limit.interval * refills;
+        }
+        if self.available == 0 {
+            return false;
+        }
+        self.available -= 1;
+        true
+    }
+}
+
+/// Returns the canonical form of a reaction if it is a single emoji, or None if it isn't.
+///
+/// Surrounding whitespace is removed, text presentation selectors are replaced by emoji
+/// presentation selectors, and a lone symbol that defaults to text presentation (such as
+/// U+2764 HEAVY BLACK HEART) gets an emoji presentation selector, so that the same emoji
+/// sent from different platforms compares equal.
+pub fn normalize_reaction(value: &str) -> Option<String> {
+    const TEXT_PRESENTATION: char = '\u{FE0E}';
+    const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+    let mut normalized: String = value
+        .trim()
+        .chars()
+        .map(|c| {
+            if c == TEXT_PRESENTATION {
+                EMOJI_PRESENTATION
+            } else {
+                c
+            }
+        })
+        .collect();
+    if normalized.len() > REACTION_STRING_MAX_SIZE || !emoji::is_single_emoji(&normalized) {
+        return None;
+    }
+    let mut chars = normalized.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if (c as u32) < 0x1F000 {
+            normalized.push(EMOJI_PRESENTATION);
+        }
+    }
+    Some(normalized)
+}
+
+// A small subset of the Unicode emoji sequence grammar (UTS #51), enough to tell a single
+// emoji from text with only the Extended_Pictographic table.
+mod emoji {
+    const ZWJ: char = '\u{200D}';
+    const EMOJI_PRESENTATION: char = '\u{FE0F}';
+    const COMBINING_KEYCAP: char = '\u{20E3}';
+    const CANCEL_TAG: char = '\u{E007F}';
+
+    fn is_regional_indicator(c: char) -> bool {
+        ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+    }
+
+    fn is_skin_tone_modifier(c: char) -> bool {
+        ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+    }
+
+    fn is_tag(c: char) -> bool {
+        ('\u{E0020}'..='\u{E007E}').contains(&c)
+    }
+
+    fn is_keycap_base(c: char) -> bool {
+        c.is_ascii_digit() || c == '#' || c == '*'
+    }
+
+    /// The Extended_Pictographic property from the Unicode emoji data (emoji-data.txt,
+    /// Unicode 15.1), as sorted, inclusive ranges. Regional indicators and skin tone
+    /// modifiers aren't part of it.
+    const EXTENDED_PICTOGRAPHIC: &[(char, char)] = &[
+        ('\u{00A9}', '\u{00A9}'),
+        ('\u{00AE}', '\u{00AE}'),
+        ('\u{203C}', '\u{203C}'),
+        ('\u{2049}', '\u{2049}'),
+        ('\u{2122}', '\u{2122}'),
+        ('\u{2139}', '\u{2139}'),
+        ('\u{2194}', '\u{2199}'),
+        ('\u{21A9}', '\u{21AA}'),
+        ('\u{231A}', '\u{231B}'),
+        ('\u{2328}', '\u{2328}'),
+        ('\u{2388}', '\u{2388}'),
+        ('\u{23CF}', '\u{23CF}'),
+        ('\u{23E9}', '\u{23F3}'),
+        ('\u{23F8}', '\u{23FA}'),
+        ('\u{24C2}', '\u{24C2}'),
+        ('\u{25AA}', '\u{25AB}'),
+        ('\u{25B6}', '\u{25B6}'),
+        ('\u{25C0}', '\u{25C0}'),
+        ('\u{25FB}', '\u{25FE}'),
+        ('\u{2600}', '\u{2605}'),
+        ('\u{2607}', '\u{2612}'),
+        ('\u{2614}', '\u{2685}'),
+        ('\u{2690}', '\u{2705}'),
+        ('\u{2708}', '\u{2712}'),
+        ('\u{2714}', '\u{2714}'),
+        ('\u{2716}', '\u{2716}'),
+        ('\u{271D}', '\u{271D}'),
+        ('\u{2721}', '\u{2721}'),
+        ('\u{2728}', '\u{2728}'),
+        ('\u{2733}', '\u{2734}'),
+        ('\u{2744}', '\u{2744}'),
+        ('\u{2747}', '\u{2747}'),
+        ('\u{274C}', '\u{274C}'),
+        ('\u{274E}', '\u{274E}'),
+        ('\u{2753}', '\u{2755}'),
+        ('\u{2757}', '\u{2757}'),
+        ('\u{2763}', '\u{2767}'),
+        ('\u{2795}', '\u{2797}'),
+        ('\u{27A1}', '\u{27A1}'),
+        ('\u{27B0}', '\u{27B0}'),
+        ('\u{27BF}', '\u{27BF}'),
+        ('\u{2934}', '\u{2935}'),
+        ('\u{2B05}', '\u{2B07}'),
+        ('\u{2B1B}', '\u{2B1C}'),
+        ('\u{2B50}', '\u{2B50}'),
+        ('\u{2B55}', '\u{2B55}'),
+        ('\u{3030}', '\u{3030}'),
+        ('\u{303D}', '\u{303D}'),
+        ('\u{3297}', '\u{3297}'),
+        ('\u{3299}', '\u{3299}'),
+        ('\u{1F000}', '\u{1F0FF}'),
+        ('\u{1F10D}', '\u{1F10F}'),
+        ('\u{1F12F}', '\u{1F12F}'),
+        ('\u{1F16C}', '\u{1F171}'),
+        ('\u{1F17E}', '\u{1F17F}'),
+        ('\u{1F18E}', '\u{1F18E}'),
+        ('\u{1F191}', '\u{1F19A}'),
+        ('\u{1F1AD}', '\u{1F1E5}'),
+        ('\u{1F201}', '\u{1F20F}'),
+        ('\u{1F21A}', '\u{1F21A}'),
+        ('\u{1F22F}', '\u{1F22F}'),
+        ('\u{1F232}', '\u{1F23A}'),
+        ('\u{1F23C}', '\u{1F23F}'),
+        ('\u{1F249}', '\u{1F3FA}'),
+        ('\u{1F400}', '\u{1F53D}'),
+        ('\u{1F546}', '\u{1F64F}'),
+        ('\u{1F680}', '\u{1F6FF}'),
+        ('\u{1F774}', '\u{1F77F}'),
+        ('\u{1F7D5}', '\u{1F7FF}'),
+        ('\u{1F80C}', '\u{1F80F}'),
+        ('\u{1F848}', '\u{1F84F}'),
+        ('\u{1F85A}', '\u{1F85F}'),
+        ('\u{1F888}', '\u{1F88F}'),
+        ('\u{1F8AE}', '\u{1F8FF}'),
+        ('\u{1F90C}', '\u{1F93A}'),
+        ('\u{1F93C}', '\u{1F945}'),
+        ('\u{1F947}', '\u{1FAFF}'),
+        ('\u{1FC00}', '\u{1FFFD}'),
+    ];
+
+    fn is_pictographic(c: char) -> bool {
+        EXTENDED_PICTOGRAPHIC
+            .binary_search_by(|&(first, last)| {
+                if last < c {
+                    std::cmp::Ordering::Less
+                } else if first > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    pub(super) fn is_single_emoji(value: &str) -> bool {
+        let chars: Vec<char> = value.chars().collect();
+        match chars.as_slice() {
+            [] => false,
+            // A flag is a pair of regional indicators.
+            [a, b] if is_regional_indicator(*a) => is_regional_indicator(*b),
+            chars => chars.split(|c| *c == ZWJ).all(is_element),
+        }
+    }
+
+    // One element of a ZWJ sequence (which may be the only one).
+    fn is_element(chars: &[char]) -> bool {
+        match chars {
+            [base, rest @ ..] if is_keycap_base(*base) => {
+                matches!(
+                    rest,
+                    [COMBINING_KEYCAP] | [EMOJI_PRESENTATION, COMBINING_KEYCAP]
+                )
+            }
+            [base, rest @ ..] if is_pictographic(*base) => {
+                let rest = rest.strip_prefix(&[EMOJI_PRESENTATION]).unwrap_or(rest);
+                let rest = match rest {
+                    [modifier, rest @ ..] if is_skin_tone_modifier(*modifier) => rest,
+                    rest => rest,
+                };
+                match rest {
+                    [] => true,
+                    [tags @ .., CANCEL_TAG] => !tags.is_empty() && tags.iter().all(|c| is_tag(*c)),
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub demux_id: DemuxId,
+    /// Unique among the messages sent by the device with `demux_id`.
+    pub id: u64,
+    /// When the sender sent it, according to the sender's clock.
+    pub timestamp: SystemTime,
+    pub text: String,
+}
+
+impl ChatMessage {
+    pub fn timestamp_as_unix_millis(&self) -> u64 {
+        as_unix_millis(Some(self.timestamp))
+    }
 }
 
 // The callbacks from the Client to the "SFU client" for the group call.
@@ -509,6 +798,8 @@ pub struct Joined {
     pub creator: Option<UserId>,
     pub era_id: String,
     pub join_state: JoinState,
+    /// Whether the local device joined as an admin of the call link.
+    pub is_admin: bool,
 }
 
 /// Communicates with the SFU using HTTP.
@@ -563,6 +854,7 @@ impl HttpSfuClient {
         client: Client,
     ) {
         let hkdf_extra_info = self.hkdf_extra_info.clone();
+        let is_admin = self.admin_passkey.is_some();
         sfu::join(
             self.http_client.as_ref(),
             &self.sfu_url,
@@ -589,6 +881,7 @@ impl HttpSfuClient {
                         creator: join_response.call_creator,
                         era_id: join_response.era_id,
                         hkdf_extra_info,
+                        is_admin,
                         join_state: match join_response.client_status {
                             ClientStatus::Active => {
                                 JoinState::Joined(join_response.client_demux_id)
@@ -854,6 +1147,8 @@ pub struct Client {
     // because WebRTC calls back to the PeerConnectionObserver
     // synchronously.
     frame_crypto_context: Arc<CallMutex<frame_crypto::Context>>,
+    // Also outside the actor state so that it can be read at any time.
+    speaking_stats: Arc<CallMutex<SpeakingStatsTracker>>,
     actor: Actor<State>,
 }
 
@@ -935,6 +1230,27 @@ enum BweCheckState {
     None,
 }
 
+/// Progress of rebuilding the connection to the SFU after ICE failed.
+#[derive(Debug, Clone, Copy)]
+struct ConnectionRecoveryState {
+    /// When to give up and end the call.
+    deadline: Instant,
+    /// When to try rejoining the SFU again. None while an attempt is in progress.
+    next_attempt_time: Option<Instant>,
+    /// How long to wait after the next failed attempt.
+    backoff: Duration,
+}
+
+/// A chat message sent by the local device that not every remote device has acked yet.
+struct OutgoingChatMessage {
+    id: u64,
+    /// The encoded DeviceToDevice to resend.
+    payload: Vec<u8>,
+    unacked_demux_ids: HashSet<DemuxId>,
+    next_resend_time: Instant,
+    expiration_time: Instant,
+}
+
 #[derive(Default)]
 struct RaiseHandState {
     pub seqnum: u32,
@@ -984,6 +1300,12 @@ struct State {
     sfu_info: Option<SfuInfo>,
     peer_connection: PeerConnection,
     peer_connection_observer_impl: Box<PeerConnectionObserverImpl>,
+    // Incremented every time the PeerConnection is replaced so that late
+    // callbacks from an old PeerConnection can be ignored.
+    peer_connection_generation: u32,
+    // PeerConnections replaced during connection recovery. They are closed, but
+    // kept alive along with their observers until the replacement connects.
+    retired_peer_connections: Vec<(PeerConnection, Box<PeerConnectionObserverImpl>)>,
     rtp_data_to_sfu_next_seqnum: u32,
     rtp_data_through_sfu_next_seqnum: u32,
     next_heartbeat_time: Option<Instant>,
@@ -1012,6 +1334,8 @@ struct State {
     // we change the keys from within the actor.
     frame_crypto_context: Arc<CallMutex<frame_crypto::Context>>,
 
+    speaking_stats: Arc<CallMutex<SpeakingStatsTracker>>,
+
     // If we receive a media key before we know about the remote device,
     // we store it here until we do know about the remote device.
     pending_media_receive_keys: Vec<(
@@ -1050,11 +1374,43 @@ struct State {
 
     outgoing_ring_state: OutgoingRingState,
 
+    // Received reactions waiting to be passed to the observer, aggregated by value.
     reactions: Vec<Reaction>,
+    // When to pass them to the observer next (None means on the next tick).
+    next_reactions_time: Option<Instant>,
+    reaction_aggregation_window: Duration,
+    reaction_send_limit: ReactionRateLimit,
+    reaction_receive_limit: ReactionRateLimit,
+    reaction_send_limiter: Option<ReactionRateLimiter>,
+    reaction_receive_limiters: HashMap<DemuxId, ReactionRateLimiter>,
     raised_hands: Vec<DemuxId>,
     raise_hand_state: RaiseHandState,
+    // Whether the SFU will accept admin actions from the local device.
+    is_admin: bool,
+
+    // Things for in-call chat.
+    next_chat_message_id: u64,
+    outgoing_chat_messages: Vec<OutgoingChatMessage>,
+    // Received chat messages waiting to be passed to the observer on the next tick.
+    chat_messages: Vec<ChatMessage>,
+    // Acks to send on the next tick, by the demux ID of the sender of the messages.
+    pending_chat_acks: HashMap<DemuxId, Vec<u64>>,
+    // The most recent IDs received from each remote device, so resends aren't passed
+    // to the observer twice. Only the last CHAT_MESSAGE_ID_WINDOW IDs are kept.
+    received_chat_message_ids: HashMap<DemuxId, BTreeSet<u64>>,
 
     sfu_reliable_stream: MrpStream<Vec<u8>, (rtp::Header, SfuToDevice)>,
+
+    // Things to rebuild the PeerConnection if ICE fails after connecting.
+    peer_connection_factory: PeerConnectionFactory,
+    outgoing_audio_track: AudioTrack,
+    outgoing_video_track: Option<VideoTrack>,
+    connection_recovery_window: Duration,
+    connection_recovery_state: Option<ConnectionRecoveryState>,
+    // Demux IDs the local device had before rejoining the SFU. The SFU may still
+    // list them until it notices they're gone, so they're ignored in peek results.
+    abandoned_local_demux_ids: HashSet<DemuxId>,
+
     actor: Actor<State>,
 }
 
@@ -1130,6 +1486,26 @@ const DELAYED_BWE_CHECK: Duration = Duration::from_secs(10);
 
 const REACTION_STRING_MAX_SIZE: usize = 256;
 
+// Small enough that a chat message fits in a single RTP data packet along with
+// the headers and encryption overhead.
+const CHAT_MESSAGE_MAX_SIZE: usize = 1024;
+// How many of the latest chat message IDs to remember per sender. Anything older is
+// treated as already received.
+const CHAT_MESSAGE_ID_WINDOW: u64 = 256;
+// How long to wait for acks before resending a chat message.
+const CHAT_MESSAGE_RESEND_INTERVAL: Duration = Duration::from_millis(1000);
+// How long to keep resending a chat message to devices that haven't acked it.
+const CHAT_MESSAGE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// How long to keep trying to rejoin the SFU after ICE fails, unless changed with
+// Client::set_connection_recovery_window.
+const DEFAULT_CONNECTION_RECOVERY_WINDOW: Duration = Duration::from_secs(30);
+// How long to wait before retrying a failed rejoin. Doubles with each failure.
+const CONNECTION_RECOVERY_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const CONNECTION_RECOVERY_MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+const AUDIO_RTCP_REPORT_INTERVAL_MS: i32 = 5000;
+
 impl Client {
     #[allow(clippy::too_many_arguments)]
     pub fn start(
@@ -1157,6 +1533,11 @@ impl Client {
             "Frame encryption context",
         ));
         let frame_crypto_context_for_outside_actor = frame_crypto_context.clone();
+        let speaking_stats = Arc::new(CallMutex::new(
+            SpeakingStatsTracker::default(),
+            "Speaking stats",
+        ));
+        let speaking_stats_for_outside_actor = speaking_stats.clone();
         let client = Self {
             client_id,
             group_id: group_id.clone(),
@@ -1177,23 +1558,26 @@ impl Client {
                     Some(v) => v,
                 };
 
+                let peer_connection_generation = 0;
                 let (peer_connection_observer_impl, peer_connection_observer) =
-                    PeerConnectionObserverImpl::uninitialized(incoming_video_sink)?;
+                    PeerConnectionObserverImpl::uninitialized(
+                        incoming_video_sink,
+                        peer_connection_generation,
+                    )?;
                 // WebRTC uses alphanumeric plus + and /, which is just barely a superset of this,
                 // but we can't uses dashes due to the sfu.
                 let local_ice_ufrag = random_alphanumeric(4);
                 let local_ice_pwd = random_alphanumeric(22);
-                let audio_rtcp_report_interval_ms = 5000;
                 let ice_servers = vec![];
                 let peer_connection = peer_connection_factory
                     .create_peer_connection(
                         peer_connection_observer,
                         pcf::RffiPeerConnectionKind::GroupCall,
                         &AudioJitterBufferConfig::default(),
-                        audio_rtcp_report_interval_ms,
+                        AUDIO_RTCP_REPORT_INTERVAL_MS,
                         &ice_servers,
-                        outgoing_audio_track,
-                        outgoing_video_track,
+                        outgoing_audio_track.clone(),
+                        outgoing_video_track.clone(),
                     )
                     .inspect_err(|_| {
                         observer.handle_ended(client_id, EndReason::FailedToCreatePeerConnection);
@@ -1242,6 +1626,8 @@ impl Client {
                     sfu_info: None,
                     peer_connection_observer_impl,
                     peer_connection,
+                    peer_connection_generation,
+                    retired_peer_connections: Vec::new(),
                     rtp_data_to_sfu_next_seqnum: 1,
                     rtp_data_through_sfu_next_seqnum: 1,
 
@@ -1252,6 +1638,7 @@ impl Client {
                     stats_observer: create_stats_observer(
                         call_id_for_stats,
                         DEFAULT_STATS_INTERVAL,
+                        Some(peer_connection_factory.audio_device_stats_tracker()),
                     ),
 
                     audio_levels_interval,
@@ -1264,6 +1651,7 @@ impl Client {
                     bwe_check_state: BweCheckState::Disabled,
 
                     frame_crypto_context,
+                    speaking_stats,
                     pending_media_receive_keys: Vec::new(),
                     media_send_key_rotation_state: KeyRotationState::Applied,
 
@@ -1282,15 +1670,36 @@ impl Client {
                     outgoing_ring_state: OutgoingRingState::Unknown,
 
                     reactions: Vec::new(),
+                    next_reactions_time: None,
+                    reaction_aggregation_window: Duration::ZERO,
+                    reaction_send_limit: ReactionRateLimit::UNLIMITED,
+                    reaction_receive_limit: ReactionRateLimit::UNLIMITED,
+                    reaction_send_limiter: None,
+                    reaction_receive_limiters: HashMap::new(),
                     raised_hands: Vec::new(),
                     raise_hand_state: RaiseHandState::default(),
+                    is_admin: false,
+
+                    next_chat_message_id: 1,
+                    outgoing_chat_messages: Vec::new(),
+                    chat_messages: Vec::new(),
+                    pending_chat_acks: HashMap::new(),
+                    received_chat_message_ids: HashMap::new(),
 
                     sfu_reliable_stream: MrpStream::new(RELIABLE_RTP_BUFFER_SIZE),
 
+                    peer_connection_factory,
+                    outgoing_audio_track,
+                    outgoing_video_track,
+                    connection_recovery_window: DEFAULT_CONNECTION_RECOVERY_WINDOW,
+                    connection_recovery_state: None,
+                    abandoned_local_demux_ids: HashSet::new(),
+
                     actor,
                 })
             })?,
             frame_crypto_context: frame_crypto_context_for_outside_actor,
+            speaking_stats: speaking_stats_for_outside_actor,
         };
 
         // After we have the actor, we can initialize the PeerConnectionObserverImpl
@@ -1345,6 +1754,20 @@ impl Client {
             state.client_id
         );
 
+        if let Some(recovery) = state.connection_recovery_state {
+            if now >= recovery.deadline {
+                info!("Giving up on recovering the connection to the SFU");
+                Self::end(state, EndReason::IceFailedAfterConnected);
+                return;
+            }
+            if recovery
+                .next_attempt_time
+                .is_some_and(|next_attempt_time| now >= next_attempt_time)
+            {
+                Self::rejoin_sfu(state);
+            }
+        }
+
         Self::request_remote_devices_from_sfu_if_older_than(state, Duration::from_secs(10));
 
         if let Some(next_heartbeat_time) = state.next_heartbeat_time {
@@ -1376,6 +1799,11 @@ impl Client {
         {
             if now >= next_audio_levels_time {
                 let (captured_level, received_levels) = state.peer_connection.get_audio_levels();
+                state
+                    .speaking_stats
+                    .lock()
+                    .expect("Get lock for speaking stats to add audio levels")
+                    .on_audio_levels(captured_level, &received_levels, audio_levels_interval);
                 state.observer.handle_audio_levels(
                     state.client_id,
                     captured_level,
@@ -1441,12 +1869,25 @@ impl Client {
             BweCheckState::None => {}
         }
 
-        if !state.reactions.is_empty() {
+        if !state.reactions.is_empty()
+            && state
+                .next_reactions_time
+                .map_or(true, |next_reactions_time| now >= next_reactions_time)
+        {
+            state.next_reactions_time = None;
             state
                 .observer
                 .handle_reactions(state.client_id, std::mem::take(&mut state.reactions));
         }
 
+        if !state.chat_messages.is_empty() {
+            state
+                .observer
+                .handle_chat_messages(state.client_id, std::mem::take(&mut state.chat_messages));
+        }
+        Self::send_pending_chat_acks(state);
+        Self::resend_unacked_chat_messages(state, now);
+
         if let Some(next_raise_hand_time) = state.next_raise_hand_time {
             if now >= next_raise_hand_time && state.raise_hand_state.outstanding {
                 state.next_raise_hand_time = Some(now + RAISE_HAND_INTERVAL);
@@ -2284,6 +2725,94 @@ impl Client {
         });
     }
 
+    // Unlike removing and blocking, which the SFU authorizes on its own, these only
+    // make sense for admins, so don't bother sending them otherwise.
+    fn send_admin_action_if_admin(
+        state: &mut State,
+        action: protobuf::group_call::device_to_sfu::AdminAction,
+        action_to_log: &str,
+    ) {
+        if !state.is_admin {
+            warn!("{ADMIN_LOG_TAG}: Only admins can send {action_to_log}");
+            return;
+        }
+
+        let msg = DeviceToSfu {
+            admin_action: Some(action),
+            ..Default::default()
+        };
+
+        if let Err(e) = Self::reliable_send_to_sfu(state, msg) {
+            warn!("{ADMIN_LOG_TAG}: Failed to send {action_to_log}: {e:?}");
+        } else {
+            info!("{ADMIN_LOG_TAG}: Sent {action_to_log}");
+        }
+    }
+
+    pub fn lower_hand(&self, other_client: DemuxId) {
+        use protobuf::group_call::device_to_sfu::{AdminAction, GenericAdminAction};
+        debug!(
+            "group_call::Client(outer)::lower_hand(client_id: {})",
+            self.client_id
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::lower_hand(client_id: {})",
+                state.client_id
+            );
+
+            Self::send_admin_action_if_admin(
+                state,
+                AdminAction::LowerHand(GenericAdminAction {
+                    target_demux_id: Some(other_client),
+                }),
+                &format!("lower hand for {other_client}"),
+            );
+        });
+    }
+
+    pub fn lower_all_hands(&self) {
+        use protobuf::group_call::device_to_sfu::{AdminAction, GenericAdminAction};
+        debug!(
+            "group_call::Client(outer)::lower_all_hands(client_id: {})",
+            self.client_id
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::lower_all_hands(client_id: {})",
+                state.client_id
+            );
+
+            Self::send_admin_action_if_admin(
+                state,
+                AdminAction::LowerAllHands(GenericAdminAction::default()),
+                "lower all hands",
+            );
+        });
+    }
+
+    pub fn request_mute(&self, other_client: DemuxId) {
+        use protobuf::group_call::device_to_sfu::{AdminAction, GenericAdminAction};
+        debug!(
+            "group_call::Client(outer)::request_mute(client_id: {})",
+            self.client_id
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::request_mute(client_id: {})",
+                state.client_id
+            );
+
+            Self::send_admin_action_if_admin(
+                state,
+                AdminAction::RequestMute(GenericAdminAction {
+                    target_demux_id: Some(other_client),
+                }),
+                &format!("mute request for {other_client}"),
+            );
+        });
+    }
+
     pub fn set_group_members(&self, group_members: Vec<GroupMember>) {
         debug!(
             "group_call::Client(outer)::set_group_members(client_id: {})",
@@ -2340,16 +2869,56 @@ impl Client {
                 value.len(),
                 REACTION_STRING_MAX_SIZE
             );
-        } else {
+        } else if let Some(value) = normalize_reaction(&value) {
             self.actor.send(move |state| {
                 debug!(
                     "group_call::Client(inner)::react(client_id: {}, value: {})",
                     state.client_id, value
                 );
+                let now = Instant::now();
+                let limit = state.reaction_send_limit;
+                if !state
+                    .reaction_send_limiter
+                    .get_or_insert_with(|| ReactionRateLimiter::new(&limit, now))
+                    .try_take(&limit, now)
+                {
+                    info!("group_call::Client(inner)::react dropping reaction over the rate limit");
+                    return;
+                }
                 if let Err(err) = Self::send_reaction(state, value) {
                     warn!("Failed to send reaction: {:?}", err);
                 }
             });
+        } else {
+            warn!("group_call::Client(outer)::react value is not a single emoji");
+        }
+    }
+
+    pub fn send_chat_message(&self, text: String) {
+        debug!(
+            "group_call::Client(outer)::send_chat_message(client_id: {}, size: {})",
+            self.client_id,
+            text.len()
+        );
+
+        if text.is_empty() {
+            warn!("group_call::send_chat_message text is empty");
+        } else if text.len() > CHAT_MESSAGE_MAX_SIZE {
+            warn!(
+                "group_call::send_chat_message text size of {} exceeded allowed size of {}",
+                text.len(),
+                CHAT_MESSAGE_MAX_SIZE
+            );
+        } else {
+            self.actor.send(move |state| {
+                debug!(
+                    "group_call::Client(inner)::send_chat_message(client_id: {})",
+                    state.client_id
+                );
+                if let Err(err) = Self::send_chat_message_inner(state, text) {
+                    warn!("Failed to send chat message: {:?}", err);
+                }
+            });
         }
     }
 
@@ -2360,17 +2929,21 @@ impl Client {
         );
 
         self.actor.send(move |state| {
-            state.raise_hand_state.seqnum += 1;
-            state.raise_hand_state.raise = raise;
-            state.raise_hand_state.outstanding = true;
+            Self::raise_hand_inner(state, raise);
+        });
+    }
 
-            info!(
-                "group_call::Client(inner)::raise_hand(client_id: {}, raise: {} seqnum: {})",
-                state.client_id, state.raise_hand_state.raise, state.raise_hand_state.seqnum
-            );
+    fn raise_hand_inner(state: &mut State, raise: bool) {
+        state.raise_hand_state.seqnum += 1;
+        state.raise_hand_state.raise = raise;
+        state.raise_hand_state.outstanding = true;
 
-            Self::send_raise_hand(state);
-        });
+        info!(
+            "group_call::Client(inner)::raise_hand(client_id: {}, raise: {} seqnum: {})",
+            state.client_id, state.raise_hand_state.raise, state.raise_hand_state.seqnum
+        );
+
+        Self::send_raise_hand(state);
     }
 
     // Pulled into a named private method because it can be called in many places.
@@ -2420,6 +2993,17 @@ impl Client {
                 state.client_id
             );
 
+            if state.connection_recovery_state.is_some() {
+                match joined {
+                    Ok(joined) => Self::finish_rejoining_sfu(state, joined),
+                    Err(err) => {
+                        warn!("Failed to rejoin the SFU: {:?}", err);
+                        Self::recover_connection_or_end(state);
+                    }
+                }
+                return;
+            }
+
             if let Ok(Joined {
                 sfu_info,
                 local_demux_id,
@@ -2428,8 +3012,10 @@ impl Client {
                 creator,
                 era_id,
                 join_state,
+                is_admin,
             }) = joined
             {
+                state.is_admin = is_admin;
                 match state.connection_state {
                     ConnectionState::NotConnected => {
                         warn!("The SFU completed joining before connect() was requested.");
@@ -2565,75 +3151,296 @@ impl Client {
             .configure_audio_encoders(&AudioEncoderConfig::default());
     }
 
-    pub fn on_signaling_message_received(
-        &self,
-        sender_user_id: UserId,
-        message: protobuf::group_call::DeviceToDevice,
-    ) {
-        debug!(
-            "group_call::Client(outer)::on_signaling_message_received(client_id: {})",
-            self.client_id
-        );
-        self.actor.send(move |state| {
-            debug!(
-                "group_call::Client(inner)::on_signaling_message_received(client_id: {})",
-                state.client_id
-            );
-            match message {
-                protobuf::group_call::DeviceToDevice {
-                    media_key:
-                        Some(protobuf::group_call::device_to_device::MediaKey {
-                            demux_id: Some(sender_demux_id),
-                            ratchet_counter: Some(ratchet_counter),
-                            secret: Some(secret_vec),
-                            ..
-                        }),
-                    ..
-                } => {
-                    if secret_vec.len() != size_of::<frame_crypto::Secret>() {
-                        warn!("on_signaling_message_received(): ignoring media receive key with wrong length");
-                        return;
-                    }
-                    if let Ok(ratchet_counter) = ratchet_counter.try_into() {
-                        let mut secret = frame_crypto::Secret::default();
-                        secret.copy_from_slice(&secret_vec);
-                        Self::add_media_receive_key_or_store_for_later(
-                            state,
-                            sender_user_id,
-                            sender_demux_id,
-                            ratchet_counter,
-                            secret,
-                        );
-                    } else {
-                        warn!("on_signaling_message_received(): ignoring media receive key with ratchet counter that's too big");
-                    }
-                    let known = state.remote_devices.iter().any(|rd| rd.demux_id == sender_demux_id);
-                    if !known {
-                        // It's likely someone this demux ID just joined.
-                        debug!("Request devices because we receive a signaling message from unknown demux_id = {}", sender_demux_id);
-                        Self::request_remote_devices_as_soon_as_possible(state);
-                    }
-                }
-                protobuf::group_call::DeviceToDevice {
-                    group_id: Some(group_id),
-                    leaving: Some(protobuf::group_call::device_to_device::Leaving {
-                        demux_id: Some(leaving_demux_id),
-                        ..
-                    }),
-                    ..
-                } => {
-                    if group_id == state.group_id {
-                        Self::handle_leaving_received(state, leaving_demux_id);
-                    }
-                }
-                _ => {
-                    warn!("on_signaling_message_received(): ignoring unknown message");
-                }
+    // Called when ICE fails after having connected, including while a previous
+    // recovery attempt is in progress.
+    fn recover_connection_or_end(state: &mut State) {
+        let now = Instant::now();
+
+        if let Some(recovery) = &mut state.connection_recovery_state {
+            if now >= recovery.deadline {
+                Self::end(state, EndReason::IceFailedAfterConnected);
+                return;
             }
-        });
-    }
+            info!(
+                "Failed to recover the connection to the SFU; trying again in {:?}",
+                recovery.backoff
+            );
+            recovery.next_attempt_time = Some(now + recovery.backoff);
+            recovery.backoff = std::cmp::min(recovery.backoff * 2, CONNECTION_RECOVERY_MAX_BACKOFF);
+            return;
+        }
 
-    // Pulled into a named private method because it's more convenient to deal with errors that way
+        let joined = matches!(
+            state.join_state,
+            JoinState::Pending(_) | JoinState::Joined(_)
+        );
+        if state.connection_recovery_window.is_zero() || !joined || state.sfu_info.is_none() {
+            Self::end(state, EndReason::IceFailedAfterConnected);
+            return;
+        }
+
+        info!(
+            "ICE failed after connecting; trying to rejoin the SFU for up to {:?}",
+            state.connection_recovery_window
+        );
+        state.connection_recovery_state = Some(ConnectionRecoveryState {
+            deadline: now + state.connection_recovery_window,
+            next_attempt_time: None,
+            backoff: CONNECTION_RECOVERY_INITIAL_BACKOFF,
+        });
+        if state.connection_state != ConnectionState::Reconnecting {
+            Self::set_connection_state_and_notify_observer(state, ConnectionState::Reconnecting);
+        }
+        Self::rejoin_sfu(state);
+    }
+
+    // Replaces the PeerConnection and asks the SfuClient to join again.
+    // The rest happens in finish_rejoining_sfu.
+    fn rejoin_sfu(state: &mut State) {
+        debug!(
+            "group_call::Client(inner)::rejoin_sfu(client_id: {})",
+            state.client_id
+        );
+
+        if let Some(recovery) = &mut state.connection_recovery_state {
+            recovery.next_attempt_time = None;
+        }
+
+        if let Err(err) = Self::replace_peer_connection(state) {
+            warn!("Failed to replace the PeerConnection: {:?}", err);
+            Self::recover_connection_or_end(state);
+            return;
+        }
+
+        let client_secret = EphemeralSecret::random_from_rng(OsRng);
+        let client_pub_key = PublicKey::from(&client_secret);
+        state.dhe_state = DheState::start(client_secret);
+        let callback = Client {
+            client_id: state.client_id,
+            group_id: state.group_id.clone(),
+            frame_crypto_context: state.frame_crypto_context.clone(),
+            speaking_stats: state.speaking_stats.clone(),
+            actor: state.actor.clone(),
+        };
+        state
+            .sfu_client
+            .join(&state.local_ice_ufrag, *client_pub_key.as_bytes(), callback);
+    }
+
+    fn replace_peer_connection(state: &mut State) -> Result<()> {
+        let peer_connection_generation = state.peer_connection_generation.wrapping_add(1);
+        let (mut peer_connection_observer_impl, peer_connection_observer) =
+            PeerConnectionObserverImpl::uninitialized(
+                state
+                    .peer_connection_observer_impl
+                    .incoming_video_sink
+                    .clone(),
+                peer_connection_generation,
+            )?;
+        let peer_connection = state.peer_connection_factory.create_peer_connection(
+            peer_connection_observer,
+            pcf::RffiPeerConnectionKind::GroupCall,
+            &AudioJitterBufferConfig::default(),
+            AUDIO_RTCP_REPORT_INTERVAL_MS,
+            &[],
+            state.outgoing_audio_track.clone(),
+            state.outgoing_video_track.clone(),
+        )?;
+        peer_connection_observer_impl.initialize(Client {
+            client_id: state.client_id,
+            group_id: state.group_id.clone(),
+            frame_crypto_context: state.frame_crypto_context.clone(),
+            speaking_stats: state.speaking_stats.clone(),
+            actor: state.actor.clone(),
+        });
+
+        state.peer_connection.close();
+        let old_peer_connection = std::mem::replace(&mut state.peer_connection, peer_connection);
+        let old_peer_connection_observer_impl = std::mem::replace(
+            &mut state.peer_connection_observer_impl,
+            peer_connection_observer_impl,
+        );
+        state
+            .retired_peer_connections
+            .push((old_peer_connection, old_peer_connection_observer_impl));
+        state.peer_connection_generation = peer_connection_generation;
+
+        // The SFU identifies the client by its ICE ufrag, so a fresh join needs a fresh one.
+        state.local_ice_ufrag = random_alphanumeric(4);
+        state.local_ice_pwd = random_alphanumeric(22);
+        // The new PeerConnection has no transceivers yet.
+        state.remote_transceiver_demux_ids.clear();
+        Ok(())
+    }
+
+    // Sets up the new PeerConnection after rejoining the SFU and restores everything
+    // that other devices and the SFU knew about the local device.
+    fn finish_rejoining_sfu(state: &mut State, joined: Joined) {
+        debug!(
+            "group_call::Client(inner)::finish_rejoining_sfu(client_id: {})",
+            state.client_id
+        );
+
+        let Joined {
+            sfu_info,
+            local_demux_id,
+            server_dhe_pub_key,
+            hkdf_extra_info,
+            join_state,
+            is_admin,
+            ..
+        } = joined;
+        state.is_admin = is_admin;
+
+        let previous_local_demux_id = match state.join_state {
+            JoinState::Pending(demux_id) | JoinState::Joined(demux_id) => demux_id,
+            JoinState::NotJoined(_) | JoinState::Joining => {
+                info!("Not finishing rejoining the SFU because the call was left.");
+                Self::end(state, EndReason::IceFailedAfterConnected);
+                return;
+            }
+        };
+        if local_demux_id != previous_local_demux_id {
+            state
+                .abandoned_local_demux_ids
+                .insert(previous_local_demux_id);
+        }
+
+        // Recreate the transceivers for everyone we already know about.
+        state.remote_transceiver_demux_ids = state
+            .remote_devices
+            .iter()
+            .map(|device| Some(device.demux_id))
+            .collect();
+
+        state
+            .dhe_state
+            .negotiate_in_place(&PublicKey::from(server_dhe_pub_key), &hkdf_extra_info);
+        let srtp_keys = match &state.dhe_state {
+            DheState::Negotiated { srtp_keys } => srtp_keys,
+            _ => {
+                warn!("Failed to negotiate SRTP keys while rejoining the SFU");
+                Self::recover_connection_or_end(state);
+                return;
+            }
+        };
+        if Self::start_peer_connection(state, &sfu_info, local_demux_id, srtp_keys).is_err() {
+            warn!("Failed to start the PeerConnection while rejoining the SFU");
+            Self::recover_connection_or_end(state);
+            return;
+        }
+        state.sfu_info = Some(sfu_info);
+        state.sfu_reliable_stream = MrpStream::new(RELIABLE_RTP_BUFFER_SIZE);
+
+        // Apply the current send rates to the new PeerConnection.
+        let send_rates = std::mem::take(&mut state.send_rates);
+        Self::set_send_rates_inner(state, send_rates);
+
+        if state.join_state != join_state {
+            Self::set_join_state_and_notify_observer(state, join_state);
+        }
+        if matches!(join_state, JoinState::Joined(_)) {
+            Self::on_client_joined(state);
+        }
+
+        // Everyone else needs a media key for the new demux ID.
+        let user_ids: HashSet<UserId> = state
+            .remote_devices
+            .iter()
+            .map(|device| device.user_id.clone())
+            .collect();
+        if !user_ids.is_empty() {
+            Self::advance_media_send_key_and_send_to_users_with_added_devices(
+                state,
+                user_ids.clone(),
+            );
+            Self::send_pending_media_send_key_to_users_with_added_devices(state, user_ids);
+        }
+
+        if let Err(err) = Self::send_heartbeat(state) {
+            warn!(
+                "Failed to send heartbeat after rejoining the SFU: {:?}",
+                err
+            );
+        }
+        if state.raise_hand_state.raise {
+            state.raise_hand_state.seqnum += 1;
+            state.raise_hand_state.outstanding = true;
+            Self::send_raise_hand(state);
+        }
+        Self::send_video_requests_to_sfu(state);
+        Self::request_remote_devices_as_soon_as_possible(state);
+    }
+
+    pub fn on_signaling_message_received(
+        &self,
+        sender_user_id: UserId,
+        message: protobuf::group_call::DeviceToDevice,
+    ) {
+        debug!(
+            "group_call::Client(outer)::on_signaling_message_received(client_id: {})",
+            self.client_id
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::on_signaling_message_received(client_id: {})",
+                state.client_id
+            );
+            match message {
+                protobuf::group_call::DeviceToDevice {
+                    media_key:
+                        Some(protobuf::group_call::device_to_device::MediaKey {
+                            demux_id: Some(sender_demux_id),
+                            ratchet_counter: Some(ratchet_counter),
+                            secret: Some(secret_vec),
+                            ..
+                        }),
+                    ..
+                } => {
+                    if secret_vec.len() != size_of::<frame_crypto::Secret>() {
+                        warn!("on_signaling_message_received(): ignoring media receive key with wrong length");
+                        return;
+                    }
+                    if let Ok(ratchet_counter) = ratchet_counter.try_into() {
+                        let mut secret = frame_crypto::Secret::default();
+                        secret.copy_from_slice(&secret_vec);
+                        Self::add_media_receive_key_or_store_for_later(
+                            state,
+                            sender_user_id,
+                            sender_demux_id,
+                            ratchet_counter,
+                            secret,
+                        );
+                    } else {
+                        warn!("on_signaling_message_received(): ignoring media receive key with ratchet counter that's too big");
+                    }
+                    let known = state.remote_devices.iter().any(|rd| rd.demux_id == sender_demux_id);
+                    if !known {
+                        // It's likely someone this demux ID just joined.
+                        debug!("Request devices because we receive a signaling message from unknown demux_id = {}", sender_demux_id);
+                        Self::request_remote_devices_as_soon_as_possible(state);
+                    }
+                }
+                protobuf::group_call::DeviceToDevice {
+                    group_id: Some(group_id),
+                    leaving: Some(protobuf::group_call::device_to_device::Leaving {
+                        demux_id: Some(leaving_demux_id),
+                        ..
+                    }),
+                    ..
+                } => {
+                    if group_id == state.group_id {
+                        Self::handle_leaving_received(state, leaving_demux_id);
+                    }
+                }
+                _ => {
+                    warn!("on_signaling_message_received(): ignoring unknown message");
+                }
+            }
+        });
+    }
+
+    // Pulled into a named private method because it's more convenient to deal with errors that way
     fn start_peer_connection(
         state: &State,
         sfu_info: &SfuInfo,
@@ -2745,6 +3552,61 @@ impl Client {
         });
     }
 
+    /// Sets how long to keep trying to rejoin the SFU after ICE fails before ending
+    /// the call with [`EndReason::IceFailedAfterConnected`]. Zero disables recovery.
+    pub fn set_connection_recovery_window(&self, window: Duration) {
+        info!(
+            "group_call::Client(outer)::set_connection_recovery_window: {}, window: {:?})",
+            self.client_id, window
+        );
+
+        self.actor.send(move |state| {
+            state.connection_recovery_window = window;
+        });
+    }
+
+    /// Returns how much each device in the call has spoken so far, including devices that
+    /// have since left.
+    pub fn speaking_summary(&self) -> SpeakingSummary {
+        match self.speaking_stats.lock() {
+            Ok(speaking_stats) => speaking_stats.summary(Instant::now()),
+            Err(err) => {
+                error!("Failed to get speaking stats: {:?}", err);
+                SpeakingSummary::default()
+            }
+        }
+    }
+
+    /// Sets how many reactions can be sent by this device and how many will be accepted
+    /// from each remote device. Reactions over the limit are dropped.
+    pub fn set_reaction_rate_limits(&self, send: ReactionRateLimit, receive: ReactionRateLimit) {
+        info!(
+            "group_call::Client(outer)::set_reaction_rate_limits: {}, send: {:?}, receive: {:?})",
+            self.client_id, send, receive
+        );
+
+        self.actor.send(move |state| {
+            state.reaction_send_limit = send;
+            state.reaction_receive_limit = receive;
+            state.reaction_send_limiter = None;
+            state.reaction_receive_limiters.clear();
+        });
+    }
+
+    /// Sets how long to collect received reactions before passing them to the observer,
+    /// with identical reactions combined into one. Zero passes them on at the next tick.
+    pub fn set_reaction_aggregation_window(&self, window: Duration) {
+        info!(
+            "group_call::Client(outer)::set_reaction_aggregation_window: {}, window: {:?})",
+            self.client_id, window
+        );
+
+        self.actor.send(move |state| {
+            state.reaction_aggregation_window = window;
+            state.next_reactions_time = None;
+        });
+    }
+
     // Most of the logic moved to inner method so this can be called by both
     // set_peek_result() and as a callback to SfuClient::request_remote_devices.
     fn set_peek_result_inner(state: &mut State, result: PeekResult) {
@@ -2846,6 +3708,10 @@ impl Client {
                         // Don't add a remote device to represent the local device.
                         return None;
                     }
+                    if state.abandoned_local_demux_ids.contains(&device.demux_id) {
+                        // Nor for where the local device was before rejoining.
+                        return None;
+                    }
                     device.user_id.as_ref().map(|user_id| {
                         // Keep the old one, with its state, if there is one and the user ID
                         // matches.
@@ -2868,6 +3734,26 @@ impl Client {
                 new_demux_ids.difference(&old_demux_ids).copied().collect();
 
             let demux_ids_changed = old_demux_ids != new_demux_ids;
+            state
+                .received_chat_message_ids
+                .retain(|demux_id, _| new_demux_ids.contains(demux_id));
+            state
+                .reaction_receive_limiters
+                .retain(|demux_id, _| new_demux_ids.contains(demux_id));
+            if demux_ids_changed {
+                let mut speaking_stats = state
+                    .speaking_stats
+                    .lock()
+                    .expect("Get lock for speaking stats to update remote devices");
+                speaking_stats.update_remote_devices(
+                    state
+                        .remote_devices
+                        .iter()
+                        .map(|device| (device.demux_id, &device.user_id)),
+                    Instant::now(),
+                    SystemTime::now(),
+                );
+            }
             // If demux IDs changed, let the PeerConnection know that related SSRCs changed as well
             if demux_ids_changed {
                 info!(
@@ -3562,6 +4448,81 @@ impl Client {
         Self::broadcast_data_through_sfu(state, &react_msg.encode_to_vec())
     }
 
+    fn send_chat_message_inner(state: &mut State, text: String) -> Result<()> {
+        let id = state.next_chat_message_id;
+        state.next_chat_message_id += 1;
+
+        let chat_msg = protobuf::group_call::DeviceToDevice {
+            chat_message: Some(protobuf::group_call::device_to_device::ChatMessage {
+                id: Some(id),
+                timestamp_millis: Some(as_unix_millis(Some(SystemTime::now()))),
+                text: Some(text),
+            }),
+            ..Default::default()
+        };
+        let payload = chat_msg.encode_to_vec();
+        Self::broadcast_data_through_sfu(state, &payload)?;
+
+        let unacked_demux_ids = state.remote_devices.demux_id_set();
+        if !unacked_demux_ids.is_empty() {
+            let now = Instant::now();
+            state.outgoing_chat_messages.push(OutgoingChatMessage {
+                id,
+                payload,
+                unacked_demux_ids,
+                next_resend_time: now + CHAT_MESSAGE_RESEND_INTERVAL,
+                expiration_time: now + CHAT_MESSAGE_TIMEOUT,
+            });
+        }
+        Ok(())
+    }
+
+    fn resend_unacked_chat_messages(state: &mut State, now: Instant) {
+        let current_demux_ids = state.remote_devices.demux_id_set();
+        let mut to_resend = Vec::new();
+        state.outgoing_chat_messages.retain_mut(|message| {
+            // Don't wait on devices that have left.
+            message
+                .unacked_demux_ids
+                .retain(|demux_id| current_demux_ids.contains(demux_id));
+            if message.unacked_demux_ids.is_empty() {
+                return false;
+            }
+            if now >= message.expiration_time {
+                warn!(
+                    "Giving up on chat message {} without acks from {:?}",
+                    message.id, message.unacked_demux_ids
+                );
+                return false;
+            }
+            if now >= message.next_resend_time {
+                message.next_resend_time = now + CHAT_MESSAGE_RESEND_INTERVAL;
+                to_resend.push(message.payload.clone());
+            }
+            true
+        });
+        for payload in to_resend {
+            if let Err(err) = Self::broadcast_data_through_sfu(state, &payload) {
+                warn!("Failed to resend chat message: {:?}", err);
+            }
+        }
+    }
+
+    fn send_pending_chat_acks(state: &mut State) {
+        for (demux_id, ids) in std::mem::take(&mut state.pending_chat_acks) {
+            let ack_msg = protobuf::group_call::DeviceToDevice {
+                chat_ack: Some(protobuf::group_call::device_to_device::ChatAck {
+                    demux_id: Some(demux_id),
+                    ids,
+                }),
+                ..Default::default()
+            };
+            if let Err(err) = Self::broadcast_data_through_sfu(state, &ack_msg.encode_to_vec()) {
+                warn!("Failed to send chat ack: {:?}", err);
+            }
+        }
+    }
+
     fn send_raise_hand(state: &mut State) {
         use protobuf::group_call::device_to_sfu::RaiseHand;
         let msg = DeviceToSfu {
@@ -3827,6 +4788,12 @@ impl Client {
                         if let Some(reaction) = msg.reaction {
                             self.handle_reaction(demux_id, reaction);
                         }
+                        if let Some(chat_message) = msg.chat_message {
+                            self.handle_chat_message(demux_id, chat_message);
+                        }
+                        if let Some(chat_ack) = msg.chat_ack {
+                            self.handle_chat_ack(demux_id, chat_ack);
+                        }
                     } else {
                         warn!(
                             "Ignoring received RTP data because decoding failed. demux_id: {}",
@@ -3891,7 +4858,8 @@ impl Client {
 
     fn handle_sfu_to_device_inner(actor: &Actor<State>, header: rtp::Header, msg: SfuToDevice) {
         use protobuf::group_call::sfu_to_device::{
-            CurrentDevices, DeviceJoinedOrLeft, RaisedHands, Removed, Speaker,
+            CurrentDevices, DeviceJoinedOrLeft, LowerHand, MuteRequest, RaisedHands, Removed,
+            Speaker,
         };
         // TODO: Use video_request to throttle down how much we send when it's not needed.
         let SfuToDevice {
@@ -3903,6 +4871,8 @@ impl Client {
             removed,
             raised_hands,
             mrp_header: _,
+            lower_hand,
+            mute_request,
         } = msg;
 
         if let Some(Speaker {
@@ -3946,6 +4916,42 @@ impl Client {
         {
             Self::handle_raised_hands(actor, demux_ids, target_seqnum);
         }
+        if let Some(LowerHand { source_demux_id }) = lower_hand {
+            Self::handle_lower_hand_received(actor, source_demux_id);
+        }
+        if let Some(MuteRequest {
+            source_demux_id: Some(source_demux_id),
+        }) = mute_request
+        {
+            Self::handle_mute_request_received(actor, source_demux_id);
+        }
+    }
+
+    fn handle_lower_hand_received(actor: &Actor<State>, source_demux_id: Option<DemuxId>) {
+        actor.send(move |state| {
+            info!(
+                "{ADMIN_LOG_TAG}: Hand lowered by {:?} (client_id: {}, raised: {})",
+                source_demux_id, state.client_id, state.raise_hand_state.raise
+            );
+            if state.raise_hand_state.raise {
+                Self::raise_hand_inner(state, false);
+            }
+        });
+    }
+
+    fn handle_mute_request_received(actor: &Actor<State>, source_demux_id: DemuxId) {
+        actor.send(move |state| {
+            if !matches!(state.join_state, JoinState::Joined(_)) {
+                return;
+            }
+            info!(
+                "{ADMIN_LOG_TAG}: Mute requested by {} (client_id: {})",
+                source_demux_id, state.client_id
+            );
+            state
+                .observer
+                .handle_remote_mute_request(state.client_id, source_demux_id);
+        });
     }
 
     fn handle_removed_received(actor: &Actor<State>) {
@@ -3972,6 +4978,17 @@ impl Client {
             }
             state.speaker_rtp_timestamp = Some(timestamp);
 
+            let is_local = matches!(
+                state.join_state,
+                JoinState::Pending(local_demux_id) | JoinState::Joined(local_demux_id)
+                    if local_demux_id == demux_id
+            );
+            state
+                .speaking_stats
+                .lock()
+                .expect("Get lock for speaking stats to add speaker")
+                .on_active_speaker(demux_id, is_local, Instant::now(), SystemTime::now());
+
             let latest_speaker_demux_id = state.remote_devices.latest_speaker_demux_id();
 
             if let Some(speaker_device) = state.remote_devices.find_by_demux_id_mut(demux_id) {
@@ -4127,23 +5144,134 @@ impl Client {
                 value.len(),
                 REACTION_STRING_MAX_SIZE
             );
-        } else {
+        } else if let Some(value) = normalize_reaction(&value) {
             self.actor.send(move |state| {
-                state.reactions.push(Reaction { demux_id, value });
+                let now = Instant::now();
+                let limit = state.reaction_receive_limit;
+                if !state
+                    .reaction_receive_limiters
+                    .entry(demux_id)
+                    .or_insert_with(|| ReactionRateLimiter::new(&limit, now))
+                    .try_take(&limit, now)
+                {
+                    debug!(
+                        "group_call::handle_reaction dropping reaction from demux_id = {} over the rate limit",
+                        demux_id
+                    );
+                    return;
+                }
+                Self::add_reaction(state, demux_id, value, now);
             });
+        } else {
+            warn!("group_call::handle_reaction reaction value is not a single emoji");
         }
     }
 
-    fn handle_raised_hands(actor: &Actor<State>, raised_hands: Vec<DemuxId>, server_seqnum: u32) {
-        actor.send(move |state| {
-            // The server has previously received a hand raise request from the client or admin
-            if server_seqnum != 0 {
-                if server_seqnum >= state.raise_hand_state.seqnum {
-                    // Set the local raised hand seqnum to the latest from the server
-                    state.raise_hand_state.seqnum = server_seqnum;
-
-                    // Issue a callback when the seqnum value of the local demux id in the
-                    // servers list is equal to or greater than the local seqnum and a raised
+    fn add_reaction(state: &mut State, demux_id: DemuxId, value: String, now: Instant) {
+        if let Some(reaction) = state.reactions.iter_mut().find(|r| r.value == value) {
+            reaction.count = reaction.count.saturating_add(1);
+            if !reaction.sender_demux_ids.contains(&demux_id) {
+                reaction.sender_demux_ids.push(demux_id);
+            }
+        } else {
+            state.reactions.push(Reaction {
+                demux_id,
+                value,
+                count: 1,
+                sender_demux_ids: vec![demux_id],
+            });
+        }
+        if state.next_reactions_time.is_none() && !state.reaction_aggregation_window.is_zero() {
+            state.next_reactions_time = Some(now + state.reaction_aggregation_window);
+        }
+    }
+
+    fn handle_chat_message(
+        &self,
+        demux_id: DemuxId,
+        chat_message: protobuf::group_call::device_to_device::ChatMessage,
+    ) {
+        trace!("handle_chat_message(): demux_id = {}", demux_id);
+
+        let (Some(id), Some(text)) = (chat_message.id, chat_message.text) else {
+            warn!("group_call::handle_chat_message missing id or text");
+            return;
+        };
+        if text.is_empty() || text.len() > CHAT_MESSAGE_MAX_SIZE {
+            warn!(
+                "group_call::handle_chat_message text size of {} is not allowed",
+                text.len()
+            );
+            return;
+        }
+        let timestamp = SystemTime::UNIX_EPOCH
+            + Duration::from_millis(chat_message.timestamp_millis.unwrap_or_default());
+
+        self.actor.send(move |state| {
+            // Ack even if we've seen it before, since the previous ack might have been lost.
+            state
+                .pending_chat_acks
+                .entry(demux_id)
+                .or_default()
+                .push(id);
+            let received_ids = state.received_chat_message_ids.entry(demux_id).or_default();
+            let oldest_tracked_id = received_ids.last().map_or(0, |highest_id| {
+                highest_id.saturating_sub(CHAT_MESSAGE_ID_WINDOW)
+            });
+            let is_new = id > oldest_tracked_id && received_ids.insert(id);
+            if is_new {
+                let oldest_tracked_id = received_ids.last().map_or(0, |highest_id| {
+                    highest_id.saturating_sub(CHAT_MESSAGE_ID_WINDOW)
+                });
+                while received_ids
+                    .first()
+                    .is_some_and(|first_id| *first_id <= oldest_tracked_id)
+                {
+                    received_ids.pop_first();
+                }
+                state.chat_messages.push(ChatMessage {
+                    demux_id,
+                    id,
+                    timestamp,
+                    text,
+                });
+            }
+        });
+    }
+
+    fn handle_chat_ack(
+        &self,
+        acker_demux_id: DemuxId,
+        chat_ack: protobuf::group_call::device_to_device::ChatAck,
+    ) {
+        self.actor.send(move |state| {
+            // Acks are broadcast, so ignore the ones for other devices' messages.
+            match (chat_ack.demux_id, state.join_state) {
+                (Some(demux_id), JoinState::Joined(local_demux_id))
+                    if demux_id == local_demux_id => {}
+                _ => return,
+            }
+            for message in &mut state.outgoing_chat_messages {
+                if chat_ack.ids.contains(&message.id) {
+                    message.unacked_demux_ids.remove(&acker_demux_id);
+                }
+            }
+            state
+                .outgoing_chat_messages
+                .retain(|message| !message.unacked_demux_ids.is_empty());
+        });
+    }
+
+    fn handle_raised_hands(actor: &Actor<State>, raised_hands: Vec<DemuxId>, server_seqnum: u32) {
+        actor.send(move |state| {
+            // The server has previously received a hand raise request from the client or admin
+            if server_seqnum != 0 {
+                if server_seqnum >= state.raise_hand_state.seqnum {
+                    // Set the local raised hand seqnum to the latest from the server
+                    state.raise_hand_state.seqnum = server_seqnum;
+
+                    // Issue a callback when the seqnum value of the local demux id in the
+                    // servers list is equal to or greater than the local seqnum and a raised
                     // hand is outstanding. This ensures that a client will get a callback to
                     // "unlock" the UI state even if the raised hand list is the same as before.
                     if state.raise_hand_state.outstanding {
@@ -4216,6 +5344,8 @@ impl Client {
 // more convenient (fewer "if let Some(x) = x" to do).
 struct PeerConnectionObserverImpl {
     client: Option<Client>,
+    // Matches State::peer_connection_generation while this is the current observer.
+    peer_connection_generation: u32,
     incoming_video_sink: Option<Box<dyn VideoSink>>,
     last_height_by_demux_id: CallMutex<HashMap<DemuxId, u32>>,
 }
@@ -4223,10 +5353,12 @@ struct PeerConnectionObserverImpl {
 impl PeerConnectionObserverImpl {
     fn uninitialized(
         incoming_video_sink: Option<Box<dyn VideoSink>>,
+        peer_connection_generation: u32,
     ) -> Result<(Box<Self>, PeerConnectionObserver<Self>)> {
         let enable_video_frame_content = incoming_video_sink.is_some();
         let boxed_observer_impl = Box::new(Self {
             client: None,
+            peer_connection_generation,
             incoming_video_sink,
             last_height_by_demux_id: CallMutex::new(HashMap::new(), "last_height_by_demux_id"),
         });
@@ -4276,9 +5408,15 @@ impl PeerConnectionObserverTrait for PeerConnectionObserverImpl {
             ice_connection_state
         );
         if let Some(client) = &self.client {
+            let peer_connection_generation = self.peer_connection_generation;
             client.actor.send(move |state| {
                 debug!("group_call::Client(inner)::handle_ice_connection_state_changed(client_id: {}, state: {:?})", state.client_id, ice_connection_state);
 
+                if peer_connection_generation != state.peer_connection_generation {
+                    debug!("Ignoring ICE connection state from a replaced PeerConnection");
+                    return;
+                }
+
                 match (state.connection_state, ice_connection_state) {
                     (ConnectionState::Connecting, IceConnectionState::Disconnected) |
                     (ConnectionState::Connecting, IceConnectionState::Closed) |
@@ -4302,8 +5440,27 @@ impl PeerConnectionObserverTrait for PeerConnectionObserverImpl {
                     (ConnectionState::Reconnecting, IceConnectionState::Connected) |
                     (ConnectionState::Reconnecting, IceConnectionState::Completed) => {
                         // The connectivity problems have gone away it seems.
+                        if state.connection_recovery_state.take().is_some() {
+                            info!("Recovered the connection to the SFU");
+                            // The replacement PeerConnection is up, so the ones it replaced
+                            // won't be needed for any more callbacks.
+                            state.retired_peer_connections.clear();
+                        }
                         Client::set_connection_state_and_notify_observer(state, ConnectionState::Connected);
                     }
+                    (ConnectionState::Reconnecting, IceConnectionState::New) |
+                    (ConnectionState::Reconnecting, IceConnectionState::Checking) |
+                    (ConnectionState::Reconnecting, IceConnectionState::Disconnected) if state.connection_recovery_state.is_some() => {
+                        // A replacement PeerConnection is on its way to connecting.
+                    }
+                    (ConnectionState::Connected, IceConnectionState::Failed) |
+                    (ConnectionState::Connected, IceConnectionState::Closed) |
+                    (ConnectionState::Reconnecting, IceConnectionState::Failed) |
+                    (ConnectionState::Reconnecting, IceConnectionState::Closed) => {
+                        // The connectivity problems persisted.  Try rejoining the SFU
+                        // with a new PeerConnection before giving up.
+                        Client::recover_connection_or_end(state);
+                    }
                     (_, IceConnectionState::Failed) |
                     (_, IceConnectionState::Closed) => {
                         // The connectivity problems persisted.  ICE has failed.
@@ -4631,8 +5788,10 @@ mod tests {
         local_demux_id: DemuxId,
         call_creator: Option<UserId>,
         request_count: Arc<AtomicU64>,
+        join_count: Arc<AtomicU64>,
         era_id: String,
         response_join_state: Arc<Mutex<JoinState>>,
+        is_admin: bool,
     }
 
     impl FakeSfuClient {
@@ -4649,8 +5808,10 @@ mod tests {
                 local_demux_id,
                 call_creator,
                 request_count: Arc::new(AtomicU64::new(0)),
+                join_count: Arc::new(AtomicU64::new(0)),
                 era_id: "1111111111111111".to_string(),
                 response_join_state: Arc::new(Mutex::new(JoinState::Joined(local_demux_id))),
+                is_admin: false,
             }
         }
 
@@ -4668,10 +5829,15 @@ mod tests {
         pub fn request_count(&self) -> u64 {
             self.request_count.load(atomic::Ordering::SeqCst)
         }
+
+        pub fn join_count(&self) -> u64 {
+            self.join_count.load(atomic::Ordering::SeqCst)
+        }
     }
 
     impl SfuClient for FakeSfuClient {
         fn join(&mut self, _ice_ufrag: &str, _dhe_pub_key: [u8; 32], client: Client) {
+            self.join_count.fetch_add(1, atomic::Ordering::SeqCst);
             client.on_sfu_client_joined(Ok(Joined {
                 sfu_info: self.sfu_info.clone(),
                 local_demux_id: self.local_demux_id,
@@ -4680,6 +5846,7 @@ mod tests {
                 creator: self.call_creator.clone(),
                 era_id: self.era_id.clone(),
                 join_state: self.get_response_join_state(),
+                is_admin: self.is_admin,
             }));
         }
         fn peek(&mut self, _peek_result_callback: PeekResultCallback) {
@@ -4758,16 +5925,21 @@ mod tests {
         sent_group_signaling_messages: Arc<CallMutex<Vec<protobuf::signaling::CallMessage>>>,
 
         connecting: Event,
+        connected: Event,
+        reconnecting: Event,
         joined: Event,
         peek_changed: Event,
         reactions_called: Event,
+        chat_messages_called: Event,
         remote_devices_changed: Event,
         remote_devices: Arc<CallMutex<Vec<RemoteDeviceState>>>,
         remote_devices_at_join_time: Arc<CallMutex<Vec<RemoteDeviceState>>>,
         peek_state: Arc<CallMutex<FakeObserverPeekState>>,
         send_rates: Arc<CallMutex<Option<SendRates>>>,
         ended: Waitable<EndReason>,
+        remote_mute_request: Waitable<DemuxId>,
         reactions: Arc<CallMutex<Vec<Reaction>>>,
+        chat_messages: Arc<CallMutex<Vec<ChatMessage>>>,
 
         request_membership_proof_invocation_count: Arc<AtomicU64>,
         request_group_members_invocation_count: Arc<AtomicU64>,
@@ -4794,9 +5966,12 @@ mod tests {
                     "FakeObserver sent group messages",
                 )),
                 connecting: Event::default(),
+                connected: Event::default(),
+                reconnecting: Event::default(),
                 joined: Event::default(),
                 peek_changed: Event::default(),
                 reactions_called: Event::default(),
+                chat_messages_called: Event::default(),
                 remote_devices_changed: Event::default(),
                 remote_devices: Arc::new(CallMutex::new(Vec::new(), "FakeObserver remote devices")),
                 remote_devices_at_join_time: Arc::new(CallMutex::new(
@@ -4809,7 +5984,12 @@ mod tests {
                 )),
                 send_rates: Arc::new(CallMutex::new(None, "FakeObserver send rates")),
                 ended: Waitable::default(),
+                remote_mute_request: Waitable::default(),
                 reactions: Arc::new(CallMutex::new(Default::default(), "FakeObserver reactions")),
+                chat_messages: Arc::new(CallMutex::new(
+                    Default::default(),
+                    "FakeObserver chat messages",
+                )),
                 request_membership_proof_invocation_count: Default::default(),
                 request_group_members_invocation_count: Default::default(),
                 handle_remote_devices_changed_invocation_count: Default::default(),
@@ -4882,6 +6062,14 @@ mod tests {
             reactions.clone()
         }
 
+        fn chat_messages(&self) -> Vec<ChatMessage> {
+            let chat_messages = self
+                .chat_messages
+                .lock()
+                .expect("Lock chat messages to read them");
+            chat_messages.clone()
+        }
+
         /// Gets the number of `request_membership_proof` since last checked.
         fn request_membership_proof_invocation_count(&self) -> u64 {
             self.request_membership_proof_invocation_count
@@ -4946,8 +6134,11 @@ mod tests {
             _client_id: ClientId,
             connection_state: ConnectionState,
         ) {
-            if connection_state == ConnectionState::Connecting {
-                self.connecting.set();
+            match connection_state {
+                ConnectionState::Connecting => self.connecting.set(),
+                ConnectionState::Connected => self.connected.set(),
+                ConnectionState::Reconnecting => self.reconnecting.set(),
+                ConnectionState::NotConnected => {}
             }
         }
 
@@ -5007,8 +6198,21 @@ mod tests {
             self.reactions_called.set();
         }
 
+        fn handle_chat_messages(&self, _client_id: ClientId, messages: Vec<ChatMessage>) {
+            let mut owned = self
+                .chat_messages
+                .lock()
+                .expect("Lock chat messages to handle update");
+            owned.extend(messages);
+            self.chat_messages_called.set();
+        }
+
         fn handle_raised_hands(&self, _client_id: ClientId, _raised_hands: Vec<DemuxId>) {}
 
+        fn handle_remote_mute_request(&self, _client_id: ClientId, mute_source: DemuxId) {
+            self.remote_mute_request.set(mute_source);
+        }
+
         fn handle_rtc_stats_report(&self, _report_json: String) {}
 
         fn handle_peek_changed(
@@ -5361,6 +6565,21 @@ mod tests {
             devices.iter().map(|device| device.demux_id).collect()
         }
 
+        fn set_ice_connection_state_and_wait_until_applied(
+            &self,
+            ice_connection_state: IceConnectionState,
+        ) {
+            self.client.actor.send(move |state| {
+                state
+                    .peer_connection_observer_impl
+                    .handle_ice_connection_state_changed(ice_connection_state)
+                    .expect("Handle ICE connection state");
+            });
+            // Once to run the task above, and once more for the task it sends.
+            self.wait_for_client_to_process();
+            self.wait_for_client_to_process();
+        }
+
         fn disconnect_and_wait_until_ended(&self) {
             self.client.disconnect();
             self.observer.ended.wait(Duration::from_secs(5));
@@ -6013,7 +7232,7 @@ mod tests {
 
         set_group_and_wait_until_applied(&[&client1, &client2]);
 
-        let value = "hello".to_string();
+        let value = "👍".to_string();
 
         client1.client.react(value.clone());
         assert!(client2
@@ -6024,7 +7243,282 @@ mod tests {
         assert_eq!(1, client2.observer.reactions_count());
         assert_eq!(1, client2.observer.reactions().len());
         assert_eq!(value, client2.observer.reactions()[0].value.to_string());
-        assert_eq!(1, client2.observer.reactions()[0].demux_id)
+        assert_eq!(1, client2.observer.reactions()[0].demux_id);
+        assert_eq!(1, client2.observer.reactions()[0].count);
+
+        // Not an emoji, so not sent.
+        client1.client.react("hello".to_string());
+        assert!(!client2
+            .observer
+            .reactions_called
+            .wait(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn reactions_are_aggregated() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+        let client2 = TestClient::new(vec![2], 2);
+        client2.connect_join_and_wait_until_joined();
+        let client3 = TestClient::new(vec![3], 3);
+        client3.connect_join_and_wait_until_joined();
+
+        set_group_and_wait_until_applied(&[&client1, &client2, &client3]);
+        client3
+            .client
+            .set_reaction_aggregation_window(Duration::from_secs(1));
+
+        client1.client.react("👍".to_string());
+        client1.client.react("👍".to_string());
+        // Make sure client1's reactions are received first.
+        client1.wait_for_client_to_process();
+        client2.client.react("👍".to_string());
+        client2.client.react("❤".to_string());
+        assert!(client3
+            .observer
+            .reactions_called
+            .wait(Duration::from_secs(5)));
+        assert_eq!(1, client3.observer.handle_reactions_invocation_count());
+
+        let reactions = client3.observer.reactions();
+        assert_eq!(2, reactions.len());
+        assert_eq!("👍", reactions[0].value);
+        assert_eq!(1, reactions[0].demux_id);
+        assert_eq!(3, reactions[0].count);
+        assert_eq!(vec![1, 2], reactions[0].sender_demux_ids);
+        assert_eq!("❤\u{FE0F}", reactions[1].value);
+        assert_eq!(1, reactions[1].count);
+        assert_eq!(vec![2], reactions[1].sender_demux_ids);
+    }
+
+    #[test]
+    fn reactions_are_rate_limited() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+        let client2 = TestClient::new(vec![2], 2);
+        client2.connect_join_and_wait_until_joined();
+
+        set_group_and_wait_until_applied(&[&client1, &client2]);
+        let unlimited = ReactionRateLimit::UNLIMITED;
+        let limit = ReactionRateLimit {
+            burst: 2,
+            interval: Duration::from_secs(60),
+        };
+        client1.client.set_reaction_rate_limits(limit, unlimited);
+        client2
+            .client
+            .set_reaction_aggregation_window(Duration::from_secs(1));
+
+        for _ in 0..5 {
+            client1.client.react("🎉".to_string());
+        }
+        assert!(client2
+            .observer
+            .reactions_called
+            .wait(Duration::from_secs(5)));
+        assert_eq!(2, client2.observer.reactions()[0].count);
+
+        // The receiver limits each sender too.
+        client1
+            .client
+            .set_reaction_rate_limits(unlimited, unlimited);
+        client2.client.set_reaction_rate_limits(unlimited, limit);
+        for _ in 0..5 {
+            client1.client.react("🎉".to_string());
+        }
+        assert!(client2
+            .observer
+            .reactions_called
+            .wait(Duration::from_secs(5)));
+        assert_eq!(2, client2.observer.reactions()[0].count);
+    }
+
+    #[test]
+    fn reaction_rate_limiter() {
+        let limit = ReactionRateLimit {
+            burst: 2,
+            interval: Duration::from_secs(1),
+        };
+        let start = Instant::now();
+        let mut limiter = ReactionRateLimiter::new(&limit, start);
+        assert!(limiter.try_take(&limit, start));
+        assert!(limiter.try_take(&limit, start));
+        assert!(!limiter.try_take(&limit, start));
+        assert!(!limiter.try_take(&limit, start + Duration::from_millis(999)));
+        assert!(limiter.try_take(&limit, start + Duration::from_millis(1000)));
+        assert!(!limiter.try_take(&limit, start + Duration::from_millis(1500)));
+        // Never more than the burst, however long it's been.
+        let later = start + Duration::from_secs(60);
+        assert!(limiter.try_take(&limit, later));
+        assert!(limiter.try_take(&limit, later));
+        assert!(!limiter.try_take(&limit, later));
+    }
+
+    #[test]
+    fn reaction_normalization() {
+        for (value, expected) in [
+            ("👍", "👍"),
+            (" 👍 ", "👍"),
+            ("👍🏽", "👍🏽"),
+            ("❤", "❤\u{FE0F}"),
+            ("❤\u{FE0E}", "❤\u{FE0F}"),
+            ("❤\u{FE0F}", "❤\u{FE0F}"),
+            ("🇺🇸", "🇺🇸"),
+            ("1\u{FE0F}\u{20E3}", "1\u{FE0F}\u{20E3}"),
+            ("👩\u{200D}👩\u{200D}👧", "👩\u{200D}👩\u{200D}👧"),
+            ("🏳\u{FE0F}\u{200D}🌈", "🏳\u{FE0F}\u{200D}🌈"),
+            (
+                "🏴\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}",
+                "🏴\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}",
+            ),
+        ] {
+            assert_eq!(
+                Some(expected.to_string()),
+                normalize_reaction(value),
+                "{value}"
+            );
+        }
+
+        for value in [
+            "",
+            " ",
+            "hello",
+            "a",
+            "1",
+            "👍👍",
+            "👍 👍",
+            "🇺",
+            "🇺🇸🇺",
+            "🏽",
+            "👍\u{200D}",
+            "\u{200D}👍",
+            "🏴\u{E007F}",
+            // Symbols that aren't emoji, even though nearby code points are.
+            "↚",
+            "■",
+            "⌀",
+            "⬀",
+            "☆",
+        ] {
+            assert_eq!(None, normalize_reaction(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn chat_messages() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+
+        let client2 = TestClient::new(vec![2], 2);
+        client2.connect_join_and_wait_until_joined();
+
+        set_group_and_wait_until_applied(&[&client1, &client2]);
+
+        client1.client.send_chat_message("hello".to_string());
+        assert!(client2
+            .observer
+            .chat_messages_called
+            .wait(Duration::from_secs(5)));
+        let messages = client2.observer.chat_messages();
+        assert_eq!(1, messages.len());
+        assert_eq!(1, messages[0].demux_id);
+        assert_eq!(1, messages[0].id);
+        assert_eq!("hello", messages[0].text);
+
+        // client2 acks on its next tick, after which client1 stops resending.
+        std::thread::sleep(TICK_INTERVAL * 3);
+        let (sender, receiver) = mpsc::channel();
+        client1.client.actor.send(move |state| {
+            sender
+                .send(state.outgoing_chat_messages.len())
+                .expect("Send outgoing chat message count");
+        });
+        assert_eq!(
+            0,
+            receiver
+                .recv_timeout(Duration::from_secs(5))
+                .expect("Get outgoing chat message count")
+        );
+
+        // Too big to send.
+        client1
+            .client
+            .send_chat_message("x".repeat(CHAT_MESSAGE_MAX_SIZE + 1));
+        assert!(!client2
+            .observer
+            .chat_messages_called
+            .wait(Duration::from_millis(500)));
+        assert_eq!(1, client2.observer.chat_messages().len());
+    }
+
+    #[test]
+    fn chat_message_resends_are_ignored() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+
+        let message = protobuf::group_call::device_to_device::ChatMessage {
+            id: Some(7),
+            timestamp_millis: Some(1000),
+            text: Some("hello".to_string()),
+        };
+        client1.client.handle_chat_message(2, message.clone());
+        client1.client.handle_chat_message(2, message);
+        assert!(client1
+            .observer
+            .chat_messages_called
+            .wait(Duration::from_secs(5)));
+        let messages = client1.observer.chat_messages();
+        assert_eq!(
+            vec![ChatMessage {
+                demux_id: 2,
+                id: 7,
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(1000),
+                text: "hello".to_string(),
+            }],
+            messages
+        );
+    }
+
+    #[test]
+    fn chat_message_ids_outside_the_window_are_ignored() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+
+        let message = |id: u64| protobuf::group_call::device_to_device::ChatMessage {
+            id: Some(id),
+            timestamp_millis: Some(1000),
+            text: Some(id.to_string()),
+        };
+        client1.client.handle_chat_message(2, message(1));
+        client1
+            .client
+            .handle_chat_message(2, message(CHAT_MESSAGE_ID_WINDOW + 2));
+        // Now too old to tell whether it's a resend.
+        client1.client.handle_chat_message(2, message(1));
+        client1.client.handle_chat_message(2, message(2));
+        client1.wait_for_client_to_process();
+        // Let the received messages go to the observer on the next tick.
+        std::thread::sleep(TICK_INTERVAL * 2);
+        let ids: Vec<u64> = client1
+            .observer
+            .chat_messages()
+            .iter()
+            .map(|message| message.id)
+            .collect();
+        assert_eq!(vec![1, CHAT_MESSAGE_ID_WINDOW + 2], ids);
+
+        let (sender, receiver) = mpsc::channel();
+        client1.client.actor.send(move |state| {
+            sender
+                .send(state.received_chat_message_ids[&2].len())
+                .expect("Send received chat message ID count");
+        });
+        assert_eq!(
+            1,
+            receiver
+                .recv_timeout(Duration::from_secs(5))
+                .expect("Get received chat message ID count")
+        );
     }
 
     #[test]
@@ -6599,6 +8093,212 @@ mod tests {
         client1.disconnect_and_wait_until_ended();
     }
 
+    #[test]
+    fn device_to_sfu_lower_hand_and_request_mute() {
+        use protobuf::group_call::{
+            device_to_sfu::{AdminAction, GenericAdminAction},
+            DeviceToSfu,
+        };
+
+        let mut sfu_client = FakeSfuClient::new(1, None);
+        sfu_client.is_admin = true;
+        let mut client1 = TestClient::with_sfu_client(vec![1], 1, sfu_client);
+
+        let (sender, receiver) = mpsc::channel();
+        client1.sfu_rtp_packet_sender = Some(sender);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[]);
+
+        client1.client.lower_hand(32);
+        client1.client.lower_all_hands();
+        client1.client.request_mute(32);
+
+        for (seqnum, admin_action) in [
+            AdminAction::LowerHand(GenericAdminAction {
+                target_demux_id: Some(32),
+            }),
+            AdminAction::LowerAllHands(GenericAdminAction::default()),
+            AdminAction::RequestMute(GenericAdminAction {
+                target_demux_id: Some(32),
+            }),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let (header, payload) = receiver
+                .recv_timeout(Duration::from_secs(1))
+                .expect("Get RTP packet to SFU");
+            assert_eq!(1, header.ssrc);
+            assert_eq!(
+                DeviceToSfu {
+                    admin_action: Some(admin_action),
+                    mrp_header: Some(MrpHeader {
+                        seqnum: Some(seqnum as u64 + 1),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                DeviceToSfu::decode(&payload[..]).unwrap()
+            );
+        }
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn device_to_sfu_lower_hand_and_request_mute_require_admin() {
+        let mut client1 = TestClient::new(vec![1], 1);
+
+        let (sender, receiver) = mpsc::channel();
+        client1.sfu_rtp_packet_sender = Some(sender);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[]);
+
+        client1.client.lower_hand(32);
+        client1.client.lower_all_hands();
+        client1.client.request_mute(32);
+        client1.wait_for_client_to_process();
+
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn sfu_to_device_lower_hand_and_mute_request() {
+        use protobuf::group_call::{
+            device_to_sfu::RaiseHand,
+            sfu_to_device::{LowerHand, MuteRequest},
+            DeviceToSfu,
+        };
+
+        let mut client1 = TestClient::new(vec![1], 1);
+
+        let (sender, receiver) = mpsc::channel();
+        client1.sfu_rtp_packet_sender = Some(sender);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[]);
+
+        let sfu_header = rtp::Header {
+            pt: RTP_DATA_PAYLOAD_TYPE,
+            ssrc: RTP_DATA_TO_SFU_SSRC,
+            seqnum: 1,
+            timestamp: 1,
+        };
+
+        client1.client.raise_hand(true);
+        let (_header, payload) = receiver
+            .recv_timeout(Duration::from_secs(1))
+            .expect("Get raise hand");
+        assert_eq!(
+            Some(RaiseHand {
+                raise: Some(true),
+                seqnum: Some(1),
+            }),
+            DeviceToSfu::decode(&payload[..]).unwrap().raise_hand
+        );
+
+        client1.client.handle_rtp_received(
+            sfu_header.clone(),
+            &SfuToDevice {
+                lower_hand: Some(LowerHand {
+                    source_demux_id: Some(32),
+                }),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        );
+        let (_header, payload) = receiver
+            .recv_timeout(Duration::from_secs(1))
+            .expect("Get lowered hand");
+        assert_eq!(
+            Some(RaiseHand {
+                raise: Some(false),
+                seqnum: Some(2),
+            }),
+            DeviceToSfu::decode(&payload[..]).unwrap().raise_hand
+        );
+
+        client1.client.handle_rtp_received(
+            sfu_header,
+            &SfuToDevice {
+                mute_request: Some(MuteRequest {
+                    source_demux_id: Some(32),
+                }),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        );
+        assert_eq!(
+            Some(32),
+            client1
+                .observer
+                .remote_mute_request
+                .wait(Duration::from_secs(5))
+        );
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn speaking_summary() {
+        use protobuf::group_call::sfu_to_device::Speaker;
+
+        let client1 = TestClient::new(vec![1], 1);
+        let client2 = TestClient::new(vec![2], 2);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[&client2]);
+
+        let receive_speaker = |demux_id: DemuxId, timestamp: rtp::Timestamp| {
+            client1.client.handle_rtp_received(
+                rtp::Header {
+                    pt: RTP_DATA_PAYLOAD_TYPE,
+                    ssrc: RTP_DATA_TO_SFU_SSRC,
+                    seqnum: timestamp as u16,
+                    timestamp,
+                },
+                &SfuToDevice {
+                    speaker: Some(Speaker {
+                        demux_id: Some(demux_id),
+                    }),
+                    ..Default::default()
+                }
+                .encode_to_vec(),
+            );
+        };
+        receive_speaker(2, 1);
+        receive_speaker(1, 2);
+        // Out of order, so ignored.
+        receive_speaker(2, 1);
+        receive_speaker(2, 3);
+        client1.wait_for_client_to_process();
+
+        let summary = client1.client.speaking_summary();
+        assert_eq!(1, summary.local.turns);
+        assert_eq!(1, summary.remote.len());
+        assert_eq!(2, summary.remote[0].demux_id);
+        assert_eq!(vec![2], summary.remote[0].user_id);
+        assert_eq!(2, summary.remote[0].stats.turns);
+        assert_eq!(None, summary.remote[0].left_time);
+        assert_eq!(
+            vec![(2, false), (1, true), (2, false)],
+            summary
+                .transitions
+                .iter()
+                .map(|transition| (transition.demux_id, transition.is_local))
+                .collect::<Vec<_>>()
+        );
+
+        // Devices that leave are still included.
+        client1.set_remotes_and_wait_until_applied(&[]);
+        let summary = client1.client.speaking_summary();
+        assert_eq!(1, summary.remote.len());
+        assert_eq!(2, summary.remote[0].stats.turns);
+        assert!(summary.remote[0].left_time.is_some());
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
     #[test]
     fn device_to_sfu_approve() {
         use protobuf::group_call::{
@@ -7191,6 +8891,89 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rejoin_after_ice_failure() {
+        let client1 = TestClient::new(vec![1], 1);
+        let client2 = TestClient::new(vec![2], 2);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[&client1, &client2]);
+        client1.set_ice_connection_state_and_wait_until_applied(IceConnectionState::Connected);
+        assert!(client1.observer.connected.wait(Duration::from_secs(5)));
+        assert_eq!(1, client1.sfu_client.join_count());
+        client1.observer.send_signaling_message_invocation_count();
+
+        client1.set_ice_connection_state_and_wait_until_applied(IceConnectionState::Failed);
+        assert!(client1.observer.reconnecting.wait(Duration::from_secs(5)));
+        // Let the SFU's response to the rejoin be processed.
+        client1.wait_for_client_to_process();
+        assert_eq!(2, client1.sfu_client.join_count());
+        // The new media send key goes to client2.
+        assert_eq!(
+            1,
+            client1.observer.send_signaling_message_invocation_count()
+        );
+        assert_eq!(
+            None,
+            client1.observer.ended.wait(Duration::from_millis(100))
+        );
+
+        client1.set_ice_connection_state_and_wait_until_applied(IceConnectionState::Checking);
+        client1.set_ice_connection_state_and_wait_until_applied(IceConnectionState::Connected);
+        assert!(client1.observer.connected.wait(Duration::from_secs(5)));
+        let (sender, receiver) = mpsc::channel();
+        client1.client.actor.send(move |state| {
+            sender
+                .send((
+                    state.connection_recovery_state.is_none(),
+                    state.retired_peer_connections.len(),
+                    state.join_state,
+                ))
+                .expect("Send state");
+        });
+        assert_eq!(
+            (true, 0, JoinState::Joined(1)),
+            receiver
+                .recv_timeout(Duration::from_secs(5))
+                .expect("Get state")
+        );
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn ice_failure_ends_when_recovery_window_expires() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_ice_connection_state_and_wait_until_applied(IceConnectionState::Connected);
+        client1
+            .client
+            .set_connection_recovery_window(Duration::from_millis(100));
+
+        client1.set_ice_connection_state_and_wait_until_applied(IceConnectionState::Failed);
+        assert!(client1.observer.reconnecting.wait(Duration::from_secs(5)));
+        assert_eq!(
+            Some(EndReason::IceFailedAfterConnected),
+            client1.observer.ended.wait(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn ice_failure_ends_without_recovery_window() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_ice_connection_state_and_wait_until_applied(IceConnectionState::Connected);
+        client1
+            .client
+            .set_connection_recovery_window(Duration::ZERO);
+
+        client1.set_ice_connection_state_and_wait_until_applied(IceConnectionState::Failed);
+        assert_eq!(
+            Some(EndReason::IceFailedAfterConnected),
+            client1.observer.ended.wait(Duration::from_secs(5))
+        );
+        assert_eq!(1, client1.sfu_client.join_count());
+    }
+
     #[test]
     fn send_rates() {
         init_logging();
@@ -17,6 +17,7 @@ use crate::core::connection::{Connection, ConnectionType};
 use crate::core::group_call::Reaction;
 use crate::core::{group_call, signaling};
 use crate::lite::{
+    call_links::CallLinkRootKey,
     sfu,
     sfu::{DemuxId, PeekInfo, UserId},
 };
@@ -93,6 +94,25 @@ pub trait Platform: sfu::Delegate + fmt::Debug + fmt::Display + Send + Sized + '
         recovered: bool,
     ) -> Result<()>;
 
+    /// Notify the client application that audio devices were added or
+    /// removed. Only called on platforms whose audio device module reports
+    /// such changes (see CallManager::audio_devices_changed).
+    fn on_audio_devices_changed(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that the call is being escalated to
+    /// the call link with the given root key. The application should create
+    /// a call link call client for it and join.
+    fn on_escalate_to_call_link(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _root_key: &CallLinkRootKey,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Send an offer to a remote peer using the signaling
     /// channel.  Offers are always broadcast to all devices.
     fn on_send_offer(
@@ -265,8 +285,17 @@ pub trait Platform: sfu::Delegate + fmt::Debug + fmt::Display + Send + Sized + '
 
     fn handle_reactions(&self, client_id: group_call::ClientId, reactions: Vec<Reaction>);
 
+    fn handle_chat_messages(
+        &self,
+        _client_id: group_call::ClientId,
+        _messages: Vec<group_call::ChatMessage>,
+    ) {
+    }
+
     fn handle_raised_hands(&self, client_id: group_call::ClientId, raised_hands: Vec<DemuxId>);
 
+    fn handle_remote_mute_request(&self, _client_id: group_call::ClientId, _mute_source: DemuxId) {}
+
     fn handle_rtc_stats_report(&self, _report_json: String) {}
 
     fn handle_ended(&self, client_id: group_call::ClientId, reason: group_call::EndReason);
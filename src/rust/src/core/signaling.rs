@@ -26,6 +26,9 @@ pub enum Version {
     V3,
     // V4 is the same as V3 but replaces SDP with discrete protobuf fields.
     V4,
+    // V5 is the same as V4 but adds an ML-KEM encapsulation to the Diffie-Hellman
+    // exchange used to derive SRTP keys. V5 offers also carry V4 parameters.
+    V5,
 }
 
 impl fmt::Display for Version {
@@ -33,6 +36,7 @@ impl fmt::Display for Version {
         let display = match self {
             Self::V3 => "V3".to_string(),
             Self::V4 => "V4".to_string(),
+            Self::V5 => "V5".to_string(),
         };
         write!(f, "{}", display)
     }
@@ -118,14 +122,33 @@ impl Offer {
     }
 
     pub fn latest_version(&self) -> Version {
-        Version::V4
+        match self.proto {
+            protobuf::signaling::Offer {
+                v4: Some(_),
+                v5: Some(_),
+                ..
+            } => Version::V5,
+            _ => Version::V4,
+        }
     }
 
     pub fn from_v4(
         call_media_type: CallMediaType,
         v4: protobuf::signaling::ConnectionParametersV4,
     ) -> Result<Self> {
-        let proto = protobuf::signaling::Offer { v4: Some(v4) };
+        Self::from_v4_and_v5(call_media_type, v4, None)
+    }
+
+    pub fn from_v4_and_v5(
+        call_media_type: CallMediaType,
+        v4: protobuf::signaling::ConnectionParametersV4,
+        v5: Option<protobuf::signaling::ConnectionParametersV5>,
+    ) -> Result<Self> {
+        let proto = protobuf::signaling::Offer {
+            v4: Some(v4),
+            v5,
+            handoff: None,
+        };
 
         let mut opaque = BytesMut::with_capacity(proto.encoded_len());
         proto.encode(&mut opaque)?;
@@ -133,6 +156,24 @@ impl Offer {
         Self::new(call_media_type, opaque.to_vec())
     }
 
+    /// Returns a copy of this offer marked as taking over an active call
+    /// from another of the caller's devices.
+    pub fn into_handoff(self) -> Result<Self> {
+        let proto = protobuf::signaling::Offer {
+            handoff: Some(true),
+            ..self.proto
+        };
+
+        let mut opaque = BytesMut::with_capacity(proto.encoded_len());
+        proto.encode(&mut opaque)?;
+
+        Self::new(self.call_media_type, opaque.to_vec())
+    }
+
+    pub fn is_handoff(&self) -> bool {
+        self.proto.handoff.unwrap_or(false)
+    }
+
     pub fn to_v4(&self) -> Option<protobuf::signaling::ConnectionParametersV4> {
         match self {
             Self {
@@ -143,12 +184,20 @@ impl Offer {
         }
     }
 
+    pub fn to_v5(&self) -> Option<protobuf::signaling::ConnectionParametersV5> {
+        match self.latest_version() {
+            Version::V5 => self.proto.v5.clone(),
+            _ => None,
+        }
+    }
+
     pub fn to_info_string(&self) -> String {
         format!(
-            "opaque.len={}\tproto.version={}\ttype={}",
+            "opaque.len={}\tproto.version={}\ttype={}\thandoff={}",
             self.opaque.len(),
             self.latest_version(),
-            self.call_media_type
+            self.call_media_type,
+            self.is_handoff()
         )
     }
 }
@@ -174,11 +223,24 @@ impl Answer {
     }
 
     pub fn latest_version(&self) -> Version {
-        Version::V4
+        match self.proto {
+            protobuf::signaling::Answer {
+                v4: Some(_),
+                v5: Some(_),
+            } => Version::V5,
+            _ => Version::V4,
+        }
     }
 
     pub fn from_v4(v4: protobuf::signaling::ConnectionParametersV4) -> Result<Self> {
-        let proto = protobuf::signaling::Answer { v4: Some(v4) };
+        Self::from_v4_and_v5(v4, None)
+    }
+
+    pub fn from_v4_and_v5(
+        v4: protobuf::signaling::ConnectionParametersV4,
+        v5: Option<protobuf::signaling::ConnectionParametersV5>,
+    ) -> Result<Self> {
+        let proto = protobuf::signaling::Answer { v4: Some(v4), v5 };
 
         let mut opaque = BytesMut::with_capacity(proto.encoded_len());
         proto.encode(&mut opaque)?;
@@ -197,6 +259,13 @@ impl Answer {
         }
     }
 
+    pub fn to_v5(&self) -> Option<protobuf::signaling::ConnectionParametersV5> {
+        match self.latest_version() {
+            Version::V5 => self.proto.v5.clone(),
+            _ => None,
+        }
+    }
+
     pub fn to_info_string(&self) -> String {
         format!(
             "opaque.len={}\tproto.version={}",
@@ -347,6 +416,8 @@ pub enum Hangup {
     // If you want to express that you NeedPermission on your device,
     // You can either fill it in or with your own device_id.
     NeedPermission(Option<DeviceId>),
+    // The call continues on the given device of the same user.
+    HandedOffToAnotherDevice(DeviceId),
 }
 
 impl Hangup {
@@ -363,6 +434,9 @@ impl Hangup {
                 (HangupType::BusyOnAnotherDevice, Some(*other_device_id))
             }
             Self::NeedPermission(other_device_id) => (HangupType::NeedPermission, *other_device_id),
+            Self::HandedOffToAnotherDevice(other_device_id) => {
+                (HangupType::HandedOffToAnotherDevice, Some(*other_device_id))
+            }
         }
     }
 
@@ -378,6 +452,7 @@ impl Hangup {
             HangupType::DeclinedOnAnotherDevice => Self::DeclinedOnAnotherDevice(device_id),
             HangupType::BusyOnAnotherDevice => Self::BusyOnAnotherDevice(device_id),
             HangupType::NeedPermission => Self::NeedPermission(Some(device_id)),
+            HangupType::HandedOffToAnotherDevice => Self::HandedOffToAnotherDevice(device_id),
         }
     }
 }
@@ -405,6 +480,7 @@ pub enum HangupType {
     BusyOnAnotherDevice = 3,
     // On either another device or this device
     NeedPermission = 4,
+    HandedOffToAnotherDevice = 5,
 }
 
 impl HangupType {
@@ -415,6 +491,7 @@ impl HangupType {
             2 => Some(HangupType::DeclinedOnAnotherDevice),
             3 => Some(HangupType::BusyOnAnotherDevice),
             4 => Some(HangupType::NeedPermission),
+            5 => Some(HangupType::HandedOffToAnotherDevice),
             _ => None,
         }
     }
@@ -486,4 +563,56 @@ pub struct SenderStatus {
     pub video_enabled: Option<bool>,
     pub sharing_screen: Option<bool>,
     pub audio_enabled: Option<bool>,
+    pub held: Option<bool>,
+    pub device_status: Option<DeviceStatus>,
+}
+
+/// Conditions of the sender's devices beyond what the user chose in the
+/// [`SenderStatus`], such as an unplugged microphone. Unset fields are left
+/// as they were.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct DeviceStatus {
+    pub microphone_disconnected: Option<bool>,
+    pub backgrounded: Option<bool>,
+    pub camera_switching: Option<bool>,
+}
+
+/// Progress of moving a direct call into a call link call, as exchanged
+/// between the two peers.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Escalation {
+    /// The root key of the call link both peers should join.
+    pub root_key: [u8; 16],
+    /// Whether the sender has joined the call link call.
+    pub joined: bool,
+}
+
+impl fmt::Debug for Escalation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Leave out the root key so it doesn't end up in logs.
+        f.debug_struct("Escalation")
+            .field("joined", &self.joined)
+            .finish_non_exhaustive()
+    }
+}
+
+/// What a video upgrade message says about a request to turn an audio call
+/// into a video call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VideoUpgradeType {
+    Request,
+    Accept,
+    Decline,
+    /// The request was withdrawn, for example because it timed out.
+    Cancel,
+}
+
+/// A request to turn an audio call into a video call, or a response to one,
+/// as exchanged between the two peers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VideoUpgrade {
+    /// Identifies the request, so that responses to an earlier request
+    /// aren't taken for responses to a later one.
+    pub request_id: u32,
+    pub upgrade_type: VideoUpgradeType,
 }
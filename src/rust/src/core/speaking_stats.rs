@@ -0,0 +1,331 @@
+//
+// Copyright 2026 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Speaking statistics for group calls: who talked, for how long and in what order.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::lite::sfu::{DemuxId, UserId};
+use crate::webrtc::peer_connection::{AudioLevel, ReceivedAudioLevel};
+
+/// How many changes of active speaker are remembered.
+pub const SPEAKER_TRANSITION_HISTORY_LEN: usize = 50;
+
+/// Audio levels (which go up to 32767) at or above this count as speaking.
+pub const SPEAKING_AUDIO_LEVEL_THRESHOLD: AudioLevel = 1000;
+
+/// Statistics accumulated for one device over the course of a call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpeakingStats {
+    /// How long the device was the active speaker, according to the SFU.
+    pub active_speaker_time: Duration,
+    /// How many times the device became the active speaker.
+    pub turns: u32,
+    /// How long the device's audio level was high enough to count as speaking.
+    /// Only accumulated while audio levels are being polled.
+    pub talk_time: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteSpeakingStats {
+    pub demux_id: DemuxId,
+    pub user_id: UserId,
+    /// When the device left the call, if it has.
+    pub left_time: Option<SystemTime>,
+    pub stats: SpeakingStats,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpeakerTransition {
+    /// The device that became the active speaker.
+    pub demux_id: DemuxId,
+    pub is_local: bool,
+    pub time: SystemTime,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpeakingSummary {
+    pub local: SpeakingStats,
+    /// Every remote device seen during the call, including those that have left,
+    /// in the order they were first seen.
+    pub remote: Vec<RemoteSpeakingStats>,
+    /// The most recent changes of active speaker, oldest first.
+    pub transitions: Vec<SpeakerTransition>,
+}
+
+impl SpeakingSummary {
+    /// Remote devices ordered from most to least talkative.
+    pub fn most_active_speakers(&self) -> Vec<&RemoteSpeakingStats> {
+        let mut remote: Vec<&RemoteSpeakingStats> = self.remote.iter().collect();
+        // A stable sort keeps devices that never spoke in the order they were seen.
+        remote.sort_by(|a, b| {
+            (b.stats.talk_time, b.stats.active_speaker_time)
+                .cmp(&(a.stats.talk_time, a.stats.active_speaker_time))
+        });
+        remote
+    }
+}
+
+/// Accumulates SpeakingStats from active speaker changes and audio levels.
+#[derive(Debug, Default)]
+pub struct SpeakingStatsTracker {
+    local: SpeakingStats,
+    remote: Vec<RemoteSpeakingStats>,
+    transitions: VecDeque<SpeakerTransition>,
+    // The current active speaker (demux_id, is_local) and when it became active.
+    active_speaker: Option<(DemuxId, bool, Instant)>,
+}
+
+impl SpeakingStatsTracker {
+    /// Starts tracking any new remote devices and notes which ones have left.
+    pub fn update_remote_devices<'a>(
+        &mut self,
+        devices: impl IntoIterator<Item = (DemuxId, &'a UserId)>,
+        now: Instant,
+        time: SystemTime,
+    ) {
+        let mut present = Vec::new();
+        for (demux_id, user_id) in devices {
+            present.push(demux_id);
+            if let Some(remote) = self.find_mut(demux_id) {
+                // Demux IDs aren't reused within a call, but be safe if they are.
+                remote.left_time = None;
+            } else {
+                self.remote.push(RemoteSpeakingStats {
+                    demux_id,
+                    user_id: user_id.clone(),
+                    left_time: None,
+                    stats: SpeakingStats::default(),
+                });
+            }
+        }
+
+        let mut active_speaker_left = false;
+        for remote in &mut self.remote {
+            if remote.left_time.is_none() && !present.contains(&remote.demux_id) {
+                remote.left_time = Some(time);
+                if matches!(self.active_speaker, Some((demux_id, false, _)) if demux_id == remote.demux_id)
+                {
+                    active_speaker_left = true;
+                }
+            }
+        }
+        if active_speaker_left {
+            self.end_active_speaker(now);
+        }
+    }
+
+    /// Records that the SFU chose a new active speaker. Unknown remote devices are ignored.
+    pub fn on_active_speaker(
+        &mut self,
+        demux_id: DemuxId,
+        is_local: bool,
+        now: Instant,
+        time: SystemTime,
+    ) {
+        if matches!(self.active_speaker, Some((active, active_is_local, _)) if active == demux_id && active_is_local == is_local)
+        {
+            return;
+        }
+        if !is_local && self.find_mut(demux_id).is_none() {
+            return;
+        }
+
+        self.end_active_speaker(now);
+        self.active_speaker = Some((demux_id, is_local, now));
+        if let Some(stats) = self.stats_mut(demux_id, is_local) {
+            stats.turns = stats.turns.saturating_add(1);
+        }
+        if self.transitions.len() == SPEAKER_TRANSITION_HISTORY_LEN {
+            self.transitions.pop_front();
+        }
+        self.transitions.push_back(SpeakerTransition {
+            demux_id,
+            is_local,
+            time,
+        });
+    }
+
+    /// Adds `elapsed` to the talk time of every device whose level counts as speaking.
+    /// `elapsed` should be the time since the levels were last polled.
+    pub fn on_audio_levels(
+        &mut self,
+        captured_level: AudioLevel,
+        received_levels: &[ReceivedAudioLevel],
+        elapsed: Duration,
+    ) {
+        if captured_level >= SPEAKING_AUDIO_LEVEL_THRESHOLD {
+            self.local.talk_time += elapsed;
+        }
+        for received in received_levels {
+            if received.level >= SPEAKING_AUDIO_LEVEL_THRESHOLD {
+                if let Some(remote) = self.find_mut(received.demux_id) {
+                    if remote.left_time.is_none() {
+                        remote.stats.talk_time += elapsed;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The statistics so far, including the current active speaker's ongoing turn.
+    pub fn summary(&self, now: Instant) -> SpeakingSummary {
+        let mut summary = SpeakingSummary {
+            local: self.local.clone(),
+            remote: self.remote.clone(),
+            transitions: self.transitions.iter().cloned().collect(),
+        };
+        if let Some((demux_id, is_local, since)) = self.active_speaker {
+            let ongoing = now.saturating_duration_since(since);
+            if is_local {
+                summary.local.active_speaker_time += ongoing;
+            } else if let Some(remote) = summary.remote.iter_mut().find(|r| r.demux_id == demux_id)
+            {
+                remote.stats.active_speaker_time += ongoing;
+            }
+        }
+        summary
+    }
+
+    fn end_active_speaker(&mut self, now: Instant) {
+        if let Some((demux_id, is_local, since)) = self.active_speaker.take() {
+            if let Some(stats) = self.stats_mut(demux_id, is_local) {
+                stats.active_speaker_time += now.saturating_duration_since(since);
+            }
+        }
+    }
+
+    fn find_mut(&mut self, demux_id: DemuxId) -> Option<&mut RemoteSpeakingStats> {
+        self.remote
+            .iter_mut()
+            .find(|remote| remote.demux_id == demux_id)
+    }
+
+    fn stats_mut(&mut self, demux_id: DemuxId, is_local: bool) -> Option<&mut SpeakingStats> {
+        if is_local {
+            Some(&mut self.local)
+        } else {
+            self.find_mut(demux_id).map(|remote| &mut remote.stats)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(demux_id: DemuxId, level: AudioLevel) -> ReceivedAudioLevel {
+        ReceivedAudioLevel { demux_id, level }
+    }
+
+    #[test]
+    fn active_speaker_turns_and_time() {
+        let start = Instant::now();
+        let time = SystemTime::UNIX_EPOCH;
+        let mut tracker = SpeakingStatsTracker::default();
+        tracker.update_remote_devices([(2, &vec![2]), (3, &vec![3])], start, time);
+
+        tracker.on_active_speaker(2, false, start, time);
+        // Repeats don't start a new turn.
+        tracker.on_active_speaker(2, false, start + Duration::from_secs(1), time);
+        tracker.on_active_speaker(1, true, start + Duration::from_secs(3), time);
+        tracker.on_active_speaker(3, false, start + Duration::from_secs(4), time);
+        tracker.on_active_speaker(2, false, start + Duration::from_secs(6), time);
+        // Not a known device.
+        tracker.on_active_speaker(4, false, start + Duration::from_secs(7), time);
+
+        let summary = tracker.summary(start + Duration::from_secs(8));
+        assert_eq!(1, summary.local.turns);
+        assert_eq!(Duration::from_secs(1), summary.local.active_speaker_time);
+        assert_eq!(2, summary.remote[0].stats.turns);
+        assert_eq!(
+            Duration::from_secs(5),
+            summary.remote[0].stats.active_speaker_time
+        );
+        assert_eq!(1, summary.remote[1].stats.turns);
+        assert_eq!(
+            Duration::from_secs(2),
+            summary.remote[1].stats.active_speaker_time
+        );
+        assert_eq!(
+            vec![(2, false), (1, true), (3, false), (2, false)],
+            summary
+                .transitions
+                .iter()
+                .map(|t| (t.demux_id, t.is_local))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn transition_history_is_bounded() {
+        let start = Instant::now();
+        let time = SystemTime::UNIX_EPOCH;
+        let mut tracker = SpeakingStatsTracker::default();
+        tracker.update_remote_devices([(2, &vec![2]), (3, &vec![3])], start, time);
+        for i in 0..(SPEAKER_TRANSITION_HISTORY_LEN + 1) {
+            tracker.on_active_speaker(2 + (i % 2) as DemuxId, false, start, time);
+        }
+        let summary = tracker.summary(start);
+        assert_eq!(SPEAKER_TRANSITION_HISTORY_LEN, summary.transitions.len());
+        // The first one (demux ID 2) was dropped.
+        assert_eq!(3, summary.transitions[0].demux_id);
+    }
+
+    #[test]
+    fn talk_time_from_audio_levels() {
+        let start = Instant::now();
+        let time = SystemTime::UNIX_EPOCH;
+        let mut tracker = SpeakingStatsTracker::default();
+        tracker.update_remote_devices([(2, &vec![2]), (3, &vec![3])], start, time);
+
+        let interval = Duration::from_millis(200);
+        tracker.on_audio_levels(
+            SPEAKING_AUDIO_LEVEL_THRESHOLD,
+            &[level(2, 20000), level(3, 10)],
+            interval,
+        );
+        tracker.on_audio_levels(0, &[level(2, 20000), level(3, 5000)], interval);
+
+        let summary = tracker.summary(start);
+        assert_eq!(interval, summary.local.talk_time);
+        assert_eq!(interval * 2, summary.remote[0].stats.talk_time);
+        assert_eq!(interval, summary.remote[1].stats.talk_time);
+        assert_eq!(
+            vec![2, 3],
+            summary
+                .most_active_speakers()
+                .iter()
+                .map(|remote| remote.demux_id)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn devices_that_left_are_kept() {
+        let start = Instant::now();
+        let joined = SystemTime::UNIX_EPOCH;
+        let left = joined + Duration::from_secs(10);
+        let mut tracker = SpeakingStatsTracker::default();
+        tracker.update_remote_devices([(2, &vec![2]), (3, &vec![3])], start, joined);
+        tracker.on_active_speaker(3, false, start, joined);
+
+        tracker.update_remote_devices([(2, &vec![2])], start + Duration::from_secs(10), left);
+        tracker.on_audio_levels(0, &[level(3, 20000)], Duration::from_secs(1));
+
+        let summary = tracker.summary(start + Duration::from_secs(20));
+        assert_eq!(2, summary.remote.len());
+        assert_eq!(None, summary.remote[0].left_time);
+        assert_eq!(Some(left), summary.remote[1].left_time);
+        assert_eq!(vec![3], summary.remote[1].user_id);
+        // The turn ended when the device left.
+        assert_eq!(
+            Duration::from_secs(10),
+            summary.remote[1].stats.active_speaker_time
+        );
+        assert_eq!(Duration::ZERO, summary.remote[1].stats.talk_time);
+    }
+}
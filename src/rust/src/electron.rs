@@ -14,10 +14,12 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crate::common::{CallConfig, CallId, CallMediaType, DataMode, DeviceId, Result};
-use crate::core::call_manager::CallManager;
+use crate::core::call_manager::{CallLinkCreateParams, CallManager, RingPolicy};
+use crate::core::candidate_policy::CandidatePolicy;
 use crate::core::group_call;
 use crate::core::group_call::{GroupId, SignalingMessageUrgency};
 use crate::core::signaling;
+use crate::core::speaking_stats::SpeakingStats;
 use crate::core::util::minmax;
 use crate::lite::sfu;
 use crate::lite::{
@@ -29,8 +31,9 @@ use crate::lite::{
     sfu::{DemuxId, GroupMember, PeekInfo, UserId},
 };
 use crate::native::{
-    CallState, CallStateHandler, EndReason, GroupUpdate, GroupUpdateHandler, NativeCallContext,
-    NativePlatform, PeerId, SignalingSender,
+    CallState, CallStateHandler, EndReason, EscalationState, GroupUpdate, GroupUpdateHandler,
+    NativeCallContext, NativePlatform, PeerId, RemoteDeviceCondition, SignalingSender,
+    VideoUpgradeState,
 };
 use crate::webrtc::field_trial;
 use crate::webrtc::media::{
@@ -38,7 +41,8 @@ use crate::webrtc::media::{
 };
 use crate::webrtc::peer_connection::AudioLevel;
 use crate::webrtc::peer_connection_factory::{
-    self as pcf, AudioDevice, IceServer, PeerConnectionFactory, RffiAudioDeviceModuleType,
+    self as pcf, AudioDevice, AudioDevicesChanged, IceServer, PeerConnectionFactory,
+    RffiAudioDeviceModuleType,
 };
 use crate::webrtc::peer_connection_observer::NetworkRoute;
 use neon::types::buffer::TypedArray;
@@ -151,6 +155,21 @@ pub enum Event {
     // Whether the remote is sharing its screen or not changed.
     // Like call state, we ID the call by PeerId and assume there is only one.
     RemoteSharingScreenChange(PeerId, bool),
+    // Whether the remote has put the call on hold or not changed.
+    // Like call state, we ID the call by PeerId and assume there is only one.
+    RemoteHeldChange(PeerId, bool),
+    // Whether the call is on hold locally, because another call is active.
+    // Like call state, we ID the call by PeerId and assume there is only one.
+    LocalHeldChange(PeerId, bool),
+    // Escalating the call to a call link call has made progress.
+    // Like call state, we ID the call by PeerId and assume there is only one.
+    EscalationChange(PeerId, EscalationState),
+    // A request to turn the call into a video call has made progress.
+    // Like call state, we ID the call by PeerId and assume there is only one.
+    VideoUpgradeChange(PeerId, VideoUpgradeState),
+    // A condition of the remote side's devices started or ended.
+    // Like call state, we ID the call by PeerId and assume there is only one.
+    RemoteDeviceConditionChange(PeerId, RemoteDeviceCondition, bool),
     // The group call has an update.
     GroupUpdate(GroupUpdate),
     // A call link request has completed.
@@ -179,6 +198,8 @@ pub enum Event {
         peer_id: PeerId,
         recovered: bool,
     },
+    // Audio devices were added or removed.
+    AudioDevicesChanged(AudioDevicesChanged),
 }
 
 /// Wraps a [`std::sync::mpsc::Sender`] with a callback to report new events.
@@ -298,6 +319,35 @@ impl CallStateHandler for EventReporter {
         ))
     }
 
+    fn handle_remote_held(&self, remote_peer_id: &str, held: bool) -> Result<()> {
+        self.send(Event::RemoteHeldChange(remote_peer_id.to_string(), held))
+    }
+
+    fn handle_local_held(&self, remote_peer_id: &str, held: bool) -> Result<()> {
+        self.send(Event::LocalHeldChange(remote_peer_id.to_string(), held))
+    }
+
+    fn handle_escalation(&self, remote_peer_id: &str, state: EscalationState) -> Result<()> {
+        self.send(Event::EscalationChange(remote_peer_id.to_string(), state))
+    }
+
+    fn handle_video_upgrade(&self, remote_peer_id: &str, state: VideoUpgradeState) -> Result<()> {
+        self.send(Event::VideoUpgradeChange(remote_peer_id.to_string(), state))
+    }
+
+    fn handle_remote_device_condition(
+        &self,
+        remote_peer_id: &str,
+        condition: RemoteDeviceCondition,
+        active: bool,
+    ) -> Result<()> {
+        self.send(Event::RemoteDeviceConditionChange(
+            remote_peer_id.to_string(),
+            condition,
+            active,
+        ))
+    }
+
     fn handle_audio_levels(
         &self,
         remote_peer_id: &str,
@@ -317,6 +367,10 @@ impl CallStateHandler for EventReporter {
             recovered,
         })
     }
+
+    fn handle_audio_devices_changed(&self, change: AudioDevicesChanged) -> Result<()> {
+        self.send(Event::AudioDevicesChanged(change))
+    }
 }
 
 impl http::Delegate for EventReporter {
@@ -352,6 +406,8 @@ pub struct CallEndpoint {
     incoming_video_sink: Box<LastFramesVideoSink>,
 
     peer_connection_factory: PeerConnectionFactory,
+    // Whether 1:1 calls send stereo audio.
+    use_stereo_audio: bool,
 
     // NOTE: This creates a reference cycle, since the JS-side NativeCallManager has a reference
     // to the CallEndpoint box. Since we use the NativeCallManager as a singleton, though, this
@@ -368,12 +424,14 @@ impl CallEndpoint {
         cx: &mut impl Context<'a>,
         js_object: Handle<'a, JsObject>,
         use_ringrtc_adm: bool,
+        use_stereo_audio: bool,
     ) -> Result<Self> {
         // Relevant for both group calls and 1:1 calls
         let (events_sender, events_receiver) = channel::<Event>();
         let mut audio_config = pcf::AudioConfig::default();
         if use_ringrtc_adm {
             audio_config.audio_device_module_type = RffiAudioDeviceModuleType::RingRtc;
+            audio_config.stereo_enabled = use_stereo_audio;
         }
         let peer_connection_factory = PeerConnectionFactory::new(&audio_config, false)?;
         let outgoing_audio_track = peer_connection_factory.create_outgoing_audio_track()?;
@@ -463,6 +521,14 @@ impl CallEndpoint {
         );
         let call_manager = CallManager::new(platform, http_client)?;
 
+        // NOTE: This creates a reference cycle (the factory holds the CallManager, whose
+        // platform holds the factory), but like the js_object below, the CallEndpoint is
+        // used as a singleton, so it isn't a problem in practice.
+        let mut call_manager_for_audio_devices = call_manager.clone();
+        peer_connection_factory.set_audio_devices_changed_callback(Some(Box::new(move || {
+            let _ = call_manager_for_audio_devices.audio_devices_changed();
+        })));
+
         Ok(Self {
             call_manager,
             events_receiver,
@@ -472,6 +538,7 @@ impl CallEndpoint {
             outgoing_video_track,
             incoming_video_sink,
             peer_connection_factory,
+            use_stereo_audio,
             js_object,
             most_recent_overlarge_frame_dimensions: (0, 0),
         })
@@ -683,6 +750,7 @@ fn createCallEndpoint(mut cx: FunctionContext) -> JsResult<JsValue> {
     let js_call_manager = cx.argument::<JsObject>(0)?;
     let field_trial_string = cx.argument::<JsString>(1)?.value(&mut cx);
     let use_ringrtc_adm = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let use_stereo_audio = cx.argument::<JsBoolean>(3)?.value(&mut cx);
 
     if ENABLE_LOGGING {
         let is_first_time_initializing_logger = log::set_logger(&LOG).is_ok();
@@ -707,7 +775,7 @@ fn createCallEndpoint(mut cx: FunctionContext) -> JsResult<JsValue> {
     let _ = field_trial::init(&field_trial_string);
     info!("initialized field trials with {}", field_trial_string);
 
-    let endpoint = CallEndpoint::new(&mut cx, js_call_manager, use_ringrtc_adm)
+    let endpoint = CallEndpoint::new(&mut cx, js_call_manager, use_ringrtc_adm, use_stereo_audio)
         .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
     Ok(cx.boxed(RefCell::new(endpoint)).upcast())
 }
@@ -727,6 +795,42 @@ fn setSelfUuid(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn setRingPolicy(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.setRingPolicy()");
+
+    let outgoing_ring_timeout_millis = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    let incoming_ring_timeout_millis = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+    let max_offer_age_millis = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let group_ring_expiry_millis = cx.argument::<JsNumber>(3)?.value(&mut cx) as u64;
+    let policy = RingPolicy {
+        outgoing_ring_timeout: Duration::from_millis(outgoing_ring_timeout_millis),
+        incoming_ring_timeout: Duration::from_millis(incoming_ring_timeout_millis),
+        max_offer_age: Duration::from_millis(max_offer_age_millis),
+        group_ring_expiry: Duration::from_millis(group_ring_expiry_millis),
+    };
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_ring_policy(policy)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setCallWaitingEnabled(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let enabled = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    debug!("JsCallManager.setCallWaitingEnabled({})", enabled);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_call_waiting_enabled(enabled)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn createOutgoingCall(mut cx: FunctionContext) -> JsResult<JsValue> {
     let peer_id = cx.argument::<JsString>(0)?.value(&mut cx) as PeerId;
@@ -758,6 +862,34 @@ fn createOutgoingCall(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(create_id_arg(&mut cx, call_id.as_u64()))
 }
 
+#[allow(non_snake_case)]
+fn handoffCall(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let peer_id = cx.argument::<JsString>(0)?.value(&mut cx) as PeerId;
+    let call_id = CallId::new(get_id_arg(&mut cx, 1));
+    let video_enabled = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let local_device_id = cx.argument::<JsNumber>(3)?.value(&mut cx) as DeviceId;
+
+    let media_type = if video_enabled {
+        CallMediaType::Video
+    } else {
+        CallMediaType::Audio
+    };
+
+    debug!(
+        "JsCallManager.handoffCall({}, {}, {}, {})",
+        peer_id, call_id, media_type, local_device_id
+    );
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .handoff_call(peer_id, call_id, media_type, local_device_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn cancelGroupRing(mut cx: FunctionContext) -> JsResult<JsValue> {
     debug!("JsCallManager.cancelGroupRing()");
@@ -801,6 +933,7 @@ fn proceed(mut cx: FunctionContext) -> JsResult<JsValue> {
     let hide_ip = cx.argument::<JsBoolean>(2)?.value(&mut cx);
     let data_mode = cx.argument::<JsNumber>(3)?.value(&mut cx) as i32;
     let audio_levels_interval_millis = cx.argument::<JsNumber>(4)?.value(&mut cx) as u64;
+    let js_candidate_policy = cx.argument::<JsObject>(5)?;
 
     info!("proceed(): callId: {}, hideIp: {}", call_id, hide_ip);
     let mut ice_servers = Vec::new();
@@ -832,6 +965,29 @@ fn proceed(mut cx: FunctionContext) -> JsResult<JsValue> {
         ice_servers.push(ice_server);
     }
 
+    let exclude_host = js_candidate_policy
+        .get_opt::<JsBoolean, _, _>(&mut cx, "excludeHost")?
+        .is_some_and(|handle| handle.value(&mut cx));
+    let relay_only = js_candidate_policy
+        .get_opt::<JsBoolean, _, _>(&mut cx, "relayOnly")?
+        .is_some_and(|handle| handle.value(&mut cx));
+    let ipv4_only = js_candidate_policy
+        .get_opt::<JsBoolean, _, _>(&mut cx, "ipv4Only")?
+        .is_some_and(|handle| handle.value(&mut cx));
+    let mut excluded_adapter_types = 0;
+    if let Some(js_adapter_types) =
+        js_candidate_policy.get_opt::<JsArray, _, _>(&mut cx, "excludedAdapterTypes")?
+    {
+        for i in 0..js_adapter_types.len(&mut cx) {
+            excluded_adapter_types |= js_adapter_types
+                .get::<JsNumber, _, _>(&mut cx, i)?
+                .value(&mut cx) as u32;
+        }
+    }
+    let candidate_policy =
+        CandidatePolicy::from_flags(exclude_host, relay_only, ipv4_only, excluded_adapter_types);
+    info!("  candidate policy: {:?}", candidate_policy);
+
     let audio_levels_interval = if audio_levels_interval_millis == 0 {
         None
     } else {
@@ -858,7 +1014,10 @@ fn proceed(mut cx: FunctionContext) -> JsResult<JsValue> {
         endpoint.call_manager.proceed(
             call_id,
             call_context,
-            CallConfig::default().with_data_mode(DataMode::from_i32(data_mode)),
+            CallConfig::default()
+                .with_data_mode(DataMode::from_i32(data_mode))
+                .with_candidate_policy(candidate_policy)
+                .with_stereo_audio(endpoint.use_stereo_audio),
             audio_levels_interval,
         )?;
         Ok(())
@@ -905,6 +1064,140 @@ fn hangup(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn hangupCall(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.hangupCall({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.hangup_call(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn resumeCall(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.resumeCall({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.resume_call(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn requestVideoUpgrade(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.requestVideoUpgrade({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.request_video_upgrade(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn acceptVideoUpgrade(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.acceptVideoUpgrade({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.accept_video_upgrade(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn declineVideoUpgrade(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.declineVideoUpgrade({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.decline_video_upgrade(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setLocalDeviceStatus(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let status = signaling::DeviceStatus {
+        microphone_disconnected: Some(cx.argument::<JsBoolean>(1)?.value(&mut cx)),
+        backgrounded: Some(cx.argument::<JsBoolean>(2)?.value(&mut cx)),
+        camera_switching: Some(cx.argument::<JsBoolean>(3)?.value(&mut cx)),
+    };
+    debug!(
+        "JsCallManager.setLocalDeviceStatus({}, {:?})",
+        call_id, status
+    );
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_local_device_status(call_id, status)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn escalateToCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let root_key_bytes = cx.argument::<JsBuffer>(1)?;
+    let root_key = CallLinkRootKey::try_from(root_key_bytes.as_slice(&cx))
+        .or_else(|e| cx.throw_type_error(e.to_string()))?;
+
+    // The call link is only created first if the SFU URL is given.
+    let sfu_url = cx.argument::<JsValue>(2)?;
+    let create = if sfu_url.is_a::<JsUndefined, _>(&mut cx) {
+        None
+    } else {
+        let sfu_url = sfu_url.downcast_or_throw::<JsString, _>(&mut cx)?;
+        let sfu_url = sfu_url.value(&mut cx);
+        let create_presentation = cx.argument::<JsBuffer>(3)?;
+        let create_presentation = create_presentation.as_slice(&cx).to_vec();
+        let admin_passkey = cx.argument::<JsBuffer>(4)?;
+        let admin_passkey = admin_passkey.as_slice(&cx).to_vec();
+        let public_zkparams = cx.argument::<JsBuffer>(5)?;
+        let public_zkparams = public_zkparams.as_slice(&cx).to_vec();
+        let restrictions = cx.argument::<JsValue>(6)?;
+        let restrictions = jsvalue_to_restrictions(restrictions, &mut cx)?;
+        Some(CallLinkCreateParams {
+            sfu_url,
+            auth_presentation: create_presentation,
+            admin_passkey,
+            public_zkparams,
+            restrictions,
+        })
+    };
+    debug!(
+        "JsCallManager.escalateToCallLink({}, create: {})",
+        call_id,
+        create.is_some()
+    );
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .escalate_to_call_link(call_id, root_key, create)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn signalingMessageSent(mut cx: FunctionContext) -> JsResult<JsValue> {
     let call_id = CallId::new(get_id_arg(&mut cx, 0));
@@ -1637,6 +1930,19 @@ fn groupReact(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn groupSendChatMessage(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let text = cx.argument::<JsString>(1)?.value(&mut cx);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.send_chat_message(client_id, text);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn groupRaiseHand(mut cx: FunctionContext) -> JsResult<JsValue> {
     let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
@@ -1763,6 +2069,46 @@ fn removeClient(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn lowerHand(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let other_demux_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as DemuxId;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.lower_hand(client_id, other_demux_id);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn lowerAllHands(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.lower_all_hands(client_id);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn requestMute(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let other_demux_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as DemuxId;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .request_mute(client_id, other_demux_id);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn blockClient(mut cx: FunctionContext) -> JsResult<JsValue> {
     let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
@@ -2086,6 +2432,69 @@ fn deleteCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+fn to_js_audio_device<'a>(
+    cx: &mut impl Context<'a>,
+    index: u16,
+    device: &AudioDevice,
+) -> JsResult<'a, JsObject> {
+    let js_device = JsObject::new(cx);
+    let name = cx.string(device.name.clone());
+    js_device.set(cx, "name", name)?;
+    let unique_id = cx.string(device.unique_id.clone());
+    js_device.set(cx, "uniqueId", unique_id)?;
+    let index = cx.number(index);
+    js_device.set(cx, "index", index)?;
+    if !device.i18n_key.is_empty() {
+        let i18n_key = cx.string(device.i18n_key.clone());
+        js_device.set(cx, "i18nKey", i18n_key)?;
+    }
+    Ok(js_device)
+}
+
+fn to_js_audio_devices<'a>(
+    cx: &mut impl Context<'a>,
+    devices: &[AudioDevice],
+) -> JsResult<'a, JsArray> {
+    let js_devices = JsArray::new(cx, devices.len());
+    for (i, device) in devices.iter().enumerate() {
+        let js_device = to_js_audio_device(cx, i as u16, device)?;
+        js_devices.set(cx, i as u32, js_device)?;
+    }
+    Ok(js_devices)
+}
+
+/// An audio device to select: either an index into the most recently returned
+/// devices, or a list of preferred unique IDs to try in order before the default.
+enum AudioDeviceArg {
+    Index(u16),
+    PreferredUniqueIds(Vec<String>),
+}
+
+fn get_audio_device_arg(cx: &mut FunctionContext, i: usize) -> NeonResult<AudioDeviceArg> {
+    let js_device = cx.argument::<JsValue>(i)?;
+    if let Ok(js_unique_ids) = js_device.downcast::<JsArray, _>(cx) {
+        let mut unique_ids = Vec::with_capacity(js_unique_ids.len(cx) as usize);
+        for i in 0..js_unique_ids.len(cx) {
+            let unique_id = js_unique_ids.get::<JsString, _, _>(cx, i)?.value(cx);
+            unique_ids.push(unique_id);
+        }
+        Ok(AudioDeviceArg::PreferredUniqueIds(unique_ids))
+    } else {
+        let index = js_device.downcast_or_throw::<JsNumber, _>(cx)?.value(cx) as u16;
+        Ok(AudioDeviceArg::Index(index))
+    }
+}
+
+fn active_audio_device_to_js(
+    mut cx: FunctionContext,
+    device: Option<(u16, AudioDevice)>,
+) -> JsResult<JsValue> {
+    match device {
+        Some((index, device)) => Ok(to_js_audio_device(&mut cx, index, &device)?.upcast()),
+        None => Ok(cx.undefined().upcast()),
+    }
+}
+
 #[allow(non_snake_case)]
 fn getAudioInputs(mut cx: FunctionContext) -> JsResult<JsValue> {
     let devices = with_call_endpoint(&mut cx, |endpoint| {
@@ -2095,31 +2504,20 @@ fn getAudioInputs(mut cx: FunctionContext) -> JsResult<JsValue> {
     })
     .unwrap_or_else(|_| Vec::<AudioDevice>::new());
 
-    let js_devices = JsArray::new(&mut cx, devices.len());
-    for (i, device) in devices.iter().enumerate() {
-        let js_device = JsObject::new(&mut cx);
-        let name = cx.string(device.name.clone());
-        js_device.set(&mut cx, "name", name)?;
-        let unique_id = cx.string(device.unique_id.clone());
-        js_device.set(&mut cx, "uniqueId", unique_id)?;
-        let index = cx.number(i as f64);
-        js_device.set(&mut cx, "index", index)?;
-        if !device.i18n_key.is_empty() {
-            let i18n_key = cx.string(device.i18n_key.clone());
-            js_device.set(&mut cx, "i18nKey", i18n_key)?;
-        }
-        js_devices.set(&mut cx, i as u32, js_device)?;
-    }
-    Ok(js_devices.upcast())
+    Ok(to_js_audio_devices(&mut cx, &devices)?.upcast())
 }
 
 #[allow(non_snake_case)]
 fn setAudioInput(mut cx: FunctionContext) -> JsResult<JsValue> {
-    let index = cx.argument::<JsNumber>(0)?.value(&mut cx) as u16;
-    match with_call_endpoint(&mut cx, |endpoint| {
-        endpoint
+    let device = get_audio_device_arg(&mut cx, 0)?;
+    match with_call_endpoint(&mut cx, |endpoint| match device {
+        AudioDeviceArg::Index(index) => endpoint
+            .peer_connection_factory
+            .set_audio_recording_device(index),
+        AudioDeviceArg::PreferredUniqueIds(unique_ids) => endpoint
             .peer_connection_factory
-            .set_audio_recording_device(index)
+            .set_audio_recording_device_by_id(&unique_ids)
+            .map(|_| ()),
     }) {
         Ok(_) => (),
         Err(err) => error!("setAudioInput failed: {}", err),
@@ -2128,6 +2526,20 @@ fn setAudioInput(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn getActiveAudioInput(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let device = with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .peer_connection_factory
+            .active_audio_recording_device()
+    })
+    .unwrap_or_else(|err| {
+        error!("getActiveAudioInput failed: {}", err);
+        None
+    });
+    active_audio_device_to_js(cx, device)
+}
+
 #[allow(non_snake_case)]
 fn getAudioOutputs(mut cx: FunctionContext) -> JsResult<JsValue> {
     let devices = with_call_endpoint(&mut cx, |endpoint| {
@@ -2135,31 +2547,20 @@ fn getAudioOutputs(mut cx: FunctionContext) -> JsResult<JsValue> {
     })
     .unwrap_or_else(|_| Vec::<AudioDevice>::new());
 
-    let js_devices = JsArray::new(&mut cx, devices.len());
-    for (i, device) in devices.iter().enumerate() {
-        let js_device = JsObject::new(&mut cx);
-        let name = cx.string(device.name.clone());
-        js_device.set(&mut cx, "name", name)?;
-        let unique_id = cx.string(device.unique_id.clone());
-        js_device.set(&mut cx, "uniqueId", unique_id)?;
-        let index = cx.number(i as f64);
-        js_device.set(&mut cx, "index", index)?;
-        if !device.i18n_key.is_empty() {
-            let i18n_key = cx.string(device.i18n_key.clone());
-            js_device.set(&mut cx, "i18nKey", i18n_key)?;
-        }
-        js_devices.set(&mut cx, i as u32, js_device)?;
-    }
-    Ok(js_devices.upcast())
+    Ok(to_js_audio_devices(&mut cx, &devices)?.upcast())
 }
 
 #[allow(non_snake_case)]
 fn setAudioOutput(mut cx: FunctionContext) -> JsResult<JsValue> {
-    let index = cx.argument::<JsNumber>(0)?.value(&mut cx) as u16;
-    match with_call_endpoint(&mut cx, |endpoint| {
-        endpoint
+    let device = get_audio_device_arg(&mut cx, 0)?;
+    match with_call_endpoint(&mut cx, |endpoint| match device {
+        AudioDeviceArg::Index(index) => endpoint
+            .peer_connection_factory
+            .set_audio_playout_device(index),
+        AudioDeviceArg::PreferredUniqueIds(unique_ids) => endpoint
             .peer_connection_factory
-            .set_audio_playout_device(index)
+            .set_audio_playout_device_by_id(&unique_ids)
+            .map(|_| ()),
     }) {
         Ok(_) => (),
         Err(err) => error!("setAudioOutput failed: {}", err),
@@ -2168,6 +2569,20 @@ fn setAudioOutput(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn getActiveAudioOutput(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let device = with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .peer_connection_factory
+            .active_audio_playout_device()
+    })
+    .unwrap_or_else(|err| {
+        error!("getActiveAudioOutput failed: {}", err);
+        None
+    });
+    active_audio_device_to_js(cx, device)
+}
+
 #[allow(non_snake_case)]
 fn setRtcStatsInterval(mut cx: FunctionContext) -> JsResult<JsValue> {
     let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
@@ -2181,6 +2596,121 @@ fn setRtcStatsInterval(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn setConnectionRecoveryWindow(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let window = Duration::from_millis(cx.argument::<JsNumber>(1)?.value(&mut cx) as u64);
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_connection_recovery_window(client_id, window)
+    });
+
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setReactionRateLimits(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let send = group_call::ReactionRateLimit {
+        burst: cx.argument::<JsNumber>(1)?.value(&mut cx) as u32,
+        interval: Duration::from_millis(cx.argument::<JsNumber>(2)?.value(&mut cx) as u64),
+    };
+    let receive = group_call::ReactionRateLimit {
+        burst: cx.argument::<JsNumber>(3)?.value(&mut cx) as u32,
+        interval: Duration::from_millis(cx.argument::<JsNumber>(4)?.value(&mut cx) as u64),
+    };
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_reaction_rate_limits(client_id, send, receive)
+    });
+
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setReactionAggregationWindow(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let window = Duration::from_millis(cx.argument::<JsNumber>(1)?.value(&mut cx) as u64);
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_reaction_aggregation_window(client_id, window)
+    });
+
+    Ok(cx.undefined().upcast())
+}
+
+fn speaking_stats_to_js<'a>(
+    cx: &mut impl Context<'a>,
+    stats: &SpeakingStats,
+) -> JsResult<'a, JsObject> {
+    let js_stats = JsObject::new(cx);
+    let js_active_speaker_time = cx.number(stats.active_speaker_time.as_millis() as f64);
+    js_stats.set(cx, "activeSpeakerMillis", js_active_speaker_time)?;
+    let js_turns = cx.number(stats.turns);
+    js_stats.set(cx, "turns", js_turns)?;
+    let js_talk_time = cx.number(stats.talk_time.as_millis() as f64);
+    js_stats.set(cx, "talkMillis", js_talk_time)?;
+    Ok(js_stats)
+}
+
+#[allow(non_snake_case)]
+fn groupGetSpeakingSummary(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let Some(summary) = with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.speaking_summary(client_id)
+    }) else {
+        return Ok(cx.undefined().upcast());
+    };
+
+    let js_summary = JsObject::new(&mut cx);
+    let js_local = speaking_stats_to_js(&mut cx, &summary.local)?;
+    js_summary.set(&mut cx, "local", js_local)?;
+
+    let js_remote = JsArray::new(&mut cx, summary.remote.len());
+    for (i, remote) in summary.remote.iter().enumerate() {
+        let js_device = speaking_stats_to_js(&mut cx, &remote.stats)?;
+        let js_demux_id = cx.number(remote.demux_id);
+        js_device.set(&mut cx, "demuxId", js_demux_id)?;
+        let js_user_id = to_js_buffer(&mut cx, &remote.user_id);
+        js_device.set(&mut cx, "userId", js_user_id)?;
+        if let Some(left_time) = remote.left_time {
+            let js_left_time = cx.number(
+                left_time
+                    .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as f64,
+            );
+            js_device.set(&mut cx, "leftTime", js_left_time)?;
+        }
+        js_remote.set(&mut cx, i as u32, js_device)?;
+    }
+    js_summary.set(&mut cx, "remote", js_remote)?;
+
+    let js_transitions = JsArray::new(&mut cx, summary.transitions.len());
+    for (i, transition) in summary.transitions.iter().enumerate() {
+        let js_transition = JsObject::new(&mut cx);
+        let js_demux_id = cx.number(transition.demux_id);
+        js_transition.set(&mut cx, "demuxId", js_demux_id)?;
+        let js_is_local = cx.boolean(transition.is_local);
+        js_transition.set(&mut cx, "isLocal", js_is_local)?;
+        let js_time = cx.number(
+            transition
+                .time
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as f64,
+        );
+        js_transition.set(&mut cx, "time", js_time)?;
+        js_transitions.set(&mut cx, i as u32, js_transition)?;
+    }
+    js_summary.set(&mut cx, "transitions", js_transitions)?;
+
+    Ok(js_summary.upcast())
+}
+
 #[allow(non_snake_case)]
 fn processEvents(mut cx: FunctionContext) -> JsResult<JsValue> {
     let this = cx.this::<JsObject>()?;
@@ -2331,6 +2861,8 @@ fn processEvents(mut cx: FunctionContext) -> JsResult<JsValue> {
                     EndReason::AcceptedOnAnotherDevice => "AcceptedOnAnotherDevice",
                     EndReason::DeclinedOnAnotherDevice => "DeclinedOnAnotherDevice",
                     EndReason::BusyOnAnotherDevice => "BusyOnAnotherDevice",
+                    EndReason::HandedOffToAnotherDevice => "HandedOffToAnotherDevice",
+                    EndReason::EscalatedToCallLink => "EscalatedToCallLink",
                 };
                 let age = match reason {
                     EndReason::ReceivedOfferExpired { age } => age,
@@ -2416,6 +2948,74 @@ fn processEvents(mut cx: FunctionContext) -> JsResult<JsValue> {
                 method.call(&mut cx, observer, args)?;
             }
 
+            Event::RemoteHeldChange(peer_id, held) => {
+                let method_name = "onRemoteHeld";
+                let args = [cx.string(peer_id).upcast(), cx.boolean(held).upcast()];
+                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+                method.call(&mut cx, observer, args)?;
+            }
+
+            Event::LocalHeldChange(peer_id, held) => {
+                let method_name = "onLocalHeld";
+                let args = [cx.string(peer_id).upcast(), cx.boolean(held).upcast()];
+                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+                method.call(&mut cx, observer, args)?;
+            }
+
+            Event::EscalationChange(peer_id, state) => {
+                let method_name = "onEscalation";
+                let (state, root_key) = match state {
+                    EscalationState::Started { root_key } => (0, Some(root_key)),
+                    EscalationState::RemoteJoined => (1, None),
+                    EscalationState::Failed => (2, None),
+                };
+                let root_key: Handle<JsValue> = match root_key {
+                    Some(root_key) => {
+                        let mut js_root_key = cx.buffer(root_key.len())?;
+                        js_root_key.as_mut_slice(&mut cx).copy_from_slice(&root_key);
+                        js_root_key.upcast()
+                    }
+                    None => cx.null().upcast(),
+                };
+                let args = [
+                    cx.string(peer_id).upcast(),
+                    cx.number(state).upcast(),
+                    root_key,
+                ];
+                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+                method.call(&mut cx, observer, args)?;
+            }
+
+            Event::VideoUpgradeChange(peer_id, state) => {
+                let method_name = "onVideoUpgrade";
+                let state = match state {
+                    VideoUpgradeState::RemoteRequested => 0,
+                    VideoUpgradeState::RemoteCanceled => 1,
+                    VideoUpgradeState::Accepted => 2,
+                    VideoUpgradeState::Declined => 3,
+                    VideoUpgradeState::TimedOut => 4,
+                };
+                let args = [cx.string(peer_id).upcast(), cx.number(state).upcast()];
+                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+                method.call(&mut cx, observer, args)?;
+            }
+
+            Event::RemoteDeviceConditionChange(peer_id, condition, active) => {
+                let method_name = "onRemoteDeviceCondition";
+                let condition = match condition {
+                    RemoteDeviceCondition::MicrophoneDisconnected => 0,
+                    RemoteDeviceCondition::Backgrounded => 1,
+                    RemoteDeviceCondition::CameraSwitching => 2,
+                };
+                let args = [
+                    cx.string(peer_id).upcast(),
+                    cx.number(condition).upcast(),
+                    cx.boolean(active).upcast(),
+                ];
+                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+                method.call(&mut cx, observer, args)?;
+            }
+
             Event::AudioLevels {
                 peer_id,
                 captured_level,
@@ -2440,6 +3040,19 @@ fn processEvents(mut cx: FunctionContext) -> JsResult<JsValue> {
                 method.call(&mut cx, observer, args)?;
             }
 
+            Event::AudioDevicesChanged(AudioDevicesChanged { playout, recording }) => {
+                let method_name = "onAudioDevicesChanged";
+                let args = [
+                    to_js_audio_devices(&mut cx, &playout.devices)?.upcast(),
+                    to_js_audio_devices(&mut cx, &recording.devices)?.upcast(),
+                    cx.boolean(playout.fell_back_to_default).upcast(),
+                    cx.boolean(recording.fell_back_to_default).upcast(),
+                ];
+
+                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+                method.call(&mut cx, observer, args)?;
+            }
+
             Event::SendHttpRequest {
                 request_id,
                 request:
@@ -2824,6 +3437,15 @@ fn processEvents(mut cx: FunctionContext) -> JsResult<JsValue> {
                     js_reaction.set(&mut cx, "demuxId", js_demux_id)?;
                     let js_value = cx.string(reaction.value);
                     js_reaction.set(&mut cx, "value", js_value)?;
+                    let js_count = cx.number(reaction.count);
+                    js_reaction.set(&mut cx, "count", js_count)?;
+                    let js_sender_demux_ids =
+                        JsArray::new(&mut cx, reaction.sender_demux_ids.len());
+                    for (j, sender_demux_id) in reaction.sender_demux_ids.into_iter().enumerate() {
+                        let js_sender_demux_id = cx.number(sender_demux_id);
+                        js_sender_demux_ids.set(&mut cx, j as u32, js_sender_demux_id)?;
+                    }
+                    js_reaction.set(&mut cx, "senderDemuxIds", js_sender_demux_ids)?;
                     js_reactions.set(&mut cx, i as u32, js_reaction)?;
                 }
 
@@ -2833,6 +3455,29 @@ fn processEvents(mut cx: FunctionContext) -> JsResult<JsValue> {
                 method.call(&mut cx, observer, args)?;
             }
 
+            Event::GroupUpdate(GroupUpdate::ChatMessages(client_id, messages)) => {
+                let method_name = "handleChatMessages";
+
+                let js_messages = JsArray::new(&mut cx, messages.len());
+                for (i, message) in messages.into_iter().enumerate() {
+                    let js_message = JsObject::new(&mut cx);
+                    let js_demux_id = cx.number(message.demux_id);
+                    js_message.set(&mut cx, "demuxId", js_demux_id)?;
+                    let js_id = cx.number(message.id as f64);
+                    js_message.set(&mut cx, "id", js_id)?;
+                    let js_timestamp = cx.number(message.timestamp_as_unix_millis() as f64);
+                    js_message.set(&mut cx, "timestamp", js_timestamp)?;
+                    let js_text = cx.string(message.text);
+                    js_message.set(&mut cx, "text", js_text)?;
+                    js_messages.set(&mut cx, i as u32, js_message)?;
+                }
+
+                let args = [cx.number(client_id).upcast(), js_messages.upcast()];
+
+                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+                method.call(&mut cx, observer, args)?;
+            }
+
             Event::GroupUpdate(GroupUpdate::RaisedHands(client_id, raised_hands)) => {
                 let js_raised_hands = JsArray::new(&mut cx, raised_hands.len());
                 for (i, raised_hand) in raised_hands.into_iter().enumerate() {
@@ -2847,6 +3492,19 @@ fn processEvents(mut cx: FunctionContext) -> JsResult<JsValue> {
                 method.call(&mut cx, observer, args)?;
             }
 
+            Event::GroupUpdate(GroupUpdate::RemoteMuteRequest {
+                client_id,
+                mute_source,
+            }) => {
+                let method_name = "handleRemoteMuteRequest";
+                let args = [
+                    cx.number(client_id).upcast(),
+                    cx.number(mute_source).upcast(),
+                ];
+                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+                method.call(&mut cx, observer, args)?;
+            }
+
             Event::GroupUpdate(GroupUpdate::RtcStatsReportComplete { report_json }) => {
                 let method_name = "handleRtcStatsReportComplete";
                 let args = [cx.string(report_json).upcast()];
@@ -2951,12 +3609,22 @@ fn register(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_value("callEndpointPropertyKey", js_property_key)?;
 
     cx.export_function("cm_setSelfUuid", setSelfUuid)?;
+    cx.export_function("cm_setRingPolicy", setRingPolicy)?;
+    cx.export_function("cm_setCallWaitingEnabled", setCallWaitingEnabled)?;
     cx.export_function("cm_createOutgoingCall", createOutgoingCall)?;
+    cx.export_function("cm_handoffCall", handoffCall)?;
     cx.export_function("cm_cancelGroupRing", cancelGroupRing)?;
     cx.export_function("cm_proceed", proceed)?;
     cx.export_function("cm_accept", accept)?;
     cx.export_function("cm_ignore", ignore)?;
     cx.export_function("cm_hangup", hangup)?;
+    cx.export_function("cm_hangupCall", hangupCall)?;
+    cx.export_function("cm_resumeCall", resumeCall)?;
+    cx.export_function("cm_requestVideoUpgrade", requestVideoUpgrade)?;
+    cx.export_function("cm_acceptVideoUpgrade", acceptVideoUpgrade)?;
+    cx.export_function("cm_declineVideoUpgrade", declineVideoUpgrade)?;
+    cx.export_function("cm_setLocalDeviceStatus", setLocalDeviceStatus)?;
+    cx.export_function("cm_escalateToCallLink", escalateToCallLink)?;
     cx.export_function("cm_signalingMessageSent", signalingMessageSent)?;
     cx.export_function("cm_signalingMessageSendFailed", signalingMessageSendFailed)?;
     cx.export_function("cm_updateDataMode", updateDataMode)?;
@@ -2993,6 +3661,7 @@ fn register(mut cx: ModuleContext) -> NeonResult<()> {
     )?;
     cx.export_function("cm_groupRing", groupRing)?;
     cx.export_function("cm_groupReact", groupReact)?;
+    cx.export_function("cm_groupSendChatMessage", groupSendChatMessage)?;
     cx.export_function("cm_groupRaiseHand", groupRaiseHand)?;
     cx.export_function("cm_resendMediaKeys", resendMediaKeys)?;
     cx.export_function("cm_setDataMode", setDataMode)?;
@@ -3001,6 +3670,9 @@ fn register(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("cm_denyUser", denyUser)?;
     cx.export_function("cm_removeClient", removeClient)?;
     cx.export_function("cm_blockClient", blockClient)?;
+    cx.export_function("cm_lowerHand", lowerHand)?;
+    cx.export_function("cm_lowerAllHands", lowerAllHands)?;
+    cx.export_function("cm_requestMute", requestMute)?;
     cx.export_function("cm_setGroupMembers", setGroupMembers)?;
     cx.export_function("cm_setMembershipProof", setMembershipProof)?;
     cx.export_function("cm_peekGroupCall", peekGroupCall)?;
@@ -3011,9 +3683,21 @@ fn register(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("cm_deleteCallLink", deleteCallLink)?;
     cx.export_function("cm_getAudioInputs", getAudioInputs)?;
     cx.export_function("cm_setAudioInput", setAudioInput)?;
+    cx.export_function("cm_getActiveAudioInput", getActiveAudioInput)?;
     cx.export_function("cm_getAudioOutputs", getAudioOutputs)?;
     cx.export_function("cm_setAudioOutput", setAudioOutput)?;
+    cx.export_function("cm_getActiveAudioOutput", getActiveAudioOutput)?;
     cx.export_function("cm_setRtcStatsInterval", setRtcStatsInterval)?;
+    cx.export_function(
+        "cm_setConnectionRecoveryWindow",
+        setConnectionRecoveryWindow,
+    )?;
+    cx.export_function("cm_setReactionRateLimits", setReactionRateLimits)?;
+    cx.export_function("cm_groupGetSpeakingSummary", groupGetSpeakingSummary)?;
+    cx.export_function(
+        "cm_setReactionAggregationWindow",
+        setReactionAggregationWindow,
+    )?;
     cx.export_function("cm_processEvents", processEvents)?;
     Ok(())
 }
@@ -16,9 +16,11 @@ use crate::ios::call_manager;
 use crate::ios::call_manager::IosCallManager;
 
 use crate::common::{CallConfig, CallMediaType, DataMode, DeviceId};
+use crate::core::call_manager::{CallLinkCreateParams, RingPolicy};
+use crate::core::candidate_policy::CandidatePolicy;
 use crate::core::group_call;
 use crate::core::signaling;
-use crate::lite::call_links::CallLinkRootKey;
+use crate::lite::call_links::{CallLinkRestrictions, CallLinkRootKey};
 use crate::lite::{http, sfu, sfu::DemuxId};
 use crate::webrtc::peer_connection::AudioLevel;
 use crate::webrtc::{self, media, peer_connection_factory as pcf};
@@ -272,6 +274,9 @@ pub struct AppReceivedAudioLevelArray {
 pub struct AppReaction {
     pub demuxId: DemuxId,
     pub value: AppByteSlice,
+    pub count: u32,
+    pub senderDemuxIds: *const DemuxId,
+    pub senderDemuxIdsCount: size_t,
 }
 
 #[repr(C)]
@@ -360,6 +365,12 @@ pub struct AppInterface {
     ),
     pub onLowBandwidthForVideo:
         extern "C" fn(object: *mut c_void, remote: *const c_void, recovered: bool),
+    pub onEscalateToCallLink: extern "C" fn(
+        object: *mut c_void,
+        remote: *const c_void,
+        callId: u64,
+        rootKey: AppByteSlice,
+    ),
     pub onSendOffer: extern "C" fn(
         object: *mut c_void,
         callId: u64,
@@ -557,6 +568,45 @@ pub extern "C" fn ringrtcSetSelfUuid(callManager: *mut c_void, uuid: AppByteSlic
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetRingPolicy(
+    callManager: *mut c_void,
+    outgoingRingTimeoutMillis: u64,
+    incomingRingTimeoutMillis: u64,
+    maxOfferAgeMillis: u64,
+    groupRingExpiryMillis: u64,
+) -> *mut c_void {
+    let policy = RingPolicy {
+        outgoing_ring_timeout: Duration::from_millis(outgoingRingTimeoutMillis),
+        incoming_ring_timeout: Duration::from_millis(incomingRingTimeoutMillis),
+        max_offer_age: Duration::from_millis(maxOfferAgeMillis),
+        group_ring_expiry: Duration::from_millis(groupRingExpiryMillis),
+    };
+    match call_manager::set_ring_policy(callManager as *mut IosCallManager, policy) {
+        Ok(_) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetCallWaitingEnabled(
+    callManager: *mut c_void,
+    enabled: bool,
+) -> *mut c_void {
+    match call_manager::set_call_waiting_enabled(callManager as *mut IosCallManager, enabled) {
+        Ok(_) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_) => ptr::null_mut(),
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcCall(
@@ -579,6 +629,30 @@ pub extern "C" fn ringrtcCall(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcHandoffCall(
+    callManager: *mut c_void,
+    appRemote: *const c_void,
+    callId: u64,
+    callMediaType: i32,
+    appLocalDevice: u32,
+) -> *mut c_void {
+    match call_manager::handoff_call(
+        callManager as *mut IosCallManager,
+        appRemote,
+        callId,
+        CallMediaType::from_i32(callMediaType),
+        appLocalDevice as DeviceId,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcProceed(
@@ -587,17 +661,25 @@ pub extern "C" fn ringrtcProceed(
     appCallContext: AppCallContext,
     dataMode: i32,
     audioLevelsIntervalMillis: u64,
+    excludeHost: bool,
+    relayOnly: bool,
+    ipv4Only: bool,
+    excludedAdapterTypes: u32,
 ) -> *mut c_void {
     let audio_levels_interval = if audioLevelsIntervalMillis == 0 {
         None
     } else {
         Some(Duration::from_millis(audioLevelsIntervalMillis))
     };
+    let candidate_policy =
+        CandidatePolicy::from_flags(excludeHost, relayOnly, ipv4Only, excludedAdapterTypes);
     match call_manager::proceed(
         callManager as *mut IosCallManager,
         callId,
         appCallContext,
-        CallConfig::default().with_data_mode(DataMode::from_i32(dataMode)),
+        CallConfig::default()
+            .with_data_mode(DataMode::from_i32(dataMode))
+            .with_candidate_policy(candidate_policy),
         audio_levels_interval,
     ) {
         Ok(_v) => {
@@ -644,6 +726,143 @@ pub extern "C" fn ringrtcHangup(callManager: *mut c_void) -> *mut c_void {
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcHangupCall(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::hangup_call(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcResumeCall(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::resume_call(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcRequestVideoUpgrade(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::request_video_upgrade(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcAcceptVideoUpgrade(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::accept_video_upgrade(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcDeclineVideoUpgrade(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::decline_video_upgrade(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetLocalDeviceStatus(
+    callManager: *mut c_void,
+    callId: u64,
+    microphoneDisconnected: bool,
+    backgrounded: bool,
+    cameraSwitching: bool,
+) -> *mut c_void {
+    let status = signaling::DeviceStatus {
+        microphone_disconnected: Some(microphoneDisconnected),
+        backgrounded: Some(backgrounded),
+        camera_switching: Some(cameraSwitching),
+    };
+    match call_manager::set_local_device_status(callManager as *mut IosCallManager, callId, status)
+    {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case, clippy::too_many_arguments)]
+pub extern "C" fn ringrtcEscalateToCallLink(
+    callManager: *mut c_void,
+    callId: u64,
+    rootKeyBytes: AppByteSlice,
+    sfuUrl: AppByteSlice,
+    createCredentialPresentation: AppByteSlice,
+    adminPasskey: AppByteSlice,
+    callLinkPublicParams: AppByteSlice,
+    restrictions: i8,
+) -> *mut c_void {
+    let root_key = rootKeyBytes
+        .as_slice()
+        .and_then(|bytes| CallLinkRootKey::try_from(bytes).ok());
+    let root_key = match root_key {
+        Some(root_key) => root_key,
+        None => {
+            error!("Invalid rootKey");
+            return ptr::null_mut();
+        }
+    };
+    // The call link is only created first if the SFU URL is given.
+    let create = match string_from_app_slice(&sfuUrl) {
+        Some(sfu_url) if !sfu_url.is_empty() => Some(CallLinkCreateParams {
+            sfu_url,
+            auth_presentation: byte_vec_from_app_slice(&createCredentialPresentation)
+                .unwrap_or_default(),
+            admin_passkey: byte_vec_from_app_slice(&adminPasskey).unwrap_or_default(),
+            public_zkparams: byte_vec_from_app_slice(&callLinkPublicParams).unwrap_or_default(),
+            restrictions: match restrictions {
+                0 => Some(CallLinkRestrictions::None),
+                1 => Some(CallLinkRestrictions::AdminApproval),
+                _ => None,
+            },
+        }),
+        _ => None,
+    };
+
+    match call_manager::escalate_to_call_link(
+        callManager as *mut IosCallManager,
+        callId,
+        root_key,
+        create,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcCancelGroupRing(
@@ -1445,11 +1664,13 @@ pub extern "C" fn ringrtcIsValidOffer(
     opaque: AppByteSlice,
     messageAgeSec: u64,
     callMediaType: i32,
+    maxOfferAgeMillis: u64,
 ) -> bool {
     match call_manager::validate_offer(
         byte_vec_from_app_slice(&opaque),
         messageAgeSec,
         CallMediaType::from_i32(callMediaType),
+        Duration::from_millis(maxOfferAgeMillis),
     ) {
         Ok(()) => true,
         Err(e) => {
@@ -1464,6 +1685,7 @@ pub extern "C" fn ringrtcIsValidOffer(
 pub extern "C" fn ringrtcIsCallMessageValidOpaqueRing(
     message: AppByteSlice,
     messageAgeSec: u64,
+    maxOfferAgeMillis: u64,
     callbackContext: *mut c_void,
     validateGroupIdAndRing: extern "C" fn(AppByteSlice, i64, *mut c_void) -> bool,
 ) -> bool {
@@ -1476,6 +1698,7 @@ pub extern "C" fn ringrtcIsCallMessageValidOpaqueRing(
     match call_manager::validate_call_message_as_opaque_ring(
         message.unwrap(),
         Duration::from_secs(messageAgeSec),
+        Duration::from_millis(maxOfferAgeMillis),
         |group_id, ring_id| {
             validateGroupIdAndRing(
                 AppByteSlice {
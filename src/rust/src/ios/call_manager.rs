@@ -15,7 +15,7 @@ use crate::ios::api::call_manager_interface::{AppCallContext, AppInterface, AppO
 use crate::ios::ios_platform::IosPlatform;
 
 use crate::common::{CallConfig, CallId, CallMediaType, DataMode, DeviceId, Result};
-use crate::core::call_manager::CallManager;
+use crate::core::call_manager::{CallLinkCreateParams, CallManager, RingPolicy};
 use crate::core::util::{ptr_as_box, ptr_as_mut};
 use crate::core::{call_manager, group_call, signaling};
 use crate::error::RingRtcError;
@@ -46,6 +46,18 @@ pub fn set_self_uuid(call_manager: *mut IosCallManager, uuid: UserId) -> Result<
     call_manager.set_self_uuid(uuid)
 }
 
+/// Updates the ring timeouts and message age limits.
+pub fn set_ring_policy(call_manager: *mut IosCallManager, policy: RingPolicy) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_ring_policy(policy)
+}
+
+/// Enables or disables call waiting for 1:1 calls.
+pub fn set_call_waiting_enabled(call_manager: *mut IosCallManager, enabled: bool) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_call_waiting_enabled(enabled)
+}
+
 /// Application notification to start a new call.
 pub fn call(
     call_manager: *mut IosCallManager,
@@ -61,6 +73,23 @@ pub fn call(
     )
 }
 
+/// Application notification to take over a call from another device
+pub fn handoff_call(
+    call_manager: *mut IosCallManager,
+    app_remote: *const c_void,
+    call_id: u64,
+    call_media_type: CallMediaType,
+    app_local_device: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.handoff_call(
+        AppObject::from(app_remote),
+        CallId::from(call_id),
+        call_media_type,
+        app_local_device,
+    )
+}
+
 /// Application notification to proceed with a new call
 pub fn proceed(
     call_manager: *mut IosCallManager,
@@ -99,6 +128,70 @@ pub fn hangup(call_manager: *mut IosCallManager) -> Result<()> {
     call_manager.hangup()
 }
 
+/// Application notification of local hangup of a specific call, which may be
+/// active, waiting or held.
+pub fn hangup_call(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.hangup_call(call_id)
+}
+
+/// Application notification to resume the held call, putting the active call
+/// (if any) on hold.
+pub fn resume_call(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.resume_call(call_id)
+}
+
+/// Application notification to ask the remote peer to turn the active call
+/// into a video call.
+pub fn request_video_upgrade(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.request_video_upgrade(call_id)
+}
+
+/// Application notification accepting the remote peer's video upgrade
+/// request.
+pub fn accept_video_upgrade(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.accept_video_upgrade(call_id)
+}
+
+/// Application notification declining the remote peer's video upgrade
+/// request.
+pub fn decline_video_upgrade(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.decline_video_upgrade(call_id)
+}
+
+/// Application notification of conditions of the local devices, such as an
+/// unplugged microphone.
+pub fn set_local_device_status(
+    call_manager: *mut IosCallManager,
+    call_id: u64,
+    status: signaling::DeviceStatus,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.set_local_device_status(call_id, status)
+}
+
+/// Application notification to move the active call to a call link call.
+pub fn escalate_to_call_link(
+    call_manager: *mut IosCallManager,
+    call_id: u64,
+    root_key: CallLinkRootKey,
+    create: Option<CallLinkCreateParams>,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let call_id = CallId::from(call_id);
+    call_manager.escalate_to_call_link(call_id, root_key, create)
+}
+
 /// Application notification cancelling a group ring.
 pub fn cancel_group_ring(
     call_manager: *mut IosCallManager,
@@ -634,6 +727,7 @@ pub fn validate_offer(
     opaque: Option<Vec<u8>>,
     age_sec: u64,
     call_media_type: CallMediaType,
+    max_offer_age: Duration,
 ) -> Result<()> {
     let opaque = match opaque {
         Some(v) => v,
@@ -646,24 +740,41 @@ pub fn validate_offer(
         }
     };
 
-    call_manager::validate_offer(&signaling::ReceivedOffer {
-        offer: signaling::Offer::new(call_media_type, opaque)?,
-        age: Duration::from_secs(age_sec),
-        sender_device_id: 1,
-        receiver_device_id: 1,
-        receiver_device_is_primary: true,
-        sender_identity_key: vec![],
-        receiver_identity_key: vec![],
-    })
+    let ring_policy = RingPolicy {
+        max_offer_age,
+        ..Default::default()
+    };
+    call_manager::validate_offer(
+        &signaling::ReceivedOffer {
+            offer: signaling::Offer::new(call_media_type, opaque)?,
+            age: Duration::from_secs(age_sec),
+            sender_device_id: 1,
+            receiver_device_id: 1,
+            receiver_device_is_primary: true,
+            sender_identity_key: vec![],
+            receiver_identity_key: vec![],
+        },
+        &ring_policy,
+    )
     .map_err(|e| anyhow!("{:?}", e))
 }
 
 pub fn validate_call_message_as_opaque_ring(
     message: &[u8],
     age: Duration,
+    max_offer_age: Duration,
     validate_group_ring: impl FnOnce(group_call::GroupIdRef, group_call::RingId) -> bool,
 ) -> Result<()> {
     let message: protobuf::signaling::CallMessage = prost::Message::decode(message)?;
-    call_manager::validate_call_message_as_opaque_ring(&message, age, validate_group_ring)
-        .map_err(|e| anyhow!("{:?}", e))
+    let ring_policy = RingPolicy {
+        max_offer_age,
+        ..Default::default()
+    };
+    call_manager::validate_call_message_as_opaque_ring(
+        &message,
+        age,
+        &ring_policy,
+        validate_group_ring,
+    )
+    .map_err(|e| anyhow!("{:?}", e))
 }
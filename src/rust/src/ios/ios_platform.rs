@@ -27,6 +27,7 @@ use crate::ios::api::call_manager_interface::{
 use crate::ios::error::IosError;
 use crate::ios::ios_media_stream::IosMediaStream;
 use crate::lite::{
+    call_links::CallLinkRootKey,
     sfu,
     sfu::{DemuxId, PeekInfo, PeekResult, UserId},
 };
@@ -256,6 +257,23 @@ impl Platform for IosPlatform {
         Ok(())
     }
 
+    fn on_escalate_to_call_link(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        root_key: &CallLinkRootKey,
+    ) -> Result<()> {
+        info!("on_escalate_to_call_link(): call_id: {}", call_id);
+        let root_key = root_key.bytes();
+        (self.app_interface.onEscalateToCallLink)(
+            self.app_interface.object,
+            remote_peer.ptr,
+            u64::from(call_id),
+            app_slice_from_bytes(Some(&root_key)),
+        );
+        Ok(())
+    }
+
     fn on_send_offer(
         &self,
         remote_peer: &Self::AppRemotePeer,
@@ -625,6 +643,9 @@ impl Platform for IosPlatform {
             .map(|reaction| AppReaction {
                 demuxId: reaction.demux_id,
                 value: app_slice_from_str(Some(&reaction.value)),
+                count: reaction.count,
+                senderDemuxIds: reaction.sender_demux_ids.as_ptr(),
+                senderDemuxIdsCount: reaction.sender_demux_ids.len(),
             })
             .collect();
 
@@ -37,12 +37,14 @@ pub mod core {
     pub mod call_fsm;
     pub mod call_manager;
     pub mod call_mutex;
+    pub mod candidate_policy;
     pub mod connection;
     pub mod connection_fsm;
     pub mod crypto;
     pub mod group_call;
     pub mod platform;
     pub mod signaling;
+    pub mod speaking_stats;
     pub mod util;
 }
 
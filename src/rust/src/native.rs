@@ -15,6 +15,7 @@ use crate::core::connection::{Connection, ConnectionType};
 use crate::core::platform::{Platform, PlatformItem};
 use crate::core::{group_call, signaling};
 use crate::lite::{
+    call_links::CallLinkRootKey,
     sfu,
     sfu::{DemuxId, PeekInfo, PeekResult, UserId},
 };
@@ -22,7 +23,7 @@ use crate::webrtc::media::MediaStream;
 use crate::webrtc::media::{AudioTrack, VideoSink, VideoTrack};
 use crate::webrtc::peer_connection::{AudioLevel, ReceivedAudioLevel};
 use crate::webrtc::peer_connection_factory::{
-    IceServer, PeerConnectionFactory, RffiPeerConnectionKind,
+    AudioDevicesChanged, IceServer, PeerConnectionFactory, RffiPeerConnectionKind,
 };
 use crate::webrtc::peer_connection_observer::{NetworkRoute, PeerConnectionObserver};
 
@@ -120,6 +121,16 @@ pub trait CallStateHandler {
     fn handle_remote_audio_state(&self, remote_peer_id: &str, enabled: bool) -> Result<()>;
     fn handle_remote_video_state(&self, remote_peer_id: &str, enabled: bool) -> Result<()>;
     fn handle_remote_sharing_screen(&self, remote_peer_id: &str, enabled: bool) -> Result<()>;
+    fn handle_remote_held(&self, remote_peer_id: &str, held: bool) -> Result<()>;
+    fn handle_local_held(&self, remote_peer_id: &str, held: bool) -> Result<()>;
+    fn handle_escalation(&self, remote_peer_id: &str, state: EscalationState) -> Result<()>;
+    fn handle_video_upgrade(&self, remote_peer_id: &str, state: VideoUpgradeState) -> Result<()>;
+    fn handle_remote_device_condition(
+        &self,
+        remote_peer_id: &str,
+        condition: RemoteDeviceCondition,
+        active: bool,
+    ) -> Result<()>;
     fn handle_network_route(&self, remote_peer_id: &str, network_route: NetworkRoute)
         -> Result<()>;
     fn handle_audio_levels(
@@ -129,6 +140,7 @@ pub trait CallStateHandler {
         received_level: AudioLevel,
     ) -> Result<()>;
     fn handle_low_bandwidth_for_video(&self, remote_peer_id: &str, recovered: bool) -> Result<()>;
+    fn handle_audio_devices_changed(&self, change: AudioDevicesChanged) -> Result<()>;
 }
 
 // These are the different states a call can be in.
@@ -168,6 +180,40 @@ impl fmt::Debug for CallState {
     }
 }
 
+// The progress of escalating a call to a call link call.
+// Closely tied to call_manager::ApplicationEvent.
+#[derive(Debug)]
+pub enum EscalationState {
+    // Create a call link call client for the root key and join it.
+    Started { root_key: Vec<u8> },
+    RemoteJoined,
+    Failed,
+}
+
+// The progress of a request to turn the call into a video call.
+// Closely tied to call_manager::ApplicationEvent.
+#[derive(Debug)]
+pub enum VideoUpgradeState {
+    // Accept or decline the remote request.
+    RemoteRequested,
+    RemoteCanceled,
+    // Turn on the camera.
+    Accepted,
+    Declined,
+    TimedOut,
+}
+
+// A condition of the remote side's devices that the remote user didn't choose.
+// Closely tied to call_manager::ApplicationEvent.
+#[derive(Debug)]
+pub enum RemoteDeviceCondition {
+    MicrophoneDisconnected,
+    // Video is paused by the OS.
+    Backgrounded,
+    // Video is briefly paused.
+    CameraSwitching,
+}
+
 // These are the different reasons a call can end.
 // Closely tied to call_manager::ApplicationEvent.
 #[derive(Debug)]
@@ -190,6 +236,8 @@ pub enum EndReason {
     AcceptedOnAnotherDevice,
     DeclinedOnAnotherDevice,
     BusyOnAnotherDevice,
+    HandedOffToAnotherDevice,
+    EscalatedToCallLink,
 }
 
 impl fmt::Display for EndReason {
@@ -213,6 +261,8 @@ impl fmt::Display for EndReason {
             EndReason::AcceptedOnAnotherDevice => "AcceptedOnAnotherDevice",
             EndReason::DeclinedOnAnotherDevice => "DeclinedOnAnotherDevice",
             EndReason::BusyOnAnotherDevice => "BusyOnAnotherDevice",
+            EndReason::HandedOffToAnotherDevice => "HandedOffToAnotherDevice",
+            EndReason::EscalatedToCallLink => "EscalatedToCallLink",
         };
         write!(f, "({})", display)
     }
@@ -252,7 +302,12 @@ pub enum GroupUpdate {
         recovered: bool,
     },
     Reactions(group_call::ClientId, Vec<group_call::Reaction>),
+    ChatMessages(group_call::ClientId, Vec<group_call::ChatMessage>),
     RaisedHands(group_call::ClientId, Vec<DemuxId>),
+    RemoteMuteRequest {
+        client_id: group_call::ClientId,
+        mute_source: DemuxId,
+    },
     RtcStatsReportComplete {
         report_json: String,
     },
@@ -282,9 +337,15 @@ impl fmt::Display for GroupUpdate {
             GroupUpdate::Reactions(_, reactions) => {
                 format!("Reactions({:?})", reactions)
             }
+            GroupUpdate::ChatMessages(_, messages) => {
+                format!("ChatMessages({})", messages.len())
+            }
             GroupUpdate::RaisedHands(_, raised_hands) => {
                 format!("RaisedHands({:?})", raised_hands)
             }
+            GroupUpdate::RemoteMuteRequest { mute_source, .. } => {
+                format!("RemoteMuteRequest({})", mute_source)
+            }
             GroupUpdate::RtcStatsReportComplete { .. } => "RtcStatsReportComplete".to_string(),
         };
         write!(f, "({})", display)
@@ -375,6 +436,32 @@ impl NativePlatform {
             .handle_remote_sharing_screen(peer_id, enabled)
     }
 
+    fn send_remote_held(&self, peer_id: &str, held: bool) -> Result<()> {
+        self.state_handler.handle_remote_held(peer_id, held)
+    }
+
+    fn send_local_held(&self, peer_id: &str, held: bool) -> Result<()> {
+        self.state_handler.handle_local_held(peer_id, held)
+    }
+
+    fn send_escalation(&self, peer_id: &str, state: EscalationState) -> Result<()> {
+        self.state_handler.handle_escalation(peer_id, state)
+    }
+
+    fn send_video_upgrade(&self, peer_id: &str, state: VideoUpgradeState) -> Result<()> {
+        self.state_handler.handle_video_upgrade(peer_id, state)
+    }
+
+    fn send_remote_device_condition(
+        &self,
+        peer_id: &str,
+        condition: RemoteDeviceCondition,
+        active: bool,
+    ) -> Result<()> {
+        self.state_handler
+            .handle_remote_device_condition(peer_id, condition, active)
+    }
+
     fn send_signaling(
         &self,
         recipient_id: &str,
@@ -450,7 +537,7 @@ impl Platform for NativePlatform {
             true,  /* enable_video_frame_event */
             true,  /* enable_video_frame_content */
         )?;
-        let kind = if context.hide_ip {
+        let kind = if context.hide_ip || connection.call_config().candidate_policy.relay_only {
             RffiPeerConnectionKind::Relayed
         } else {
             RffiPeerConnectionKind::Direct
@@ -466,6 +553,8 @@ impl Platform for NativePlatform {
         )?;
 
         connection.set_peer_connection(pc)?;
+        connection
+            .set_audio_device_stats(self.peer_connection_factory.audio_device_stats_tracker())?;
         Ok(connection)
     }
 
@@ -617,6 +706,11 @@ impl Platform for NativePlatform {
                 call_id,
                 CallState::Ended(EndReason::BusyOnAnotherDevice),
             ),
+            ApplicationEvent::EndedHandedOffToAnotherDevice => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::HandedOffToAnotherDevice),
+            ),
             ApplicationEvent::RemoteAudioEnable => self.send_remote_audio_state(remote_peer, true),
             ApplicationEvent::RemoteAudioDisable => {
                 self.send_remote_audio_state(remote_peer, false)
@@ -631,6 +725,66 @@ impl Platform for NativePlatform {
             ApplicationEvent::RemoteSharingScreenDisable => {
                 self.send_remote_sharing_screen(remote_peer, false)
             }
+            ApplicationEvent::RemoteHoldEnable => self.send_remote_held(remote_peer, true),
+            ApplicationEvent::RemoteHoldDisable => self.send_remote_held(remote_peer, false),
+            ApplicationEvent::EscalationRemoteJoined => {
+                self.send_escalation(remote_peer, EscalationState::RemoteJoined)
+            }
+            ApplicationEvent::EscalationFailed => {
+                self.send_escalation(remote_peer, EscalationState::Failed)
+            }
+            ApplicationEvent::EndedEscalatedToCallLink => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::EscalatedToCallLink),
+            ),
+            ApplicationEvent::RemoteVideoUpgradeRequested => {
+                self.send_video_upgrade(remote_peer, VideoUpgradeState::RemoteRequested)
+            }
+            ApplicationEvent::RemoteVideoUpgradeCanceled => {
+                self.send_video_upgrade(remote_peer, VideoUpgradeState::RemoteCanceled)
+            }
+            ApplicationEvent::VideoUpgradeAccepted => {
+                self.send_video_upgrade(remote_peer, VideoUpgradeState::Accepted)
+            }
+            ApplicationEvent::VideoUpgradeDeclined => {
+                self.send_video_upgrade(remote_peer, VideoUpgradeState::Declined)
+            }
+            ApplicationEvent::VideoUpgradeTimedOut => {
+                self.send_video_upgrade(remote_peer, VideoUpgradeState::TimedOut)
+            }
+            ApplicationEvent::RemoteMicrophoneDisconnected => self.send_remote_device_condition(
+                remote_peer,
+                RemoteDeviceCondition::MicrophoneDisconnected,
+                true,
+            ),
+            ApplicationEvent::RemoteMicrophoneReconnected => self.send_remote_device_condition(
+                remote_peer,
+                RemoteDeviceCondition::MicrophoneDisconnected,
+                false,
+            ),
+            ApplicationEvent::RemoteBackgrounded => self.send_remote_device_condition(
+                remote_peer,
+                RemoteDeviceCondition::Backgrounded,
+                true,
+            ),
+            ApplicationEvent::RemoteForegrounded => self.send_remote_device_condition(
+                remote_peer,
+                RemoteDeviceCondition::Backgrounded,
+                false,
+            ),
+            ApplicationEvent::RemoteCameraSwitchStarted => self.send_remote_device_condition(
+                remote_peer,
+                RemoteDeviceCondition::CameraSwitching,
+                true,
+            ),
+            ApplicationEvent::RemoteCameraSwitchEnded => self.send_remote_device_condition(
+                remote_peer,
+                RemoteDeviceCondition::CameraSwitching,
+                false,
+            ),
+            ApplicationEvent::LocalHoldEnable => self.send_local_held(remote_peer, true),
+            ApplicationEvent::LocalHoldDisable => self.send_local_held(remote_peer, false),
         }?;
         Ok(())
     }
@@ -676,6 +830,41 @@ impl Platform for NativePlatform {
         self.send_low_bandwidth_for_video(remote_peer, recovered)
     }
 
+    fn on_audio_devices_changed(&mut self) -> Result<()> {
+        let change = self.peer_connection_factory.refresh_audio_devices()?;
+        info!(
+            "NativePlatform::on_audio_devices_changed(): playout: +{} -{}, recording: +{} -{}",
+            change.playout.added.len(),
+            change.playout.removed.len(),
+            change.recording.added.len(),
+            change.recording.removed.len(),
+        );
+        if change.is_empty() {
+            return Ok(());
+        }
+
+        self.state_handler.handle_audio_devices_changed(change)
+    }
+
+    fn on_escalate_to_call_link(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        root_key: &CallLinkRootKey,
+    ) -> Result<()> {
+        info!(
+            "NativePlatform::on_escalate_to_call_link(): remote_peer: {}, call_id: {}",
+            remote_peer, call_id
+        );
+
+        self.send_escalation(
+            remote_peer,
+            EscalationState::Started {
+                root_key: root_key.bytes().to_vec(),
+            },
+        )
+    }
+
     fn on_offer_expired(
         &self,
         remote_peer: &Self::AppRemotePeer,
@@ -928,6 +1117,18 @@ impl Platform for NativePlatform {
         }
     }
 
+    fn handle_chat_messages(
+        &self,
+        client_id: group_call::ClientId,
+        messages: Vec<group_call::ChatMessage>,
+    ) {
+        trace!("NativePlatform::handle_chat_messages(): id: {}", client_id);
+        let result = self.send_group_update(GroupUpdate::ChatMessages(client_id, messages));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
     fn handle_raised_hands(&self, client_id: group_call::ClientId, raised_hands: Vec<DemuxId>) {
         info!("NativePlatform::handle_raised_hands(): id: {}", client_id);
 
@@ -937,6 +1138,21 @@ impl Platform for NativePlatform {
         }
     }
 
+    fn handle_remote_mute_request(&self, client_id: group_call::ClientId, mute_source: DemuxId) {
+        info!(
+            "NativePlatform::handle_remote_mute_request(): id: {}",
+            client_id
+        );
+
+        let result = self.send_group_update(GroupUpdate::RemoteMuteRequest {
+            client_id,
+            mute_source,
+        });
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
     fn handle_join_state_changed(
         &self,
         client_id: group_call::ClientId,
@@ -20,6 +20,7 @@ use crate::core::connection::{Connection, ConnectionType};
 use crate::core::platform::{Platform, PlatformItem};
 use crate::core::{group_call, signaling};
 use crate::lite::{
+    call_links::CallLinkRootKey,
     sfu,
     sfu::{DemuxId, PeekInfo, PeekResult, UserId},
 };
@@ -52,6 +53,8 @@ struct SimStats {
     busy_hangups_sent: AtomicUsize,
     /// Number of need permission hangups sent
     need_permission_hangups_sent: AtomicUsize,
+    /// Number of handed off hangups sent
+    handed_off_hangups_sent: AtomicUsize,
     /// Number of busy messages sent
     busys_sent: AtomicUsize,
     /// Number of start outgoing call events
@@ -99,12 +102,18 @@ pub struct SimPlatform {
     group_call_ring_updates: Arc<Mutex<Vec<GroupCallRingUpdate>>>,
     /// Track outgoing opaque messages
     outgoing_call_messages: Arc<Mutex<Vec<OutgoingCallMessage>>>,
+    /// Track the root keys of call links the app was told to escalate to
+    escalation_root_keys: Arc<Mutex<Vec<[u8; 16]>>>,
     /// Call Manager
     call_manager: Arc<Mutex<Option<CallManager<Self>>>>,
     /// True to manually require message_sent() to be invoked for Ice messages.
     no_auto_message_sent_for_ice: Arc<AtomicBool>,
     /// Last sent message from on_send_ice
     last_ice_sent: Arc<Mutex<Option<signaling::SendIce>>>,
+    /// Last sent message from on_send_offer
+    last_offer_sent: Arc<Mutex<Option<signaling::Offer>>>,
+    /// Last sent message from on_send_answer
+    last_answer_sent: Arc<Mutex<Option<signaling::Answer>>>,
 }
 
 impl fmt::Display for SimPlatform {
@@ -232,6 +241,24 @@ impl Platform for SimPlatform {
         Ok(())
     }
 
+    fn on_escalate_to_call_link(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        root_key: &CallLinkRootKey,
+    ) -> Result<()> {
+        info!(
+            "on_escalate_to_call_link(): remote_peer: {}, call_id: {}",
+            remote_peer, call_id
+        );
+
+        self.escalation_root_keys
+            .lock()
+            .unwrap()
+            .push(root_key.bytes());
+        Ok(())
+    }
+
     fn on_send_offer(
         &self,
         remote_peer: &Self::AppRemotePeer,
@@ -245,6 +272,8 @@ impl Platform for SimPlatform {
             offer.to_info_string()
         );
 
+        *self.last_offer_sent.lock().unwrap() = Some(offer);
+
         if self.force_internal_fault.load(Ordering::Acquire) {
             Err(SimError::SendOfferError.into())
         } else {
@@ -272,6 +301,8 @@ impl Platform for SimPlatform {
             send.answer.to_info_string()
         );
 
+        *self.last_answer_sent.lock().unwrap() = Some(send.answer);
+
         if self.force_internal_fault.load(Ordering::Acquire) {
             Err(SimError::SendAnswerError.into())
         } else {
@@ -364,6 +395,12 @@ impl Platform for SimPlatform {
                         .need_permission_hangups_sent
                         .fetch_add(1, Ordering::AcqRel);
                 }
+                signaling::Hangup::HandedOffToAnotherDevice(_) => {
+                    let _ = self
+                        .stats
+                        .handed_off_hangups_sent
+                        .fetch_add(1, Ordering::AcqRel);
+                }
             }
             if self.force_internal_fault.load(Ordering::Acquire) {
                 self.message_send_failure(call_id).unwrap();
@@ -718,6 +755,14 @@ impl SimPlatform {
         self.last_ice_sent.lock().unwrap().clone()
     }
 
+    pub fn last_offer_sent(&self) -> Option<signaling::Offer> {
+        self.last_offer_sent.lock().unwrap().clone()
+    }
+
+    pub fn last_answer_sent(&self) -> Option<signaling::Answer> {
+        self.last_answer_sent.lock().unwrap().clone()
+    }
+
     pub fn normal_hangups_sent(&self) -> usize {
         self.stats.normal_hangups_sent.load(Ordering::Acquire)
     }
@@ -740,6 +785,10 @@ impl SimPlatform {
             .load(Ordering::Acquire)
     }
 
+    pub fn handed_off_hangups_sent(&self) -> usize {
+        self.stats.handed_off_hangups_sent.load(Ordering::Acquire)
+    }
+
     pub fn busys_sent(&self) -> usize {
         self.stats.busys_sent.load(Ordering::Acquire)
     }
@@ -768,6 +817,10 @@ impl SimPlatform {
         self.stats.call_concluded.load(Ordering::Acquire)
     }
 
+    pub fn take_escalation_root_keys(&self) -> Vec<[u8; 16]> {
+        std::mem::take(&mut *self.escalation_root_keys.lock().unwrap())
+    }
+
     pub fn take_group_call_ring_updates(&self) -> Vec<GroupCallRingUpdate> {
         std::mem::take(&mut *self.group_call_ring_updates.lock().unwrap())
     }
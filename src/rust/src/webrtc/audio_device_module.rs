@@ -6,16 +6,29 @@
 use crate::webrtc;
 use crate::webrtc::audio_device_module_utils::{copy_and_truncate_string, DeviceCollectionWrapper};
 use crate::webrtc::ffi::audio_device_module::RffiAudioTransport;
+use crate::webrtc::peer_connection_factory::{
+    AudioDeviceChangeNotifier, AudioDeviceIdOutcome, AudioDeviceIdRequest, AudioDeviceIdSelector,
+    AudioDeviceStatsTracker,
+};
 use anyhow::anyhow;
-use cubeb::{Context, DeviceId, DeviceType, MonoFrame, Stream, StreamPrefs};
-use cubeb_core::InputProcessingParams;
+use cubeb::{Context, DeviceId, DeviceType, MonoFrame, StereoFrame, Stream, StreamPrefs};
+use cubeb_core::{InputProcessingParams, StreamRef};
 use std::collections::VecDeque;
 use std::ffi::{c_uchar, c_void, CString};
+use std::ops::Deref;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 #[cfg(target_os = "windows")]
 use windows::Win32::System::Com;
 
+#[cfg(feature = "virtual_audio")]
+mod virtual_device;
+
+#[cfg(feature = "virtual_audio")]
+pub use virtual_device::{
+    VirtualAudioClock, VirtualAudioDevice, VirtualAudioSink, VirtualAudioSource,
+};
+
 // Stays in sync with AudioLayer in webrtc
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -45,7 +58,6 @@ pub enum WindowsDeviceType {
 
 /// Return type for need_more_play_data
 #[derive(Clone, Debug)]
-#[allow(dead_code)]
 struct PlayData {
     /// Actual return value of the underlying C function
     success: i32,
@@ -57,7 +69,142 @@ struct PlayData {
     ntp_time: Option<Duration>,
 }
 
-type Frame = MonoFrame<i16>;
+/// A frame as cubeb passes it to the data callbacks. WebRTC instead expects
+/// interleaved samples (left then right for stereo).
+trait InterleavedFrame: Copy + Send + Sync + 'static {
+    const CHANNELS: u32;
+    const LAYOUT: cubeb::ChannelLayout;
+
+    /// Builds a frame from the first CHANNELS samples.
+    fn from_interleaved(samples: &[i16]) -> Self;
+    fn append_interleaved(&self, samples: &mut Vec<i16>);
+}
+
+impl InterleavedFrame for MonoFrame<i16> {
+    const CHANNELS: u32 = 1;
+    const LAYOUT: cubeb::ChannelLayout = cubeb::ChannelLayout::MONO;
+
+    fn from_interleaved(samples: &[i16]) -> Self {
+        Self { m: samples[0] }
+    }
+
+    fn append_interleaved(&self, samples: &mut Vec<i16>) {
+        samples.push(self.m);
+    }
+}
+
+impl InterleavedFrame for StereoFrame<i16> {
+    const CHANNELS: u32 = 2;
+    const LAYOUT: cubeb::ChannelLayout = cubeb::ChannelLayout::STEREO;
+
+    fn from_interleaved(samples: &[i16]) -> Self {
+        Self {
+            l: samples[0],
+            r: samples[1],
+        }
+    }
+
+    fn append_interleaved(&self, samples: &mut Vec<i16>) {
+        samples.push(self.l);
+        samples.push(self.r);
+    }
+}
+
+/// A cubeb stream of either mono or stereo frames.
+enum AdmStream {
+    Mono(Stream<MonoFrame<i16>>),
+    Stereo(Stream<StereoFrame<i16>>),
+}
+
+impl Deref for AdmStream {
+    type Target = StreamRef;
+
+    fn deref(&self) -> &StreamRef {
+        match self {
+            Self::Mono(stream) => stream,
+            Self::Stereo(stream) => stream,
+        }
+    }
+}
+
+/// Measures how far the time between a stream's data callbacks strays from
+/// the duration of audio each one carries.
+#[derive(Default)]
+struct CallbackJitter {
+    last_callback: Option<Instant>,
+}
+
+impl CallbackJitter {
+    /// Call at the start of each callback. Returns None for the first one.
+    fn on_callback(&mut self, frames: usize) -> Option<Duration> {
+        let now = Instant::now();
+        let expected = Duration::from_secs_f64(frames as f64 / SAMPLE_FREQUENCY as f64);
+        let jitter = self
+            .last_callback
+            .map(|last| now.duration_since(last).abs_diff(expected));
+        self.last_callback = Some(now);
+        jitter
+    }
+}
+
+/// Detects input overruns: audio the backend dropped because it wasn't read
+/// in time, which shows up as fewer frames arriving than the time between
+/// callbacks calls for.
+struct InputOverrunDetector {
+    // When counting started, and the frames received since then.
+    start: Option<Instant>,
+    frames: u64,
+    // How many frames the backlog can reach before they count as dropped
+    // rather than late.
+    threshold: u64,
+}
+
+impl InputOverrunDetector {
+    /// `latency` is the stream latency in frames. Callbacks may lag by about
+    /// that much, so only a backlog of more than twice it counts.
+    fn new(latency: u32) -> Self {
+        Self {
+            start: None,
+            frames: 0,
+            threshold: 2 * latency.max(WEBRTC_WINDOW as u32) as u64,
+        }
+    }
+
+    /// Call at the start of each callback. Returns the number of frames
+    /// dropped since the last overrun, if the backlog passed the threshold.
+    fn on_callback(&mut self, frames: usize) -> Option<u64> {
+        self.on_callback_at(Instant::now(), frames)
+    }
+
+    fn on_callback_at(&mut self, now: Instant, frames: usize) -> Option<u64> {
+        let Some(start) = self.start else {
+            // The first callback's frames were captured before it, so
+            // start counting from the next one.
+            self.start = Some(now);
+            return None;
+        };
+        self.frames += frames as u64;
+        let expected =
+            (now.duration_since(start).as_micros() * SAMPLE_FREQUENCY as u128 / 1_000_000) as u64;
+        if expected > self.frames + self.threshold {
+            let dropped = expected - self.frames;
+            self.start = Some(now);
+            self.frames = 0;
+            Some(dropped)
+        } else {
+            if self.frames >= expected {
+                // Caught up; restart so that clock drift doesn't build up.
+                self.start = Some(now);
+                self.frames = 0;
+            }
+            None
+        }
+    }
+}
+
+fn samples_to_duration(samples: u32) -> Duration {
+    Duration::from_micros(samples as u64 * 1_000_000 / SAMPLE_FREQUENCY as u64)
+}
 
 pub struct AudioDeviceModule {
     audio_transport: Arc<Mutex<RffiAudioTransport>>,
@@ -65,10 +212,25 @@ pub struct AudioDeviceModule {
     initialized: bool,
     playout_device: Option<DeviceId>,
     recording_device: Option<DeviceId>,
-    output_stream: Option<Stream<Frame>>,
-    input_stream: Option<Stream<Frame>>,
+    // The most channels the selected devices support.
+    playout_max_channels: u32,
+    recording_max_channels: u32,
+    // Whether stereo may be offered to WebRTC at all.
+    stereo_enabled: bool,
+    stereo_playout: bool,
+    stereo_recording: bool,
+    output_stream: Option<AdmStream>,
+    input_stream: Option<AdmStream>,
     playing: bool,
     recording: bool,
+    // Boxed so that its address, which cubeb holds on to, stays the same.
+    device_change_notifier: Box<AudioDeviceChangeNotifier>,
+    device_change_registered: bool,
+    stats: AudioDeviceStatsTracker,
+    device_id_selector: AudioDeviceIdSelector,
+    // When set, stands in for cubeb and the system's devices.
+    #[cfg(feature = "virtual_audio")]
+    virtual_device: Option<VirtualAudioDevice>,
 }
 
 impl Default for AudioDeviceModule {
@@ -81,10 +243,21 @@ impl Default for AudioDeviceModule {
             initialized: false,
             playout_device: None,
             recording_device: None,
+            playout_max_channels: 1,
+            recording_max_channels: 1,
+            stereo_enabled: false,
+            stereo_playout: false,
+            stereo_recording: false,
             output_stream: None,
             input_stream: None,
             playing: false,
             recording: false,
+            device_change_notifier: Default::default(),
+            device_change_registered: false,
+            stats: Default::default(),
+            device_id_selector: Default::default(),
+            #[cfg(feature = "virtual_audio")]
+            virtual_device: None,
         }
     }
 }
@@ -117,7 +290,17 @@ const SAMPLE_LATENCY: u32 = SAMPLE_FREQUENCY / 100;
 const WEBRTC_WINDOW: usize = SAMPLE_FREQUENCY as usize / 100;
 
 const STREAM_FORMAT: cubeb::SampleFormat = cubeb::SampleFormat::S16NE;
-const NUM_CHANNELS: u32 = 1;
+
+/// Called by cubeb (on one of its own threads) when devices are added or removed.
+unsafe extern "C" fn device_collection_changed(
+    _context: *mut cubeb_core::ffi::cubeb,
+    user_ptr: *mut c_void,
+) {
+    // Safety: user_ptr is the ADM's boxed notifier, which is only freed after
+    // the callbacks are unregistered.
+    let notifier = &*(user_ptr as *const AudioDeviceChangeNotifier);
+    notifier.notify();
+}
 
 fn write_to_null_or_valid_pointer<T>(ptr: webrtc::ptr::Borrowed<T>, v: T) -> anyhow::Result<()> {
     // Safety: As long as the C code passes a valid or null pointer, this is safe.
@@ -137,6 +320,40 @@ impl AudioDeviceModule {
         Self::default()
     }
 
+    /// Creates an ADM that reports device additions and removals to `notifier`.
+    pub fn with_device_change_notifier(notifier: AudioDeviceChangeNotifier) -> Self {
+        Self {
+            device_change_notifier: Box::new(notifier),
+            ..Default::default()
+        }
+    }
+
+    /// Creates an ADM that plays and records through `device` instead of any
+    /// sound hardware.
+    #[cfg(feature = "virtual_audio")]
+    pub fn with_virtual_device(device: VirtualAudioDevice) -> Self {
+        Self {
+            virtual_device: Some(device),
+            ..Default::default()
+        }
+    }
+
+    /// Allows stereo playout and recording on devices that support it. Otherwise,
+    /// the ADM only reports mono as available.
+    pub fn set_stereo_enabled(&mut self, enabled: bool) {
+        self.stereo_enabled = enabled;
+    }
+
+    /// Reports callback timing, glitches, and latency to `stats`.
+    pub fn set_stats_tracker(&mut self, stats: AudioDeviceStatsTracker) {
+        self.stats = stats;
+    }
+
+    /// Takes requests to select devices by ID from `selector`.
+    pub fn set_device_id_selector(&mut self, selector: AudioDeviceIdSelector) {
+        self.device_id_selector = selector;
+    }
+
     pub fn active_audio_layer(&self, _audio_layer: webrtc::ptr::Borrowed<AudioLayer>) -> i32 {
         -1
     }
@@ -160,6 +377,12 @@ impl AudioDeviceModule {
         if self.initialized {
             return 0;
         }
+        #[cfg(feature = "virtual_audio")]
+        if self.virtual_device.is_some() {
+            info!("Using virtual audio device");
+            self.initialized = true;
+            return 0;
+        }
         #[cfg(target_os = "windows")]
         {
             // Safety: calling with valid parameters.
@@ -183,6 +406,7 @@ impl AudioDeviceModule {
                 );
                 self.cubeb_ctx = Some(ctx);
                 self.initialized = true;
+                self.register_device_collection_changed();
                 0
             }
             Err(e) => {
@@ -193,18 +417,28 @@ impl AudioDeviceModule {
     }
 
     pub fn backend_name(&self) -> Option<String> {
+        #[cfg(feature = "virtual_audio")]
+        if self.virtual_device.is_some() {
+            return Some("virtual".to_string());
+        }
         self.cubeb_ctx
             .as_ref()
             .map(|ctx| ctx.backend_id().to_string())
     }
 
     pub fn terminate(&mut self) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            self.initialized = false;
+            return device.terminate();
+        }
         if self.recording {
             self.stop_recording();
         }
         if self.playing {
             self.stop_playout();
         }
+        self.unregister_device_collection_changed();
         // Cause these to Drop
         self.input_stream = None;
         self.output_stream = None;
@@ -224,6 +458,55 @@ impl AudioDeviceModule {
         self.initialized
     }
 
+    // Ask cubeb to tell us when input or output devices come and go, so the
+    // application doesn't have to poll the device lists. Failure isn't fatal:
+    // the lists can still be queried.
+    fn register_device_collection_changed(&mut self) {
+        let Some(ctx) = &self.cubeb_ctx else {
+            return;
+        };
+        let user_ptr =
+            &*self.device_change_notifier as *const AudioDeviceChangeNotifier as *mut c_void;
+        for device_type in [DeviceType::INPUT, DeviceType::OUTPUT] {
+            // Safety: the callback only uses user_ptr, which stays valid until
+            // unregister_device_collection_changed is called.
+            let result = unsafe {
+                ctx.register_device_collection_changed(
+                    device_type,
+                    Some(device_collection_changed),
+                    user_ptr,
+                )
+            };
+            match result {
+                Ok(()) => self.device_change_registered = true,
+                Err(e) => warn!(
+                    "Failed to register for {:?} device changes: {}",
+                    device_type, e
+                ),
+            }
+        }
+    }
+
+    fn unregister_device_collection_changed(&mut self) {
+        if !self.device_change_registered {
+            return;
+        }
+        if let Some(ctx) = &self.cubeb_ctx {
+            for device_type in [DeviceType::INPUT, DeviceType::OUTPUT] {
+                // Safety: passing no callback removes the registration.
+                if let Err(e) = unsafe {
+                    ctx.register_device_collection_changed(device_type, None, std::ptr::null_mut())
+                } {
+                    warn!(
+                        "Failed to unregister for {:?} device changes: {}",
+                        device_type, e
+                    );
+                }
+            }
+        }
+        self.device_change_registered = false;
+    }
+
     fn enumerate_devices(
         &self,
         device_type: DeviceType,
@@ -282,6 +565,10 @@ impl AudioDeviceModule {
 
     // Device enumeration
     pub fn playout_devices(&self) -> i16 {
+        #[cfg(feature = "virtual_audio")]
+        if self.virtual_device.is_some() {
+            return 1;
+        }
         match self.enumerate_devices(DeviceType::OUTPUT) {
             Ok(device_collection) => device_collection.count().try_into().unwrap_or(-1),
             Err(e) => {
@@ -292,6 +579,10 @@ impl AudioDeviceModule {
     }
 
     pub fn recording_devices(&self) -> i16 {
+        #[cfg(feature = "virtual_audio")]
+        if self.virtual_device.is_some() {
+            return 1;
+        }
         match self.enumerate_devices(DeviceType::INPUT) {
             Ok(device_collection) => device_collection.count().try_into().unwrap_or(-1),
             Err(e) => {
@@ -348,6 +639,16 @@ impl AudioDeviceModule {
         name_out: webrtc::ptr::Borrowed<c_uchar>,
         guid_out: webrtc::ptr::Borrowed<c_uchar>,
     ) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.device_name(
+                index,
+                "Virtual speaker",
+                "ringrtc-virtual-speaker",
+                name_out,
+                guid_out,
+            );
+        }
         match self.enumerate_devices(DeviceType::OUTPUT) {
             Ok(devices) => {
                 match AudioDeviceModule::copy_name_and_id(index, devices, name_out, guid_out) {
@@ -371,6 +672,16 @@ impl AudioDeviceModule {
         name_out: webrtc::ptr::Borrowed<c_uchar>,
         guid_out: webrtc::ptr::Borrowed<c_uchar>,
     ) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.device_name(
+                index,
+                "Virtual microphone",
+                "ringrtc-virtual-microphone",
+                name_out,
+                guid_out,
+            );
+        }
         match self.enumerate_devices(DeviceType::INPUT) {
             Ok(devices) => {
                 match AudioDeviceModule::copy_name_and_id(index, devices, name_out, guid_out) {
@@ -388,9 +699,50 @@ impl AudioDeviceModule {
         }
     }
 
+    /// Returns the index of the device to select: the first device in a
+    /// pending request by ID that is in `devices`, or else `index`.
+    fn requested_device_index(
+        request: &mut AudioDeviceIdRequest,
+        devices: &DeviceCollectionWrapper,
+        index: u16,
+    ) -> usize {
+        let Some(device_ids) = request.pending.take() else {
+            return index as usize;
+        };
+        let found = device_ids.into_iter().find_map(|device_id| {
+            devices
+                .position(&device_id)
+                .map(|position| (position, device_id))
+        });
+        match found {
+            Some((position, device_id)) => {
+                request.outcome = Some(AudioDeviceIdOutcome::Selected(device_id));
+                position
+            }
+            None => {
+                info!("None of the requested devices are present");
+                request.outcome = Some(AudioDeviceIdOutcome::NotPresent);
+                index as usize
+            }
+        }
+    }
+
     // Device selection
     pub fn set_playout_device(&mut self, index: u16) -> i32 {
-        let device = match self.enumerate_devices(DeviceType::OUTPUT) {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.set_playout_device(index);
+        }
+        // Hold the lock while enumerating, so that a request by ID is matched
+        // against the same enumeration that the device is selected from.
+        let mut requests = match self.device_id_selector.lock() {
+            Ok(requests) => requests,
+            Err(e) => {
+                error!("{}", e);
+                return -1;
+            }
+        };
+        let (device, max_channels) = match self.enumerate_devices(DeviceType::OUTPUT) {
             Ok(devices) => {
                 for device in devices.iter() {
                     info!(
@@ -399,8 +751,9 @@ impl AudioDeviceModule {
                     );
                 }
 
-                match devices.get(index as usize) {
-                    Some(device) => device.devid(),
+                let index = Self::requested_device_index(&mut requests.playout, &devices, index);
+                match devices.get(index) {
+                    Some(device) => (device.devid(), device.max_channels()),
                     None => {
                         error!(
                             "Invalid device index {} requested (len {})",
@@ -417,10 +770,16 @@ impl AudioDeviceModule {
             }
         };
         self.playout_device = Some(device);
+        self.playout_max_channels = max_channels;
         0
     }
 
     pub fn set_playout_device_win(&mut self, device: WindowsDeviceType) -> i32 {
+        // The virtual device is the default for everything.
+        #[cfg(feature = "virtual_audio")]
+        if self.virtual_device.is_some() {
+            return self.set_playout_device(0);
+        }
         // DefaultDevice is at index 0 and DefaultCommunicationDevice at index 1
         self.set_playout_device(if device == WindowsDeviceType::DefaultDevice {
             0
@@ -430,7 +789,20 @@ impl AudioDeviceModule {
     }
 
     pub fn set_recording_device(&mut self, index: u16) -> i32 {
-        let device = match self.enumerate_devices(DeviceType::INPUT) {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.set_recording_device(index);
+        }
+        // Hold the lock while enumerating, so that a request by ID is matched
+        // against the same enumeration that the device is selected from.
+        let mut requests = match self.device_id_selector.lock() {
+            Ok(requests) => requests,
+            Err(e) => {
+                error!("{}", e);
+                return -1;
+            }
+        };
+        let (device, max_channels) = match self.enumerate_devices(DeviceType::INPUT) {
             Ok(devices) => {
                 for device in devices.iter() {
                     info!(
@@ -438,8 +810,9 @@ impl AudioDeviceModule {
                         AudioDeviceModule::device_str(device)
                     );
                 }
-                match devices.get(index as usize) {
-                    Some(device) => device.devid(),
+                let index = Self::requested_device_index(&mut requests.recording, &devices, index);
+                match devices.get(index) {
+                    Some(device) => (device.devid(), device.max_channels()),
                     None => {
                         error!(
                             "Invalid device index {} requested (len {})",
@@ -456,10 +829,16 @@ impl AudioDeviceModule {
             }
         };
         self.recording_device = Some(device);
+        self.recording_max_channels = max_channels;
         0
     }
 
     pub fn set_recording_device_win(&mut self, device: WindowsDeviceType) -> i32 {
+        // The virtual device is the default for everything.
+        #[cfg(feature = "virtual_audio")]
+        if self.virtual_device.is_some() {
+            return self.set_recording_device(0);
+        }
         // DefaultDevice is at index 0 and DefaultCommunicationDevice at index 1
         self.set_recording_device(if device == WindowsDeviceType::DefaultDevice {
             0
@@ -470,6 +849,10 @@ impl AudioDeviceModule {
 
     // Audio transport initialization
     pub fn playout_is_available(&self, available_out: webrtc::ptr::Borrowed<bool>) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.playout_is_available(available_out);
+        }
         let available = self.initialized && self.playout_device.is_some();
         match write_to_null_or_valid_pointer(available_out, available) {
             Ok(_) => 0,
@@ -485,6 +868,10 @@ impl AudioDeviceModule {
             error!("Tried to init playout without initializing ADM");
             return -1;
         }
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.init_playout(Arc::clone(&self.audio_transport));
+        }
         let out_device = if let Some(device) = self.playout_device {
             device
         } else {
@@ -497,15 +884,41 @@ impl AudioDeviceModule {
             error!("Tried to init playout without a ctx");
             return -1;
         };
+        let transport = Arc::clone(&self.audio_transport);
+        let stats = self.stats.clone();
+        let stream = if self.stereo_playout {
+            Self::init_output_stream::<StereoFrame<i16>>(ctx, out_device, transport, stats)
+                .map(AdmStream::Stereo)
+        } else {
+            Self::init_output_stream::<MonoFrame<i16>>(ctx, out_device, transport, stats)
+                .map(AdmStream::Mono)
+        };
+        match stream {
+            Ok(stream) => {
+                self.output_stream = Some(stream);
+                0
+            }
+            Err(e) => {
+                error!("Couldn't initialize output stream: {}", e);
+                -1
+            }
+        }
+    }
+
+    fn init_output_stream<F: InterleavedFrame>(
+        ctx: &Context,
+        out_device: DeviceId,
+        transport: Arc<Mutex<RffiAudioTransport>>,
+        stats: AudioDeviceStatsTracker,
+    ) -> cubeb_core::Result<Stream<F>> {
         let params = cubeb::StreamParamsBuilder::new()
             .format(STREAM_FORMAT)
             .rate(SAMPLE_FREQUENCY)
-            .channels(NUM_CHANNELS)
-            .layout(cubeb::ChannelLayout::MONO)
+            .channels(F::CHANNELS)
+            .layout(F::LAYOUT)
             .prefs(StreamPrefs::VOICE)
             .take();
-        let mut builder = cubeb::StreamBuilder::<Frame>::new();
-        let transport = Arc::clone(&self.audio_transport);
+        let mut builder = cubeb::StreamBuilder::<F>::new();
         let min_latency = ctx.min_latency(&params).unwrap_or_else(|e| {
             error!(
                 "Could not get min latency for playout; using default: {:?}",
@@ -513,17 +926,25 @@ impl AudioDeviceModule {
             );
             SAMPLE_LATENCY
         });
-        info!("min playout latency: {}", min_latency);
+        info!(
+            "min playout latency: {} ({} channels)",
+            min_latency,
+            F::CHANNELS
+        );
         // WebRTC can only report data in WEBRTC_WINDOW-sized chunks.
-        // This buffer tracks any extra data that would not fit in `output`,
+        // This buffer tracks any extra frames that would not fit in `output`,
         // if `output.len()` is not an exact multiple of WEBRTC_WINDOW.
-        let mut buffer = VecDeque::<i16>::new();
+        let mut buffer = VecDeque::<F>::new();
         buffer.reserve(WEBRTC_WINDOW);
+        let mut jitter = CallbackJitter::default();
+        let state_stats = stats.clone();
+        let device_changed_stats = stats.clone();
         builder
             .name("ringrtc output")
             .output(out_device, &params)
             .latency(std::cmp::max(SAMPLE_LATENCY, min_latency))
             .data_callback(move |_, output| {
+                stats.record_playout_callback(jitter.on_callback(output.len()));
                 if output.is_empty() {
                     return 0;
                 }
@@ -536,8 +957,8 @@ impl AudioDeviceModule {
 
                 // First, copy any leftover data from prior invocations.
                 let mut written = 0;
-                while let Some(data) = buffer.pop_front() {
-                    output[written] = Frame { m: data };
+                while let Some(frame) = buffer.pop_front() {
+                    output[written] = frame;
                     written += 1;
                     if written >= output.len() {
                         // Short-circuit; we already have enough data.
@@ -550,26 +971,32 @@ impl AudioDeviceModule {
                     let play_data = AudioDeviceModule::need_more_play_data(
                         Arc::clone(&transport),
                         WEBRTC_WINDOW,
-                        NUM_CHANNELS,
+                        F::CHANNELS,
                         SAMPLE_FREQUENCY,
                     );
                     if play_data.success < 0 {
                         // C function failed; propagate error and don't continue.
                         return play_data.success as isize;
-                    } else if play_data.data.len() > WEBRTC_WINDOW {
+                    } else if play_data.data.len() > WEBRTC_WINDOW * F::CHANNELS as usize {
                         error!("need_more_play_data returned too much data");
                         return -1;
                     }
+                    stats.record_playout_window(
+                        play_data.data.len() < WEBRTC_WINDOW * F::CHANNELS as usize,
+                        play_data.elapsed_time,
+                        play_data.ntp_time,
+                    );
                     // Put data into the right format and add it to the output
                     // array for cubeb to play.
                     // If there's more data than was requested, add it to the
                     // buffer for the next invocation of the callback.
-                    for data in play_data.data.iter() {
+                    for samples in play_data.data.chunks_exact(F::CHANNELS as usize) {
+                        let frame = F::from_interleaved(samples);
                         if written < output.len() {
-                            output[written] = Frame { m: *data };
+                            output[written] = frame;
                             written += 1;
                         } else {
-                            buffer.push_back(*data);
+                            buffer.push_back(frame);
                         }
                     }
                 }
@@ -583,26 +1010,32 @@ impl AudioDeviceModule {
                 }
                 written as isize
             })
-            .state_callback(|state| {
+            .state_callback(move |state| {
                 warn!("Playout state: {:?}", state);
+                if matches!(state, cubeb::State::Error) {
+                    state_stats.record_stream_error();
+                }
+            })
+            .device_changed_cb(move || {
+                info!("Playout device changed");
+                device_changed_stats.record_device_change();
             });
-        match builder.init(ctx) {
-            Ok(stream) => {
-                self.output_stream = Some(stream);
-                0
-            }
-            Err(e) => {
-                error!("Couldn't initialize output stream: {}", e);
-                -1
-            }
-        }
+        builder.init(ctx)
     }
 
     pub fn playout_is_initialized(&self) -> bool {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.playout_is_initialized();
+        }
         self.output_stream.is_some()
     }
 
     pub fn recording_is_available(&self, available_out: webrtc::ptr::Borrowed<bool>) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.recording_is_available(available_out);
+        }
         let available = self.initialized && self.recording_device.is_some();
         match write_to_null_or_valid_pointer(available_out, available) {
             Ok(_) => 0,
@@ -618,6 +1051,10 @@ impl AudioDeviceModule {
             error!("Tried to init recording without initializing ADM");
             return -1;
         }
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.init_recording(Arc::clone(&self.audio_transport));
+        }
         let recording_device = if let Some(device) = self.recording_device {
             device
         } else {
@@ -630,15 +1067,60 @@ impl AudioDeviceModule {
             error!("Tried to init recording without a ctx");
             return -1;
         };
+        let transport = Arc::clone(&self.audio_transport);
+        let stats = self.stats.clone();
+        let stream = if self.stereo_recording {
+            Self::init_input_stream::<StereoFrame<i16>>(ctx, recording_device, transport, stats)
+                .map(AdmStream::Stereo)
+        } else {
+            Self::init_input_stream::<MonoFrame<i16>>(ctx, recording_device, transport, stats)
+                .map(AdmStream::Mono)
+        };
+        match stream {
+            Ok(stream) => {
+                match ctx.supported_input_processing_params() {
+                    Ok(params) => {
+                        // With cubeb-coreaudio-rs, the VPIO input is inaudible without these settings.
+                        // See https://github.com/mozilla/cubeb-coreaudio-rs/issues/239#issuecomment-2430361990
+                        info!("Available input processing params: {:?}", params);
+                        let mut desired_params = InputProcessingParams::empty();
+                        if params.contains(InputProcessingParams::AUTOMATIC_GAIN_CONTROL) {
+                            desired_params |= InputProcessingParams::AUTOMATIC_GAIN_CONTROL;
+                        }
+                        // With the coreaudio-rust backend, these settings must be set together.
+                        if params.contains(InputProcessingParams::ECHO_CANCELLATION | InputProcessingParams::NOISE_SUPPRESSION) {
+                           desired_params |= InputProcessingParams::ECHO_CANCELLATION | InputProcessingParams::NOISE_SUPPRESSION;
+                        }
+                        if let Err(e) = stream.set_input_processing_params(desired_params) {
+                            error!("couldn't set input params: {:?}", e);
+                        }
+                    }
+                    Err(e) => warn!("Failed to get supported input processing parameters; proceeding without: {}", e)
+                }
+                self.input_stream = Some(stream);
+                0
+            }
+            Err(e) => {
+                error!("Couldn't initialize input stream: {}", e);
+                -1
+            }
+        }
+    }
+
+    fn init_input_stream<F: InterleavedFrame>(
+        ctx: &Context,
+        recording_device: DeviceId,
+        transport: Arc<Mutex<RffiAudioTransport>>,
+        stats: AudioDeviceStatsTracker,
+    ) -> cubeb_core::Result<Stream<F>> {
         let params = cubeb::StreamParamsBuilder::new()
             .format(STREAM_FORMAT)
             .rate(SAMPLE_FREQUENCY)
-            .channels(NUM_CHANNELS)
-            .layout(cubeb::ChannelLayout::MONO)
+            .channels(F::CHANNELS)
+            .layout(F::LAYOUT)
             .prefs(StreamPrefs::VOICE)
             .take();
-        let mut builder = cubeb::StreamBuilder::<Frame>::new();
-        let transport = Arc::clone(&self.audio_transport);
+        let mut builder = cubeb::StreamBuilder::<F>::new();
         let min_latency = ctx.min_latency(&params).unwrap_or_else(|e| {
             error!(
                 "Could not get min latency for recording; using default: {:?}",
@@ -646,36 +1128,54 @@ impl AudioDeviceModule {
             );
             SAMPLE_LATENCY
         });
-        info!("min recording latency: {}", min_latency);
+        info!(
+            "min recording latency: {} ({} channels)",
+            min_latency,
+            F::CHANNELS
+        );
         // WebRTC can only accept data in WEBRTC_WINDOW-sized chunks.
-        // This buffer tracks any extra data that would not fit in a call to WebRTC,
+        // This buffer tracks any extra frames that would not fit in a call to WebRTC,
         // if `input.len()` is not an exact multiple of WEBRTC_WINDOW.
-        let mut buffer = VecDeque::<i16>::new();
+        let mut buffer = VecDeque::<F>::new();
         buffer.reserve(WEBRTC_WINDOW);
+        let latency = std::cmp::max(SAMPLE_LATENCY, min_latency);
+        let mut jitter = CallbackJitter::default();
+        let mut overruns = InputOverrunDetector::new(latency);
+        let state_stats = stats.clone();
+        let device_changed_stats = stats.clone();
         builder
             .name("ringrtc input")
             .input(recording_device, &params)
-            .latency(std::cmp::max(SAMPLE_LATENCY, min_latency))
+            .latency(latency)
             .data_callback(move |input, _| {
+                stats.record_recording_callback(jitter.on_callback(input.len()));
+                if let Some(dropped_frames) = overruns.on_callback(input.len()) {
+                    warn!("Recording overrun: about {} frames dropped", dropped_frames);
+                    stats.record_recording_overrun(dropped_frames);
+                }
                 // First add data from prior call(s).
-                let data = buffer
+                let frames = buffer
                     .drain(0..)
-                    .chain(input.iter().map(|f| f.m))
+                    .chain(input.iter().copied())
                     .collect::<Vec<_>>();
                 // WebRTC cannot accept data in anything other than 10ms chunks, so report in these.
                 // Buffer any excess data beyond a multiple of WEBRTC_WINDOW for a subsequent
                 // callback invocation.
-                let input_chunks = data.chunks(WEBRTC_WINDOW);
+                let input_chunks = frames.chunks(WEBRTC_WINDOW);
                 for chunk in input_chunks {
                     if chunk.len() < WEBRTC_WINDOW {
                         // Do not try to invoke WebRTC with a too-short chunk.
                         buffer.extend(chunk);
                         break;
                     }
+                    let mut samples = Vec::with_capacity(WEBRTC_WINDOW * F::CHANNELS as usize);
+                    for frame in chunk {
+                        frame.append_interleaved(&mut samples);
+                    }
                     let (ret, _new_mic_level) = AudioDeviceModule::recorded_data_is_available(
                         Arc::clone(&transport),
-                        chunk.to_vec(),
-                        NUM_CHANNELS,
+                        samples,
+                        F::CHANNELS,
                         SAMPLE_FREQUENCY,
                         // TODO(mutexlox): do we need different values here?
                         Duration::new(0, 0),
@@ -686,51 +1186,39 @@ impl AudioDeviceModule {
                     );
                     if ret < 0 {
                         error!("Failed to report recorded data: {}", ret);
+                        stats.record_recording_delivery_error();
                         return ret as isize;
                     }
                 }
                 input.len() as isize
             })
-            .state_callback(|state| {
+            .state_callback(move |state| {
                 warn!("recording state: {:?}", state);
-            });
-        match builder.init(ctx) {
-            Ok(stream) => {
-                match ctx.supported_input_processing_params() {
-                    Ok(params) => {
-                        // With cubeb-coreaudio-rs, the VPIO input is inaudible without these settings.
-                        // See https://github.com/mozilla/cubeb-coreaudio-rs/issues/239#issuecomment-2430361990
-                        info!("Available input processing params: {:?}", params);
-                        let mut desired_params = InputProcessingParams::empty();
-                        if params.contains(InputProcessingParams::AUTOMATIC_GAIN_CONTROL) {
-                            desired_params |= InputProcessingParams::AUTOMATIC_GAIN_CONTROL;
-                        }
-                        // With the coreaudio-rust backend, these settings must be set together.
-                        if params.contains(InputProcessingParams::ECHO_CANCELLATION | InputProcessingParams::NOISE_SUPPRESSION) {
-                           desired_params |= InputProcessingParams::ECHO_CANCELLATION | InputProcessingParams::NOISE_SUPPRESSION;
-                        }
-                        if let Err(e) = stream.set_input_processing_params(desired_params) {
-                            error!("couldn't set input params: {:?}", e);
-                        }
-                    }
-                    Err(e) => warn!("Failed to get supported input processing parameters; proceeding without: {}", e)
+                if matches!(state, cubeb::State::Error) {
+                    state_stats.record_stream_error();
                 }
-                self.input_stream = Some(stream);
-                0
-            }
-            Err(e) => {
-                error!("Couldn't initialize input stream: {}", e);
-                -1
-            }
-        }
+            })
+            .device_changed_cb(move || {
+                info!("Recording device changed");
+                device_changed_stats.record_device_change();
+            });
+        builder.init(ctx)
     }
 
     pub fn recording_is_initialized(&self) -> bool {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.recording_is_initialized();
+        }
         self.input_stream.is_some()
     }
 
     // Audio transport control
     pub fn start_playout(&mut self) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.start_playout();
+        }
         if let Some(output_stream) = &self.output_stream {
             if let Err(e) = output_stream.start() {
                 error!("Failed to start playout: {}", e);
@@ -745,6 +1233,10 @@ impl AudioDeviceModule {
     }
 
     pub fn stop_playout(&mut self) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.stop_playout();
+        }
         if let Some(output_stream) = &self.output_stream {
             if let Err(e) = output_stream.stop() {
                 error!("Failed to stop playout: {}", e);
@@ -758,10 +1250,18 @@ impl AudioDeviceModule {
     }
 
     pub fn playing(&self) -> bool {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.playing();
+        }
         self.playing
     }
 
     pub fn start_recording(&mut self) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.start_recording();
+        }
         if let Some(input_stream) = &self.input_stream {
             if let Err(e) = input_stream.start() {
                 error!("Failed to start recording: {}", e);
@@ -778,6 +1278,10 @@ impl AudioDeviceModule {
     }
 
     pub fn stop_recording(&mut self) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.stop_recording();
+        }
         if let Some(input_stream) = &self.input_stream {
             if let Err(e) = input_stream.stop() {
                 error!("Failed to stop recording: {}", e);
@@ -791,6 +1295,10 @@ impl AudioDeviceModule {
     }
 
     pub fn recording(&self) -> bool {
+        #[cfg(feature = "virtual_audio")]
+        if let Some(device) = &self.virtual_device {
+            return device.recording();
+        }
         self.recording
     }
 
@@ -927,11 +1435,19 @@ impl AudioDeviceModule {
     }
 
     // Stereo support
+    fn stereo_playout_supported(&self) -> bool {
+        self.stereo_enabled && self.playout_max_channels >= 2
+    }
+
+    fn stereo_recording_supported(&self) -> bool {
+        self.stereo_enabled && self.recording_max_channels >= 2
+    }
+
     pub fn stereo_playout_is_available(&self, available: webrtc::ptr::Borrowed<bool>) -> i32 {
         if !self.initialized {
             return -1;
         }
-        match write_to_null_or_valid_pointer(available, false) {
+        match write_to_null_or_valid_pointer(available, self.stereo_playout_supported()) {
             Ok(_) => 0,
             Err(e) => {
                 error!("writing stereo playout status: {:?}", e);
@@ -940,20 +1456,35 @@ impl AudioDeviceModule {
         }
     }
 
-    // This implementation only supports mono playout
-    pub fn set_stereo_playout(&self, _enable: bool) -> i32 {
-        -1
+    pub fn set_stereo_playout(&mut self, enable: bool) -> i32 {
+        if enable && !self.stereo_playout_supported() {
+            error!("Stereo playout is not available");
+            return -1;
+        }
+        // The channel count is fixed when the stream is created.
+        if self.playout_is_initialized() && enable != self.stereo_playout {
+            error!("Cannot change stereo playout after init_playout");
+            return -1;
+        }
+        self.stereo_playout = enable;
+        0
     }
 
-    pub fn stereo_playout(&self, _enabled: webrtc::ptr::Borrowed<bool>) -> i32 {
-        -1
+    pub fn stereo_playout(&self, enabled: webrtc::ptr::Borrowed<bool>) -> i32 {
+        match write_to_null_or_valid_pointer(enabled, self.stereo_playout) {
+            Ok(_) => 0,
+            Err(e) => {
+                error!("writing stereo playout: {:?}", e);
+                -1
+            }
+        }
     }
 
     pub fn stereo_recording_is_available(&self, available: webrtc::ptr::Borrowed<bool>) -> i32 {
         if !self.initialized {
             return -1;
         }
-        match write_to_null_or_valid_pointer(available, false) {
+        match write_to_null_or_valid_pointer(available, self.stereo_recording_supported()) {
             Ok(_) => 0,
             Err(e) => {
                 error!("writing stereo recording status: {:?}", e);
@@ -962,21 +1493,57 @@ impl AudioDeviceModule {
         }
     }
 
-    // This implementation only supports mono recording.
-    pub fn set_stereo_recording(&self, _enable: bool) -> i32 {
-        -1
+    pub fn set_stereo_recording(&mut self, enable: bool) -> i32 {
+        if enable && !self.stereo_recording_supported() {
+            error!("Stereo recording is not available");
+            return -1;
+        }
+        // The channel count is fixed when the stream is created.
+        if self.recording_is_initialized() && enable != self.stereo_recording {
+            error!("Cannot change stereo recording after init_recording");
+            return -1;
+        }
+        self.stereo_recording = enable;
+        0
     }
 
-    pub fn stereo_recording(&self, _enabled: webrtc::ptr::Borrowed<bool>) -> i32 {
-        -1
+    pub fn stereo_recording(&self, enabled: webrtc::ptr::Borrowed<bool>) -> i32 {
+        match write_to_null_or_valid_pointer(enabled, self.stereo_recording) {
+            Ok(_) => 0,
+            Err(e) => {
+                error!("writing stereo recording: {:?}", e);
+                -1
+            }
+        }
     }
 
     pub fn playout_delay(&self, delay_ms: webrtc::ptr::Borrowed<u16>) -> i32 {
+        #[cfg(feature = "virtual_audio")]
+        if self.virtual_device.is_some() {
+            // Played audio goes straight to the sink.
+            return match write_to_null_or_valid_pointer(delay_ms, 0) {
+                Ok(_) => 0,
+                Err(e) => {
+                    error!("writing delay: {:?}", e);
+                    -1
+                }
+            };
+        }
         match &self.output_stream {
             Some(output_stream) => {
                 let latency_samples = output_stream.latency();
                 match latency_samples {
                     Ok(latency_samples) => {
+                        // WebRTC asks for this regularly, so it's a convenient
+                        // time to sample the latency of both streams.
+                        let recording_latency = self
+                            .input_stream
+                            .as_ref()
+                            .and_then(|input_stream| input_stream.input_latency().ok());
+                        self.stats.record_latency(
+                            Some(samples_to_duration(latency_samples)),
+                            recording_latency.map(samples_to_duration),
+                        );
                         let latency_ms = latency_samples / (SAMPLE_FREQUENCY / 1000);
                         match write_to_null_or_valid_pointer(delay_ms, latency_ms as u16) {
                             Ok(_) => 0,
@@ -1024,17 +1591,17 @@ impl AudioDeviceModule {
         // Safety:
         // * self.audio_transport is within self, and will remain valid while this function is running
         //   because we enforce that the callback cannot change while playing or recording.
-        // * The vector has sizeof(i16) * samples bytes allocated, and we pass both of these
-        //   to the C layer, which should not read beyond that bound.
+        // * The vector has sizeof(i16) * samples bytes allocated, and we pass the number of
+        //   frames and the size of each to the C layer, which should not read beyond that bound.
         // * The local new_mic_level pointer is valid and this function is synchronous, so it'll
         //   remain valid while it runs.
         let ret = unsafe {
             crate::webrtc::ffi::audio_device_module::Rust_recordedDataIsAvailable(
                 guard.callback,
                 samples.as_ptr() as *const c_void,
-                samples.len(),
-                std::mem::size_of::<i16>(),
-                channels.try_into().unwrap(), // constant, so unwrap is safe
+                samples.len() / channels as usize,
+                std::mem::size_of::<i16>() * channels as usize,
+                channels.try_into().unwrap(), // 1 or 2, so unwrap is safe
                 samples_per_sec,
                 total_delay.as_millis() as u32,
                 clock_drift,
@@ -1047,13 +1614,15 @@ impl AudioDeviceModule {
         (ret, new_mic_level)
     }
 
+    /// Requests `frames` frames of `channels` samples each. The returned data
+    /// is interleaved.
     fn need_more_play_data(
         rffi_audio_transport: Arc<Mutex<RffiAudioTransport>>,
-        samples: usize,
+        frames: usize,
         channels: u32,
         samples_per_sec: u32,
     ) -> PlayData {
-        let mut data = vec![0i16; samples];
+        let mut data = vec![0i16; frames * channels as usize];
         let mut samples_out = 0usize;
         let mut elapsed_time_ms = 0i64;
         let mut ntp_time_ms = 0i64;
@@ -1073,16 +1642,18 @@ impl AudioDeviceModule {
         // Safety:
         // * rffi_audio_transport will remain valid while this function is running
         //   because we enforce that the callback cannot change while playing or recording.
-        // * The vector has sizeof(i16) * samples bytes allocated, and we pass both of these
-        //   to the C layer, which should not write beyond that bound.
+        // * The vector has sizeof(i16) * channels * frames bytes allocated, and we pass the
+        //   number of frames and the size of each to the C layer, which should not write
+        //   beyond that bound.
         // * The local variable pointers are all valid and this function is synchronous, so they'll
         //   remain valid while it runs.
+        // samples_out counts samples in all channels, not frames.
         let ret = unsafe {
             crate::webrtc::ffi::audio_device_module::Rust_needMorePlayData(
                 guard.callback,
-                samples,
-                std::mem::size_of::<i16>(),
-                channels.try_into().unwrap(), // constant, so unwrap is safe
+                frames,
+                std::mem::size_of::<i16>() * channels as usize,
+                channels.try_into().unwrap(), // 1 or 2, so unwrap is safe
                 samples_per_sec,
                 data.as_mut_ptr() as *mut c_void,
                 &mut samples_out,
@@ -1111,7 +1682,59 @@ impl AudioDeviceModule {
 
 #[cfg(test)]
 mod audio_device_module_tests {
-    use crate::webrtc::audio_device_module::AudioDeviceModule;
+    use crate::webrtc::audio_device_module::{
+        AudioDeviceModule, InputOverrunDetector, InterleavedFrame, WEBRTC_WINDOW,
+    };
+    use cubeb::{MonoFrame, StereoFrame};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn interleaving() {
+        let samples = [1i16, -2, 3, -4];
+
+        let frames = samples
+            .chunks_exact(2)
+            .map(StereoFrame::<i16>::from_interleaved)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(1, -2), (3, -4)],
+            frames.iter().map(|f| (f.l, f.r)).collect::<Vec<_>>()
+        );
+        let mut interleaved = Vec::new();
+        for frame in &frames {
+            frame.append_interleaved(&mut interleaved);
+        }
+        assert_eq!(samples.to_vec(), interleaved);
+
+        let frames = samples
+            .chunks_exact(1)
+            .map(MonoFrame::<i16>::from_interleaved)
+            .collect::<Vec<_>>();
+        assert_eq!(4, frames.len());
+        let mut interleaved = Vec::new();
+        for frame in &frames {
+            frame.append_interleaved(&mut interleaved);
+        }
+        assert_eq!(samples.to_vec(), interleaved);
+    }
+
+    #[test]
+    fn input_overrun_detector() {
+        let mut detector = InputOverrunDetector::new(WEBRTC_WINDOW as u32);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(None, detector.on_callback_at(at(0), WEBRTC_WINDOW));
+        // Late callbacks that catch up aren't overruns.
+        assert_eq!(None, detector.on_callback_at(at(25), WEBRTC_WINDOW));
+        assert_eq!(None, detector.on_callback_at(at(30), 2 * WEBRTC_WINDOW));
+        // 50ms pass but only 10ms of audio arrives.
+        assert_eq!(
+            Some(4 * WEBRTC_WINDOW as u64),
+            detector.on_callback_at(at(80), WEBRTC_WINDOW)
+        );
+        assert_eq!(None, detector.on_callback_at(at(90), WEBRTC_WINDOW));
+    }
 
     #[test]
     fn init_backend_id() {
@@ -0,0 +1,706 @@
+//
+// Copyright 2026 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! A virtual audio device for RingRTC's ADM, for tests and headless bots.
+//!
+//! Recorded audio comes from a [`VirtualAudioSource`] and played audio goes to a
+//! [`VirtualAudioSink`] instead of sound hardware, driven by either a real-time
+//! clock or one the owner advances by hand.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+
+use crate::common::Result;
+use crate::webrtc;
+use crate::webrtc::audio_device_module_utils::copy_and_truncate_string;
+use crate::webrtc::ffi::audio_device_module::RffiAudioTransport;
+
+use super::{
+    write_to_null_or_valid_pointer, AudioDeviceModule, ADM_MAX_DEVICE_NAME_SIZE, ADM_MAX_GUID_SIZE,
+    SAMPLE_FREQUENCY, WEBRTC_WINDOW,
+};
+
+/// The virtual device only supports mono.
+const CHANNELS: u32 = 1;
+
+/// How much audio is recorded and played per tick of the clock.
+const WINDOW_DURATION: Duration = Duration::from_millis(10);
+
+/// What drives the virtual device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VirtualAudioClock {
+    /// A background thread records and plays 10ms of audio every 10ms while
+    /// playout or recording is started.
+    RealTime,
+    /// Nothing happens until [`VirtualAudioDevice::advance`] is called.
+    Manual,
+}
+
+enum SourceKind {
+    Silence,
+    Sine {
+        frequency_hz: f64,
+        amplitude: i16,
+        position: u64,
+    },
+    Samples {
+        samples: Vec<i16>,
+        looping: bool,
+        position: usize,
+    },
+    Channel {
+        receiver: Receiver<Vec<i16>>,
+        pending: VecDeque<i16>,
+    },
+}
+
+/// Where a virtual device's recorded (i.e. sent) audio comes from.
+///
+/// All audio is mono at 48kHz. Whenever a source runs dry, it produces silence.
+pub struct VirtualAudioSource(SourceKind);
+
+impl fmt::Debug for VirtualAudioSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.0 {
+            SourceKind::Silence => "Silence",
+            SourceKind::Sine { .. } => "Sine",
+            SourceKind::Samples { .. } => "Samples",
+            SourceKind::Channel { .. } => "Channel",
+        };
+        f.debug_tuple("VirtualAudioSource").field(&kind).finish()
+    }
+}
+
+impl VirtualAudioSource {
+    pub fn silence() -> Self {
+        Self(SourceKind::Silence)
+    }
+
+    pub fn sine(frequency_hz: f64, amplitude: i16) -> Self {
+        Self(SourceKind::Sine {
+            frequency_hz,
+            amplitude,
+            position: 0,
+        })
+    }
+
+    /// Plays `samples` once, or over and over if `looping`.
+    pub fn samples(samples: Vec<i16>, looping: bool) -> Self {
+        Self(SourceKind::Samples {
+            samples,
+            looping,
+            position: 0,
+        })
+    }
+
+    /// Reads a 16-bit, 48kHz WAV file. Multiple channels are mixed down to mono.
+    pub fn wav_file(path: impl AsRef<Path>, looping: bool) -> Result<Self> {
+        let mut reader = hound::WavReader::open(path)?;
+        let spec = reader.spec();
+        if spec.sample_rate != SAMPLE_FREQUENCY
+            || spec.bits_per_sample != 16
+            || spec.sample_format != hound::SampleFormat::Int
+        {
+            return Err(anyhow!(
+                "Unsupported WAV format {:?}; need 16-bit samples at {}Hz",
+                spec,
+                SAMPLE_FREQUENCY
+            ));
+        }
+        let interleaved = reader
+            .samples::<i16>()
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let samples = interleaved
+            .chunks(spec.channels as usize)
+            .map(|frame| (frame.iter().map(|s| *s as i32).sum::<i32>() / frame.len() as i32) as i16)
+            .collect();
+        Ok(Self::samples(samples, looping))
+    }
+
+    /// Returns a source that plays whatever is sent to the returned Sender, as
+    /// it arrives.
+    pub fn channel() -> (Sender<Vec<i16>>, Self) {
+        let (sender, receiver) = channel();
+        (
+            sender,
+            Self(SourceKind::Channel {
+                receiver,
+                pending: VecDeque::new(),
+            }),
+        )
+    }
+
+    fn next_window(&mut self, len: usize) -> Vec<i16> {
+        match &mut self.0 {
+            SourceKind::Silence => vec![0; len],
+            SourceKind::Sine {
+                frequency_hz,
+                amplitude,
+                position,
+            } => {
+                let step = 2.0 * std::f64::consts::PI * *frequency_hz / SAMPLE_FREQUENCY as f64;
+                let window = (0..len as u64)
+                    .map(|i| ((*position + i) as f64 * step).sin() * *amplitude as f64)
+                    .map(|s| s as i16)
+                    .collect();
+                *position += len as u64;
+                window
+            }
+            SourceKind::Samples {
+                samples,
+                looping,
+                position,
+            } => {
+                let mut window = Vec::with_capacity(len);
+                while window.len() < len {
+                    if *position >= samples.len() {
+                        if !*looping || samples.is_empty() {
+                            break;
+                        }
+                        *position = 0;
+                    }
+                    let end = std::cmp::min(samples.len(), *position + len - window.len());
+                    window.extend_from_slice(&samples[*position..end]);
+                    *position = end;
+                }
+                window.resize(len, 0);
+                window
+            }
+            SourceKind::Channel { receiver, pending } => {
+                while pending.len() < len {
+                    match receiver.try_recv() {
+                        Ok(samples) => pending.extend(samples),
+                        Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => break,
+                    }
+                }
+                let available = std::cmp::min(len, pending.len());
+                let mut window = pending.drain(..available).collect::<Vec<_>>();
+                window.resize(len, 0);
+                window
+            }
+        }
+    }
+}
+
+enum SinkKind {
+    Discard,
+    WavFile(hound::WavWriter<BufWriter<File>>),
+    Channel(Sender<Vec<i16>>),
+}
+
+/// Where a virtual device's played (i.e. received) audio goes.
+///
+/// All audio is mono at 48kHz, delivered 10ms at a time.
+pub struct VirtualAudioSink(SinkKind);
+
+impl fmt::Debug for VirtualAudioSink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.0 {
+            SinkKind::Discard => "Discard",
+            SinkKind::WavFile(_) => "WavFile",
+            SinkKind::Channel(_) => "Channel",
+        };
+        f.debug_tuple("VirtualAudioSink").field(&kind).finish()
+    }
+}
+
+impl VirtualAudioSink {
+    pub fn discard() -> Self {
+        Self(SinkKind::Discard)
+    }
+
+    /// Writes a 16-bit, 48kHz, mono WAV file, which is finalized when the sink
+    /// is dropped.
+    pub fn wav_file(path: impl AsRef<Path>) -> Result<Self> {
+        let spec = hound::WavSpec {
+            channels: CHANNELS as u16,
+            sample_rate: SAMPLE_FREQUENCY,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        Ok(Self(SinkKind::WavFile(hound::WavWriter::create(
+            path, spec,
+        )?)))
+    }
+
+    /// Returns a sink that sends everything played to the returned Receiver.
+    pub fn channel() -> (Self, Receiver<Vec<i16>>) {
+        let (sender, receiver) = channel();
+        (Self(SinkKind::Channel(sender)), receiver)
+    }
+
+    fn write(&mut self, samples: Vec<i16>) {
+        match &mut self.0 {
+            SinkKind::Discard => {}
+            SinkKind::WavFile(writer) => {
+                for sample in samples {
+                    if let Err(e) = writer.write_sample(sample) {
+                        error!("Failed to write virtual audio to file: {}", e);
+                        break;
+                    }
+                }
+            }
+            SinkKind::Channel(sender) => {
+                // The receiver going away just means nobody is listening anymore.
+                let _ = sender.send(samples);
+            }
+        }
+    }
+}
+
+struct VirtualAudioDeviceState {
+    source: VirtualAudioSource,
+    sink: VirtualAudioSink,
+    playout_device_selected: bool,
+    recording_device_selected: bool,
+    // Set by init_playout/init_recording, like the cubeb streams.
+    playout_transport: Option<Arc<Mutex<RffiAudioTransport>>>,
+    recording_transport: Option<Arc<Mutex<RffiAudioTransport>>>,
+    playing: bool,
+    recording: bool,
+    clock_thread_running: bool,
+}
+
+impl VirtualAudioDeviceState {
+    /// Takes what's needed to record and play the next window of audio, for
+    /// whichever of playout and recording are started.
+    fn next_window(&mut self) -> VirtualAudioWindow {
+        let recorded = match &self.recording_transport {
+            Some(transport) if self.recording => Some((
+                Arc::clone(transport),
+                self.source.next_window(WEBRTC_WINDOW),
+            )),
+            _ => None,
+        };
+        let playout_transport = match &self.playout_transport {
+            Some(transport) if self.playing => Some(Arc::clone(transport)),
+            _ => None,
+        };
+        VirtualAudioWindow {
+            recorded,
+            playout_transport,
+        }
+    }
+}
+
+/// One window of audio, copied out of the state so that the state isn't
+/// locked while WebRTC is called, since WebRTC may call back into the ADM.
+struct VirtualAudioWindow {
+    recorded: Option<(Arc<Mutex<RffiAudioTransport>>, Vec<i16>)>,
+    playout_transport: Option<Arc<Mutex<RffiAudioTransport>>>,
+}
+
+impl VirtualAudioWindow {
+    /// Hands the recorded audio to WebRTC and writes the played audio to the
+    /// sink, which is the only time `state` is locked.
+    fn run(self, state: &Mutex<VirtualAudioDeviceState>) {
+        if let Some((transport, samples)) = self.recorded {
+            let (ret, _new_mic_level) = AudioDeviceModule::recorded_data_is_available(
+                transport,
+                samples,
+                CHANNELS,
+                SAMPLE_FREQUENCY,
+                Duration::ZERO,
+                0,
+                0,
+                false,
+                None,
+            );
+            if ret < 0 {
+                error!("Failed to report virtual recorded data: {}", ret);
+            }
+        }
+        if let Some(transport) = self.playout_transport {
+            let play_data = AudioDeviceModule::need_more_play_data(
+                transport,
+                WEBRTC_WINDOW,
+                CHANNELS,
+                SAMPLE_FREQUENCY,
+            );
+            if play_data.success < 0 {
+                error!("Failed to get virtual play data: {}", play_data.success);
+            } else {
+                // Keep the sink's timeline intact even if WebRTC came up short.
+                let mut samples = play_data.data;
+                samples.resize(WEBRTC_WINDOW, 0);
+                match state.lock() {
+                    Ok(mut state) => state.sink.write(samples),
+                    Err(_) => error!("VirtualAudioDevice: state mutex poisoned"),
+                }
+            }
+        }
+    }
+}
+
+/// An audio device with no hardware behind it.
+///
+/// Pass one to [`AudioConfig`](crate::webrtc::peer_connection_factory::AudioConfig)
+/// to use it in place of the system's devices, and keep a clone around to
+/// control it. It presents a single input and a single output device.
+#[derive(Clone)]
+pub struct VirtualAudioDevice {
+    clock: VirtualAudioClock,
+    state: Arc<Mutex<VirtualAudioDeviceState>>,
+}
+
+impl fmt::Debug for VirtualAudioDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VirtualAudioDevice")
+            .field("clock", &self.clock)
+            .finish()
+    }
+}
+
+impl VirtualAudioDevice {
+    pub fn new(
+        source: VirtualAudioSource,
+        sink: VirtualAudioSink,
+        clock: VirtualAudioClock,
+    ) -> Self {
+        Self {
+            clock,
+            state: Arc::new(Mutex::new(VirtualAudioDeviceState {
+                source,
+                sink,
+                playout_device_selected: false,
+                recording_device_selected: false,
+                playout_transport: None,
+                recording_transport: None,
+                playing: false,
+                recording: false,
+                clock_thread_running: false,
+            })),
+        }
+    }
+
+    /// Replaces the source of recorded audio, e.g. to start "speaking".
+    pub fn set_source(&self, source: VirtualAudioSource) {
+        if let Some(mut state) = self.state() {
+            state.source = source;
+        }
+    }
+
+    /// Replaces the destination of played audio.
+    pub fn set_sink(&self, sink: VirtualAudioSink) {
+        if let Some(mut state) = self.state() {
+            state.sink = sink;
+        }
+    }
+
+    /// Records and plays `windows` windows of 10ms of audio, for whichever of
+    /// playout and recording are started. Meant for [`VirtualAudioClock::Manual`].
+    pub fn advance(&self, windows: usize) {
+        for _ in 0..windows {
+            let window = match self.state() {
+                Some(mut state) => state.next_window(),
+                None => return,
+            };
+            window.run(&self.state);
+        }
+    }
+
+    fn state(&self) -> Option<MutexGuard<'_, VirtualAudioDeviceState>> {
+        match self.state.lock() {
+            Ok(state) => Some(state),
+            Err(_) => {
+                error!("VirtualAudioDevice: state mutex poisoned");
+                None
+            }
+        }
+    }
+
+    fn start_clock_if_needed(&self, state: &mut VirtualAudioDeviceState) {
+        if self.clock != VirtualAudioClock::RealTime || state.clock_thread_running {
+            return;
+        }
+        state.clock_thread_running = true;
+        let shared = Arc::clone(&self.state);
+        thread::spawn(move || {
+            let mut next_tick = Instant::now();
+            loop {
+                let window = {
+                    let Ok(mut state) = shared.lock() else {
+                        return;
+                    };
+                    if !state.playing && !state.recording {
+                        state.clock_thread_running = false;
+                        return;
+                    }
+                    state.next_window()
+                };
+                window.run(&shared);
+                // Schedule from the previous deadline so that time spent in
+                // WebRTC doesn't make the clock drift.
+                next_tick += WINDOW_DURATION;
+                thread::sleep(next_tick.saturating_duration_since(Instant::now()));
+            }
+        });
+    }
+
+    // The rest mirror the AudioDeviceModule methods that delegate to them.
+
+    pub(super) fn terminate(&self) -> i32 {
+        if let Some(mut state) = self.state() {
+            state.playing = false;
+            state.recording = false;
+            state.playout_transport = None;
+            state.recording_transport = None;
+        }
+        0
+    }
+
+    pub(super) fn device_name(
+        &self,
+        index: u16,
+        name: &str,
+        guid: &str,
+        name_out: webrtc::ptr::Borrowed<u8>,
+        guid_out: webrtc::ptr::Borrowed<u8>,
+    ) -> i32 {
+        if index != 0 {
+            error!("Invalid virtual device index {} requested", index);
+            return -1;
+        }
+        match copy_and_truncate_string(name, name_out, ADM_MAX_DEVICE_NAME_SIZE)
+            .and_then(|_| copy_and_truncate_string(guid, guid_out, ADM_MAX_GUID_SIZE))
+        {
+            Ok(_) => 0,
+            Err(e) => {
+                error!("Failed to copy name and ID for virtual device: {}", e);
+                -1
+            }
+        }
+    }
+
+    pub(super) fn set_playout_device(&self, index: u16) -> i32 {
+        match (index, self.state()) {
+            (0, Some(mut state)) => {
+                state.playout_device_selected = true;
+                0
+            }
+            _ => -1,
+        }
+    }
+
+    pub(super) fn set_recording_device(&self, index: u16) -> i32 {
+        match (index, self.state()) {
+            (0, Some(mut state)) => {
+                state.recording_device_selected = true;
+                0
+            }
+            _ => -1,
+        }
+    }
+
+    pub(super) fn playout_is_available(&self, available_out: webrtc::ptr::Borrowed<bool>) -> i32 {
+        let available = self
+            .state()
+            .map_or(false, |state| state.playout_device_selected);
+        match write_to_null_or_valid_pointer(available_out, available) {
+            Ok(_) => 0,
+            Err(e) => {
+                error!("writing playout available state: {:?}", e);
+                -1
+            }
+        }
+    }
+
+    pub(super) fn recording_is_available(&self, available_out: webrtc::ptr::Borrowed<bool>) -> i32 {
+        let available = self
+            .state()
+            .map_or(false, |state| state.recording_device_selected);
+        match write_to_null_or_valid_pointer(available_out, available) {
+            Ok(_) => 0,
+            Err(e) => {
+                error!("writing recording available state: {:?}", e);
+                -1
+            }
+        }
+    }
+
+    pub(super) fn init_playout(&self, transport: Arc<Mutex<RffiAudioTransport>>) -> i32 {
+        match self.state() {
+            Some(mut state) if state.playout_device_selected => {
+                state.playout_transport = Some(transport);
+                0
+            }
+            _ => {
+                error!("Tried to init virtual playout without a playout device");
+                -1
+            }
+        }
+    }
+
+    pub(super) fn playout_is_initialized(&self) -> bool {
+        self.state()
+            .map_or(false, |state| state.playout_transport.is_some())
+    }
+
+    pub(super) fn init_recording(&self, transport: Arc<Mutex<RffiAudioTransport>>) -> i32 {
+        match self.state() {
+            Some(mut state) if state.recording_device_selected => {
+                state.recording_transport = Some(transport);
+                0
+            }
+            _ => {
+                error!("Tried to init virtual recording without a recording device");
+                -1
+            }
+        }
+    }
+
+    pub(super) fn recording_is_initialized(&self) -> bool {
+        self.state()
+            .map_or(false, |state| state.recording_transport.is_some())
+    }
+
+    pub(super) fn start_playout(&self) -> i32 {
+        match self.state() {
+            Some(mut state) if state.playout_transport.is_some() => {
+                state.playing = true;
+                self.start_clock_if_needed(&mut state);
+                0
+            }
+            _ => {
+                error!("Cannot start virtual playout -- did you forget init_playout?");
+                -1
+            }
+        }
+    }
+
+    pub(super) fn stop_playout(&self) -> i32 {
+        if let Some(mut state) = self.state() {
+            state.playing = false;
+            state.playout_transport = None;
+        }
+        0
+    }
+
+    pub(super) fn playing(&self) -> bool {
+        self.state().map_or(false, |state| state.playing)
+    }
+
+    pub(super) fn start_recording(&self) -> i32 {
+        match self.state() {
+            Some(mut state) if state.recording_transport.is_some() => {
+                state.recording = true;
+                self.start_clock_if_needed(&mut state);
+                0
+            }
+            _ => {
+                error!("Cannot start virtual recording -- did you forget init_recording?");
+                -1
+            }
+        }
+    }
+
+    pub(super) fn stop_recording(&self) -> i32 {
+        if let Some(mut state) = self.state() {
+            state.recording = false;
+            state.recording_transport = None;
+        }
+        0
+    }
+
+    pub(super) fn recording(&self) -> bool {
+        self.state().map_or(false, |state| state.recording)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silence() {
+        let mut source = VirtualAudioSource::silence();
+        assert_eq!(vec![0; 480], source.next_window(480));
+    }
+
+    #[test]
+    fn sine_continues_across_windows() {
+        let mut source = VirtualAudioSource::sine(1000.0, 10000);
+        let mut first = source.next_window(24);
+        let second = source.next_window(24);
+        first.extend(second);
+
+        let mut whole = VirtualAudioSource::sine(1000.0, 10000);
+        assert_eq!(whole.next_window(48), first);
+        // 1kHz at 48kHz: a quarter period in, the wave peaks.
+        assert_eq!(0, first[0]);
+        assert_eq!(10000, first[12]);
+    }
+
+    #[test]
+    fn samples_once_then_silence() {
+        let mut source = VirtualAudioSource::samples(vec![1, 2, 3], false);
+        assert_eq!(vec![1, 2], source.next_window(2));
+        assert_eq!(vec![3, 0], source.next_window(2));
+        assert_eq!(vec![0, 0], source.next_window(2));
+    }
+
+    #[test]
+    fn samples_looping() {
+        let mut source = VirtualAudioSource::samples(vec![1, 2, 3], true);
+        assert_eq!(vec![1, 2, 3, 1, 2], source.next_window(5));
+        assert_eq!(vec![3, 1, 2, 3, 1], source.next_window(5));
+
+        let mut empty = VirtualAudioSource::samples(vec![], true);
+        assert_eq!(vec![0, 0], empty.next_window(2));
+    }
+
+    #[test]
+    fn channel_source_fills_with_silence() {
+        let (sender, mut source) = VirtualAudioSource::channel();
+        sender.send(vec![1, 2, 3]).unwrap();
+        sender.send(vec![4]).unwrap();
+        assert_eq!(vec![1, 2], source.next_window(2));
+        assert_eq!(vec![3, 4, 0], source.next_window(3));
+        assert_eq!(vec![0], source.next_window(1));
+    }
+
+    #[test]
+    fn channel_sink() {
+        let (mut sink, receiver) = VirtualAudioSink::channel();
+        sink.write(vec![1, 2]);
+        sink.write(vec![3]);
+        assert_eq!(vec![1, 2], receiver.recv().unwrap());
+        assert_eq!(vec![3], receiver.recv().unwrap());
+    }
+
+    #[test]
+    fn device_state() {
+        let device = VirtualAudioDevice::new(
+            VirtualAudioSource::silence(),
+            VirtualAudioSink::discard(),
+            VirtualAudioClock::Manual,
+        );
+        let transport = Arc::new(Mutex::new(RffiAudioTransport {
+            callback: std::ptr::null(),
+        }));
+
+        assert_eq!(-1, device.init_playout(Arc::clone(&transport)));
+        assert_eq!(-1, device.set_playout_device(1));
+        assert_eq!(0, device.set_playout_device(0));
+        assert_eq!(-1, device.start_playout());
+        assert_eq!(0, device.init_playout(Arc::clone(&transport)));
+        assert!(device.playout_is_initialized());
+        assert_eq!(0, device.start_playout());
+        assert!(device.playing());
+        assert!(!device.recording());
+        assert_eq!(0, device.stop_playout());
+        assert!(!device.playing());
+        assert!(!device.playout_is_initialized());
+    }
+}
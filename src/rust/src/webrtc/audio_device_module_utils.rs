@@ -12,6 +12,13 @@ use cubeb::{DeviceCollection, DeviceInfo, DeviceState};
 use cubeb_core::DevicePref;
 use std::ffi::{c_uchar, CString};
 
+/// The number of leading indexes that stand for the system default devices
+/// rather than a particular device.
+#[cfg(target_os = "windows")]
+const DEFAULT_DEVICE_SLOTS: usize = 2;
+#[cfg(not(target_os = "windows"))]
+const DEFAULT_DEVICE_SLOTS: usize = 1;
+
 /// Wrapper struct for DeviceCollection that handles default devices.
 pub struct DeviceCollectionWrapper<'a> {
     device_collection: DeviceCollection<'a>,
@@ -120,6 +127,15 @@ impl DeviceCollectionWrapper<'_> {
             count + 1
         }
     }
+
+    /// Returns the index that `get` returns the device with `device_id` at,
+    /// not counting the default device slots, which may also be that device.
+    pub fn position(&self, device_id: &str) -> Option<usize> {
+        (DEFAULT_DEVICE_SLOTS..)
+            .map_while(|idx| self.get(idx).map(|device| (idx, device)))
+            .find(|(_, device)| device.device_id() == Some(device_id))
+            .map(|(idx, _)| idx)
+    }
 }
 
 /// Copy from |src| into |dest| at most |dest_size| - 1 bytes and write a nul terminator either after |src| or at the end of |dest_size|
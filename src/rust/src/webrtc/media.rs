@@ -369,6 +369,10 @@ pub struct RffiAudioEncoderConfig {
     enable_cbr: bool,
     enable_dtx: bool,
     enable_fec: bool,
+
+    // Maps to the channel count of the Opus encoder on the C++ side, which
+    // reads this as the field after enable_fec.
+    enable_stereo: bool,
 }
 
 // A nice form of RffiAudioEncoderConfig
@@ -394,6 +398,10 @@ pub struct AudioEncoderConfig {
     pub enable_cbr: bool,
     pub enable_dtx: bool,
     pub enable_fec: bool,
+
+    // Encode 2 channels when the capturer provides them. Stereo needs
+    // roughly twice the bitrate of mono for the same quality.
+    pub enable_stereo: bool,
 }
 
 impl Default for AudioEncoderConfig {
@@ -414,6 +422,8 @@ impl Default for AudioEncoderConfig {
             enable_cbr: true,
             enable_dtx: true,
             enable_fec: true,
+
+            enable_stereo: false,
         }
     }
 }
@@ -433,6 +443,7 @@ impl AudioEncoderConfig {
             enable_cbr: self.enable_cbr,
             enable_dtx: self.enable_dtx,
             enable_fec: self.enable_fec,
+            enable_stereo: self.enable_stereo,
         }
     }
 }
@@ -11,13 +11,18 @@ use std::ffi::c_void;
 #[cfg(feature = "native")]
 use std::ffi::CStr;
 use std::ffi::CString;
+use std::fmt;
 use std::os::raw::c_char;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use crate::common::Result;
 use crate::error::RingRtcError;
 use crate::webrtc;
 #[cfg(all(not(feature = "sim"), feature = "native"))]
 use crate::webrtc::audio_device_module::AudioDeviceModule;
+#[cfg(all(not(feature = "sim"), feature = "virtual_audio"))]
+use crate::webrtc::audio_device_module::VirtualAudioDevice;
 #[cfg(all(not(feature = "sim"), feature = "native"))]
 use crate::webrtc::ffi::audio_device_module::AUDIO_DEVICE_CBS_PTR;
 #[cfg(feature = "injectable_network")]
@@ -128,6 +133,295 @@ pub struct AudioDevice {
     pub i18n_key: String,
 }
 
+/// Lets the application find out when audio devices are added or removed.
+///
+/// RingRTC's ADM calls [`AudioDeviceChangeNotifier::notify`] on a cubeb thread
+/// whenever its device collections change, so the callback must be quick and
+/// must not query devices itself; re-enumerate elsewhere, e.g. with
+/// [`PeerConnectionFactory::refresh_audio_devices`].
+#[derive(Clone, Default)]
+pub struct AudioDeviceChangeNotifier {
+    callback: Arc<Mutex<Option<Box<dyn FnMut() + Send>>>>,
+}
+
+impl fmt::Debug for AudioDeviceChangeNotifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AudioDeviceChangeNotifier").finish()
+    }
+}
+
+impl AudioDeviceChangeNotifier {
+    pub fn set_callback(&self, callback: Option<Box<dyn FnMut() + Send>>) {
+        match self.callback.lock() {
+            Ok(mut guard) => *guard = callback,
+            Err(_) => error!("AudioDeviceChangeNotifier: callback mutex poisoned"),
+        }
+    }
+
+    pub fn notify(&self) {
+        info!("AudioDeviceChangeNotifier::notify()");
+        if let Ok(mut guard) = self.callback.lock() {
+            if let Some(callback) = guard.as_mut() {
+                callback();
+            }
+        }
+    }
+}
+
+/// How well the local audio devices are keeping up, as measured by RingRTC's
+/// ADM since the PeerConnectionFactory was created. Other ADMs leave these
+/// at zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioDeviceStats {
+    /// Data callbacks from the playout stream.
+    pub playout_callbacks: u64,
+    /// 10ms windows of silence played because WebRTC had no audio ready.
+    pub playout_underruns: u64,
+    /// How far the intervals between playout callbacks strayed from the
+    /// duration of audio each one asked for.
+    pub playout_mean_jitter: Duration,
+    pub playout_max_jitter: Duration,
+    /// Data callbacks from the recording stream.
+    pub recording_callbacks: u64,
+    /// Times WebRTC returned an error when handed a 10ms window of recorded
+    /// audio.
+    pub recording_delivery_errors: u64,
+    /// Times the recording stream fell behind and the backend dropped
+    /// audio, detected by fewer frames arriving than the time between
+    /// callbacks calls for, and about how many frames were dropped.
+    pub recording_overruns: u64,
+    pub recording_dropped_frames: u64,
+    /// How far the intervals between recording callbacks strayed from the
+    /// duration of audio each one delivered.
+    pub recording_mean_jitter: Duration,
+    pub recording_max_jitter: Duration,
+    /// The elapsed and NTP times WebRTC reported with the last audio played.
+    pub last_playout_elapsed_time: Option<Duration>,
+    pub last_playout_ntp_time: Option<Duration>,
+    /// The most recent latency reported by the backend for each stream.
+    pub playout_latency: Option<Duration>,
+    pub recording_latency: Option<Duration>,
+    /// Times the backend switched a stream to another device, e.g. because
+    /// the system default changed.
+    pub device_changes: u64,
+    /// Times a stream reported an error.
+    pub stream_errors: u64,
+}
+
+impl AudioDeviceStats {
+    /// How long it takes sound to get from the microphone to the speaker
+    /// locally, not counting the network.
+    pub fn input_to_output_latency(&self) -> Option<Duration> {
+        Some(self.playout_latency? + self.recording_latency?)
+    }
+}
+
+#[derive(Debug, Default)]
+struct AudioDeviceStatsState {
+    stats: AudioDeviceStats,
+    playout_total_jitter: Duration,
+    playout_jitter_samples: u32,
+    recording_total_jitter: Duration,
+    recording_jitter_samples: u32,
+}
+
+/// Collects [`AudioDeviceStats`] from RingRTC's ADM.
+///
+/// Most of the `record_` methods are called from the audio backend's own
+/// threads, so they only update counters.
+#[derive(Clone, Debug, Default)]
+pub struct AudioDeviceStatsTracker {
+    state: Arc<Mutex<AudioDeviceStatsState>>,
+}
+
+impl AudioDeviceStatsTracker {
+    fn update(&self, f: impl FnOnce(&mut AudioDeviceStatsState)) {
+        match self.state.lock() {
+            Ok(mut state) => f(&mut state),
+            Err(_) => error!("AudioDeviceStatsTracker: state mutex poisoned"),
+        }
+    }
+
+    /// Returns the stats collected so far.
+    pub fn stats(&self) -> AudioDeviceStats {
+        self.state
+            .lock()
+            .map(|state| state.stats.clone())
+            .unwrap_or_default()
+    }
+
+    /// `jitter` is None for the first callback of a stream.
+    pub fn record_playout_callback(&self, jitter: Option<Duration>) {
+        self.update(|state| {
+            state.stats.playout_callbacks += 1;
+            if let Some(jitter) = jitter {
+                state.playout_total_jitter += jitter;
+                state.playout_jitter_samples += 1;
+                state.stats.playout_mean_jitter =
+                    state.playout_total_jitter / state.playout_jitter_samples;
+                state.stats.playout_max_jitter = state.stats.playout_max_jitter.max(jitter);
+            }
+        });
+    }
+
+    /// Records one 10ms window of playout.
+    pub fn record_playout_window(
+        &self,
+        underrun: bool,
+        elapsed_time: Option<Duration>,
+        ntp_time: Option<Duration>,
+    ) {
+        self.update(|state| {
+            if underrun {
+                state.stats.playout_underruns += 1;
+            }
+            state.stats.last_playout_elapsed_time = elapsed_time;
+            state.stats.last_playout_ntp_time = ntp_time;
+        });
+    }
+
+    /// `jitter` is None for the first callback of a stream.
+    pub fn record_recording_callback(&self, jitter: Option<Duration>) {
+        self.update(|state| {
+            state.stats.recording_callbacks += 1;
+            if let Some(jitter) = jitter {
+                state.recording_total_jitter += jitter;
+                state.recording_jitter_samples += 1;
+                state.stats.recording_mean_jitter =
+                    state.recording_total_jitter / state.recording_jitter_samples;
+                state.stats.recording_max_jitter = state.stats.recording_max_jitter.max(jitter);
+            }
+        });
+    }
+
+    pub fn record_recording_delivery_error(&self) {
+        self.update(|state| state.stats.recording_delivery_errors += 1);
+    }
+
+    pub fn record_recording_overrun(&self, dropped_frames: u64) {
+        self.update(|state| {
+            state.stats.recording_overruns += 1;
+            state.stats.recording_dropped_frames += dropped_frames;
+        });
+    }
+
+    pub fn record_latency(&self, playout: Option<Duration>, recording: Option<Duration>) {
+        self.update(|state| {
+            state.stats.playout_latency = playout;
+            state.stats.recording_latency = recording;
+        });
+    }
+
+    pub fn record_device_change(&self) {
+        self.update(|state| state.stats.device_changes += 1);
+    }
+
+    pub fn record_stream_error(&self) {
+        self.update(|state| state.stats.stream_errors += 1);
+    }
+}
+
+/// Hands unique IDs from [`PeerConnectionFactory::set_audio_playout_device_by_id`]
+/// and [`PeerConnectionFactory::set_audio_recording_device_by_id`] to RingRTC's
+/// ADM.
+///
+/// The ADM matches them against its own enumeration while holding the lock,
+/// so that devices added or removed since the factory's last enumeration
+/// can't make it select the wrong device.
+#[derive(Clone, Debug, Default)]
+pub struct AudioDeviceIdSelector {
+    state: Arc<Mutex<AudioDeviceIdSelectorState>>,
+}
+
+#[derive(Debug, Default)]
+pub struct AudioDeviceIdSelectorState {
+    pub playout: AudioDeviceIdRequest,
+    pub recording: AudioDeviceIdRequest,
+}
+
+/// A request to select a device by ID the next time the ADM selects one.
+#[derive(Debug, Default)]
+pub struct AudioDeviceIdRequest {
+    /// Device IDs in order of preference, until the ADM takes them.
+    pub pending: Option<Vec<String>>,
+    /// What the ADM did with the last request it took.
+    pub outcome: Option<AudioDeviceIdOutcome>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AudioDeviceIdOutcome {
+    /// The device with this ID was selected.
+    Selected(String),
+    /// None of the devices were present, so the ADM used the index it was
+    /// given.
+    NotPresent,
+}
+
+impl AudioDeviceIdSelector {
+    pub fn lock(&self) -> Result<std::sync::MutexGuard<'_, AudioDeviceIdSelectorState>> {
+        self.state
+            .lock()
+            .map_err(|_| anyhow!("AudioDeviceIdSelector: state mutex poisoned"))
+    }
+}
+
+/// How one list of audio devices (playout or recording) changed since it was
+/// last enumerated.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioDeviceListChange {
+    /// All current devices, in the order used to select them by index.
+    pub devices: Vec<AudioDevice>,
+    /// Devices that weren't present in the previous enumeration.
+    pub added: Vec<AudioDevice>,
+    /// Devices from the previous enumeration that are now gone.
+    pub removed: Vec<AudioDevice>,
+    /// The selected device was removed, so the default device (the default
+    /// communications device on Windows) was selected instead.
+    pub fell_back_to_default: bool,
+}
+
+impl AudioDeviceListChange {
+    /// Compares devices by unique ID. `selected` is the unique ID of the selected
+    /// device, or None if a default device slot is selected.
+    fn new(previous: &[AudioDevice], devices: Vec<AudioDevice>, selected: Option<&str>) -> Self {
+        let added = devices
+            .iter()
+            .filter(|d| !previous.iter().any(|p| p.unique_id == d.unique_id))
+            .cloned()
+            .collect();
+        let removed = previous
+            .iter()
+            .filter(|p| !devices.iter().any(|d| d.unique_id == p.unique_id))
+            .cloned()
+            .collect();
+        let fell_back_to_default =
+            selected.is_some_and(|selected| !devices.iter().any(|d| d.unique_id == selected));
+        Self {
+            devices,
+            added,
+            removed,
+            fell_back_to_default,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && !self.fell_back_to_default
+    }
+}
+
+/// The result of [`PeerConnectionFactory::refresh_audio_devices`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioDevicesChanged {
+    pub playout: AudioDeviceListChange,
+    pub recording: AudioDeviceListChange,
+}
+
+impl AudioDevicesChanged {
+    pub fn is_empty(&self) -> bool {
+        self.playout.is_empty() && self.recording.is_empty()
+    }
+}
+
 /// Stays in sync with RffiAudioDeviceModuleType in peer_connection_factory.h.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -171,6 +465,13 @@ pub struct AudioConfig {
     pub aec_enabled: bool,
     pub ns_enabled: bool,
     pub agc_enabled: bool,
+    /// Allow stereo playout and recording on devices that support it.
+    /// Only used by RingRTC's ADM.
+    pub stereo_enabled: bool,
+    /// Play and record through this device instead of the system's devices.
+    /// Only used by RingRTC's ADM.
+    #[cfg(all(not(feature = "sim"), feature = "virtual_audio"))]
+    pub virtual_audio_device: Option<VirtualAudioDevice>,
 }
 
 impl Default for AudioConfig {
@@ -182,14 +483,27 @@ impl Default for AudioConfig {
             aec_enabled: true,
             ns_enabled: true,
             agc_enabled: true,
+            stereo_enabled: false,
+            #[cfg(all(not(feature = "sim"), feature = "virtual_audio"))]
+            virtual_audio_device: None,
         }
     }
 }
 
 impl AudioConfig {
-    // Return both the RffiAudioConfig as well as the name of the cubeb backend
-    // in use, if any.
-    fn rffi(&self) -> Result<(RffiAudioConfig, Option<String>)> {
+    // Return the RffiAudioConfig, the name of the cubeb backend in use, if any,
+    // the notifier the ADM uses to report device changes, the tracker it
+    // reports its stats to, and, if RingRTC's ADM is used, the selector it
+    // selects devices by ID with.
+    fn rffi(
+        &self,
+    ) -> Result<(
+        RffiAudioConfig,
+        Option<String>,
+        AudioDeviceChangeNotifier,
+        AudioDeviceStatsTracker,
+        Option<AudioDeviceIdSelector>,
+    )> {
         let (input_file, output_file) =
             if self.audio_device_module_type == RffiAudioDeviceModuleType::File {
                 if let Some(file_based_adm_config) = &self.file_based_adm_config {
@@ -204,21 +518,45 @@ impl AudioConfig {
                 (std::ptr::null(), std::ptr::null())
             };
 
+        let audio_device_notifier = AudioDeviceChangeNotifier::default();
+        let audio_device_stats = AudioDeviceStatsTracker::default();
+        let audio_device_id_selector = AudioDeviceIdSelector::default();
+
         #[cfg(all(not(feature = "sim"), feature = "native"))]
         let (adm_borrowed, backend_name) = {
-            let mut adm = AudioDeviceModule::new();
-            // Initialize the ADM here. This isn't strictly necessary, but allows
-            // us to log the backend name (e.g. audiounit vs audiounit-rust).
-            adm.init();
-            let backend_name = adm.backend_name();
+            #[cfg(feature = "virtual_audio")]
+            let virtual_adm = self
+                .virtual_audio_device
+                .as_ref()
+                .map(|device| AudioDeviceModule::with_virtual_device(device.clone()));
+            #[cfg(not(feature = "virtual_audio"))]
+            let virtual_adm: Option<AudioDeviceModule> = None;
+            let (mut ringrtc_adm, backend_name) = if let Some(ringrtc_adm) = virtual_adm {
+                let backend_name = ringrtc_adm.backend_name();
+                (ringrtc_adm, backend_name)
+            } else {
+                let mut adm = AudioDeviceModule::new();
+                // Initialize the ADM here. This isn't strictly necessary, but allows
+                // us to log the backend name (e.g. audiounit vs audiounit-rust).
+                adm.init();
+                (
+                    AudioDeviceModule::with_device_change_notifier(audio_device_notifier.clone()),
+                    adm.backend_name(),
+                )
+            };
+            ringrtc_adm.set_stereo_enabled(self.stereo_enabled);
+            ringrtc_adm.set_stats_tracker(audio_device_stats.clone());
+            ringrtc_adm.set_device_id_selector(audio_device_id_selector.clone());
             (
-                webrtc::ptr::Borrowed::from_ptr(Box::into_raw(Box::new(AudioDeviceModule::new())))
-                    .to_void(),
+                webrtc::ptr::Borrowed::from_ptr(Box::into_raw(Box::new(ringrtc_adm))).to_void(),
                 backend_name,
             )
         };
         #[cfg(any(feature = "sim", not(feature = "native")))]
         let backend_name = None;
+        let audio_device_id_selector = (cfg!(all(not(feature = "sim"), feature = "native"))
+            && self.audio_device_module_type == RffiAudioDeviceModuleType::RingRtc)
+            .then_some(audio_device_id_selector);
 
         Ok((
             RffiAudioConfig {
@@ -236,6 +574,9 @@ impl AudioConfig {
                     .to_void(),
             },
             backend_name,
+            audio_device_notifier,
+            audio_device_stats,
+            audio_device_id_selector,
         ))
     }
 }
@@ -286,12 +627,144 @@ pub struct DeviceCounts {
     recording: Option<u16>,
 }
 
+/// The most recently enumerated audio devices and the current selections,
+/// shared by all clones of a PeerConnectionFactory.
+#[cfg(feature = "native")]
+#[derive(Debug, Default)]
+struct KnownAudioDevices {
+    playout: Vec<AudioDevice>,
+    recording: Vec<AudioDevice>,
+    /// The selected playout device, or None if none was selected yet.
+    selected_playout: Option<AudioDeviceSelection>,
+    /// The selected recording device, or None if none was selected yet.
+    selected_recording: Option<AudioDeviceSelection>,
+}
+
+/// What was selected with set_audio_playout_device or set_audio_recording_device.
+#[cfg(feature = "native")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum AudioDeviceSelection {
+    /// One of the leading slots that follow the system default device, which
+    /// can change without the selection becoming invalid.
+    DefaultSlot(usize),
+    /// A particular device, by unique ID.
+    Device(String),
+}
+
+#[cfg(feature = "native")]
+impl AudioDeviceSelection {
+    fn new(devices: &[AudioDevice], index: u16) -> Option<Self> {
+        let index = index as usize;
+        if index < DEFAULT_AUDIO_DEVICE_SLOTS {
+            Some(Self::DefaultSlot(index))
+        } else {
+            devices
+                .get(index)
+                .map(|d| Self::Device(d.unique_id.clone()))
+        }
+    }
+
+    /// The unique ID of a particular selected device, or None for a default slot.
+    fn unique_id(&self) -> Option<&str> {
+        match self {
+            Self::DefaultSlot(_) => None,
+            Self::Device(unique_id) => Some(unique_id),
+        }
+    }
+
+    /// Finds the selected device in `devices`, along with its index.
+    fn find(&self, devices: &[AudioDevice]) -> Option<(u16, AudioDevice)> {
+        let index = match self {
+            Self::DefaultSlot(index) => Some(*index).filter(|i| *i < devices.len()),
+            Self::Device(unique_id) => devices.iter().position(|d| &d.unique_id == unique_id),
+        }?;
+        Some((index as u16, devices[index].clone()))
+    }
+}
+
+/// The number of leading device slots that follow the system default device
+/// rather than naming a particular device (see get_audio_playout_devices).
+#[cfg(all(feature = "native", target_os = "windows"))]
+const DEFAULT_AUDIO_DEVICE_SLOTS: usize = 2;
+#[cfg(all(feature = "native", not(target_os = "windows")))]
+const DEFAULT_AUDIO_DEVICE_SLOTS: usize = 1;
+
+/// The device slot to fall back to when the selected device is removed. On
+/// Windows, this is the default communications device, which is what calls
+/// should use.
+#[cfg(all(feature = "native", target_os = "windows"))]
+const FALLBACK_AUDIO_DEVICE_INDEX: u16 = 1;
+#[cfg(all(feature = "native", not(target_os = "windows")))]
+const FALLBACK_AUDIO_DEVICE_INDEX: u16 = 0;
+
+/// Returns the index of the first device in `preferred_unique_ids` that is
+/// present in `devices`, or 0 (the default device) if none of them are.
+#[cfg(feature = "native")]
+fn preferred_audio_device_index(devices: &[AudioDevice], preferred_unique_ids: &[String]) -> u16 {
+    preferred_unique_ids
+        .iter()
+        .find_map(|unique_id| devices.iter().position(|d| &d.unique_id == unique_id))
+        .unwrap_or(0) as u16
+}
+
+/// Splits `preferred_unique_ids` into the IDs of particular devices, for
+/// RingRTC's ADM to look for in its own enumeration, and the index of the
+/// default slot to use if none of them are present. That is the first slot in
+/// `preferred_unique_ids`, or 0; any preferences after it don't matter, as the
+/// slots are always present.
+#[cfg(feature = "native")]
+fn audio_device_id_request(
+    devices: &[AudioDevice],
+    preferred_unique_ids: &[String],
+) -> (Vec<String>, u16) {
+    let mut device_ids = Vec::new();
+    for unique_id in preferred_unique_ids {
+        match devices.iter().position(|d| &d.unique_id == unique_id) {
+            Some(index) if index < DEFAULT_AUDIO_DEVICE_SLOTS => {
+                return (device_ids, index as u16);
+            }
+            // Even if it's not in `devices`, it may be in the ADM's more recent
+            // enumeration.
+            _ => device_ids.push(unique_id.clone()),
+        }
+    }
+    (device_ids, 0)
+}
+
+/// Finds what the ADM selected for a request made with
+/// [`audio_device_id_request`], in an enumeration made afterwards.
+#[cfg(feature = "native")]
+fn audio_device_selected_by_id(
+    devices: &[AudioDevice],
+    outcome: Option<AudioDeviceIdOutcome>,
+    default_index: u16,
+) -> Option<(AudioDeviceSelection, AudioDevice)> {
+    let selection = match outcome {
+        Some(AudioDeviceIdOutcome::Selected(device_id)) => AudioDeviceSelection::Device(device_id),
+        Some(AudioDeviceIdOutcome::NotPresent) => {
+            AudioDeviceSelection::new(devices, default_index)?
+        }
+        None => {
+            warn!("The ADM didn't handle the request to select a device by ID");
+            AudioDeviceSelection::new(devices, default_index)?
+        }
+    };
+    let (_, device) = selection.find(devices)?;
+    Some((selection, device))
+}
+
 /// Rust wrapper around WebRTC C++ PeerConnectionFactory object.
 #[derive(Clone, Debug)]
 pub struct PeerConnectionFactory {
     rffi: webrtc::Arc<RffiPeerConnectionFactoryOwner>,
     #[cfg(feature = "native")]
     device_counts: DeviceCounts,
+    #[cfg(feature = "native")]
+    known_audio_devices: Arc<Mutex<KnownAudioDevices>>,
+    audio_device_notifier: AudioDeviceChangeNotifier,
+    audio_device_stats: AudioDeviceStatsTracker,
+    /// Only set for RingRTC's ADM, which can select devices by ID.
+    audio_device_id_selector: Option<AudioDeviceIdSelector>,
     backend_name: Option<String>,
 }
 
@@ -301,7 +774,13 @@ impl PeerConnectionFactory {
     pub fn new(audio_config: &AudioConfig, use_injectable_network: bool) -> Result<Self> {
         debug!("PeerConnectionFactory::new()");
 
-        let (audio_config_rffi, backend_name) = audio_config.rffi()?;
+        let (
+            audio_config_rffi,
+            backend_name,
+            audio_device_notifier,
+            audio_device_stats,
+            audio_device_id_selector,
+        ) = audio_config.rffi()?;
 
         let rffi = unsafe {
             webrtc::Arc::from_owned(pcf::Rust_createPeerConnectionFactory(
@@ -316,6 +795,11 @@ impl PeerConnectionFactory {
             rffi,
             #[cfg(feature = "native")]
             device_counts: Default::default(),
+            #[cfg(feature = "native")]
+            known_audio_devices: Default::default(),
+            audio_device_notifier,
+            audio_device_stats,
+            audio_device_id_selector,
             backend_name,
         })
     }
@@ -339,10 +823,36 @@ impl PeerConnectionFactory {
             rffi,
             #[cfg(feature = "native")]
             device_counts: Default::default(),
+            #[cfg(feature = "native")]
+            known_audio_devices: Default::default(),
+            audio_device_notifier: Default::default(),
+            audio_device_stats: Default::default(),
+            audio_device_id_selector: None,
             backend_name: None,
         }
     }
 
+    /// Sets the callback invoked when audio devices are added or removed.
+    ///
+    /// Only RingRTC's ADM reports changes; see [`AudioDeviceChangeNotifier`]
+    /// for the restrictions on the callback.
+    pub fn set_audio_devices_changed_callback(&self, callback: Option<Box<dyn FnMut() + Send>>) {
+        self.audio_device_notifier.set_callback(callback);
+    }
+
+    /// Returns how well the local audio devices have been keeping up.
+    ///
+    /// Only RingRTC's ADM collects these; see [`AudioDeviceStats`].
+    pub fn audio_device_stats(&self) -> AudioDeviceStats {
+        self.audio_device_stats.stats()
+    }
+
+    /// Returns the tracker behind [`Self::audio_device_stats`], e.g. to include
+    /// the stats in periodic logging.
+    pub fn audio_device_stats_tracker(&self) -> AudioDeviceStatsTracker {
+        self.audio_device_stats.clone()
+    }
+
     #[cfg(feature = "injectable_network")]
     pub fn injectable_network(&self) -> Option<InjectableNetwork> {
         let rffi = unsafe { pcf::Rust_getInjectableNetwork(self.rffi.as_borrowed()) };
@@ -540,11 +1050,15 @@ impl PeerConnectionFactory {
             devices[1].unique_id.push_str("-1");
         }
 
+        self.known_audio_devices()?.playout = devices.clone();
+
         Ok(devices)
     }
 
     #[cfg(feature = "native")]
     pub fn set_audio_playout_device(&mut self, index: u16) -> Result<()> {
+        let selected = AudioDeviceSelection::new(&self.known_audio_devices()?.playout, index);
+
         #[cfg(target_os = "windows")]
         // Swap the first two devices back to ordinal if either are selected.
         let index = match index {
@@ -557,6 +1071,7 @@ impl PeerConnectionFactory {
 
         let ok = unsafe { pcf::Rust_setAudioPlayoutDevice(self.rffi.as_borrowed(), index) };
         if ok {
+            self.known_audio_devices()?.selected_playout = selected;
             Ok(())
         } else {
             error!("setAudioPlayoutDevice({}) failed", index);
@@ -656,11 +1171,15 @@ impl PeerConnectionFactory {
             devices[1].unique_id.push_str("-1");
         }
 
+        self.known_audio_devices()?.recording = devices.clone();
+
         Ok(devices)
     }
 
     #[cfg(feature = "native")]
     pub fn set_audio_recording_device(&mut self, index: u16) -> Result<()> {
+        let selected = AudioDeviceSelection::new(&self.known_audio_devices()?.recording, index);
+
         #[cfg(target_os = "windows")]
         // Swap the first two devices back to ordinal if either are selected.
         let index = match index {
@@ -676,6 +1195,7 @@ impl PeerConnectionFactory {
 
         let ok = unsafe { pcf::Rust_setAudioRecordingDevice(self.rffi.as_borrowed(), index) };
         if ok {
+            self.known_audio_devices()?.selected_recording = selected;
             Ok(())
         } else {
             error!("setAudioRecordingDevice({}) failed", index);
@@ -683,7 +1203,336 @@ impl PeerConnectionFactory {
         }
     }
 
+    /// Selects the first playout device in `preferred_unique_ids` that is
+    /// currently present, or the default device if none are, and returns it.
+    ///
+    /// With RingRTC's ADM, the ADM matches the IDs against its own enumeration,
+    /// so unlike set_audio_playout_device, this can't select the wrong device when
+    /// devices were added or removed since they were last enumerated.
+    #[cfg(feature = "native")]
+    pub fn set_audio_playout_device_by_id(
+        &mut self,
+        preferred_unique_ids: &[String],
+    ) -> Result<AudioDevice> {
+        let devices = self.get_audio_playout_devices()?;
+        let Some(selector) = self.audio_device_id_selector.clone() else {
+            // Other ADMs can only select by index.
+            let index = preferred_audio_device_index(&devices, preferred_unique_ids);
+            self.set_audio_playout_device(index)?;
+            return Ok(devices
+                .get(index as usize)
+                .cloned()
+                .ok_or(RingRtcError::SetAudioDevice)?);
+        };
+
+        let (device_ids, default_index) = audio_device_id_request(&devices, preferred_unique_ids);
+        selector.lock()?.playout = AudioDeviceIdRequest {
+            pending: Some(device_ids),
+            outcome: None,
+        };
+        let result = self.set_audio_playout_device(default_index);
+        let outcome = std::mem::take(&mut selector.lock()?.playout).outcome;
+        result?;
+
+        let devices = self.get_audio_playout_devices()?;
+        match audio_device_selected_by_id(&devices, outcome, default_index) {
+            Some((selection, device)) => {
+                self.known_audio_devices()?.selected_playout = Some(selection);
+                Ok(device)
+            }
+            None => {
+                error!("Selected playout device is already gone");
+                Err(RingRtcError::SetAudioDevice.into())
+            }
+        }
+    }
+
+    /// Selects the first recording device in `preferred_unique_ids` that is
+    /// currently present, or the default device if none are, and returns it.
+    ///
+    /// With RingRTC's ADM, the ADM matches the IDs against its own enumeration,
+    /// so unlike set_audio_recording_device, this can't select the wrong device when
+    /// devices were added or removed since they were last enumerated.
+    #[cfg(feature = "native")]
+    pub fn set_audio_recording_device_by_id(
+        &mut self,
+        preferred_unique_ids: &[String],
+    ) -> Result<AudioDevice> {
+        let devices = self.get_audio_recording_devices()?;
+        let Some(selector) = self.audio_device_id_selector.clone() else {
+            // Other ADMs can only select by index.
+            let index = preferred_audio_device_index(&devices, preferred_unique_ids);
+            self.set_audio_recording_device(index)?;
+            return Ok(devices
+                .get(index as usize)
+                .cloned()
+                .ok_or(RingRtcError::SetAudioDevice)?);
+        };
+
+        let (device_ids, default_index) = audio_device_id_request(&devices, preferred_unique_ids);
+        selector.lock()?.recording = AudioDeviceIdRequest {
+            pending: Some(device_ids),
+            outcome: None,
+        };
+        let result = self.set_audio_recording_device(default_index);
+        let outcome = std::mem::take(&mut selector.lock()?.recording).outcome;
+        result?;
+
+        let devices = self.get_audio_recording_devices()?;
+        match audio_device_selected_by_id(&devices, outcome, default_index) {
+            Some((selection, device)) => {
+                self.known_audio_devices()?.selected_recording = Some(selection);
+                Ok(device)
+            }
+            None => {
+                error!("Selected recording device is already gone");
+                Err(RingRtcError::SetAudioDevice.into())
+            }
+        }
+    }
+
+    /// Returns the selected playout device and its index in the most recent
+    /// enumeration, or None if no device was selected or it is no longer present.
+    #[cfg(feature = "native")]
+    pub fn active_audio_playout_device(&self) -> Result<Option<(u16, AudioDevice)>> {
+        let known = self.known_audio_devices()?;
+        Ok(known
+            .selected_playout
+            .as_ref()
+            .and_then(|selected| selected.find(&known.playout)))
+    }
+
+    /// Returns the selected recording device and its index in the most recent
+    /// enumeration, or None if no device was selected or it is no longer present.
+    #[cfg(feature = "native")]
+    pub fn active_audio_recording_device(&self) -> Result<Option<(u16, AudioDevice)>> {
+        let known = self.known_audio_devices()?;
+        Ok(known
+            .selected_recording
+            .as_ref()
+            .and_then(|selected| selected.find(&known.recording)))
+    }
+
+    #[cfg(feature = "native")]
+    fn known_audio_devices(&self) -> Result<std::sync::MutexGuard<'_, KnownAudioDevices>> {
+        self.known_audio_devices
+            .lock()
+            .map_err(|_| RingRtcError::MutexPoisoned("known_audio_devices".to_string()).into())
+    }
+
+    /// Re-enumerates the audio devices after a change was reported, and compares
+    /// them to the previous enumeration. If a selected device is gone, selects
+    /// the default device (see FALLBACK_AUDIO_DEVICE_INDEX) instead.
+    #[cfg(feature = "native")]
+    pub fn refresh_audio_devices(&mut self) -> Result<AudioDevicesChanged> {
+        let (previous_playout, previous_recording, selected_playout, selected_recording) = {
+            let known = self.known_audio_devices()?;
+            (
+                known.playout.clone(),
+                known.recording.clone(),
+                known.selected_playout.clone(),
+                known.selected_recording.clone(),
+            )
+        };
+
+        let mut playout = AudioDeviceListChange::new(
+            &previous_playout,
+            self.get_audio_playout_devices()?,
+            selected_playout
+                .as_ref()
+                .and_then(AudioDeviceSelection::unique_id),
+        );
+        if playout.fell_back_to_default {
+            warn!("Selected playout device was removed; using the default device");
+            if let Err(e) = self.set_audio_playout_device(FALLBACK_AUDIO_DEVICE_INDEX) {
+                error!("Failed to fall back to the default playout device: {}", e);
+                playout.fell_back_to_default = false;
+            }
+        }
+
+        let mut recording = AudioDeviceListChange::new(
+            &previous_recording,
+            self.get_audio_recording_devices()?,
+            selected_recording
+                .as_ref()
+                .and_then(AudioDeviceSelection::unique_id),
+        );
+        if recording.fell_back_to_default {
+            warn!("Selected recording device was removed; using the default device");
+            if let Err(e) = self.set_audio_recording_device(FALLBACK_AUDIO_DEVICE_INDEX) {
+                error!("Failed to fall back to the default recording device: {}", e);
+                recording.fell_back_to_default = false;
+            }
+        }
+
+        Ok(AudioDevicesChanged { playout, recording })
+    }
+
     pub fn audio_backend(&self) -> Option<String> {
         self.backend_name.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(unique_id: &str) -> AudioDevice {
+        AudioDevice {
+            name: format!("{} name", unique_id),
+            unique_id: unique_id.to_string(),
+            i18n_key: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn audio_device_list_change_added_and_removed() {
+        let previous = vec![device("default"), device("a"), device("b")];
+        let current = vec![device("default"), device("b"), device("c")];
+
+        let change = AudioDeviceListChange::new(&previous, current.clone(), Some("b"));
+        assert_eq!(current, change.devices);
+        assert_eq!(vec![device("c")], change.added);
+        assert_eq!(vec![device("a")], change.removed);
+        assert!(!change.fell_back_to_default);
+        assert!(!change.is_empty());
+    }
+
+    #[test]
+    fn audio_device_list_change_selected_removed() {
+        let previous = vec![device("default"), device("a"), device("b")];
+        let current = vec![device("default"), device("b")];
+
+        let change = AudioDeviceListChange::new(&previous, current, Some("a"));
+        assert!(change.fell_back_to_default);
+
+        // A default slot is never removed out from under the selection.
+        let change = AudioDeviceListChange::new(&previous, vec![], None);
+        assert!(!change.fell_back_to_default);
+        assert_eq!(previous, change.removed);
+    }
+
+    #[test]
+    fn audio_device_list_change_unchanged() {
+        let previous = vec![device("default"), device("a")];
+
+        let change = AudioDeviceListChange::new(&previous, previous.clone(), Some("a"));
+        assert!(change.is_empty());
+        assert!(AudioDevicesChanged::default().is_empty());
+    }
+
+    #[test]
+    fn audio_device_stats_tracker() {
+        let tracker = AudioDeviceStatsTracker::default();
+        let ms = Duration::from_millis;
+
+        tracker.record_playout_callback(None);
+        tracker.record_playout_callback(Some(ms(2)));
+        tracker.record_playout_callback(Some(ms(6)));
+        tracker.record_playout_window(true, Some(ms(100)), None);
+        tracker.record_playout_window(false, Some(ms(110)), Some(ms(5000)));
+        tracker.record_recording_callback(Some(ms(1)));
+        tracker.record_recording_delivery_error();
+        tracker.record_recording_overrun(480);
+        tracker.record_device_change();
+
+        let stats = tracker.stats();
+        assert_eq!(3, stats.playout_callbacks);
+        assert_eq!(1, stats.playout_underruns);
+        assert_eq!(ms(4), stats.playout_mean_jitter);
+        assert_eq!(ms(6), stats.playout_max_jitter);
+        assert_eq!(Some(ms(110)), stats.last_playout_elapsed_time);
+        assert_eq!(Some(ms(5000)), stats.last_playout_ntp_time);
+        assert_eq!(1, stats.recording_callbacks);
+        assert_eq!(1, stats.recording_delivery_errors);
+        assert_eq!(1, stats.recording_overruns);
+        assert_eq!(480, stats.recording_dropped_frames);
+        assert_eq!(ms(1), stats.recording_max_jitter);
+        assert_eq!(1, stats.device_changes);
+        assert_eq!(0, stats.stream_errors);
+
+        assert_eq!(None, stats.input_to_output_latency());
+        tracker.record_latency(Some(ms(20)), Some(ms(15)));
+        assert_eq!(Some(ms(35)), tracker.stats().input_to_output_latency());
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn preferred_audio_device_index_uses_first_present() {
+        let devices = vec![device("default"), device("a"), device("b")];
+
+        let preferred = vec!["gone".to_string(), "b".to_string(), "a".to_string()];
+        assert_eq!(2, preferred_audio_device_index(&devices, &preferred));
+
+        let preferred = vec!["gone".to_string()];
+        assert_eq!(0, preferred_audio_device_index(&devices, &preferred));
+        assert_eq!(0, preferred_audio_device_index(&devices, &[]));
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn audio_device_id_request_stops_at_default_slot() {
+        let devices = vec![device("default"), device("extra"), device("a"), device("b")];
+        let preferred = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            (preferred(&["gone", "b"]), 0),
+            audio_device_id_request(&devices, &preferred(&["gone", "b"]))
+        );
+        assert_eq!(
+            (preferred(&["a"]), 0),
+            audio_device_id_request(&devices, &preferred(&["a", "default", "b"]))
+        );
+        assert_eq!(
+            (vec![], 0),
+            audio_device_id_request(&devices, &preferred(&["default", "a"]))
+        );
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn audio_device_selected_by_id_uses_outcome() {
+        let devices = vec![device("default"), device("extra"), device("a"), device("b")];
+
+        assert_eq!(
+            Some((AudioDeviceSelection::Device("b".to_string()), device("b"))),
+            audio_device_selected_by_id(
+                &devices,
+                Some(AudioDeviceIdOutcome::Selected("b".to_string())),
+                0
+            )
+        );
+        assert_eq!(
+            Some((AudioDeviceSelection::DefaultSlot(0), device("default"))),
+            audio_device_selected_by_id(&devices, Some(AudioDeviceIdOutcome::NotPresent), 0)
+        );
+        assert_eq!(
+            None,
+            audio_device_selected_by_id(
+                &devices,
+                Some(AudioDeviceIdOutcome::Selected("gone".to_string())),
+                0
+            )
+        );
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn audio_device_selection_follows_unique_id() {
+        let devices = vec![device("default"), device("extra"), device("a"), device("b")];
+        let selected = AudioDeviceSelection::new(&devices, 3).expect("device selected");
+        assert_eq!(Some("b"), selected.unique_id());
+
+        // The list changed since selection; the device is found at its new index.
+        let devices = vec![device("default"), device("extra"), device("b")];
+        assert_eq!(Some((2, device("b"))), selected.find(&devices));
+
+        let devices = vec![device("default"), device("extra"), device("a")];
+        assert_eq!(None, selected.find(&devices));
+
+        let default = AudioDeviceSelection::new(&devices, 0).expect("default selected");
+        assert_eq!(None, default.unique_id());
+        assert_eq!(Some((0, device("default"))), default.find(&devices));
+        assert_eq!(None, AudioDeviceSelection::new(&devices, 10));
+    }
+}
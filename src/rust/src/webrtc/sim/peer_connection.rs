@@ -55,9 +55,10 @@ impl RffiPeerConnection {
                 remote_description_set: false,
                 outgoing_audio_enabled: false,
                 rtp_packet_sink: None,
+                added_ice_candidates: vec![],
                 removed_ice_candidates: vec![],
                 max_bitrate_bps: None,
-                last_sent_rtp_data: None,
+                sent_rtp_data: vec![],
             })),
         }
     }
@@ -95,6 +96,15 @@ impl RffiPeerConnection {
         state.rtp_packet_sink = Some(rtp_packet_sink);
     }
 
+    fn add_ice_candidate(&self, sdp: String) {
+        self.state.lock().unwrap().added_ice_candidates.push(sdp);
+    }
+
+    pub fn added_ice_candidates(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state.added_ice_candidates.clone()
+    }
+
     fn remove_ice_candidates(&self, removed_addresses: impl Iterator<Item = SocketAddr>) {
         self.state
             .lock()
@@ -114,11 +124,16 @@ impl RffiPeerConnection {
     }
 
     pub fn last_sent_rtp_message(&self) -> Option<crate::protobuf::rtp_data::Message> {
+        self.sent_rtp_messages().pop()
+    }
+
+    pub fn sent_rtp_messages(&self) -> Vec<crate::protobuf::rtp_data::Message> {
         let state = self.state.lock().unwrap();
-        Some(
-            crate::protobuf::rtp_data::Message::decode(&state.last_sent_rtp_data.as_deref()?[4..])
-                .unwrap(),
-        )
+        state
+            .sent_rtp_data
+            .iter()
+            .map(|data| crate::protobuf::rtp_data::Message::decode(&data[4..]).unwrap())
+            .collect()
     }
 
     pub fn last_sent_max_bitrate_bps(&self) -> Option<u64> {
@@ -135,9 +150,10 @@ struct RffiPeerConnectionState {
     remote_description_set: bool,
     outgoing_audio_enabled: bool,
     rtp_packet_sink: Option<BoxedRtpPacketSink>,
+    added_ice_candidates: Vec<String>,
     removed_ice_candidates: Vec<SocketAddr>,
     max_bitrate_bps: Option<i32>,
-    last_sent_rtp_data: Option<Vec<u8>>,
+    sent_rtp_data: Vec<Vec<u8>>,
 }
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
@@ -223,10 +239,14 @@ pub unsafe fn Rust_setAudioRecordingEnabled(
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
 pub unsafe fn Rust_addIceCandidateFromSdp(
-    _peer_connection: webrtc::ptr::BorrowedRc<RffiPeerConnection>,
-    _sdp: webrtc::ptr::Borrowed<c_char>,
+    peer_connection: webrtc::ptr::BorrowedRc<RffiPeerConnection>,
+    sdp: webrtc::ptr::Borrowed<c_char>,
 ) -> bool {
     info!("Rust_addIceCandidateFromSdp():");
+    let sdp = std::ffi::CStr::from_ptr(sdp.as_ptr())
+        .to_string_lossy()
+        .into_owned();
+    (*peer_connection.as_ptr()).add_ice_candidate(sdp);
     true
 }
 
@@ -306,7 +326,7 @@ pub unsafe fn Rust_sendRtp(
     info!("Rust_sendRtp:");
     let mut state = (*peer_connection.as_ptr()).state.lock().unwrap();
     let payload = std::slice::from_raw_parts(payload_data.as_ptr(), payload_size);
-    state.last_sent_rtp_data = Some(payload.to_vec());
+    state.sent_rtp_data.push(payload.to_vec());
     if let Some(rtp_packet_sink) = &state.rtp_packet_sink {
         let header = rtp::Header {
             pt,
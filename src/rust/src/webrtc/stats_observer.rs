@@ -13,7 +13,10 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::{common::CallId, webrtc};
+use crate::{
+    common::CallId,
+    webrtc::{self, peer_connection_factory::AudioDeviceStatsTracker},
+};
 
 #[cfg(not(feature = "sim"))]
 use crate::webrtc::ffi::stats_observer as stats;
@@ -55,6 +58,7 @@ pub struct StatsObserver {
     stats_received_count: u32,
     #[cfg(not(target_os = "android"))]
     system_stats: sysinfo::System,
+    audio_device_stats: Option<AudioDeviceStatsTracker>,
 }
 
 impl StatsObserver {
@@ -72,6 +76,21 @@ impl StatsObserver {
                 system,\
                 cpu_usage_pct"
         );
+        info!(
+            "ringrtc_stats!,\
+                audio_device,\
+                playout_underruns,\
+                playout_mean_jitter,\
+                playout_max_jitter,\
+                recording_delivery_errors,\
+                recording_overruns,\
+                recording_dropped_frames,\
+                recording_mean_jitter,\
+                recording_max_jitter,\
+                input_to_output_latency,\
+                device_changes,\
+                stream_errors"
+        );
         info!(
             "ringrtc_stats!,\
                 audio,\
@@ -163,6 +182,33 @@ impl StatsObserver {
         }
     }
 
+    fn print_audio_device(&self) {
+        // Only RingRTC's ADM collects these; don't log zeros for the others.
+        let Some(stats) = self.audio_device_stats.as_ref().map(|t| t.stats()) else {
+            return;
+        };
+        if stats.playout_callbacks == 0 && stats.recording_callbacks == 0 {
+            return;
+        }
+        // The counts are totals since the ADM started.
+        info!(
+            "ringrtc_stats!,audio_device,{playout_underruns},{playout_mean_jitter:.1}ms,{playout_max_jitter:.1}ms,{recording_delivery_errors},{recording_overruns},{recording_dropped_frames},{recording_mean_jitter:.1}ms,{recording_max_jitter:.1}ms,{input_to_output_latency},{device_changes},{stream_errors}",
+            playout_underruns = stats.playout_underruns,
+            playout_mean_jitter = stats.playout_mean_jitter.as_secs_f64() * 1000.0,
+            playout_max_jitter = stats.playout_max_jitter.as_secs_f64() * 1000.0,
+            recording_delivery_errors = stats.recording_delivery_errors,
+            recording_overruns = stats.recording_overruns,
+            recording_dropped_frames = stats.recording_dropped_frames,
+            recording_mean_jitter = stats.recording_mean_jitter.as_secs_f64() * 1000.0,
+            recording_max_jitter = stats.recording_max_jitter.as_secs_f64() * 1000.0,
+            input_to_output_latency = stats
+                .input_to_output_latency()
+                .map_or("?".to_string(), |latency| format!("{}ms", latency.as_millis())),
+            device_changes = stats.device_changes,
+            stream_errors = stats.stream_errors,
+        );
+    }
+
     fn print_audio_sender(
         audio_sender: &AudioSenderStatistics,
         prev_audio_sender: &AudioSenderStatistics,
@@ -295,7 +341,11 @@ impl StatsObserver {
     }
 
     /// Create a new StatsObserver.
-    fn new(call_id: CallId, stats_initial_offset: Duration) -> Self {
+    fn new(
+        call_id: CallId,
+        stats_initial_offset: Duration,
+        audio_device_stats: Option<AudioDeviceStatsTracker>,
+    ) -> Self {
         Self::print_headers();
 
         #[cfg(not(target_os = "android"))]
@@ -316,6 +366,7 @@ impl StatsObserver {
             stats_received_count: 0,
             #[cfg(not(target_os = "android"))]
             system_stats,
+            audio_device_stats,
         }
     }
 
@@ -329,6 +380,7 @@ impl StatsObserver {
 
         self.print_connection(media_statistics);
         self.print_system();
+        self.print_audio_device();
 
         let stats = &mut self.stats;
         let mut stats_report_json = stats.report_json.lock().unwrap();
@@ -632,11 +684,19 @@ const STATS_OBSERVER_CBS_PTR: *const StatsObserverCallbacks = &STATS_OBSERVER_CB
 /// Creates a new WebRTC C++ StatsObserver object,
 /// registering the collector callbacks to this module, and wraps the
 /// result in a Rust StatsObserver object.
+///
+/// If `audio_device_stats` is provided, the local audio device stats are
+/// logged along with the WebRTC stats.
 pub fn create_stats_observer(
     call_id: CallId,
     stats_initial_offset: Duration,
+    audio_device_stats: Option<AudioDeviceStatsTracker>,
 ) -> Box<StatsObserver> {
-    let stats_observer = Box::new(StatsObserver::new(call_id, stats_initial_offset));
+    let stats_observer = Box::new(StatsObserver::new(
+        call_id,
+        stats_initial_offset,
+        audio_device_stats,
+    ));
     let stats_observer_ptr = Box::into_raw(stats_observer);
     let rffi_stats_observer = webrtc::Arc::from_owned(unsafe {
         stats::Rust_createStatsObserver(
@@ -12,7 +12,10 @@ use std::env;
 use std::time::{Duration, SystemTime};
 
 use lazy_static::lazy_static;
+use ml_kem::kem::Encapsulate;
+use ml_kem::{Encoded, EncodedSizeUser, KemCore, MlKem768};
 use rand::distributions::{Distribution, Standard};
+use rand::rngs::OsRng;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha20Rng;
 
@@ -21,6 +24,7 @@ use ringrtc::core::call::Call;
 use ringrtc::core::call_manager::CallManager;
 use ringrtc::core::connection::Connection;
 use ringrtc::core::{group_call, signaling};
+use ringrtc::lite::call_links::CallLinkRootKey;
 use ringrtc::lite::http;
 use ringrtc::protobuf;
 use ringrtc::sim::sim_platform::SimPlatform;
@@ -176,6 +180,16 @@ impl TestContext {
         platform.last_ice_sent()
     }
 
+    pub fn last_offer_sent(&self) -> Option<signaling::Offer> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.last_offer_sent()
+    }
+
+    pub fn last_answer_sent(&self) -> Option<signaling::Answer> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.last_answer_sent()
+    }
+
     pub fn normal_hangups_sent(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.normal_hangups_sent()
@@ -186,6 +200,16 @@ impl TestContext {
         platform.need_permission_hangups_sent()
     }
 
+    pub fn handed_off_hangups_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.handed_off_hangups_sent()
+    }
+
+    pub fn take_escalation_root_keys(&self) -> Vec<[u8; 16]> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.take_escalation_root_keys()
+    }
+
     pub fn accepted_hangups_sent(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.accepted_hangups_sent()
@@ -266,6 +290,24 @@ impl TestContext {
             None,
         )
     }
+
+    pub fn create_call_link_call(
+        &self,
+        root_key: CallLinkRootKey,
+    ) -> Result<group_call::ClientId, anyhow::Error> {
+        self.cm().create_call_link_call_client(
+            "".to_owned(),
+            &[],
+            root_key,
+            None,
+            vec![],
+            None,
+            None,
+            ringrtc::webrtc::media::AudioTrack::new(webrtc::Arc::null(), None),
+            ringrtc::webrtc::media::VideoTrack::new(webrtc::Arc::null(), None),
+            None,
+        )
+    }
 }
 
 pub fn random_received_offer(_prng: &Prng, age: Duration) -> signaling::ReceivedOffer {
@@ -293,6 +335,23 @@ pub fn random_received_offer(_prng: &Prng, age: Duration) -> signaling::Received
     }
 }
 
+// A V5 offer, which also includes V4 parameters.
+#[allow(dead_code)]
+pub fn random_received_offer_v5(prng: &Prng, age: Duration) -> signaling::ReceivedOffer {
+    let received = random_received_offer(prng, age);
+    let (_, kem_encapsulation_key) = MlKem768::generate(&mut OsRng);
+    let offer = signaling::Offer::from_v4_and_v5(
+        received.offer.call_media_type,
+        received.offer.to_v4().unwrap(),
+        Some(protobuf::signaling::ConnectionParametersV5 {
+            kem_encapsulation_key: Some(kem_encapsulation_key.as_bytes().to_vec()),
+            kem_ciphertext: None,
+        }),
+    )
+    .unwrap();
+    signaling::ReceivedOffer { offer, ..received }
+}
+
 // Not sure why this is needed.  It is used...
 #[allow(dead_code)]
 pub fn random_received_answer(
@@ -316,6 +375,36 @@ pub fn random_received_answer(
     }
 }
 
+// A V5 answer to a V5 offer, which also includes V4 parameters.
+#[allow(dead_code)]
+pub fn random_received_answer_v5(
+    prng: &Prng,
+    sender_device_id: DeviceId,
+    offer: &signaling::Offer,
+) -> signaling::ReceivedAnswer {
+    type EncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+
+    let received = random_received_answer(prng, sender_device_id);
+    let kem_encapsulation_key = offer
+        .to_v5()
+        .and_then(|v5| v5.kem_encapsulation_key)
+        .unwrap();
+    let kem_encapsulation_key =
+        Encoded::<EncapsulationKey>::try_from(&kem_encapsulation_key[..]).unwrap();
+    let (kem_ciphertext, _) = EncapsulationKey::from_bytes(&kem_encapsulation_key)
+        .encapsulate(&mut OsRng)
+        .unwrap();
+    let answer = signaling::Answer::from_v4_and_v5(
+        received.answer.to_v4().unwrap(),
+        Some(protobuf::signaling::ConnectionParametersV5 {
+            kem_encapsulation_key: None,
+            kem_ciphertext: Some(kem_ciphertext.to_vec()),
+        }),
+    )
+    .unwrap();
+    signaling::ReceivedAnswer { answer, ..received }
+}
+
 pub fn random_ice_candidate(prng: &Prng) -> signaling::IceCandidate {
     let sdp = format!("ICE-CANDIDATE-{}", prng.gen::<u16>());
     // V1 and V2 are the same for ICE candidates
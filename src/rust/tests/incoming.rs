@@ -13,13 +13,18 @@ extern crate log;
 use std::time::Duration;
 
 use prost::Message;
+use rand::rngs::OsRng;
 use ringrtc::common::{
-    units::DataRate, ApplicationEvent, CallConfig, CallId, CallState, ConnectionState, DataMode,
+    units::DataRate, ApplicationEvent, CallConfig, CallId, CallMediaType, CallState,
+    ConnectionState, DataMode, DeviceId,
 };
-use ringrtc::core::call_manager::MAX_MESSAGE_AGE;
+use ringrtc::core::call_manager::{CallLinkCreateParams, RingPolicy, MAX_MESSAGE_AGE};
+use ringrtc::core::connection::Connection;
 use ringrtc::core::group_call;
 use ringrtc::core::signaling;
+use ringrtc::lite::call_links::CallLinkRootKey;
 use ringrtc::protobuf;
+use ringrtc::sim::sim_platform::SimPlatform;
 use ringrtc::webrtc;
 use ringrtc::webrtc::media::MediaStream;
 use ringrtc::webrtc::peer_connection_observer::{
@@ -28,7 +33,10 @@ use ringrtc::webrtc::peer_connection_observer::{
 
 #[macro_use]
 mod common;
-use common::{random_received_ice_candidate, random_received_offer, test_init, TestContext};
+use common::{
+    random_received_ice_candidate, random_received_offer, random_received_offer_v5, test_init,
+    TestContext,
+};
 
 // Create an inbound call session up to the ConnectingBeforeAccepted state.
 //
@@ -44,6 +52,10 @@ use common::{random_received_ice_candidate, random_received_offer, test_init, Te
 //
 // Now in the ConnectingBeforeAccepted state.
 fn start_inbound_call() -> TestContext {
+    start_inbound_call_with_config(CallConfig::default().with_data_mode(DataMode::Normal))
+}
+
+fn start_inbound_call_with_config(call_config: CallConfig) -> TestContext {
     let context = TestContext::new();
     let mut cm = context.cm();
 
@@ -71,7 +83,7 @@ fn start_inbound_call() -> TestContext {
     cm.proceed(
         active_call.call_id(),
         format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
+        call_config,
         None,
     )
     .expect(error_line!());
@@ -111,6 +123,97 @@ fn inbound_ice_connecting() {
     let _ = start_inbound_call();
 }
 
+#[test]
+fn inbound_v4_offer_gets_v4_answer() {
+    test_init();
+
+    let context = start_inbound_call();
+
+    let answer = context.last_answer_sent().expect(error_line!());
+    assert_eq!(answer.latest_version(), signaling::Version::V4);
+    assert!(answer.to_v4().is_some());
+    assert!(answer.to_v5().is_none());
+}
+
+#[test]
+fn inbound_v5_offer_gets_v5_answer() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let received = random_received_offer_v5(&context.prng, Duration::from_secs(0));
+    assert_eq!(received.offer.latest_version(), signaling::Version::V5);
+    cm.received_offer(remote_peer, call_id, received)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.answers_sent(), 1);
+    let answer = context.last_answer_sent().expect(error_line!());
+    assert_eq!(answer.latest_version(), signaling::Version::V5);
+    // V4 parameters are still needed for everything but the KEM.
+    assert!(answer.to_v4().is_some());
+    let v5_answer = answer.to_v5().expect(error_line!());
+    assert!(v5_answer.kem_encapsulation_key.is_none());
+    assert!(v5_answer.kem_ciphertext.is_some());
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectingBeforeAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn inbound_v5_offer_without_kem_key() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let mut received = random_received_offer(&context.prng, Duration::from_secs(0));
+    received.offer = signaling::Offer::from_v4_and_v5(
+        received.offer.call_media_type,
+        received.offer.to_v4().expect(error_line!()),
+        Some(protobuf::signaling::ConnectionParametersV5::default()),
+    )
+    .expect(error_line!());
+    cm.received_offer(remote_peer, call_id, received)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // A broken V5 offer must not be downgraded to V4.
+    assert_eq!(context.answers_sent(), 0);
+    assert_eq!(context.error_count(), 1);
+}
+
 // Create an inbound call session up to the ConnectedAndAccepted state.
 //
 // 1. receive an offer
@@ -119,7 +222,11 @@ fn inbound_ice_connecting() {
 //
 // Now in the ConnectedAndAccepted state.
 fn connect_inbound_call() -> TestContext {
-    let context = start_inbound_call();
+    connect_inbound_call_with_config(CallConfig::default().with_data_mode(DataMode::Normal))
+}
+
+fn connect_inbound_call_with_config(call_config: CallConfig) -> TestContext {
+    let context = start_inbound_call_with_config(call_config);
     let mut cm = context.cm();
     let active_call = context.active_call();
     let mut active_connection = context.active_connection();
@@ -625,27 +732,1135 @@ fn update_data_mode_when_relayed() {
         })
         .unwrap();
     cm.synchronize().expect(error_line!());
-    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.error_count(), 0);
+
+    assert_eq!(
+        Some(2_000_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
+
+    assert_eq!(
+        Some(2_000_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .last_sent_max_bitrate_bps()
+    );
+}
+
+#[test]
+fn start_inbound_call_with_error() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert!(cm.active_call().is_ok());
+    assert_eq!(context.start_outgoing_count(), 0);
+    assert_eq!(context.start_incoming_count(), 1);
+
+    let active_call = context.active_call();
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::WaitingToProceed
+    );
+
+    // cause the sending of the answer to fail.
+    context.force_internal_fault(true);
+
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    active_call.get_connection(1).expect_err(error_line!());
+
+    assert_eq!(context.error_count(), 1);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.answers_sent(), 0);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Terminated
+    );
+    assert!(!cm.busy());
+}
+
+#[test]
+fn receive_offer_while_active() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::ReceivedOfferWhileActive),
+        1
+    );
+    assert_eq!(context.busys_sent(), 1);
+    assert_eq!(context.call_concluded_count(), 1);
+    assert!(cm.busy());
+}
+
+// Create a waiting call on top of a connected inbound call.
+//
+// - connect inbound call with call waiting enabled
+// - receive offer from a different peer
+// - check no busy sent and start incoming event happened
+// - call proceed() for the waiting call
+// - add received ice candidate and inject ice connected
+// - check waiting call is in ConnectedBeforeAccepted state
+//
+// Returns the context and the waiting call_id.
+fn start_waiting_call() -> (TestContext, CallId) {
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.set_call_waiting_enabled(true).expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::ReceivedOfferWhileActive),
+        0
+    );
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(context.start_incoming_count(), 2);
+    assert!(cm
+        .call_is_active(active_call.call_id())
+        .expect(error_line!()));
+    assert!(cm.call_is_waiting_or_held(call_id).expect(error_line!()));
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let waiting_call = cm.waiting_call().expect(error_line!());
+    let mut waiting_connection = waiting_call.get_connection(1).expect(error_line!());
+
+    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
+    waiting_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    waiting_connection
+        .handle_received_incoming_media(MediaStream::new(webrtc::Arc::null()))
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.answers_sent(), 2);
+    assert_eq!(
+        waiting_call.state().expect(error_line!()),
+        CallState::ConnectedBeforeAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
+
+    (context, call_id)
+}
+
+// Accept the waiting call, putting the original call on hold.
+//
+// Returns the context and the held call_id.
+fn accept_waiting_call() -> (TestContext, CallId) {
+    let (context, waiting_call_id) = start_waiting_call();
+    let mut cm = context.cm();
+    let held_call = context.active_call();
+    let held_connection = context.active_connection();
+
+    cm.accept_call(waiting_call_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.active_call().call_id(), waiting_call_id);
+    assert_eq!(
+        context.active_call().state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert!(context
+        .active_connection()
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(
+        cm.held_call().expect(error_line!()).call_id(),
+        held_call.call_id()
+    );
+    assert!(!held_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(
+        Some(true),
+        held_connection
+            .last_sent_sender_status()
+            .and_then(|status| status.held)
+    );
+    assert_eq!(context.event_count(ApplicationEvent::LocalAccepted), 2);
+    assert_eq!(context.event_count(ApplicationEvent::LocalHoldEnable), 1);
+    assert_eq!(context.event_count(ApplicationEvent::LocalHoldDisable), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
+
+    (context, held_call.call_id())
+}
+
+#[test]
+fn call_waiting_accept_holds_active_call() {
+    test_init();
+
+    let _ = accept_waiting_call();
+}
+
+#[test]
+fn call_waiting_resume_held_call() {
+    test_init();
+
+    let (context, held_call_id) = accept_waiting_call();
+    let mut cm = context.cm();
+    let other_call = context.active_call();
+    let other_connection = context.active_connection();
+
+    cm.resume_call(held_call_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.active_call().call_id(), held_call_id);
+    assert!(context
+        .active_connection()
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(
+        Some(false),
+        context
+            .active_connection()
+            .last_sent_sender_status()
+            .and_then(|status| status.held)
+    );
+    assert_eq!(
+        cm.held_call().expect(error_line!()).call_id(),
+        other_call.call_id()
+    );
+    assert!(!other_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(context.event_count(ApplicationEvent::LocalHoldEnable), 2);
+    assert_eq!(context.event_count(ApplicationEvent::LocalHoldDisable), 2);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
+}
+
+#[test]
+fn call_waiting_hangup_held_call() {
+    test_init();
+
+    let (context, held_call_id) = accept_waiting_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.hangup_call(held_call_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(context.normal_hangups_sent(), 1);
+    assert!(cm.held_call().is_err());
+    assert_eq!(context.active_call().call_id(), active_call.call_id());
+    assert!(cm.busy());
+
+    cm.hangup().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 2);
+    assert_eq!(context.error_count(), 0);
+    assert!(!cm.busy());
+}
+
+#[test]
+fn call_waiting_active_call_ends() {
+    test_init();
+
+    let (context, waiting_call_id) = start_waiting_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 1);
+    assert_eq!(context.active_call().call_id(), waiting_call_id);
+    assert!(cm.waiting_call().is_err());
+    assert!(cm.busy());
+
+    cm.accept_call(waiting_call_id).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.active_call().state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn call_waiting_remote_hangup_waiting_call() {
+    test_init();
+
+    let (context, waiting_call_id) = start_waiting_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_hangup(
+        waiting_call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 1);
+    assert!(cm.waiting_call().is_err());
+    assert_eq!(context.active_call().call_id(), active_call.call_id());
+    assert!(context
+        .active_connection()
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(context.error_count(), 0);
+    assert!(cm.busy());
+}
+
+#[test]
+fn call_waiting_second_offer_is_busy() {
+    test_init();
+
+    let (context, _) = start_waiting_call();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::ReceivedOfferWhileActive),
+        1
+    );
+    assert_eq!(context.busys_sent(), 1);
+    assert_eq!(context.start_incoming_count(), 2);
+    assert!(cm.busy());
+}
+
+#[test]
+fn received_remote_hold() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    for (seqnum, held) in [(1, true), (2, false)] {
+        active_connection
+            .inject_received_sender_status_via_rtp_data(
+                active_call.call_id(),
+                signaling::SenderStatus {
+                    held: Some(held),
+                    ..Default::default()
+                },
+                seqnum,
+            )
+            .expect(error_line!());
+    }
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteHoldEnable), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteHoldDisable), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn received_remote_device_status() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    for (seqnum, device_status) in [
+        (
+            1,
+            signaling::DeviceStatus {
+                microphone_disconnected: Some(true),
+                backgrounded: Some(true),
+                camera_switching: None,
+            },
+        ),
+        (
+            3,
+            signaling::DeviceStatus {
+                microphone_disconnected: Some(false),
+                backgrounded: Some(true),
+                camera_switching: Some(true),
+            },
+        ),
+        // Arrives out of order, so it is ignored.
+        (
+            2,
+            signaling::DeviceStatus {
+                microphone_disconnected: Some(true),
+                backgrounded: Some(false),
+                camera_switching: None,
+            },
+        ),
+    ] {
+        active_connection
+            .inject_received_sender_status_via_rtp_data(
+                active_call.call_id(),
+                signaling::SenderStatus {
+                    device_status: Some(device_status),
+                    ..Default::default()
+                },
+                seqnum,
+            )
+            .expect(error_line!());
+    }
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteMicrophoneDisconnected),
+        1
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteMicrophoneReconnected),
+        1
+    );
+    // The full status is reported whenever it changes.
+    assert_eq!(context.event_count(ApplicationEvent::RemoteBackgrounded), 2);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteForegrounded), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteCameraSwitchStarted),
+        1
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteCameraSwitchEnded),
+        0
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+fn received_handoff_offer(
+    context: &TestContext,
+    sender_device_id: DeviceId,
+) -> (String, signaling::ReceivedOffer) {
+    let remote_peer = context
+        .active_call()
+        .remote_peer()
+        .expect(error_line!())
+        .to_owned();
+    let received = random_received_offer(&context.prng, Duration::from_secs(0));
+    let received = signaling::ReceivedOffer {
+        offer: received.offer.into_handoff().expect(error_line!()),
+        sender_device_id,
+        ..received
+    };
+    (remote_peer, received)
+}
+
+#[test]
+fn handoff_to_another_device() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    info!("test: receiving handoff offer from device 2");
+    let (remote_peer, received) = received_handoff_offer(&context, 2);
+    cm.received_offer(remote_peer, active_call.call_id(), received)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The original connection stays active until the new one is connected.
+    assert_eq!(context.answers_sent(), 2);
+    assert_eq!(context.start_incoming_count(), 1);
+    assert_eq!(active_call.active_device_id().expect(error_line!()), 1);
+    let mut handoff_connection = active_call.get_connection(2).expect(error_line!());
+    handoff_connection
+        .handle_received_incoming_media(MediaStream::new(webrtc::Arc::null()))
+        .expect(error_line!());
+
+    info!("test: injecting ice connected for device 2");
+    handoff_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(active_call.active_device_id().expect(error_line!()), 2);
+    assert_eq!(
+        handoff_connection.state().expect(error_line!()),
+        ConnectionState::ConnectedAndAccepted
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+
+    info!("test: old device hangs up");
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::HandedOffToAnotherDevice(2),
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert!(active_call.get_connection(1).is_err());
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
+}
+
+#[test]
+fn handoff_offer_without_active_call() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let received = random_received_offer(&context.prng, Duration::from_secs(0));
+    let received = signaling::ReceivedOffer {
+        offer: received.offer.into_handoff().expect(error_line!()),
+        ..received
+    };
+    cm.received_offer(
+        remote_peer,
+        CallId::new(context.prng.gen::<u64>()),
+        received,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // Devices that aren't in the call don't ring.
+    assert_eq!(context.start_incoming_count(), 0);
+    assert_eq!(context.call_concluded_count(), 1);
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(context.error_count(), 0);
+    assert!(!cm.busy());
+}
+
+#[test]
+fn handed_off_to_another_device() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::HandedOffToAnotherDevice(2),
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedHandedOffToAnotherDevice),
+        1
+    );
+    // The remote side is told to drop this device rather than end the call.
+    assert_eq!(context.handed_off_hangups_sent(), 1);
+    assert_eq!(context.normal_hangups_sent(), 0);
+    assert!(!cm.busy());
+}
+
+#[test]
+fn escalate_to_call_link() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+    let root_key = CallLinkRootKey::generate(OsRng);
+
+    cm.escalate_to_call_link(active_call.call_id(), root_key.clone(), None)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.take_escalation_root_keys(), vec![root_key.bytes()]);
+    let sent = active_connection
+        .last_sent_escalation()
+        .expect(error_line!());
+    assert_eq!(sent.root_key(), root_key.bytes());
+    assert!(!sent.joined());
+    // A reliable copy goes over MRP too.
+    assert!(active_connection
+        .app_connection()
+        .expect(error_line!())
+        .sent_rtp_messages()
+        .iter()
+        .any(|message| message.escalation.is_some() && message.mrp_header.is_some()));
+
+    let client_id = context
+        .create_call_link_call(root_key.clone())
+        .expect(error_line!());
+    cm.join(client_id);
+    group_call::Observer::handle_join_state_changed(
+        &cm,
+        client_id,
+        group_call::JoinState::Joined(1),
+    );
+    cm.synchronize().expect(error_line!());
+
+    assert!(active_connection
+        .last_sent_escalation()
+        .expect(error_line!())
+        .joined());
+    assert_eq!(context.ended_count(), 0);
+
+    active_connection
+        .inject_received_escalation_via_rtp_data(
+            active_call.call_id(),
+            signaling::Escalation {
+                root_key: root_key.bytes(),
+                joined: true,
+            },
+            1,
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EscalationRemoteJoined),
+        1
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedEscalatedToCallLink),
+        1
+    );
+    assert_eq!(context.normal_hangups_sent(), 1);
+}
+
+#[test]
+fn received_escalation() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+    let root_key = CallLinkRootKey::generate(OsRng);
+
+    for (seqnum, joined) in [(1, false), (2, true)] {
+        active_connection
+            .inject_received_escalation_via_rtp_data(
+                active_call.call_id(),
+                signaling::Escalation {
+                    root_key: root_key.bytes(),
+                    joined,
+                },
+                seqnum,
+            )
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+
+    // The app is told to join, and the call continues until it has.
+    assert_eq!(context.take_escalation_root_keys(), vec![root_key.bytes()]);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EscalationRemoteJoined),
+        1
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
+}
+
+#[test]
+fn escalate_to_call_link_create_failed() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    cm.escalate_to_call_link(
+        active_call.call_id(),
+        CallLinkRootKey::generate(OsRng),
+        Some(CallLinkCreateParams {
+            sfu_url: "".to_owned(),
+            auth_presentation: vec![],
+            admin_passkey: vec![],
+            public_zkparams: vec![],
+            restrictions: None,
+        }),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The create request is the first HTTP request of the test.
+    cm.received_http_response(0, None);
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EscalationFailed), 1);
+    assert!(context.take_escalation_root_keys().is_empty());
+    assert!(active_connection.last_sent_escalation().is_none());
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+fn sent_video_upgrade(
+    connection: &Connection<SimPlatform>,
+) -> (u32, protobuf::rtp_data::video_upgrade::Type) {
+    let sent = connection.last_sent_video_upgrade().expect(error_line!());
+    (sent.request_id(), sent.r#type())
+}
+
+fn sent_video_enabled(connection: &Connection<SimPlatform>) -> bool {
+    connection
+        .last_sent_sender_status()
+        .and_then(|status| status.video_enabled)
+        .unwrap_or(false)
+}
+
+#[test]
+fn video_upgrade_accepted() {
+    use protobuf::rtp_data::video_upgrade::Type;
+
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    cm.request_video_upgrade(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let (request_id, upgrade_type) = sent_video_upgrade(&active_connection);
+    assert_eq!(upgrade_type, Type::VideoUpgradeRequest);
+    assert!(!sent_video_enabled(&active_connection));
+
+    // A response to some other request is ignored.
+    for (seqnum, request_id) in [(1, request_id + 1), (2, request_id)] {
+        active_connection
+            .inject_received_video_upgrade_via_rtp_data(
+                active_call.call_id(),
+                signaling::VideoUpgrade {
+                    request_id,
+                    upgrade_type: signaling::VideoUpgradeType::Accept,
+                },
+                seqnum,
+            )
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::VideoUpgradeAccepted),
+        1
+    );
+    assert!(sent_video_enabled(&active_connection));
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn video_upgrade_declined() {
+    use protobuf::rtp_data::video_upgrade::Type;
+
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    cm.request_video_upgrade(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let (request_id, _) = sent_video_upgrade(&active_connection);
+
+    active_connection
+        .inject_received_video_upgrade_via_rtp_data(
+            active_call.call_id(),
+            signaling::VideoUpgrade {
+                request_id,
+                upgrade_type: signaling::VideoUpgradeType::Decline,
+            },
+            1,
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::VideoUpgradeDeclined),
+        1
+    );
+    assert_eq!(
+        sent_video_upgrade(&active_connection),
+        (request_id, Type::VideoUpgradeRequest)
+    );
+    assert!(!sent_video_enabled(&active_connection));
+
+    // A new request can be made once the previous one is answered.
+    cm.request_video_upgrade(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        sent_video_upgrade(&active_connection),
+        (request_id + 1, Type::VideoUpgradeRequest)
+    );
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn video_upgrade_timed_out() {
+    use protobuf::rtp_data::video_upgrade::Type;
+
+    test_init();
+
+    let context = connect_inbound_call_with_config(
+        CallConfig::default()
+            .with_data_mode(DataMode::Normal)
+            .with_video_upgrade_timeout(Duration::from_millis(100)),
+    );
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    cm.request_video_upgrade(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let (request_id, _) = sent_video_upgrade(&active_connection);
+
+    std::thread::sleep(Duration::from_millis(400));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::VideoUpgradeTimedOut),
+        1
+    );
+    assert_eq!(
+        sent_video_upgrade(&active_connection),
+        (request_id, Type::VideoUpgradeCancel)
+    );
+    assert!(!sent_video_enabled(&active_connection));
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn remote_video_upgrade() {
+    use protobuf::rtp_data::video_upgrade::Type;
+
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    let mut inject = |request_id, upgrade_type, seqnum| {
+        active_connection
+            .inject_received_video_upgrade_via_rtp_data(
+                active_call.call_id(),
+                signaling::VideoUpgrade {
+                    request_id,
+                    upgrade_type,
+                },
+                seqnum,
+            )
+            .expect(error_line!());
+    };
+
+    inject(7, signaling::VideoUpgradeType::Request, 1);
+    // Retransmits are ignored.
+    inject(7, signaling::VideoUpgradeType::Request, 1);
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteVideoUpgradeRequested),
+        1
+    );
+
+    cm.decline_video_upgrade(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        sent_video_upgrade(&context.active_connection()),
+        (7, Type::VideoUpgradeDecline)
+    );
+    assert!(!sent_video_enabled(&context.active_connection()));
+
+    inject(8, signaling::VideoUpgradeType::Request, 2);
+    inject(8, signaling::VideoUpgradeType::Cancel, 3);
+    inject(9, signaling::VideoUpgradeType::Request, 4);
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteVideoUpgradeRequested),
+        3
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteVideoUpgradeCanceled),
+        1
+    );
+
+    cm.accept_video_upgrade(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        sent_video_upgrade(&context.active_connection()),
+        (9, Type::VideoUpgradeAccept)
+    );
+    assert!(sent_video_enabled(&context.active_connection()));
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn video_upgrade_glare() {
+    use protobuf::rtp_data::video_upgrade::Type;
+
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    cm.request_video_upgrade(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Both sides asked at the same time, so the remote request accepts the
+    // local one.
+    active_connection
+        .inject_received_video_upgrade_via_rtp_data(
+            active_call.call_id(),
+            signaling::VideoUpgrade {
+                request_id: 5,
+                upgrade_type: signaling::VideoUpgradeType::Request,
+            },
+            1,
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
     assert_eq!(
-        Some(2_000_000),
-        active_connection
-            .app_connection()
-            .unwrap()
-            .max_bitrate_bps()
+        context.event_count(ApplicationEvent::VideoUpgradeAccepted),
+        1
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteVideoUpgradeRequested),
+        0
+    );
+    assert_eq!(
+        sent_video_upgrade(&active_connection),
+        (5, Type::VideoUpgradeAccept)
     );
+    assert!(sent_video_enabled(&active_connection));
+    assert_eq!(context.error_count(), 0);
+}
+
+fn sent_reliable_accepted_count(connection: &Connection<SimPlatform>) -> usize {
+    connection
+        .app_connection()
+        .expect(error_line!())
+        .sent_rtp_messages()
+        .iter()
+        .filter(|message| {
+            message.accepted.is_some()
+                && message
+                    .mrp_header
+                    .as_ref()
+                    .is_some_and(|header| header.seqnum == Some(1))
+        })
+        .count()
+}
+
+fn sent_rtp_data_acks(connection: &Connection<SimPlatform>) -> Vec<u64> {
+    connection
+        .app_connection()
+        .expect(error_line!())
+        .sent_rtp_messages()
+        .iter()
+        .filter_map(|message| message.mrp_header.as_ref()?.ack_num)
+        .collect()
+}
+
+#[test]
+fn reliable_rtp_data() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    // Accepting sends the latest state as well as a reliable copy.
+    assert_eq!(sent_reliable_accepted_count(&active_connection), 1);
+    let latest = active_connection
+        .app_connection()
+        .expect(error_line!())
+        .sent_rtp_messages()
+        .into_iter()
+        .find(|message| message.accepted.is_some() && message.mrp_header.is_none())
+        .expect(error_line!());
+    assert!(latest.seqnum.is_some());
+
+    // Accepted is resent even though the remote peer hasn't shown that it
+    // understands MRP yet, since it may not have anything to send first.
+    std::thread::sleep(Duration::from_millis(700));
+    assert!(sent_reliable_accepted_count(&active_connection) > 1);
+
+    let reliable_sender_status = protobuf::rtp_data::Message {
+        sender_status: Some(protobuf::rtp_data::SenderStatus {
+            id: Some(u64::from(active_call.call_id())),
+            video_enabled: Some(true),
+            ..Default::default()
+        }),
+        seqnum: Some(1),
+        mrp_header: Some(protobuf::rtp_data::MrpHeader {
+            seqnum: Some(1),
+            ack_num: None,
+        }),
+        ..Default::default()
+    }
+    .encode_to_vec();
+    active_connection.inject_received_via_rtp_data(&reliable_sender_status);
+    // Retransmits are acked again but only handled once.
+    active_connection.inject_received_via_rtp_data(&reliable_sender_status);
 
+    std::thread::sleep(Duration::from_millis(700));
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.event_count(ApplicationEvent::RemoteVideoEnable), 1);
+    assert!(sent_rtp_data_acks(&active_connection).contains(&2));
+    // Now that the remote peer speaks MRP, the unacked message is resent.
+    assert!(sent_reliable_accepted_count(&active_connection) > 1);
+
+    let ack = protobuf::rtp_data::Message {
+        mrp_header: Some(protobuf::rtp_data::MrpHeader {
+            seqnum: None,
+            ack_num: Some(2),
+        }),
+        ..Default::default()
+    }
+    .encode_to_vec();
+    active_connection.inject_received_via_rtp_data(&ack);
+    std::thread::sleep(Duration::from_millis(300));
+    let resent_count = sent_reliable_accepted_count(&active_connection);
+    std::thread::sleep(Duration::from_millis(700));
     assert_eq!(
-        Some(2_000_000),
-        active_connection
-            .app_connection()
-            .unwrap()
-            .last_sent_max_bitrate_bps()
+        sent_reliable_accepted_count(&active_connection),
+        resent_count
     );
+    assert_eq!(context.error_count(), 0);
 }
 
 #[test]
-fn start_inbound_call_with_error() {
+fn receive_expired_offer() {
     test_init();
 
     let context = TestContext::new();
@@ -653,80 +1868,71 @@ fn start_inbound_call_with_error() {
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
+    let age = Duration::from_secs(86400); // one whole day
     cm.received_offer(
         remote_peer,
         call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
+        random_received_offer(&context.prng, age),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
-    assert!(cm.active_call().is_ok());
-    assert_eq!(context.start_outgoing_count(), 0);
-    assert_eq!(context.start_incoming_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.offer_expired_count(), 1);
+    assert!(!cm.busy());
+}
 
-    let active_call = context.active_call();
-    assert_eq!(
-        active_call.state().expect(error_line!()),
-        CallState::WaitingToProceed
-    );
+#[test]
+fn receive_offer_before_age_limit() {
+    test_init();
 
-    // cause the sending of the answer to fail.
-    context.force_internal_fault(true);
+    let context = TestContext::new();
+    let mut cm = context.cm();
 
-    cm.proceed(
-        active_call.call_id(),
-        format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
-        None,
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let age = MAX_MESSAGE_AGE - Duration::from_secs(1);
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, age),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
-    active_call.get_connection(1).expect_err(error_line!());
-
-    assert_eq!(context.error_count(), 1);
-    assert_eq!(context.ended_count(), 1);
-    assert_eq!(context.answers_sent(), 0);
-    assert_eq!(
-        active_call.state().expect(error_line!()),
-        CallState::Terminated
-    );
-    assert!(!cm.busy());
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.offer_expired_count(), 0);
+    assert!(cm.busy());
 }
 
 #[test]
-fn receive_offer_while_active() {
+fn receive_offer_at_age_limit() {
     test_init();
 
-    let context = connect_inbound_call();
+    let context = TestContext::new();
     let mut cm = context.cm();
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
+    let age = MAX_MESSAGE_AGE;
     cm.received_offer(
         remote_peer,
         call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
+        random_received_offer(&context.prng, age),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
-    assert_eq!(
-        context.event_count(ApplicationEvent::ReceivedOfferWhileActive),
-        1
-    );
-    assert_eq!(context.busys_sent(), 1);
-    assert_eq!(context.call_concluded_count(), 1);
+    assert_eq!(context.offer_expired_count(), 0);
     assert!(cm.busy());
 }
 
 #[test]
-fn receive_expired_offer() {
+fn receive_expired_offer_after_age_limit() {
     test_init();
 
     let context = TestContext::new();
@@ -734,7 +1940,7 @@ fn receive_expired_offer() {
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-    let age = Duration::from_secs(86400); // one whole day
+    let age = MAX_MESSAGE_AGE + Duration::from_secs(1);
     cm.received_offer(
         remote_peer,
         call_id,
@@ -750,75 +1956,100 @@ fn receive_expired_offer() {
 }
 
 #[test]
-fn receive_offer_before_age_limit() {
+fn ring_policy_outgoing_ring_timeout() {
     test_init();
 
     let context = TestContext::new();
     let mut cm = context.cm();
+    cm.set_ring_policy(RingPolicy {
+        outgoing_ring_timeout: Duration::from_millis(200),
+        ..Default::default()
+    })
+    .expect(error_line!());
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-    let age = MAX_MESSAGE_AGE - Duration::from_secs(1);
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, age),
-    )
-    .expect(error_line!());
+    cm.call(remote_peer, CallMediaType::Audio, 1)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(cm.busy());
 
+    std::thread::sleep(Duration::from_millis(400));
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.offer_expired_count(), 0);
-    assert!(cm.busy());
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+    assert!(!cm.busy());
 }
 
 #[test]
-fn receive_offer_at_age_limit() {
+fn ring_policy_incoming_ring_timeout() {
     test_init();
 
     let context = TestContext::new();
     let mut cm = context.cm();
+    cm.set_ring_policy(RingPolicy {
+        incoming_ring_timeout: Duration::from_millis(200),
+        ..Default::default()
+    })
+    .expect(error_line!());
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-    let age = MAX_MESSAGE_AGE;
     cm.received_offer(
         remote_peer,
         call_id,
-        random_received_offer(&context.prng, age),
+        random_received_offer(&context.prng, Duration::ZERO),
     )
     .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(cm.busy());
 
+    std::thread::sleep(Duration::from_millis(400));
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.offer_expired_count(), 0);
-    assert!(cm.busy());
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+    assert!(!cm.busy());
 }
 
 #[test]
-fn receive_expired_offer_after_age_limit() {
+fn ring_policy_max_offer_age() {
     test_init();
 
     let context = TestContext::new();
     let mut cm = context.cm();
+    let max_offer_age = Duration::from_secs(10);
+    cm.set_ring_policy(RingPolicy {
+        max_offer_age,
+        ..Default::default()
+    })
+    .expect(error_line!());
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-    let age = MAX_MESSAGE_AGE + Duration::from_secs(1);
     cm.received_offer(
-        remote_peer,
+        remote_peer.clone(),
         call_id,
-        random_received_offer(&context.prng, age),
+        random_received_offer(&context.prng, max_offer_age + Duration::from_secs(1)),
     )
     .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.offer_expired_count(), 1);
+    assert!(!cm.busy());
 
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, max_offer_age),
+    )
+    .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
     assert_eq!(context.offer_expired_count(), 1);
-    assert!(!cm.busy());
+    assert!(cm.busy());
 }
 
 #[test]
@@ -1806,6 +3037,124 @@ fn group_call_ring_timeout() {
     );
 }
 
+#[test]
+fn ring_policy_group_ring_expiry() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_ring_policy(RingPolicy {
+        group_ring_expiry: Duration::from_millis(200),
+        ..Default::default()
+    })
+    .expect(error_line!());
+
+    let group_id = vec![1, 1, 1];
+    let sender_id = vec![1, 2, 3];
+    let ring_id = group_call::RingId::from(42);
+
+    let message = protobuf::signaling::CallMessage {
+        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
+            group_id: Some(group_id.clone()),
+            ring_id: Some(ring_id.into()),
+            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
+
+    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    assert_eq!(
+        vec![group_call::RingUpdate::Requested],
+        ring_updates
+            .iter()
+            .map(|update| update.update)
+            .collect::<Vec<_>>()
+    );
+
+    // Shorter than the 1 second default used by the tests.
+    std::thread::sleep(Duration::from_millis(400));
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    match &ring_updates[..] {
+        [update] => {
+            assert_eq!(
+                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
+                    group_id,
+                    ring_id,
+                    sender_id,
+                    update: group_call::RingUpdate::ExpiredRequest
+                },
+                update
+            );
+        }
+        _ => panic!("unexpected ring updates: {:?}", ring_updates),
+    }
+}
+
+#[test]
+fn ring_policy_group_ring_max_age() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    let max_offer_age = Duration::from_secs(10);
+    cm.set_ring_policy(RingPolicy {
+        max_offer_age,
+        ..Default::default()
+    })
+    .expect(error_line!());
+
+    let message = protobuf::signaling::CallMessage {
+        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
+            group_id: Some(vec![1, 1, 1]),
+            ring_id: Some(42),
+            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
+
+    cm.received_call_message(
+        vec![1, 2, 3],
+        1,
+        2,
+        buf,
+        max_offer_age + Duration::from_millis(1),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    assert_eq!(
+        vec![group_call::RingUpdate::ExpiredRequest],
+        ring_updates
+            .iter()
+            .map(|update| update.update)
+            .collect::<Vec<_>>()
+    );
+}
+
 #[test]
 fn received_status_before_accepted() {
     let context = start_inbound_call();
@@ -1828,6 +3177,8 @@ fn received_status_before_accepted() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -19,6 +19,7 @@ use ringrtc::common::{
     units::DataRate, ApplicationEvent, CallConfig, CallId, CallMediaType, CallState,
     ConnectionState, DataMode, DeviceId,
 };
+use ringrtc::core::candidate_policy::CandidatePolicy;
 use ringrtc::core::{group_call, signaling};
 use ringrtc::protobuf;
 use ringrtc::sim::error::SimError;
@@ -31,8 +32,8 @@ use ringrtc::webrtc::peer_connection_observer::{
 #[macro_use]
 mod common;
 use common::{
-    random_ice_candidate, random_received_answer, random_received_ice_candidate,
-    random_received_offer, test_init, TestContext,
+    random_ice_candidate, random_received_answer, random_received_answer_v5,
+    random_received_ice_candidate, random_received_offer, test_init, TestContext,
 };
 
 // Simple test that:
@@ -491,6 +492,46 @@ fn outbound_receive_answer() {
     let _ = start_outbound_call();
 }
 
+#[test]
+fn outbound_receive_v4_and_v5_answers() {
+    test_init();
+
+    let context = start_outbound_and_proceed();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let call_id = active_call.call_id();
+
+    // The offer carries both so that V4-only callees can still answer.
+    let offer = context.last_offer_sent().expect(error_line!());
+    assert_eq!(offer.latest_version(), signaling::Version::V5);
+    assert!(offer.to_v4().is_some());
+    let v5_offer = offer.to_v5().expect(error_line!());
+    assert!(v5_offer.kem_encapsulation_key.is_some());
+    assert!(v5_offer.kem_ciphertext.is_none());
+
+    let v4_answer = random_received_answer(&context.prng, 1);
+    assert_eq!(v4_answer.answer.latest_version(), signaling::Version::V4);
+    cm.received_answer(call_id, v4_answer).expect(error_line!());
+
+    let v5_answer = random_received_answer_v5(&context.prng, 2, &offer);
+    assert_eq!(v5_answer.answer.latest_version(), signaling::Version::V5);
+    cm.received_answer(call_id, v5_answer).expect(error_line!());
+
+    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    for device_id in [1, 2] {
+        let connection = active_call.get_connection(device_id).expect(error_line!());
+        assert_eq!(
+            connection.state().expect(error_line!()),
+            ConnectionState::ConnectingBeforeAccepted
+        );
+    }
+    assert_eq!(context.error_count(), 0);
+}
+
 #[test]
 fn outbound_call_connected() {
     test_init();
@@ -855,6 +896,8 @@ fn update_sender_status() {
             video_enabled: Some(false),
             sharing_screen: None,
             audio_enabled: Some(false),
+            held: None,
+            device_status: None,
         })
         .expect(error_line!());
 
@@ -867,6 +910,8 @@ fn update_sender_status() {
             video_enabled: Some(false),
             sharing_screen: None,
             audio_enabled: Some(false),
+            held: None,
+            device_status: None,
         }),
         active_connection.last_sent_sender_status()
     );
@@ -876,6 +921,8 @@ fn update_sender_status() {
             video_enabled: Some(true),
             sharing_screen: None,
             audio_enabled: Some(true),
+            held: None,
+            device_status: None,
         })
         .expect(error_line!());
 
@@ -884,6 +931,8 @@ fn update_sender_status() {
             video_enabled: None,
             sharing_screen: Some(true),
             audio_enabled: None,
+            held: None,
+            device_status: None,
         })
         .expect(error_line!());
 
@@ -896,6 +945,8 @@ fn update_sender_status() {
             video_enabled: Some(true),
             sharing_screen: Some(true),
             audio_enabled: Some(true),
+            held: None,
+            device_status: None,
         }),
         active_connection.last_sent_sender_status()
     );
@@ -905,6 +956,8 @@ fn update_sender_status() {
             video_enabled: None,
             sharing_screen: Some(false),
             audio_enabled: None,
+            held: None,
+            device_status: None,
         })
         .expect(error_line!());
 
@@ -917,6 +970,59 @@ fn update_sender_status() {
             video_enabled: Some(true),
             sharing_screen: Some(false),
             audio_enabled: Some(true),
+            held: None,
+            device_status: None,
+        }),
+        active_connection.last_sent_sender_status()
+    );
+}
+
+#[test]
+fn update_device_status() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    for device_status in [
+        signaling::DeviceStatus {
+            microphone_disconnected: Some(true),
+            ..Default::default()
+        },
+        signaling::DeviceStatus {
+            camera_switching: Some(true),
+            ..Default::default()
+        },
+    ] {
+        cm.set_local_device_status(active_connection.call_id(), device_status)
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+
+    // Updating something else keeps the device status.
+    active_connection
+        .update_sender_status(signaling::SenderStatus {
+            audio_enabled: Some(true),
+            ..Default::default()
+        })
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+
+    assert_eq!(
+        Some(protobuf::rtp_data::SenderStatus {
+            id: Some(active_connection.call_id().into()),
+            video_enabled: None,
+            sharing_screen: None,
+            audio_enabled: Some(true),
+            held: None,
+            device_status: Some(protobuf::rtp_data::DeviceStatus {
+                microphone_disconnected: Some(true),
+                backgrounded: None,
+                camera_switching: Some(true),
+            }),
         }),
         active_connection.last_sent_sender_status()
     );
@@ -1199,6 +1305,74 @@ fn ice_candidate_removal() {
     );
 }
 
+#[test]
+fn candidate_policy_filters_candidates() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    cm.call(remote_peer, CallMediaType::Audio, 1)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    let call_id = active_call.call_id();
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default()
+            .with_data_mode(DataMode::Normal)
+            .with_candidate_policy(CandidatePolicy::no_host()),
+        None,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    cm.received_answer(call_id, random_received_answer(&context.prng, 1))
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    let mut active_connection = context.active_connection();
+
+    let host = "candidate:1 1 udp 2122260223 192.0.2.1 50000 typ host generation 0";
+    let srflx = "candidate:2 1 udp 1686052607 198.51.100.1 50001 typ srflx generation 0";
+    let candidates: Vec<signaling::IceCandidate> = [host, srflx]
+        .iter()
+        .map(|sdp| signaling::IceCandidate::from_v3_sdp(sdp.to_string()).expect(error_line!()))
+        .collect();
+
+    // Only the server reflexive candidate is sent.
+    let force_send = true;
+    for (candidate, sdp) in candidates.iter().zip([host, srflx]) {
+        active_connection
+            .inject_local_ice_candidate(candidate.clone(), force_send, sdp, None)
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ice_candidates_sent(), 1);
+
+    // Only the server reflexive candidate is used.
+    cm.received_ice(
+        call_id,
+        signaling::ReceivedIce {
+            ice: signaling::Ice { candidates },
+            sender_device_id: 1,
+        },
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        vec![srflx.to_owned()],
+        active_connection
+            .app_connection()
+            .unwrap()
+            .added_ice_candidates()
+    );
+}
+
 #[test]
 fn ice_send_failures_cause_error_before_connection() {
     test_init();
@@ -1296,6 +1470,136 @@ fn ignore_ice_send_failures_after_connection() {
     assert!(cm.busy());
 }
 
+#[test]
+fn signaling_queue_coalesces_ice() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let active_call = context.active_call();
+    let force_send = true;
+
+    // Get an ICE candidate message 'in-flight' so that later ones are queued.
+    context.no_auto_message_sent_for_ice(true);
+    active_connection
+        .inject_local_ice_candidate(random_ice_candidate(&context.prng), force_send, "", None)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.ice_candidates_sent(), 1);
+    let sent_before = cm
+        .signaling_message_queue_stats()
+        .expect(error_line!())
+        .sent;
+
+    for _ in 0..2 {
+        active_connection
+            .inject_local_ice_candidate(random_ice_candidate(&context.prng), force_send, "", None)
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+    active_call
+        .send_buffered_local_ice_candidates(active_connection.clone(), false)
+        .expect(error_line!());
+
+    let stats = cm.signaling_message_queue_stats().expect(error_line!());
+    assert_eq!(stats.depth, 1);
+    assert_eq!(stats.coalesced, 1);
+
+    cm.message_sent(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Both candidates go out in a single message.
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ice_candidates_sent(), 3);
+    let stats = cm.signaling_message_queue_stats().expect(error_line!());
+    assert_eq!(stats.depth, 0);
+    assert_eq!(stats.sent, sent_before + 1);
+    assert!(stats.max_latency >= stats.last_latency);
+}
+
+#[test]
+fn signaling_queue_sends_busy_before_ice() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let active_call = context.active_call();
+    let force_send = true;
+
+    context.no_auto_message_sent_for_ice(true);
+    for _ in 0..2 {
+        active_connection
+            .inject_local_ice_candidate(random_ice_candidate(&context.prng), force_send, "", None)
+            .expect(error_line!());
+        cm.synchronize().expect(error_line!());
+    }
+    assert_eq!(context.ice_candidates_sent(), 1);
+
+    // An offer from someone else gets a busy, which waits behind the
+    // in-flight ICE message but goes ahead of the queued one.
+    cm.received_offer(
+        format!("REMOTE_PEER-{}", context.prng.gen::<u16>()),
+        CallId::new(context.prng.gen::<u64>()),
+        random_received_offer(&context.prng, Duration::ZERO),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(
+        cm.signaling_message_queue_stats()
+            .expect(error_line!())
+            .depth,
+        2
+    );
+
+    cm.message_sent(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.busys_sent(), 1);
+    assert_eq!(context.ice_candidates_sent(), 2);
+}
+
+#[test]
+fn signaling_queue_drops_messages_for_ended_call() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let active_call = context.active_call();
+    let force_send = true;
+
+    context.no_auto_message_sent_for_ice(true);
+    for _ in 0..2 {
+        active_connection
+            .inject_local_ice_candidate(random_ice_candidate(&context.prng), force_send, "", None)
+            .expect(error_line!());
+        cm.synchronize().expect(error_line!());
+    }
+    assert_eq!(context.ice_candidates_sent(), 1);
+
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        cm.signaling_message_queue_stats()
+            .expect(error_line!())
+            .dropped,
+        1
+    );
+
+    cm.message_sent(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The hangup still goes out, but the ICE candidates don't.
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.normal_hangups_sent(), 1);
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert!(!cm.busy());
+}
+
 #[test]
 fn received_remote_hangup_before_connection() {
     test_init();
@@ -1552,6 +1856,8 @@ fn received_remote_video_status() {
                     video_enabled: Some(enable),
                     sharing_screen: None,
                     audio_enabled: None,
+                    held: None,
+                    device_status: None,
                 },
                 i,
             )
@@ -1587,6 +1893,8 @@ fn received_remote_video_status() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -1599,6 +1907,8 @@ fn received_remote_video_status() {
                 video_enabled: Some(false),
                 sharing_screen: None,
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             2,
         )
@@ -1641,6 +1951,8 @@ fn ignore_duplicate_remote_video_status() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             0,
         )
@@ -1654,6 +1966,8 @@ fn ignore_duplicate_remote_video_status() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -1667,6 +1981,8 @@ fn ignore_duplicate_remote_video_status() {
                 video_enabled: Some(false),
                 sharing_screen: None,
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             2,
         )
@@ -1680,6 +1996,8 @@ fn ignore_duplicate_remote_video_status() {
                 video_enabled: Some(false),
                 sharing_screen: None,
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             3,
         )
@@ -1725,6 +2043,8 @@ fn received_remote_sharing_screen_status() {
                     video_enabled: None,
                     sharing_screen: Some(enable),
                     audio_enabled: None,
+                    held: None,
+                    device_status: None,
                 },
                 i,
             )
@@ -1754,6 +2074,8 @@ fn received_remote_sharing_screen_status() {
                 video_enabled: None,
                 sharing_screen: Some(true),
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -1766,6 +2088,8 @@ fn received_remote_sharing_screen_status() {
                 video_enabled: None,
                 sharing_screen: Some(false),
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             2,
         )
@@ -1802,6 +2126,8 @@ fn ignore_duplicate_remote_screen_sharing_status() {
                 video_enabled: None,
                 sharing_screen: Some(true),
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             0,
         )
@@ -1815,6 +2141,8 @@ fn ignore_duplicate_remote_screen_sharing_status() {
                 video_enabled: None,
                 sharing_screen: Some(true),
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -1828,6 +2156,8 @@ fn ignore_duplicate_remote_screen_sharing_status() {
                 video_enabled: None,
                 sharing_screen: Some(false),
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             2,
         )
@@ -1841,6 +2171,8 @@ fn ignore_duplicate_remote_screen_sharing_status() {
                 video_enabled: None,
                 sharing_screen: Some(false),
                 audio_enabled: None,
+                held: None,
+                device_status: None,
             },
             3,
         )
@@ -1892,6 +2224,8 @@ fn received_remote_audio_status() {
                     video_enabled: None,
                     sharing_screen: None,
                     audio_enabled: Some(enable),
+                    held: None,
+                    device_status: None,
                 },
                 i,
             )
@@ -1927,6 +2261,8 @@ fn received_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -1939,6 +2275,8 @@ fn received_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(false),
+                held: None,
+                device_status: None,
             },
             2,
         )
@@ -1981,6 +2319,8 @@ fn ignore_duplicate_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                held: None,
+                device_status: None,
             },
             0,
         )
@@ -1994,6 +2334,8 @@ fn ignore_duplicate_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -2007,6 +2349,8 @@ fn ignore_duplicate_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(false),
+                held: None,
+                device_status: None,
             },
             2,
         )
@@ -2020,6 +2364,8 @@ fn ignore_duplicate_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(false),
+                held: None,
+                device_status: None,
             },
             3,
         )
@@ -2048,6 +2394,8 @@ fn received_remote_multiple_status() {
                 video_enabled: Some(false),
                 sharing_screen: Some(true),
                 audio_enabled: Some(false),
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -2060,6 +2408,8 @@ fn received_remote_multiple_status() {
                 video_enabled: Some(true),
                 sharing_screen: Some(false),
                 audio_enabled: Some(true),
+                held: None,
+                device_status: None,
             },
             2,
         )
@@ -2102,6 +2452,8 @@ fn received_status_before_accepted() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                held: None,
+                device_status: None,
             },
             1,
         )
@@ -2180,6 +2532,42 @@ fn call_timeout_after_connect() {
     assert!(cm.busy());
 }
 
+#[test]
+fn outbound_handoff_call() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.handoff_call(remote_peer, call_id, CallMediaType::Audio, 2)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    assert_eq!(active_call.call_id(), call_id);
+    assert!(active_call.is_handoff());
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.offers_sent(), 1);
+    let offer = context.last_offer_sent().expect(error_line!());
+    assert!(offer.is_handoff());
+    assert_eq!(offer.latest_version(), signaling::Version::V5);
+    assert_eq!(context.error_count(), 0);
+    assert!(cm.busy());
+}
+
 #[test]
 fn outbound_proceed_with_error() {
     test_init();